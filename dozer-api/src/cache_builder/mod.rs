@@ -69,7 +69,7 @@ fn build_cache(
     while let Some(op) = runtime.block_on(next_op_with_cancel(&mut log_reader, cancel)) {
         cancel = op.1;
         match op.0 {
-            ExecutorOperation::Op { op } => match op {
+            ExecutorOperation::Op { op, .. } => match op {
                 Operation::Delete { mut old } => {
                     old.schema_id = schema.identifier;
                     if let Some(meta) = cache.delete(&old)? {
@@ -124,6 +124,8 @@ fn build_cache(
             ExecutorOperation::Terminate => {
                 break;
             }
+            ExecutorOperation::Watermark { .. } => {}
+            ExecutorOperation::Batch { .. } => unreachable!("Batches are never persisted"),
         }
     }
 
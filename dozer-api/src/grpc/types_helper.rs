@@ -43,6 +43,38 @@ pub fn map_update_operation(
     }
 }
 
+/// Maps a pipeline operation that has not gone through a cache, so records have no id or version.
+pub fn map_pipeline_operation(
+    endpoint_name: String,
+    op: dozer_types::types::Operation,
+) -> Operation {
+    let (typ, old, new) = match op {
+        dozer_types::types::Operation::Insert { new } => (OperationType::Insert, None, new),
+        dozer_types::types::Operation::Delete { old } => (OperationType::Delete, None, old),
+        dozer_types::types::Operation::Update { old, new } => {
+            (OperationType::Update, Some(old), new)
+        }
+    };
+    Operation {
+        typ: typ as i32,
+        old: old.map(pipeline_record_to_internal_record),
+        new: Some(pipeline_record_to_internal_record(new)),
+        new_id: None,
+        endpoint_name,
+    }
+}
+
+fn pipeline_record_to_internal_record(record: dozer_types::types::Record) -> Record {
+    Record {
+        values: record
+            .values
+            .into_iter()
+            .map(field_to_prost_value)
+            .collect(),
+        version: 0,
+    }
+}
+
 fn record_to_internal_record(record: CacheRecord) -> Record {
     let values: Vec<Value> = record
         .record
@@ -90,7 +122,7 @@ fn map_decimal(d: Decimal) -> Value {
     }
 }
 
-fn field_to_prost_value(f: Field) -> Value {
+pub fn field_to_prost_value(f: Field) -> Value {
     match f {
         Field::UInt(n) => Value {
             value: Some(value::Value::UintValue(n)),
@@ -99,6 +99,10 @@ impl LmdbEnvironment for RwMainEnvironment {
     fn env(&self) -> &dozer_storage::lmdb::Environment {
         self.env.env()
     }
+
+    fn readers(&self) -> &dozer_storage::ReaderSlots {
+        self.env.readers()
+    }
 }
 
 impl MainEnvironment for RwMainEnvironment {
@@ -457,6 +461,10 @@ impl LmdbEnvironment for RoMainEnvironment {
     fn env(&self) -> &dozer_storage::lmdb::Environment {
         self.env.env()
     }
+
+    fn readers(&self) -> &dozer_storage::ReaderSlots {
+        self.env.readers()
+    }
 }
 
 impl MainEnvironment for RoMainEnvironment {
@@ -1,6 +1,5 @@
 mod handler;
 mod intersection;
-mod lmdb_cmp;
 mod secondary;
 
 pub use handler::LmdbQueryHandler;
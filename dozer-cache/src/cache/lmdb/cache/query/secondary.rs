@@ -1,8 +1,8 @@
-use std::{cmp::Ordering, ops::Bound};
+use std::ops::Bound;
 
 use dozer_storage::lmdb::Transaction;
 use dozer_types::{
-    borrow::{Borrow, IntoOwned},
+    borrow::IntoOwned,
     types::{Field, IndexDefinition},
 };
 
@@ -16,8 +16,6 @@ use crate::{
     errors::{CacheError, IndexError},
 };
 
-use super::lmdb_cmp::lmdb_cmp;
-
 pub fn build_index_scan<'txn, T: Transaction, S: SecondaryEnvironment>(
     secondary_txn: &'txn T,
     secondary_env: &S,
@@ -27,38 +25,14 @@ pub fn build_index_scan<'txn, T: Transaction, S: SecondaryEnvironment>(
         is_single_field_sorted_inverted(secondary_env.index_definition());
     let range = get_range_spec(index_scan_kind, is_single_field_sorted_inverted)?;
 
-    let start = match &range.start {
-        Some(KeyEndpoint::Including(key)) => Bound::Included(key.as_slice()),
-        Some(KeyEndpoint::Excluding(key)) => Bound::Excluded(key.as_slice()),
-        None => Bound::Unbounded,
-    };
-
-    let database = secondary_env.database().database();
     Ok(secondary_env
         .database()
-        .range(
+        .scan(
             secondary_txn,
-            start,
+            key_bound(&range.start),
+            key_bound(&range.end),
             range.direction == SortDirection::Ascending,
         )?
-        .take_while(move |result| match result {
-            Ok((key, _)) => {
-                if let Some(end_key) = &range.end {
-                    match lmdb_cmp(secondary_txn, database, key.borrow(), end_key.key()) {
-                        Ordering::Less => {
-                            matches!(range.direction, SortDirection::Ascending)
-                        }
-                        Ordering::Equal => matches!(end_key, KeyEndpoint::Including(_)),
-                        Ordering::Greater => {
-                            matches!(range.direction, SortDirection::Descending)
-                        }
-                    }
-                } else {
-                    true
-                }
-            }
-            Err(_) => true,
-        })
         .map(|result| {
             result
                 .map(|(_, id)| id.into_owned())
@@ -80,12 +54,11 @@ pub enum KeyEndpoint {
     Excluding(Vec<u8>),
 }
 
-impl KeyEndpoint {
-    pub fn key(&self) -> &[u8] {
-        match self {
-            KeyEndpoint::Including(key) => key,
-            KeyEndpoint::Excluding(key) => key,
-        }
+fn key_bound(endpoint: &Option<KeyEndpoint>) -> Bound<&[u8]> {
+    match endpoint {
+        Some(KeyEndpoint::Including(key)) => Bound::Included(key.as_slice()),
+        Some(KeyEndpoint::Excluding(key)) => Bound::Excluded(key.as_slice()),
+        None => Bound::Unbounded,
     }
 }
 
@@ -42,6 +42,10 @@ impl LmdbEnvironment for RwSecondaryEnvironment {
     fn env(&self) -> &dozer_storage::lmdb::Environment {
         self.env.env()
     }
+
+    fn readers(&self) -> &dozer_storage::ReaderSlots {
+        self.env.readers()
+    }
 }
 
 impl SecondaryEnvironment for RwSecondaryEnvironment {
@@ -175,6 +179,10 @@ impl LmdbEnvironment for RoSecondaryEnvironment {
     fn env(&self) -> &dozer_storage::lmdb::Environment {
         self.env.env()
     }
+
+    fn readers(&self) -> &dozer_storage::ReaderSlots {
+        self.env.readers()
+    }
 }
 
 impl SecondaryEnvironment for RoSecondaryEnvironment {
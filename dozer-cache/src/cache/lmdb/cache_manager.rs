@@ -1,7 +1,7 @@
 use std::path::Path;
 use std::{path::PathBuf, sync::Arc};
 
-use dozer_storage::lmdb::RoTransaction;
+use dozer_storage::lmdb::Transaction;
 use dozer_storage::{
     lmdb_storage::LmdbEnvironmentManager, LmdbEnvironment, LmdbMap, RoLmdbEnvironment,
     RwLmdbEnvironment,
@@ -224,8 +224,8 @@ impl LmdbRwCacheManager {
     }
 }
 
-fn resolve_alias<'a>(
-    txn: &'a RoTransaction,
+fn resolve_alias<'a, T: Transaction>(
+    txn: &'a T,
     alias_to_real_name: LmdbMap<String, String>,
     name: &'a str,
 ) -> Result<&'a str, CacheError> {
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dozer_core::channels::{ProcessorChannelForwarder, SourceChannelForwarder};
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::ExecutionError;
+use dozer_core::executor::{DagExecutor, ExecutorOptions};
+use dozer_core::node::{
+    OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory, Sink, SinkFactory,
+    Source, SourceFactory,
+};
+use dozer_core::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+
+fn schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .clone()
+}
+
+/// Sends `count` inserts, then stops.
+#[derive(Debug)]
+struct CountSourceFactory {
+    count: u64,
+}
+
+impl SourceFactory<()> for CountSourceFactory {
+    fn get_output_schema(&self, _port: &PortHandle) -> Result<(Schema, ()), ExecutionError> {
+        Ok((schema(), ()))
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, ExecutionError> {
+        Ok(Box::new(CountSource { count: self.count }))
+    }
+}
+
+#[derive(Debug)]
+struct CountSource {
+    count: u64,
+}
+
+impl Source for CountSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
+        Ok(false)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        _last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), ExecutionError> {
+        for txid in 0..self.count {
+            fw.send(
+                IngestionMessage::new_op(
+                    txid,
+                    0,
+                    Operation::Insert {
+                        new: Record::new(None, vec![Field::UInt(txid)]),
+                    },
+                ),
+                DEFAULT_PORT_HANDLE,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct PassthroughProcessorFactory;
+
+impl ProcessorFactory<()> for PassthroughProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        _input_schemas: &HashMap<PortHandle, (Schema, ())>,
+    ) -> Result<(Schema, ()), ExecutionError> {
+        Ok((schema(), ()))
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(PassthroughProcessor))
+    }
+}
+
+#[derive(Debug)]
+struct PassthroughProcessor;
+
+impl Processor for PassthroughProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        fw.send(op, DEFAULT_PORT_HANDLE)
+    }
+}
+
+#[derive(Debug)]
+struct DiscardSinkFactory;
+
+impl SinkFactory<()> for DiscardSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, ())>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(DiscardSink))
+    }
+}
+
+#[derive(Debug)]
+struct DiscardSink;
+
+impl Sink for DiscardSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(&mut self, _from_port: PortHandle, _op: Operation) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+/// Runs `count` operations through a chain of `processors` processors.
+fn run(count: u64, processors: usize, batch_size: usize) {
+    let mut dag = Dag::new();
+    let source = NodeHandle::new(None, "source".to_string());
+    dag.add_source(source.clone(), Arc::new(CountSourceFactory { count }));
+    let mut upstream = source;
+    for index in 0..processors {
+        let processor = NodeHandle::new(None, format!("processor_{index}"));
+        dag.add_processor(processor.clone(), Arc::new(PassthroughProcessorFactory));
+        dag.connect(
+            Endpoint::new(upstream, DEFAULT_PORT_HANDLE),
+            Endpoint::new(processor.clone(), DEFAULT_PORT_HANDLE),
+        )
+        .unwrap();
+        upstream = processor;
+    }
+    let sink = NodeHandle::new(None, "sink".to_string());
+    dag.add_sink(sink.clone(), Arc::new(DiscardSinkFactory));
+    dag.connect(
+        Endpoint::new(upstream, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink, DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    let options = ExecutorOptions {
+        batch_size,
+        ..Default::default()
+    };
+    DagExecutor::new(dag, options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+fn forwarding(c: &mut Criterion) {
+    let count = 100_000;
+    let mut group = c.benchmark_group("forwarding");
+    group.throughput(Throughput::Elements(count));
+    group.sample_size(10);
+    for batch_size in [1, 16, 256] {
+        group.bench_with_input(
+            BenchmarkId::new("batch_size", batch_size),
+            &batch_size,
+            |b, &batch_size| b.iter(|| run(count, 3, batch_size)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, forwarding);
+criterion_main!(benches);
@@ -2,12 +2,13 @@ use std::fmt::Debug;
 
 use daggy::petgraph::visit::IntoNodeIdentifiers;
 use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::tracing::debug_span;
 
 use crate::{
     dag_checkpoint::{DagCheckpoint, NodeKind as CheckpointNodeKind},
     dag_schemas::{DagHaveSchemas, DagSchemas, EdgeType},
     errors::ExecutionError,
-    node::{Processor, Sink, Source},
+    node::{PortHandle, Processor, Sink, Source},
 };
 
 #[derive(Debug)]
@@ -21,9 +22,14 @@ pub struct NodeType {
 
 #[derive(Debug)]
 /// Node kind, source, processor or sink. Source has a checkpoint to start from.
+/// Processor has whether it is accumulating, see [`ProcessorFactory::is_accumulating`], and its
+/// dead-letter port, see [`ProcessorFactory::dead_letter_port`].
+///
+/// [`ProcessorFactory::is_accumulating`]: crate::node::ProcessorFactory::is_accumulating
+/// [`ProcessorFactory::dead_letter_port`]: crate::node::ProcessorFactory::dead_letter_port
 pub enum NodeKind {
     Source(Box<dyn Source>, Option<OpIdentifier>),
-    Processor(Box<dyn Processor>),
+    Processor(Box<dyn Processor>, bool, Option<PortHandle>),
     Sink(Box<dyn Sink>),
 }
 
@@ -52,11 +58,18 @@ impl BuilderDag {
             let output_schemas = dag_checkpoint.get_node_output_schemas(node_index);
 
             let node = &dag_checkpoint.graph()[node_index];
+            let _span = debug_span!("build_node", node = %node.handle).entered();
             let kind = match &node.kind {
                 CheckpointNodeKind::Source(_) => None,
                 CheckpointNodeKind::Processor(processor) => {
+                    let accumulating = processor.is_accumulating();
+                    let dead_letter_port = processor.dead_letter_port();
                     let processor = processor.build(input_schemas, output_schemas)?;
-                    Some(NodeKind::Processor(processor))
+                    Some(NodeKind::Processor(
+                        processor,
+                        accumulating,
+                        dead_letter_port,
+                    ))
                 }
                 CheckpointNodeKind::Sink(sink) => {
                     let sink = sink.build(input_schemas)?;
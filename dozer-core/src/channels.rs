@@ -2,11 +2,24 @@ use crate::errors::ExecutionError;
 use crate::node::PortHandle;
 use core::marker::{Send, Sync};
 use core::result::Result;
+use dozer_types::epoch::OpTags;
 use dozer_types::ingestion_types::IngestionMessage;
 use dozer_types::types::Operation;
 
 pub trait SourceChannelForwarder: Send + Sync {
     fn send(&mut self, message: IngestionMessage, port: PortHandle) -> Result<(), ExecutionError>;
+
+    /// Sends `message` with `tags` attached, for sinks to trace the operations derived from it
+    /// back to it. Forwarders that don't carry tags send the message alone.
+    fn send_with_tags(
+        &mut self,
+        message: IngestionMessage,
+        tags: OpTags,
+        port: PortHandle,
+    ) -> Result<(), ExecutionError> {
+        let _ = tags;
+        self.send(message, port)
+    }
 }
 
 pub trait ProcessorChannelForwarder {
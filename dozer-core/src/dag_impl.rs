@@ -1,3 +1,4 @@
+use daggy::petgraph::algo::toposort;
 use daggy::petgraph::dot;
 use daggy::petgraph::visit::{Bfs, EdgeRef, IntoEdges};
 use daggy::Walker;
@@ -76,6 +77,18 @@ impl Display for EdgeType {
     }
 }
 
+/// Limits on the size of a DAG, checked by [`Dag::validate`] so that a pathological pipeline is
+/// rejected before it spawns a thread per node and a channel per edge. `None` is no limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DagLimits {
+    /// Maximum number of nodes.
+    pub max_nodes: Option<usize>,
+    /// Maximum number of edges from a single output port.
+    pub max_edges_per_port: Option<usize>,
+    /// Maximum number of nodes on a path from a source to a sink.
+    pub max_depth: Option<usize>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Dag<T> {
     /// The underlying graph.
@@ -153,7 +166,8 @@ impl<T> Dag<T> {
 
     /// Adds an edge. Panics if there's already an edge from `from` to `to`.
     ///
-    /// Returns an error if any of the port cannot be found or the edge would create a cycle.
+    /// Returns an error if any of the port cannot be found, is the default port of a processor
+    /// with several ports, or the edge would create a cycle.
     pub fn connect(&mut self, from: Endpoint, to: Endpoint) -> Result<(), ExecutionError> {
         let from_node_index = validate_endpoint(self, &from, PortDirection::Output)?;
         let to_node_index = validate_endpoint(self, &to, PortDirection::Input)?;
@@ -162,7 +176,8 @@ impl<T> Dag<T> {
 
     /// Adds an edge. Panics if there's already an edge from `from` to `to`.
     ///
-    /// Returns an error if any of the port cannot be found or the edge would create a cycle.
+    /// Returns an error if any of the port cannot be found, is the default port of a processor
+    /// with several ports, or the edge would create a cycle.
     pub fn connect_with_index(
         &mut self,
         from_node_index: daggy::NodeIndex,
@@ -190,6 +205,23 @@ impl<T> Dag<T> {
         Ok(())
     }
 
+    /// Connects `nodes` in a chain, the only output port of each node to the only input port of
+    /// the next one.
+    ///
+    /// Returns an error if a node has no port or several ports to chain it with, in which case it
+    /// must be connected with [`Dag::connect`], or if [`Dag::connect`] would fail.
+    pub fn connect_all(&mut self, nodes: &[NodeHandle]) -> Result<(), ExecutionError> {
+        for pair in nodes.windows(2) {
+            let output_port = self.single_port(&pair[0], PortDirection::Output)?;
+            let input_port = self.single_port(&pair[1], PortDirection::Input)?;
+            self.connect(
+                Endpoint::new(pair[0].clone(), output_port),
+                Endpoint::new(pair[1].clone(), input_port),
+            )?;
+        }
+        Ok(())
+    }
+
     /// Adds another whole `Dag` to `self`. Optionally under a namespace `ns`.
     pub fn merge(&mut self, ns: Option<u16>, other: Dag<T>) {
         let (other_nodes, _) = other.graph.into_graph().into_nodes_edges();
@@ -309,6 +341,57 @@ impl<T> Dag<T> {
             .map(|edge| (&self.graph[edge.target()].handle, edge.weight().to))
     }
 
+    /// Checks that the DAG is within `limits`.
+    pub fn validate(&self, limits: &DagLimits) -> Result<(), ExecutionError> {
+        if let Some(limit) = limits.max_nodes {
+            let count = self.graph.node_count();
+            if count > limit {
+                return Err(ExecutionError::TooManyNodes { count, limit });
+            }
+        }
+
+        if let Some(limit) = limits.max_edges_per_port {
+            let mut fan_out = HashMap::<(daggy::NodeIndex, PortHandle), usize>::new();
+            for edge in self.graph.raw_edges() {
+                *fan_out
+                    .entry((edge.source(), edge.weight.from))
+                    .or_default() += 1;
+            }
+            if let Some(((node_index, port), count)) =
+                fan_out.into_iter().find(|(_, count)| *count > limit)
+            {
+                return Err(ExecutionError::TooManyEdgesFromPort {
+                    node: self.graph[node_index].handle.clone(),
+                    port,
+                    count,
+                    limit,
+                });
+            }
+        }
+
+        if let Some(limit) = limits.max_depth {
+            let depth = self.depth();
+            if depth > limit {
+                return Err(ExecutionError::DagTooDeep { depth, limit });
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of nodes on the longest path of the DAG.
+    fn depth(&self) -> usize {
+        let order = toposort(self.graph.graph(), None).expect("A DAG has no cycle");
+        let mut depths = vec![1; self.graph.node_count()];
+        for node_index in order {
+            let depth = depths[node_index.index()];
+            for edge in self.graph.edges(node_index) {
+                let target = &mut depths[edge.target().index()];
+                *target = (*target).max(depth + 1);
+            }
+        }
+        depths.into_iter().max().unwrap_or(0)
+    }
+
     /// Returns an iterator over all node handles reachable from `start` in a breadth-first search.
     pub fn bfs(&self, start: &NodeHandle) -> impl Iterator<Item = &NodeHandle> {
         let start = self.node_index(start);
@@ -339,6 +422,26 @@ impl<T> Dag<T> {
         node_index
     }
 
+    /// Returns the port of `node` in `direction`, if it has exactly one.
+    fn single_port(
+        &self,
+        node: &NodeHandle,
+        direction: PortDirection,
+    ) -> Result<PortHandle, ExecutionError> {
+        let ports = ports(&self.graph[self.node_index(node)].kind, direction.clone());
+        match ports.as_slice() {
+            [port] => Ok(*port),
+            _ => Err(ExecutionError::AmbiguousPorts {
+                node: node.clone(),
+                direction: match direction {
+                    PortDirection::Input => "input",
+                    PortDirection::Output => "output",
+                },
+                count: ports.len(),
+            }),
+        }
+    }
+
     fn node_index(&self, node_handle: &NodeHandle) -> daggy::NodeIndex {
         *self
             .node_lookup_table
@@ -370,9 +473,26 @@ fn validate_port_with_index<T>(
     direction: PortDirection,
 ) -> Result<(), ExecutionError> {
     let node = &dag.graph[node_index];
-    if !contains_port(&node.kind, direction, port)? {
+    if !contains_port(&node.kind, direction.clone(), port)? {
         return Err(ExecutionError::InvalidPortHandle(port));
     }
+
+    // A processor with several ports must number all of them, or edges to the default port are
+    // ambiguous.
+    if port == DEFAULT_PORT_HANDLE {
+        if let NodeKind::Processor(p) = &node.kind {
+            let count = match direction {
+                PortDirection::Input => p.get_input_ports().len(),
+                PortDirection::Output => p.get_output_ports().len(),
+            };
+            if count > 1 {
+                return Err(ExecutionError::DefaultPortOnMultiPortNode {
+                    node: node.handle.clone(),
+                    count,
+                });
+            }
+        }
+    }
     Ok(())
 }
 
@@ -381,27 +501,23 @@ fn contains_port<T>(
     direction: PortDirection,
     port: PortHandle,
 ) -> Result<bool, ExecutionError> {
-    Ok(match node {
-        NodeKind::Processor(p) => {
-            if direction == PortDirection::Output {
-                p.get_output_ports().iter().any(|e| e.handle == port)
-            } else {
-                p.get_input_ports().contains(&port)
-            }
+    Ok(ports(node, direction).contains(&port))
+}
+
+/// Returns the ports `node` declares in `direction`.
+fn ports<T>(node: &NodeKind<T>, direction: PortDirection) -> Vec<PortHandle> {
+    match (node, direction) {
+        (NodeKind::Processor(p), PortDirection::Output) => {
+            p.get_output_ports().iter().map(|e| e.handle).collect()
         }
-        NodeKind::Sink(s) => {
-            if direction == PortDirection::Output {
-                false
-            } else {
-                s.get_input_ports().contains(&port)
-            }
+        (NodeKind::Processor(p), PortDirection::Input) => p.get_input_ports(),
+        (NodeKind::Sink(s), PortDirection::Input) => s.get_input_ports(),
+        (NodeKind::Source(s), PortDirection::Output) => {
+            s.get_output_ports().iter().map(|e| e.handle).collect()
         }
-        NodeKind::Source(s) => {
-            if direction == PortDirection::Output {
-                s.get_output_ports().iter().any(|e| e.handle == port)
-            } else {
-                false
-            }
+        (NodeKind::Sink(_), PortDirection::Output)
+        | (NodeKind::Source(_), PortDirection::Input) => {
+            vec![]
         }
-    })
+    }
 }
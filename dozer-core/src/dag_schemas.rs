@@ -6,6 +6,7 @@ use daggy::petgraph::graph::EdgeReference;
 use daggy::petgraph::visit::{EdgeRef, IntoEdges, IntoEdgesDirected, IntoNodeReferences, Topo};
 use daggy::petgraph::Direction;
 use daggy::{NodeIndex, Walker};
+use dozer_types::tracing::debug_span;
 use dozer_types::types::Schema;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -218,6 +219,7 @@ fn populate_schemas<T: Clone>(
 
     for node_index in Topo::new(&dag).iter(&dag) {
         let node = &dag.graph()[node_index];
+        let _span = debug_span!("populate_schemas", node = %node.handle).entered();
 
         match &node.kind {
             NodeKind::Source(source) => {
@@ -235,11 +237,24 @@ fn populate_schemas<T: Clone>(
                     validate_input_schemas(&dag, &edges, node_index, processor.get_input_ports())?;
 
                 let ports = processor.get_output_ports();
+                if let Some(port) = processor.dead_letter_port() {
+                    if !ports.iter().any(|def| def.handle == port) {
+                        return Err(ExecutionError::InvalidPortHandle(port));
+                    }
+                }
+                let output_schemas = processor.prepare(&input_schemas)?;
+                for port in &ports {
+                    if !output_schemas.contains_key(&port.handle) {
+                        return Err(ExecutionError::MissingOutputSchema {
+                            node: node.handle.clone(),
+                            port: port.handle,
+                        });
+                    }
+                }
 
                 for edge in dag.graph().edges(node_index) {
                     let port = find_output_port_def(&ports, edge);
-                    let (schema, ctx) =
-                        processor.get_output_schema(&port.handle, &input_schemas)?;
+                    let (schema, ctx) = output_schemas[&port.handle].clone();
                     create_edge(&mut edges, edge, port, schema, ctx);
                 }
             }
@@ -7,6 +7,7 @@ use dozer_types::errors::types::TypeError;
 use dozer_types::node::NodeHandle;
 use dozer_types::thiserror;
 use dozer_types::thiserror::Error;
+use dozer_types::types::{Field, FieldType};
 
 #[derive(Error, Debug)]
 pub enum ExecutionError {
@@ -20,12 +21,35 @@ pub enum ExecutionError {
     MissingInput { node: NodeHandle, port: PortHandle },
     #[error("Duplicate input for node {node} on port {port}")]
     DuplicateInput { node: NodeHandle, port: PortHandle },
+    #[error("Node {node} has {count} ports, so it can't be connected on the default port")]
+    DefaultPortOnMultiPortNode { node: NodeHandle, count: usize },
+    #[error("Node {node} has {count} {direction} ports, so it must be connected explicitly")]
+    AmbiguousPorts {
+        node: NodeHandle,
+        direction: &'static str,
+        count: usize,
+    },
+    #[error("Missing output schema for node {node} on port {port}")]
+    MissingOutputSchema { node: NodeHandle, port: PortHandle },
+    #[error("The DAG has {count} nodes, more than the limit of {limit}")]
+    TooManyNodes { count: usize, limit: usize },
+    #[error("Port {port} of node {node} has {count} edges, more than the limit of {limit}")]
+    TooManyEdgesFromPort {
+        node: NodeHandle,
+        port: PortHandle,
+        count: usize,
+        limit: usize,
+    },
+    #[error("The DAG is {depth} nodes deep, more than the limit of {limit}")]
+    DagTooDeep { depth: usize, limit: usize },
+    #[error("Received commit of epoch {received} while aligning epoch {expected}")]
+    MisalignedEpoch { expected: u64, received: u64 },
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
     #[error("Invalid type: {0}")]
     InvalidType(String),
-    #[error("Schema not initialized")]
-    SchemaNotInitialized,
+    #[error("Schema not initialized for {0}")]
+    SchemaNotInitialized(String),
     #[error("The database is invalid")]
     InvalidDatabase,
     #[error("Field not found at position {0}")]
@@ -142,6 +166,16 @@ pub enum SinkError {
 
     #[error("Failed to count the records during init in Cache: {0:?}, Error: {1:?}")]
     CacheCountFailed(String, #[source] BoxedError),
+
+    #[error("Record has {actual} fields, but the schema has {expected}")]
+    RecordFieldCountMismatch { expected: usize, actual: usize },
+
+    #[error("Field {field:?} is not a valid {expected:?}: {actual:?}")]
+    RecordFieldTypeMismatch {
+        field: String,
+        expected: FieldType,
+        actual: Field,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -1,14 +1,19 @@
 use crate::builder_dag::{BuilderDag, NodeKind};
 use crate::dag_schemas::DagSchemas;
 use crate::errors::ExecutionError;
-use crate::Dag;
+use crate::metrics::DagMetrics;
+use crate::node::InputSelectionPolicy;
+use crate::{Dag, DagLimits};
 
 use daggy::petgraph::visit::IntoNodeIdentifiers;
+use dozer_types::log::warn;
 use dozer_types::node::NodeHandle;
 
 use dozer_types::serde::{self, Deserialize, Serialize};
+use dozer_types::tracing::{dispatcher, info_span, Dispatch};
+use std::any::Any;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::panic::panic_any;
 use std::sync::atomic::AtomicBool;
@@ -22,6 +27,35 @@ pub struct ExecutorOptions {
     pub commit_sz: u32,
     pub channel_buffer_sz: usize,
     pub commit_time_threshold: Duration,
+    /// If set, sources commit at least this often even without new operations, so that sinks
+    /// flush and checkpoint on idle streams.
+    pub commit_interval: Option<Duration>,
+    /// If set, a node keeps running when a node downstream of it fails, and stops sending to it.
+    /// Branches of the DAG that don't go through the failed node then run to completion. Use
+    /// [`DagExecutorJoinHandle::join_all`] to collect the failures.
+    pub continue_on_failure: bool,
+    /// Cores to pin the threads of some nodes to, e.g. CPU-bound processors. Threads of other
+    /// nodes are left to the OS scheduler.
+    pub core_affinity: HashMap<NodeHandle, usize>,
+    /// Number of operations a node sends on a port as one channel message. Partial batches are
+    /// sent before any commit, watermark or termination. `1` sends every operation on its own.
+    pub batch_size: usize,
+    /// Nodes whose input channels drop operations when full, instead of blocking the nodes
+    /// sending to them, e.g. monitoring sinks that must not slow the pipeline down. Dropped
+    /// operations are lost for good, so this is unsafe for any node needing exactly-once
+    /// delivery. Commits, watermarks and other control messages still wait for room. Drops are
+    /// counted in [`NodeMetrics::dropped`].
+    ///
+    /// [`NodeMetrics::dropped`]: crate::metrics::NodeMetrics::dropped
+    pub drop_when_full: HashSet<NodeHandle>,
+    /// Limits the DAG must be within, checked before anything is built.
+    pub limits: DagLimits,
+    /// Makes every processor and sink read its inputs with
+    /// [`InputSelectionPolicy::Sequential`], whatever policy it asks for, so that tests over
+    /// nodes with several inputs see the same interleaving on every run. For tests only: the
+    /// fair selection of the other policies is lost.
+    #[cfg(any(test, feature = "deterministic-select"))]
+    pub deterministic_selection: bool,
 }
 
 impl Default for ExecutorOptions {
@@ -30,10 +64,29 @@ impl Default for ExecutorOptions {
             commit_sz: 10_000,
             channel_buffer_sz: 20_000,
             commit_time_threshold: Duration::from_millis(50),
+            commit_interval: None,
+            continue_on_failure: false,
+            core_affinity: HashMap::new(),
+            batch_size: 1,
+            drop_when_full: HashSet::new(),
+            limits: DagLimits::default(),
+            #[cfg(any(test, feature = "deterministic-select"))]
+            deterministic_selection: false,
         }
     }
 }
 
+impl ExecutorOptions {
+    /// The policy every node must read its inputs with instead of its own, if any.
+    fn selection_policy_override(&self) -> Option<InputSelectionPolicy> {
+        #[cfg(any(test, feature = "deterministic-select"))]
+        if self.deterministic_selection {
+            return Some(InputSelectionPolicy::Sequential);
+        }
+        None
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
 pub(crate) enum InputPortState {
@@ -48,6 +101,7 @@ mod processor_node;
 mod receiver_loop;
 mod sink_node;
 mod source_node;
+mod start_gate;
 
 use node::Node;
 use processor_node::ProcessorNode;
@@ -55,6 +109,7 @@ use sink_node::SinkNode;
 
 use self::execution_dag::ExecutionDag;
 use self::source_node::{create_source_nodes, SourceListenerNode, SourceSenderNode};
+use self::start_gate::StartGate;
 
 pub struct DagExecutor {
     builder_dag: BuilderDag,
@@ -63,6 +118,7 @@ pub struct DagExecutor {
 
 pub struct DagExecutorJoinHandle {
     join_handles: HashMap<NodeHandle, JoinHandle<()>>,
+    metrics: Arc<DagMetrics>,
 }
 
 impl DagExecutor {
@@ -70,6 +126,7 @@ impl DagExecutor {
         dag: Dag<T>,
         options: ExecutorOptions,
     ) -> Result<Self, ExecutionError> {
+        dag.validate(&options.limits)?;
         let dag_schemas = DagSchemas::new(dag)?;
         let builder_dag = BuilderDag::new(dag_schemas)?;
 
@@ -86,47 +143,85 @@ impl DagExecutor {
 
     pub fn start(self, running: Arc<AtomicBool>) -> Result<DagExecutorJoinHandle, ExecutionError> {
         // Construct execution dag.
-        let mut execution_dag =
-            ExecutionDag::new(self.builder_dag, self.options.channel_buffer_sz)?;
+        let mut execution_dag = ExecutionDag::new(
+            self.builder_dag,
+            self.options.channel_buffer_sz,
+            &self.options.drop_when_full,
+        )?;
         let node_indexes = execution_dag.graph().node_identifiers().collect::<Vec<_>>();
 
-        // Start the threads.
+        // Sources run a sender and a listener thread, other nodes a single thread.
+        let threads = node_indexes
+            .iter()
+            .map(|node_index| match &execution_dag.graph()[*node_index] {
+                Some(node) if matches!(node.kind, NodeKind::Source(_, _)) => 2,
+                _ => 1,
+            })
+            .sum();
+        let gate = Arc::new(StartGate::new(threads));
+
+        // Start the threads. If one fails to spawn, those already running quit without running
+        // their node.
         let mut join_handles = HashMap::new();
-        for node_index in node_indexes {
-            let node = execution_dag.graph()[node_index]
-                .as_ref()
-                .expect("We created all nodes");
-            let node_handle = node.handle.clone();
-            match &node.kind {
-                NodeKind::Source(_, _) => {
-                    let (source_sender_node, source_listener_node) = create_source_nodes(
-                        &mut execution_dag,
-                        node_index,
-                        &self.options,
-                        running.clone(),
-                    );
-                    join_handles.insert(
-                        node_handle,
-                        start_source(source_sender_node, source_listener_node)?,
-                    );
-                }
-                NodeKind::Processor(_) => {
-                    let processor_node = ProcessorNode::new(&mut execution_dag, node_index);
-                    join_handles.insert(node_handle, start_processor(processor_node)?);
-                }
-                NodeKind::Sink(_) => {
-                    let sink_node = SinkNode::new(&mut execution_dag, node_index);
-                    join_handles.insert(node_handle, start_sink(sink_node)?);
-                }
+        let mut metrics = DagMetrics::default();
+        if let Err(e) = spawn_nodes(
+            &mut execution_dag,
+            node_indexes,
+            &self.options,
+            &running,
+            &gate,
+            &mut join_handles,
+            &mut metrics,
+        ) {
+            gate.abort();
+            for (_, handle) in join_handles {
+                let _ = handle.join();
             }
+            return Err(e);
         }
 
-        Ok(DagExecutorJoinHandle { join_handles })
+        Ok(DagExecutorJoinHandle {
+            join_handles,
+            metrics: Arc::new(metrics),
+        })
     }
 }
 
 impl DagExecutorJoinHandle {
-    pub fn join(mut self) -> Result<(), ExecutionError> {
+    /// Metrics of the nodes, which stay readable once the DAG has finished.
+    pub fn metrics(&self) -> Arc<DagMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Current metrics of the nodes in the Prometheus text exposition format, for scraping a
+    /// running pipeline.
+    pub fn metrics_snapshot(&self) -> String {
+        self.metrics.render_prometheus()
+    }
+
+    /// Waits for every node to finish. Panics with the error of the first node that fails.
+    pub fn join(self) -> Result<(), ExecutionError> {
+        self.wait(|_, payload| panic_any(payload));
+        Ok(())
+    }
+
+    /// Waits for every node to finish, and returns the error of each node that failed.
+    ///
+    /// Unless [`ExecutorOptions::continue_on_failure`] is set, a failure makes the nodes around
+    /// the failed one fail too, and they are reported as well.
+    pub fn join_all(self) -> Result<(), Vec<(NodeHandle, ExecutionError)>> {
+        let mut failures = vec![];
+        self.wait(|handle, payload| failures.push((handle, panic_to_error(payload))));
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+
+    /// Polls the node threads until all are finished, calling `on_failure` for each one that
+    /// panicked, with the panic payload.
+    fn wait(mut self, mut on_failure: impl FnMut(NodeHandle, Box<dyn Any + Send>)) {
         let handles: Vec<NodeHandle> = self.join_handles.iter().map(|e| e.0.clone()).collect();
 
         loop {
@@ -134,14 +229,14 @@ impl DagExecutorJoinHandle {
                 if let Entry::Occupied(entry) = self.join_handles.entry(handle.clone()) {
                     if entry.get().is_finished() {
                         if let Err(e) = entry.remove().join() {
-                            panic_any(e);
+                            on_failure(handle.clone(), e);
                         }
                     }
                 }
             }
 
             if self.join_handles.is_empty() {
-                return Ok(());
+                return;
             }
 
             thread::sleep(Duration::from_millis(250));
@@ -149,46 +244,175 @@ impl DagExecutorJoinHandle {
     }
 }
 
+/// Spawns the threads of the nodes at `node_indexes`, adding their handles to `join_handles` and
+/// their metrics to `metrics`. The threads wait at `gate` before running their node.
+fn spawn_nodes(
+    execution_dag: &mut ExecutionDag,
+    node_indexes: Vec<daggy::NodeIndex>,
+    options: &ExecutorOptions,
+    running: &Arc<AtomicBool>,
+    gate: &Arc<StartGate>,
+    join_handles: &mut HashMap<NodeHandle, JoinHandle<()>>,
+    metrics: &mut DagMetrics,
+) -> Result<(), ExecutionError> {
+    for node_index in node_indexes {
+        let node = execution_dag.graph()[node_index]
+            .as_ref()
+            .expect("We created all nodes");
+        let node_handle = node.handle.clone();
+        let core = options.core_affinity.get(&node_handle).copied();
+        match &node.kind {
+            NodeKind::Source(_, _) => {
+                let (source_sender_node, source_listener_node) =
+                    create_source_nodes(execution_dag, node_index, options, running.clone());
+                metrics.add(node_handle.clone(), source_listener_node.metrics());
+                join_handles.insert(
+                    node_handle,
+                    start_source(source_sender_node, source_listener_node, core, gate.clone())?,
+                );
+            }
+            NodeKind::Processor(..) => {
+                let mut processor_node = ProcessorNode::new(
+                    execution_dag,
+                    node_index,
+                    options.continue_on_failure,
+                    options.batch_size,
+                );
+                if let Some(policy) = options.selection_policy_override() {
+                    processor_node.override_selection_policy(policy);
+                }
+                metrics.add(node_handle.clone(), processor_node.metrics());
+                join_handles.insert(
+                    node_handle,
+                    start_processor(processor_node, core, gate.clone())?,
+                );
+            }
+            NodeKind::Sink(_) => {
+                let mut sink_node = SinkNode::new(execution_dag, node_index);
+                if let Some(policy) = options.selection_policy_override() {
+                    sink_node.override_selection_policy(policy);
+                }
+                metrics.add(node_handle.clone(), sink_node.metrics());
+                join_handles.insert(node_handle, start_sink(sink_node, core, gate.clone())?);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Node threads panic with the `ExecutionError` they fail with. Other panics are turned into an
+/// error holding their message.
+fn panic_to_error(payload: Box<dyn Any + Send>) -> ExecutionError {
+    match payload.downcast::<ExecutionError>() {
+        Ok(e) => *e,
+        Err(payload) => match payload.downcast::<String>() {
+            Ok(message) => ExecutionError::InternalStringError(*message),
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => ExecutionError::InternalStringError(message.to_string()),
+                Err(_) => ExecutionError::InternalThreadPanic,
+            },
+        },
+    }
+}
+
+/// Pins the current thread to `core`, if any.
+fn pin_to_core(core: Option<usize>, node: &NodeHandle) {
+    if let Some(id) = core {
+        if !core_affinity::set_for_current(core_affinity::CoreId { id }) {
+            warn!("Failed to pin node {node} to core {id}");
+        }
+    }
+}
+
 fn start_source(
     source_sender: SourceSenderNode,
     source_listener: SourceListenerNode,
+    core: Option<usize>,
+    gate: Arc<StartGate>,
 ) -> Result<JoinHandle<()>, ExecutionError> {
     let handle = source_sender.handle().clone();
+    let listener_gate = gate.clone();
 
+    let sender_dispatch = dispatcher::get_default(Dispatch::clone);
+    let sender_handle = handle.clone();
     let _st_handle = Builder::new()
-        .name(format!("{handle}-sender"))
-        .spawn(move || match source_sender.run() {
-            Ok(_) => {}
-            // Channel disconnection means the source listener has quit.
-            // Maybe it quit gracefully so we don't need to panic.
-            Err(ExecutionError::CannotSendToChannel) => {}
-            // Other errors result in panic.
-            Err(e) => std::panic::panic_any(e),
+        .name(format!("source_sender:{handle}"))
+        .spawn(move || {
+            pin_to_core(core, &sender_handle);
+            if !gate.wait() {
+                return;
+            }
+            dispatcher::with_default(&sender_dispatch, || {
+                let _span = info_span!("source_sender", node = %sender_handle).entered();
+                match source_sender.run() {
+                    Ok(_) => {}
+                    // Channel disconnection means the source listener has quit.
+                    // Maybe it quit gracefully so we don't need to panic.
+                    Err(ExecutionError::CannotSendToChannel) => {}
+                    // Other errors result in panic.
+                    Err(e) => std::panic::panic_any(e),
+                }
+            })
         })?;
 
+    let listener_dispatch = dispatcher::get_default(Dispatch::clone);
     Ok(Builder::new()
-        .name(format!("{handle}-listener"))
+        .name(format!("source_listener:{handle}"))
         .spawn(move || {
-            if let Err(e) = source_listener.run() {
-                std::panic::panic_any(e);
+            pin_to_core(core, &handle);
+            if !listener_gate.wait() {
+                return;
             }
+            dispatcher::with_default(&listener_dispatch, || {
+                let _span = info_span!("source_listener", node = %handle).entered();
+                if let Err(e) = source_listener.run() {
+                    std::panic::panic_any(e);
+                }
+            })
         })?)
 }
 
-fn start_processor(processor: ProcessorNode) -> Result<JoinHandle<()>, ExecutionError> {
+fn start_processor(
+    processor: ProcessorNode,
+    core: Option<usize>,
+    gate: Arc<StartGate>,
+) -> Result<JoinHandle<()>, ExecutionError> {
+    // Spans are entered on the node thread, so carry over the caller's subscriber.
+    let dispatch = dispatcher::get_default(Dispatch::clone);
     Ok(Builder::new()
-        .name(processor.handle().to_string())
+        .name(format!("processor:{}", processor.handle()))
         .spawn(move || {
-            if let Err(e) = processor.run() {
-                std::panic::panic_any(e);
+            pin_to_core(core, processor.handle());
+            if !gate.wait() {
+                return;
             }
+            dispatcher::with_default(&dispatch, || {
+                let _span = info_span!("processor", node = %processor.handle()).entered();
+                if let Err(e) = processor.run() {
+                    std::panic::panic_any(e);
+                }
+            })
         })?)
 }
 
-fn start_sink(sink: SinkNode) -> Result<JoinHandle<()>, ExecutionError> {
-    Ok(Builder::new().name(sink.handle().to_string()).spawn(|| {
-        if let Err(e) = sink.run() {
-            std::panic::panic_any(e);
-        }
-    })?)
+fn start_sink(
+    sink: SinkNode,
+    core: Option<usize>,
+    gate: Arc<StartGate>,
+) -> Result<JoinHandle<()>, ExecutionError> {
+    let dispatch = dispatcher::get_default(Dispatch::clone);
+    Ok(Builder::new()
+        .name(format!("sink:{}", sink.handle()))
+        .spawn(move || {
+            pin_to_core(core, sink.handle());
+            if !gate.wait() {
+                return;
+            }
+            dispatcher::with_default(&dispatch, || {
+                let _span = info_span!("sink", node = %sink.handle()).entered();
+                if let Err(e) = sink.run() {
+                    std::panic::panic_any(e);
+                }
+            })
+        })?)
 }
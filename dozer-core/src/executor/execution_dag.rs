@@ -1,16 +1,17 @@
 use std::{
     borrow::BorrowMut,
     cell::RefCell,
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     fmt::Debug,
     rc::Rc,
-    sync::Arc,
+    sync::{atomic::AtomicU64, Arc},
 };
 
 use crate::{
     builder_dag::{BuilderDag, NodeKind, NodeType},
     epoch::EpochManager,
     errors::ExecutionError,
+    forwarder::NodeSender,
     hash_map_to_vec::insert_vec_element,
     node::PortHandle,
     record_store::{create_record_writer, RecordWriter},
@@ -21,6 +22,8 @@ use daggy::petgraph::{
     Direction,
 };
 use dozer_types::epoch::ExecutorOperation;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::SchemaRef;
 
 pub type SharedRecordWriter = Rc<RefCell<Option<Box<dyn RecordWriter>>>>;
 
@@ -36,6 +39,11 @@ pub struct EdgeType {
     pub input_port: PortHandle,
     /// The receiver from receiving data from upstream.
     pub receiver: Receiver<ExecutorOperation>,
+    /// Counts the operations dropped because the channel was full, if the downstream node drops
+    /// them rather than blocking. Shared by all the input edges of the node.
+    pub dropped: Option<Arc<AtomicU64>>,
+    /// The schema of the records flowing through this edge.
+    pub schema: SchemaRef,
 }
 
 #[derive(Debug)]
@@ -43,10 +51,18 @@ pub struct ExecutionDag {
     /// Nodes will be moved into execution threads.
     graph: daggy::Dag<Option<NodeType>, EdgeType>,
     epoch_manager: Arc<EpochManager>,
+    /// Counters of the operations dropped by the nodes in `drop_when_full`, by node.
+    dropped: HashMap<daggy::NodeIndex, Arc<AtomicU64>>,
 }
 
 impl ExecutionDag {
-    pub fn new(builder_dag: BuilderDag, channel_buffer_sz: usize) -> Result<Self, ExecutionError> {
+    /// Creates the channels between the nodes, of `channel_buffer_sz` messages each. The input
+    /// channels of the nodes in `drop_when_full` drop operations rather than blocking when full.
+    pub fn new(
+        builder_dag: BuilderDag,
+        channel_buffer_sz: usize,
+        drop_when_full: &HashSet<NodeHandle>,
+    ) -> Result<Self, ExecutionError> {
         // Count number of sources.
         let num_sources = builder_dag
             .graph()
@@ -65,6 +81,13 @@ impl ExecutionDag {
             builder_dag.graph().node_count()
         ];
 
+        let dropped = builder_dag
+            .graph()
+            .node_identifiers()
+            .filter(|node_index| drop_when_full.contains(&builder_dag.graph()[*node_index].handle))
+            .map(|node_index| (node_index, Arc::new(AtomicU64::new(0))))
+            .collect::<HashMap<_, _>>();
+
         // Create new edges.
         let mut edges = vec![];
         for builder_dag_edge in builder_dag.graph().raw_edges().iter() {
@@ -101,6 +124,8 @@ impl ExecutionDag {
                 record_writer,
                 input_port: edge.input_port,
                 receiver,
+                dropped: dropped.get(&builder_dag_edge.target()).cloned(),
+                schema: Arc::new(edge.schema.clone()),
             };
             edges.push(Some(edge));
         }
@@ -117,6 +142,7 @@ impl ExecutionDag {
         Ok(ExecutionDag {
             graph,
             epoch_manager: Arc::new(EpochManager::new(num_sources)),
+            dropped,
         })
     }
 
@@ -132,12 +158,17 @@ impl ExecutionDag {
         &self.epoch_manager
     }
 
+    /// Counter of the operations dropped on the way to the node, if it drops them when full.
+    pub fn dropped_counter(&self, node_index: daggy::NodeIndex) -> Option<Arc<AtomicU64>> {
+        self.dropped.get(&node_index).cloned()
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn collect_senders_and_record_writers(
         &mut self,
         node_index: daggy::NodeIndex,
     ) -> (
-        HashMap<PortHandle, Vec<Sender<ExecutorOperation>>>,
+        HashMap<PortHandle, Vec<NodeSender>>,
         HashMap<PortHandle, Box<dyn RecordWriter>>,
     ) {
         let edge_indexes = self
@@ -153,7 +184,11 @@ impl ExecutionDag {
                 .graph
                 .edge_weight_mut(edge_index)
                 .expect("We don't modify graph structure, only modify the edge weight");
-            insert_vec_element(&mut senders, edge.output_port, edge.sender.clone());
+            insert_vec_element(
+                &mut senders,
+                edge.output_port,
+                NodeSender::new(edge.sender.clone(), edge.dropped.clone()),
+            );
             if let Entry::Vacant(entry) = record_writers.entry(edge.output_port) {
                 // This interior mutability is to word around `Rc`. Other parts of this function is correctly marked `mut`.
                 if let Some(record_writer) = edge.record_writer.borrow_mut().take() {
@@ -187,4 +222,12 @@ impl ExecutionDag {
         }
         (input_ports, receivers)
     }
+
+    /// The schemas of the input edges of `node_index`, in the order of [`Self::collect_receivers`].
+    pub fn collect_input_schemas(&self, node_index: daggy::NodeIndex) -> Vec<SchemaRef> {
+        self.graph
+            .edges_directed(node_index, Direction::Incoming)
+            .map(|edge| edge.weight().schema.clone())
+            .collect()
+    }
 }
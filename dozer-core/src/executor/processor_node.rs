@@ -1,16 +1,22 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::{borrow::Cow, mem::swap};
 
 use crossbeam::channel::Receiver;
 use daggy::NodeIndex;
-use dozer_types::epoch::Epoch;
+use dozer_types::chrono::{DateTime, FixedOffset};
+use dozer_types::epoch::{Epoch, OpOrigin};
 use dozer_types::node::NodeHandle;
+use dozer_types::types::{Field, Operation, Record};
 use dozer_types::{epoch::ExecutorOperation, log::warn};
 
 use crate::{
     builder_dag::NodeKind,
+    channels::ProcessorChannelForwarder,
     errors::ExecutionError,
     forwarder::{ProcessorChannelManager, StateWriter},
-    node::{PortHandle, Processor},
+    metrics::NodeMetrics,
+    node::{InputSelectionPolicy, PortHandle, Processor},
 };
 
 use super::{execution_dag::ExecutionDag, name::Name, receiver_loop::ReceiverLoop};
@@ -28,38 +34,75 @@ pub struct ProcessorNode {
     processor: Box<dyn Processor>,
     /// This node's output channel manager, for forwarding data, writing metadata and writing port state.
     channel_manager: ProcessorChannelManager,
+    /// Port receiving the operations the processor fails on, if declared and connected.
+    dead_letter_port: Option<PortHandle>,
+    /// Metrics of this node.
+    metrics: Arc<NodeMetrics>,
+    /// How the input ports are read, the processor's own policy unless overridden.
+    selection_policy: InputSelectionPolicy,
 }
 
 impl ProcessorNode {
-    pub fn new(dag: &mut ExecutionDag, node_index: NodeIndex) -> Self {
+    /// Creates the processor at `node_index`, sending its output in batches of `batch_size`. If
+    /// `drop_disconnected`, it stops sending to downstream nodes that quit instead of failing.
+    pub fn new(
+        dag: &mut ExecutionDag,
+        node_index: NodeIndex,
+        drop_disconnected: bool,
+        batch_size: usize,
+    ) -> Self {
         let Some(node) = dag.node_weight_mut(node_index).take() else {
             panic!("Must pass in a node")
         };
         let node_handle = node.handle;
-        let NodeKind::Processor(processor) = node.kind else {
+        let NodeKind::Processor(processor, accumulating, dead_letter_port) = node.kind else {
             panic!("Must pass in a processor node");
         };
 
         let (port_handles, receivers) = dag.collect_receivers(node_index);
+        let metrics = Arc::new(NodeMetrics::new(
+            &port_handles,
+            dag.dropped_counter(node_index),
+        ));
 
         let (senders, record_writers) = dag.collect_senders_and_record_writers(node_index);
+        let dead_letter_port = dead_letter_port.filter(|port| senders.contains_key(port));
 
         let state_writer = StateWriter::new(record_writers);
-        let channel_manager =
-            ProcessorChannelManager::new(node_handle.clone(), senders, state_writer, true);
-
+        let channel_manager = ProcessorChannelManager::new(
+            node_handle.clone(),
+            senders,
+            state_writer,
+            true,
+            accumulating,
+            drop_disconnected,
+            batch_size,
+        );
+
+        let selection_policy = processor.input_selection_policy();
         Self {
             node_handle,
             port_handles,
             receivers,
             processor,
             channel_manager,
+            dead_letter_port,
+            metrics,
+            selection_policy,
         }
     }
 
     pub fn handle(&self) -> &NodeHandle {
         &self.node_handle
     }
+
+    pub fn metrics(&self) -> Arc<NodeMetrics> {
+        self.metrics.clone()
+    }
+
+    pub fn override_selection_policy(&mut self, policy: InputSelectionPolicy) {
+        self.selection_policy = policy;
+    }
 }
 
 impl Name for ProcessorNode {
@@ -79,16 +122,27 @@ impl ReceiverLoop for ProcessorNode {
         Cow::Owned(self.port_handles[index].to_string())
     }
 
+    fn selection_policy(&self) -> InputSelectionPolicy {
+        self.selection_policy
+    }
+
     fn on_op(
         &mut self,
         index: usize,
-        op: dozer_types::types::Operation,
+        op: Operation,
+        origin: Option<OpOrigin>,
     ) -> Result<(), ExecutionError> {
+        self.channel_manager.set_origin(origin);
+        let failed_op = self.dead_letter_port.map(|_| op.clone());
         let result =
             self.processor
                 .process(self.port_handles[index], op, &mut self.channel_manager);
         if let Err(e) = result {
             warn!("Processor error: {:?}", e);
+            if let (Some(port), Some(op)) = (self.dead_letter_port, failed_op) {
+                self.channel_manager
+                    .send(with_error(op, &e.to_string()), port)?;
+            }
         }
 
         // TODO: Enable "test_run_dag_proc_err_2" and "test_run_dag_proc_err_3" tests when errors threshold is implemented
@@ -96,15 +150,67 @@ impl ReceiverLoop for ProcessorNode {
     }
 
     fn on_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError> {
+        self.channel_manager.set_origin(None);
+        self.processor.flush(&mut self.channel_manager)?;
         self.processor.commit(epoch)?;
+        if let Some(size) = self.processor.state_size() {
+            self.metrics.set_state_size(size);
+        }
         self.channel_manager.store_and_send_commit(epoch)
     }
 
+    fn on_input_terminated(&mut self, index: usize) -> Result<(), ExecutionError> {
+        self.channel_manager.set_origin(None);
+        self.processor
+            .on_input_terminated(self.port_handles[index], &mut self.channel_manager)
+    }
+
     fn on_terminate(&mut self) -> Result<(), ExecutionError> {
         self.channel_manager.send_terminate()
     }
 
     fn on_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        self.channel_manager.set_origin(None);
+        self.processor
+            .on_source_snapshotting_done(&mut self.channel_manager)?;
         self.channel_manager.send_snapshotting_done()
     }
+
+    fn on_watermark(&mut self, timestamp: DateTime<FixedOffset>) -> Result<(), ExecutionError> {
+        self.channel_manager.set_origin(None);
+        self.processor
+            .on_watermark(timestamp, &mut self.channel_manager)?;
+        self.channel_manager.send_watermark(timestamp)
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        self.processor.tick_interval()
+    }
+
+    fn on_tick(&mut self, now: Instant) -> Result<(), ExecutionError> {
+        self.channel_manager.set_origin(None);
+        self.processor.on_tick(now, &mut self.channel_manager)
+    }
+
+    fn metrics(&self) -> Option<Arc<NodeMetrics>> {
+        Some(self.metrics.clone())
+    }
+}
+
+/// Appends `error` to every record of `op`, as laid out by [`dead_letter_schema`].
+///
+/// [`dead_letter_schema`]: crate::node::dead_letter_schema
+fn with_error(op: Operation, error: &str) -> Operation {
+    let attach = |mut record: Record| {
+        record.values.push(Field::String(error.to_string()));
+        record
+    };
+    match op {
+        Operation::Delete { old } => Operation::Delete { old: attach(old) },
+        Operation::Insert { new } => Operation::Insert { new: attach(new) },
+        Operation::Update { old, new } => Operation::Update {
+            old: attach(old),
+            new: attach(new),
+        },
+    }
 }
@@ -1,32 +1,72 @@
 use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crossbeam::channel::{Receiver, Select};
-use dozer_types::epoch::Epoch;
+use dozer_types::chrono::{DateTime, FixedOffset};
+use dozer_types::epoch::{Epoch, OpOrigin};
+use dozer_types::tracing::debug_span;
 use dozer_types::types::Operation;
 use dozer_types::{epoch::ExecutorOperation, log::debug};
 
 use crate::errors::ExecutionError;
+use crate::metrics::NodeMetrics;
+use crate::node::InputSelectionPolicy;
 
 use super::{name::Name, InputPortState};
 
 /// Common code for processor and sink nodes.
 ///
-/// They both select from their input channels, and respond to "op", "commit", and terminate.
+/// They both select from their input channels, and respond to "op", "commit", "watermark" and terminate.
 pub trait ReceiverLoop: Name {
     /// Returns input channels to this node. Will be called exactly once in [`receiver_loop`].
     fn receivers(&mut self) -> Vec<Receiver<ExecutorOperation>>;
     /// Returns the name of the receiver at `index`. Used for logging.
     fn receiver_name(&self, index: usize) -> Cow<str>;
-    /// Responds to `op` from the receiver at `index`.
-    fn on_op(&mut self, index: usize, op: Operation) -> Result<(), ExecutionError>;
+    /// Returns how the next receiver to read from is picked.
+    fn selection_policy(&self) -> InputSelectionPolicy {
+        InputSelectionPolicy::Ready
+    }
+    /// Responds to `op` from the receiver at `index`, ingested at `origin` if known.
+    fn on_op(
+        &mut self,
+        index: usize,
+        op: Operation,
+        origin: Option<OpOrigin>,
+    ) -> Result<(), ExecutionError>;
     /// Responds to `commit` of `epoch`.
     fn on_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError>;
+    /// Responds to the receiver at `index` terminating, while others may still be open.
+    fn on_input_terminated(&mut self, _index: usize) -> Result<(), ExecutionError> {
+        Ok(())
+    }
     /// Responds to `terminate`.
     fn on_terminate(&mut self) -> Result<(), ExecutionError>;
     /// Responds to `SnapshottingDone`.
     fn on_snapshotting_done(&mut self) -> Result<(), ExecutionError>;
+    /// Responds to the watermark of all open input ports advancing to `timestamp`.
+    fn on_watermark(&mut self, timestamp: DateTime<FixedOffset>) -> Result<(), ExecutionError>;
+    /// Returns how often [`on_tick`] is called, never if `None`.
+    ///
+    /// [`on_tick`]: ReceiverLoop::on_tick
+    fn tick_interval(&self) -> Option<Duration> {
+        None
+    }
+    /// Responds to a tick of the timer firing at `now`.
+    fn on_tick(&mut self, _now: Instant) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+    /// Returns the metrics the loop updates as it receives, if any.
+    fn metrics(&self) -> Option<Arc<NodeMetrics>> {
+        None
+    }
 
     /// The loop implementation, calls [`on_op`], [`on_commit`] and [`on_terminate`] at appropriate times.
+    ///
+    /// Commits act as barriers: once an input delivers the commit of an epoch, it isn't read again
+    /// until every other input has delivered the commit of the same epoch. [`on_commit`] then sees
+    /// one epoch holding the positions of all sources upstream of this node.
     fn receiver_loop(&mut self) -> Result<(), ExecutionError> {
         let receivers = self.receivers();
         debug_assert!(
@@ -37,39 +77,120 @@ pub trait ReceiverLoop: Name {
 
         let mut commits_received: usize = 0;
         let mut common_epoch = Epoch::new(0, Default::default());
+        let mut ops_since_commit: usize = 0;
+
+        let mut watermarks = vec![None; receivers.len()];
+        let mut common_watermark = None;
+
+        let policy = self.selection_policy();
+        let mut selectable = vec![true; receivers.len()];
+        let mut last_served = receivers.len().saturating_sub(1);
+        // The ports left to read from in the current round of `InputSelectionPolicy::Drain`.
+        let mut round = VecDeque::new();
+
+        let tick_interval = self.tick_interval();
+        let mut next_tick = tick_interval.map(|interval| Instant::now() + interval);
+
+        let metrics = self.metrics();
 
         let mut sel = init_select(&receivers);
         loop {
-            let index = sel.ready();
+            if let (Some(interval), Some(deadline)) = (tick_interval, next_tick) {
+                let now = Instant::now();
+                if now >= deadline {
+                    self.on_tick(now)?;
+                    // Skip the ticks missed while busy rather than firing them back to back.
+                    let next = deadline + interval;
+                    next_tick = Some(if next > now { next } else { now + interval });
+                }
+            }
+
+            let index = if let Some(index) = round.pop_front() {
+                // Skip the ports that delivered a commit or terminated since the round started.
+                if !selectable[index] {
+                    continue;
+                }
+                index
+            } else {
+                let ready = match next_tick {
+                    Some(deadline) => match sel.ready_deadline(deadline) {
+                        Ok(ready) => ready,
+                        Err(_) => continue,
+                    },
+                    None => sel.ready(),
+                };
+                match policy {
+                    InputSelectionPolicy::Ready => ready,
+                    InputSelectionPolicy::RoundRobin => {
+                        next_round_robin(&receivers, &selectable, last_served).unwrap_or(ready)
+                    }
+                    InputSelectionPolicy::Drain => {
+                        round = drain_round(&receivers, &selectable);
+                        round.pop_front().unwrap_or(ready)
+                    }
+                    #[cfg(any(test, feature = "deterministic-select"))]
+                    InputSelectionPolicy::Sequential => {
+                        next_in_turn(&selectable, &port_states, last_served).unwrap_or(ready)
+                    }
+                }
+            };
+            last_served = index;
             let op = receivers[index]
                 .recv()
                 .map_err(|_| ExecutionError::CannotReceiveFromChannel)?;
+            if let Some(metrics) = &metrics {
+                metrics.set_channel_occupancy(index, receivers[index].len());
+            }
 
             match op {
-                ExecutorOperation::Op { op } => {
-                    self.on_op(index, op)?;
+                ExecutorOperation::Op { op, origin } => {
+                    ops_since_commit += 1;
+                    process_op(self, metrics.as_deref(), index, op, origin)?;
+                }
+                ExecutorOperation::Batch { ops } => {
+                    ops_since_commit += ops.len();
+                    for (op, origin) in ops {
+                        process_op(self, metrics.as_deref(), index, op, origin)?;
+                    }
                 }
                 ExecutorOperation::Commit { epoch } => {
-                    assert_eq!(epoch.id, common_epoch.id);
+                    if epoch.id != common_epoch.id {
+                        return Err(ExecutionError::MisalignedEpoch {
+                            expected: common_epoch.id,
+                            received: epoch.id,
+                        });
+                    }
                     commits_received += 1;
                     sel.remove(index);
+                    selectable[index] = false;
                     common_epoch.details.extend(epoch.details);
 
                     if commits_received == receivers.len() {
+                        let _span =
+                            debug_span!("commit", epoch = common_epoch.id, ops = ops_since_commit)
+                                .entered();
                         self.on_commit(&common_epoch)?;
+                        if let Some(metrics) = &metrics {
+                            metrics.record_commit();
+                        }
+                        ops_since_commit = 0;
                         common_epoch = Epoch::new(common_epoch.id + 1, Default::default());
                         commits_received = 0;
                         sel = init_select(&receivers);
+                        selectable = vec![true; receivers.len()];
+                        round.clear();
                     }
                 }
                 ExecutorOperation::Terminate => {
                     port_states[index] = InputPortState::Terminated;
                     sel.remove(index);
+                    selectable[index] = false;
                     debug!(
                         "[{}] Received Terminate request on port {}",
                         self.name(),
                         self.receiver_name(index)
                     );
+                    self.on_input_terminated(index)?;
                     if port_states.iter().all(|v| v == &InputPortState::Terminated) {
                         self.on_terminate()?;
                         debug!("[{}] Quit", self.name());
@@ -77,11 +198,102 @@ pub trait ReceiverLoop: Name {
                     }
                 }
                 ExecutorOperation::SnapshottingDone {} => self.on_snapshotting_done()?,
+                ExecutorOperation::Watermark { timestamp } => {
+                    // Watermarks never move backwards on a port.
+                    if watermarks[index].map_or(true, |current| timestamp > current) {
+                        watermarks[index] = Some(timestamp);
+                    }
+
+                    let watermark = min_watermark(&watermarks, &port_states);
+                    if watermark > common_watermark {
+                        common_watermark = watermark;
+                        if let Some(timestamp) = watermark {
+                            self.on_watermark(timestamp)?;
+                        }
+                    }
+                }
             }
         }
     }
 }
 
+/// Calls [`ReceiverLoop::on_op`], timing it if the node has metrics.
+fn process_op<T: ReceiverLoop + ?Sized>(
+    node: &mut T,
+    metrics: Option<&NodeMetrics>,
+    index: usize,
+    op: Operation,
+    origin: Option<OpOrigin>,
+) -> Result<(), ExecutionError> {
+    let Some(metrics) = metrics else {
+        return node.on_op(index, op, origin);
+    };
+    metrics.record_op();
+    let start = Instant::now();
+    node.on_op(index, op, origin)?;
+    metrics.record_latency(start.elapsed());
+    Ok(())
+}
+
+/// Returns the first receiver after `last_served` that is still selected and has pending data.
+fn next_round_robin(
+    receivers: &[Receiver<ExecutorOperation>],
+    selectable: &[bool],
+    last_served: usize,
+) -> Option<usize> {
+    (1..=receivers.len())
+        .map(|offset| (last_served + offset) % receivers.len())
+        .find(|&index| selectable[index] && !receivers[index].is_empty())
+}
+
+/// Returns the first port after `last_served` that is still selected and open, whether it has
+/// pending data or not.
+#[cfg(any(test, feature = "deterministic-select"))]
+fn next_in_turn(
+    selectable: &[bool],
+    port_states: &[InputPortState],
+    last_served: usize,
+) -> Option<usize> {
+    (1..=selectable.len())
+        .map(|offset| (last_served + offset) % selectable.len())
+        .find(|&index| selectable[index] && port_states[index] == InputPortState::Open)
+}
+
+/// Returns the ports to read from to process everything pending on the selected ports, one
+/// operation of each port in turn.
+fn drain_round(receivers: &[Receiver<ExecutorOperation>], selectable: &[bool]) -> VecDeque<usize> {
+    let pending = receivers
+        .iter()
+        .zip(selectable)
+        .map(|(receiver, selectable)| if *selectable { receiver.len() } else { 0 })
+        .collect::<Vec<_>>();
+    let passes = pending.iter().copied().max().unwrap_or(0);
+    (0..passes)
+        .flat_map(|pass| {
+            pending
+                .iter()
+                .enumerate()
+                .filter(move |(_, pending)| **pending > pass)
+                .map(|(index, _)| index)
+        })
+        .collect()
+}
+
+/// The watermark of a node is the minimum across its open input ports, and is only known once
+/// every open port has received one.
+fn min_watermark(
+    watermarks: &[Option<DateTime<FixedOffset>>],
+    port_states: &[InputPortState],
+) -> Option<DateTime<FixedOffset>> {
+    watermarks
+        .iter()
+        .zip(port_states)
+        .filter(|(_, state)| **state == InputPortState::Open)
+        .map(|(watermark, _)| *watermark)
+        .min()
+        .flatten()
+}
+
 fn init_select(receivers: &Vec<Receiver<ExecutorOperation>>) -> Select {
     let mut sel = Select::new();
     for r in receivers {
@@ -94,8 +306,9 @@ fn init_select(receivers: &Vec<Receiver<ExecutorOperation>>) -> Select {
 mod tests {
     use std::mem::swap;
 
-    use crossbeam::channel::{unbounded, Sender};
+    use crossbeam::channel::{bounded, unbounded, Sender};
     use dozer_types::{
+        chrono::TimeZone,
         node::{NodeHandle, OpIdentifier, SourceStates},
         types::{Field, Record},
     };
@@ -107,7 +320,13 @@ mod tests {
         ops: Vec<(usize, Operation)>,
         commits: Vec<Epoch>,
         snapshotting_done: Vec<()>,
+        // Each watermark, along with the number of ops received before it.
+        watermarks: Vec<(usize, DateTime<FixedOffset>)>,
+        terminated_inputs: Vec<usize>,
         num_terminations: usize,
+        policy: InputSelectionPolicy,
+        // Port whose ops take a while to process.
+        slow_port: Option<usize>,
     }
 
     impl Name for TestReceiverLoop {
@@ -127,7 +346,19 @@ mod tests {
             Cow::Owned(format!("receiver_{index}"))
         }
 
-        fn on_op(&mut self, index: usize, op: Operation) -> Result<(), ExecutionError> {
+        fn selection_policy(&self) -> InputSelectionPolicy {
+            self.policy
+        }
+
+        fn on_op(
+            &mut self,
+            index: usize,
+            op: Operation,
+            _origin: Option<OpOrigin>,
+        ) -> Result<(), ExecutionError> {
+            if self.slow_port == Some(index) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
             self.ops.push((index, op));
             Ok(())
         }
@@ -137,6 +368,11 @@ mod tests {
             Ok(())
         }
 
+        fn on_input_terminated(&mut self, index: usize) -> Result<(), ExecutionError> {
+            self.terminated_inputs.push(index);
+            Ok(())
+        }
+
         fn on_terminate(&mut self) -> Result<(), ExecutionError> {
             self.num_terminations += 1;
             Ok(())
@@ -146,6 +382,11 @@ mod tests {
             self.snapshotting_done.push(());
             Ok(())
         }
+
+        fn on_watermark(&mut self, timestamp: DateTime<FixedOffset>) -> Result<(), ExecutionError> {
+            self.watermarks.push((self.ops.len(), timestamp));
+            Ok(())
+        }
     }
 
     impl TestReceiverLoop {
@@ -157,7 +398,11 @@ mod tests {
                     ops: vec![],
                     commits: vec![],
                     snapshotting_done: vec![],
+                    watermarks: vec![],
+                    terminated_inputs: vec![],
                     num_terminations: 0,
+                    policy: InputSelectionPolicy::Ready,
+                    slow_port: None,
                 },
                 senders,
             )
@@ -173,6 +418,16 @@ mod tests {
         assert_eq!(test_loop.num_terminations, 1);
     }
 
+    #[test]
+    fn receiver_loop_reports_each_terminated_input() {
+        let (mut test_loop, senders) = TestReceiverLoop::new(2);
+        senders[1].send(ExecutorOperation::Terminate).unwrap();
+        senders[0].send(ExecutorOperation::Terminate).unwrap();
+        test_loop.receiver_loop().unwrap();
+        assert_eq!(test_loop.terminated_inputs, vec![1, 0]);
+        assert_eq!(test_loop.num_terminations, 1);
+    }
+
     #[test]
     fn receiver_loop_forwards_snapshotting_done() {
         let (mut test_loop, senders) = TestReceiverLoop::new(2);
@@ -194,6 +449,7 @@ mod tests {
                 op: Operation::Insert {
                     new: record.clone(),
                 },
+                origin: None,
             })
             .unwrap();
         senders[0].send(ExecutorOperation::Terminate).unwrap();
@@ -202,6 +458,190 @@ mod tests {
         assert_eq!(test_loop.ops, vec![(0, Operation::Insert { new: record })]);
     }
 
+    #[test]
+    fn receiver_loop_unpacks_batch() {
+        let (mut test_loop, senders) = TestReceiverLoop::new(2);
+        let insert = |value| Operation::Insert {
+            new: Record::new(None, vec![Field::Int(value)]),
+        };
+        senders[1]
+            .send(ExecutorOperation::Batch {
+                ops: vec![(insert(1), None), (insert(2), None)],
+            })
+            .unwrap();
+        senders[0].send(ExecutorOperation::Terminate).unwrap();
+        senders[1].send(ExecutorOperation::Terminate).unwrap();
+        test_loop.receiver_loop().unwrap();
+        assert_eq!(test_loop.ops, vec![(1, insert(1)), (1, insert(2))]);
+    }
+
+    #[test]
+    fn receiver_loop_round_robin_does_not_starve_slow_port() {
+        let (mut test_loop, senders) = TestReceiverLoop::new(2);
+        test_loop.policy = InputSelectionPolicy::RoundRobin;
+        let insert = |value| ExecutorOperation::Op {
+            op: Operation::Insert {
+                new: Record::new(None, vec![Field::Int(value)]),
+            },
+            origin: None,
+        };
+        for value in 0..1000 {
+            senders[0].send(insert(value)).unwrap();
+        }
+        for value in 0..10 {
+            senders[1].send(insert(value)).unwrap();
+        }
+        senders[0].send(ExecutorOperation::Terminate).unwrap();
+        senders[1].send(ExecutorOperation::Terminate).unwrap();
+        test_loop.receiver_loop().unwrap();
+
+        assert_eq!(test_loop.ops.len(), 1010);
+        // Ports are served in turn, so all of the slow port's ops arrive among the first 20.
+        let slow_port_ops = test_loop.ops[..20]
+            .iter()
+            .filter(|(index, _)| *index == 1)
+            .count();
+        assert_eq!(slow_port_ops, 10);
+    }
+
+    #[test]
+    fn receiver_loop_drain_keeps_serving_port_behind_slow_port() {
+        // Small channels, so ops keep arriving on the slow port while the loop runs.
+        let (senders, receivers): (Vec<Sender<ExecutorOperation>>, _) =
+            (0..2).map(|_| bounded(16)).unzip();
+        let (mut test_loop, _) = TestReceiverLoop::new(0);
+        test_loop.receivers = receivers;
+        test_loop.policy = InputSelectionPolicy::Drain;
+        test_loop.slow_port = Some(0);
+        let insert = |value| ExecutorOperation::Op {
+            op: Operation::Insert {
+                new: Record::new(None, vec![Field::Int(value)]),
+            },
+            origin: None,
+        };
+
+        std::thread::scope(|scope| {
+            let handle = scope.spawn(|| test_loop.receiver_loop());
+            let [slow, fast] = [&senders[0], &senders[1]];
+            let burst = scope.spawn(move || {
+                for value in 0..100 {
+                    slow.send(insert(value)).unwrap();
+                }
+                slow.send(ExecutorOperation::Terminate).unwrap();
+            });
+            for value in 0..10 {
+                fast.send(insert(value)).unwrap();
+            }
+            fast.send(ExecutorOperation::Terminate).unwrap();
+            burst.join().unwrap();
+            handle.join().unwrap().unwrap();
+        });
+
+        assert_eq!(test_loop.ops.len(), 110);
+        // The fast port advances while the slow one is busy, rather than after its burst.
+        let last_fast_op = test_loop
+            .ops
+            .iter()
+            .rposition(|(index, _)| *index == 1)
+            .unwrap();
+        assert!(
+            last_fast_op < 100,
+            "fast port finished at op {last_fast_op}"
+        );
+    }
+
+    #[test]
+    fn drain_round_interleaves_pending_ops() {
+        let (senders, receivers): (Vec<Sender<ExecutorOperation>>, Vec<_>) =
+            (0..3).map(|_| unbounded()).unzip();
+        for (index, count) in [(0, 3), (1, 1), (2, 2)] {
+            for _ in 0..count {
+                senders[index]
+                    .send(ExecutorOperation::SnapshottingDone {})
+                    .unwrap();
+            }
+        }
+        assert_eq!(
+            drain_round(&receivers, &[true, true, true]),
+            [0, 1, 2, 0, 2, 0]
+        );
+        assert_eq!(
+            drain_round(&receivers, &[true, false, true]),
+            [0, 2, 0, 2, 0]
+        );
+    }
+
+    fn timestamp(secs: i64) -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(0)
+            .unwrap()
+            .timestamp_opt(secs, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn receiver_loop_forwards_watermarks_in_order() {
+        let (mut test_loop, senders) = TestReceiverLoop::new(1);
+        let insert = |value| ExecutorOperation::Op {
+            op: Operation::Insert {
+                new: Record::new(None, vec![Field::Int(value)]),
+            },
+            origin: None,
+        };
+        senders[0].send(insert(1)).unwrap();
+        senders[0]
+            .send(ExecutorOperation::Watermark {
+                timestamp: timestamp(1),
+            })
+            .unwrap();
+        senders[0].send(insert(2)).unwrap();
+        senders[0].send(insert(3)).unwrap();
+        senders[0]
+            .send(ExecutorOperation::Watermark {
+                timestamp: timestamp(2),
+            })
+            .unwrap();
+        // A watermark moving backwards is ignored.
+        senders[0]
+            .send(ExecutorOperation::Watermark {
+                timestamp: timestamp(1),
+            })
+            .unwrap();
+        senders[0].send(ExecutorOperation::Terminate).unwrap();
+        test_loop.receiver_loop().unwrap();
+        assert_eq!(test_loop.ops.len(), 3);
+        assert_eq!(
+            test_loop.watermarks,
+            vec![(1, timestamp(1)), (3, timestamp(2))]
+        );
+    }
+
+    #[test]
+    fn receiver_loop_forwards_minimum_watermark_of_all_ports() {
+        // Rendezvous channels, so the loop sees the messages in the order they are sent.
+        let (senders, receivers): (Vec<Sender<ExecutorOperation>>, _) =
+            (0..2).map(|_| bounded(0)).unzip();
+        let (mut test_loop, _) = TestReceiverLoop::new(0);
+        test_loop.receivers = receivers;
+
+        std::thread::scope(|scope| {
+            let handle = scope.spawn(|| test_loop.receiver_loop());
+            for (index, secs) in [(0, 5), (1, 3), (1, 7)] {
+                senders[index]
+                    .send(ExecutorOperation::Watermark {
+                        timestamp: timestamp(secs),
+                    })
+                    .unwrap();
+            }
+            senders[0].send(ExecutorOperation::Terminate).unwrap();
+            senders[1].send(ExecutorOperation::Terminate).unwrap();
+            handle.join().unwrap().unwrap();
+        });
+        assert_eq!(
+            test_loop.watermarks,
+            vec![(0, timestamp(3)), (0, timestamp(5))]
+        );
+    }
+
     #[test]
     fn receiver_loop_merges_commit_epoch_and_increases_epoch_id() {
         let (mut test_loop, senders) = TestReceiverLoop::new(2);
@@ -253,8 +693,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    fn receiver_loop_panics_on_inconsistent_commit_epoch() {
+    fn receiver_loop_fails_on_misaligned_commit_epoch() {
         let (mut test_loop, senders) = TestReceiverLoop::new(2);
         let mut details = SourceStates::new();
         details.insert(
@@ -276,6 +715,13 @@ mod tests {
             .unwrap();
         senders[0].send(ExecutorOperation::Terminate).unwrap();
         senders[1].send(ExecutorOperation::Terminate).unwrap();
-        test_loop.receiver_loop().unwrap();
+        assert!(matches!(
+            test_loop.receiver_loop(),
+            Err(ExecutionError::MisalignedEpoch {
+                expected: 0,
+                received: 1
+            })
+        ));
+        assert!(test_loop.commits.is_empty());
     }
 }
@@ -1,18 +1,22 @@
-use std::{borrow::Cow, collections::HashMap, mem::swap};
+use std::{borrow::Cow, collections::HashMap, mem::swap, sync::Arc};
 
 use crossbeam::channel::Receiver;
 use daggy::NodeIndex;
 use dozer_types::{
-    epoch::{Epoch, ExecutorOperation},
+    chrono::{DateTime, FixedOffset},
+    epoch::{Epoch, ExecutorOperation, OpOrigin},
     log::debug,
     node::NodeHandle,
+    types::SchemaRef,
 };
 
 use crate::{
     builder_dag::NodeKind,
     errors::ExecutionError,
     forwarder::StateWriter,
-    node::{PortHandle, Sink},
+    metrics::NodeMetrics,
+    node::{InputSelectionPolicy, PortHandle, Sink},
+    stats::StatsCollector,
 };
 
 use super::execution_dag::ExecutionDag;
@@ -27,10 +31,18 @@ pub struct SinkNode {
     port_handles: Vec<PortHandle>,
     /// Input data channels.
     receivers: Vec<Receiver<ExecutorOperation>>,
+    /// Schemas of the records received on each input port.
+    schemas: Vec<SchemaRef>,
     /// The sink.
     sink: Box<dyn Sink>,
     /// This node's state writer, for writing metadata and port state.
     state_writer: StateWriter,
+    /// Statistics of the records received since the last commit, if the sink asked for them.
+    stats: Option<StatsCollector>,
+    /// Metrics of this node.
+    metrics: Arc<NodeMetrics>,
+    /// How the input ports are read, the sink's own policy unless overridden.
+    selection_policy: InputSelectionPolicy,
 }
 
 impl SinkNode {
@@ -44,21 +56,40 @@ impl SinkNode {
         };
 
         let (port_handles, receivers) = dag.collect_receivers(node_index);
+        let schemas = dag.collect_input_schemas(node_index);
+        let metrics = Arc::new(NodeMetrics::new(
+            &port_handles,
+            dag.dropped_counter(node_index),
+        ));
 
         let state_writer = StateWriter::new(HashMap::new());
+        let stats = sink.collects_stats().then(StatsCollector::default);
+        let selection_policy = sink.input_selection_policy();
 
         Self {
             node_handle,
             port_handles,
             receivers,
+            schemas,
             sink,
             state_writer,
+            stats,
+            metrics,
+            selection_policy,
         }
     }
 
     pub fn handle(&self) -> &NodeHandle {
         &self.node_handle
     }
+
+    pub fn metrics(&self) -> Arc<NodeMetrics> {
+        self.metrics.clone()
+    }
+
+    pub fn override_selection_policy(&mut self, policy: InputSelectionPolicy) {
+        self.selection_policy = policy;
+    }
 }
 
 impl Name for SinkNode {
@@ -78,17 +109,33 @@ impl ReceiverLoop for SinkNode {
         Cow::Owned(self.port_handles[index].to_string())
     }
 
+    fn selection_policy(&self) -> InputSelectionPolicy {
+        self.selection_policy
+    }
+
     fn on_op(
         &mut self,
         index: usize,
         op: dozer_types::types::Operation,
+        origin: Option<OpOrigin>,
     ) -> Result<(), ExecutionError> {
-        self.sink.process(self.port_handles[index], op)
+        if let Some(stats) = &mut self.stats {
+            stats.observe(&op);
+        }
+        self.sink.process_with_schema(
+            self.port_handles[index],
+            op,
+            origin.as_ref(),
+            &self.schemas[index],
+        )
     }
 
     fn on_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError> {
         debug!("[{}] Checkpointing - {}", self.node_handle, epoch);
-        self.sink.commit()?;
+        if let Some(stats) = &mut self.stats {
+            self.sink.on_stats(stats.take())?;
+        }
+        self.sink.commit(epoch)?;
         self.state_writer.store_commit_info(epoch)
     }
 
@@ -99,4 +146,12 @@ impl ReceiverLoop for SinkNode {
     fn on_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
         self.sink.on_source_snapshotting_done()
     }
+
+    fn on_watermark(&mut self, timestamp: DateTime<FixedOffset>) -> Result<(), ExecutionError> {
+        self.sink.on_source_watermark(timestamp)
+    }
+
+    fn metrics(&self) -> Option<Arc<NodeMetrics>> {
+        Some(self.metrics.clone())
+    }
 }
@@ -7,8 +7,9 @@ use std::{
 };
 
 use crossbeam::channel::{bounded, Receiver, RecvTimeoutError, Sender};
-use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind};
 use dozer_types::{
+    epoch::OpTags,
     log::debug,
     node::{NodeHandle, OpIdentifier},
 };
@@ -18,6 +19,7 @@ use crate::{
     channels::SourceChannelForwarder,
     errors::ExecutionError,
     forwarder::{SourceChannelManager, StateWriter},
+    metrics::NodeMetrics,
     node::{PortHandle, Source},
 };
 
@@ -25,7 +27,16 @@ use super::{execution_dag::ExecutionDag, node::Node, ExecutorOptions};
 
 impl SourceChannelForwarder for InternalChannelSourceForwarder {
     fn send(&mut self, message: IngestionMessage, port: PortHandle) -> Result<(), ExecutionError> {
-        Ok(self.sender.send((port, message))?)
+        Ok(self.sender.send((port, message, None))?)
+    }
+
+    fn send_with_tags(
+        &mut self,
+        message: IngestionMessage,
+        tags: OpTags,
+        port: PortHandle,
+    ) -> Result<(), ExecutionError> {
+        Ok(self.sender.send((port, message, Some(tags)))?)
     }
 }
 
@@ -40,6 +51,8 @@ pub struct SourceSenderNode {
     last_checkpoint: Option<OpIdentifier>,
     /// The forwarder that will be passed to the source for outputting data.
     forwarder: InternalChannelSourceForwarder,
+    /// Receives the last committed position once the listener has terminated.
+    terminated: Receiver<Option<OpIdentifier>>,
 }
 
 impl SourceSenderNode {
@@ -49,14 +62,30 @@ impl SourceSenderNode {
 }
 
 impl Node for SourceSenderNode {
-    fn run(mut self) -> Result<(), ExecutionError> {
-        let result = self.source.start(
-            &mut self.forwarder,
-            self.last_checkpoint
-                .map(|op_id| (op_id.txid, op_id.seq_in_tx)),
+    fn run(self) -> Result<(), ExecutionError> {
+        let Self {
+            node_handle,
+            source,
+            last_checkpoint,
+            mut forwarder,
+            terminated,
+        } = self;
+        let result = source.start(
+            &mut forwarder,
+            last_checkpoint.map(|op_id| (op_id.txid, op_id.seq_in_tx)),
         );
-        debug!("[{}-sender] Quit", self.node_handle);
-        result
+        // The listener only terminates once the source stopped sending.
+        drop(forwarder);
+        debug!("[{}-sender] Quit", node_handle);
+
+        // The listener quits without acknowledging if it failed.
+        let terminated = match terminated.recv() {
+            Ok(last_committed) => {
+                source.on_terminate(last_committed.map(|op_id| (op_id.txid, op_id.seq_in_tx)))
+            }
+            Err(_) => Ok(()),
+        };
+        terminated.and(result)
     }
 }
 
@@ -66,18 +95,22 @@ pub struct SourceListenerNode {
     /// Node handle in description DAG.
     node_handle: NodeHandle,
     /// Output from corresponding source sender.
-    receiver: Receiver<(PortHandle, IngestionMessage)>,
+    receiver: Receiver<(PortHandle, IngestionMessage, Option<OpTags>)>,
     /// Receiving timeout.
     timeout: Duration,
     /// If the execution DAG should be running. Used for determining if a `terminate` message should be sent.
     running: Arc<AtomicBool>,
     /// This node's output channel manager, for communicating to other sources to coordinate terminate and commit, forwarding data, writing metadata and writing port state.
     channel_manager: SourceChannelManager,
+    /// Acknowledges termination to the sender, with the last committed position.
+    terminated: Sender<Option<OpIdentifier>>,
+    /// Metrics of this node.
+    metrics: Arc<NodeMetrics>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 enum DataKind {
-    Data((PortHandle, IngestionMessage)),
+    Data((PortHandle, IngestionMessage, Option<OpTags>)),
     NoDataBecauseOfTimeout,
     NoDataBecauseOfChannelDisconnection,
 }
@@ -93,21 +126,38 @@ impl SourceListenerNode {
             || !self.running.load(Ordering::SeqCst);
         // If this commit was not requested with termination at the start, we shouldn't terminate either.
         let terminating = match data {
-            DataKind::Data((port, message)) => self
-                .channel_manager
-                .send_and_trigger_commit_if_needed(message, port, terminating)?,
+            DataKind::Data((port, message, tags)) => {
+                if matches!(message.kind, IngestionMessageKind::OperationEvent(_)) {
+                    self.metrics.record_op();
+                }
+                self.channel_manager.send_and_trigger_commit_if_needed(
+                    message,
+                    tags,
+                    port,
+                    terminating,
+                )?
+            }
             DataKind::NoDataBecauseOfTimeout | DataKind::NoDataBecauseOfChannelDisconnection => {
                 self.channel_manager.trigger_commit_if_needed(terminating)?
             }
         };
+        self.metrics.set_commits(self.channel_manager.commits());
         if terminating {
             self.channel_manager.terminate()?;
+            // The sender is gone if the source failed.
+            let _ = self.terminated.send(self.channel_manager.last_committed());
             debug!("[{}-listener] Quitting", &self.node_handle);
         }
         Ok(terminating)
     }
 }
 
+impl SourceListenerNode {
+    pub fn metrics(&self) -> Arc<NodeMetrics> {
+        self.metrics.clone()
+    }
+}
+
 impl Node for SourceListenerNode {
     fn run(mut self) -> Result<(), ExecutionError> {
         loop {
@@ -129,11 +179,11 @@ impl Node for SourceListenerNode {
 
 #[derive(Debug)]
 struct InternalChannelSourceForwarder {
-    sender: Sender<(PortHandle, IngestionMessage)>,
+    sender: Sender<(PortHandle, IngestionMessage, Option<OpTags>)>,
 }
 
 impl InternalChannelSourceForwarder {
-    pub fn new(sender: Sender<(PortHandle, IngestionMessage)>) -> Self {
+    pub fn new(sender: Sender<(PortHandle, IngestionMessage, Option<OpTags>)>) -> Self {
         Self { sender }
     }
 }
@@ -157,6 +207,8 @@ pub fn create_source_nodes(
     let (source_sender, source_receiver) = bounded(options.channel_buffer_sz);
     // let (source_sender, source_receiver) = bounded(1);
 
+    let (terminated_sender, terminated_receiver) = bounded(1);
+
     // Create source listener.
     let forwarder = InternalChannelSourceForwarder::new(source_sender);
     let source_sender_node = SourceSenderNode {
@@ -164,6 +216,7 @@ pub fn create_source_nodes(
         source,
         last_checkpoint,
         forwarder,
+        terminated: terminated_receiver,
     };
 
     // Create source sender node.
@@ -176,14 +229,23 @@ pub fn create_source_nodes(
         true,
         options.commit_sz,
         options.commit_time_threshold,
+        options.commit_interval,
         dag.epoch_manager().clone(),
+        options.continue_on_failure,
+        options.batch_size,
     );
     let source_listener_node = SourceListenerNode {
         node_handle,
         receiver: source_receiver,
-        timeout: options.commit_time_threshold,
+        timeout: options
+            .commit_interval
+            .map_or(options.commit_time_threshold, |interval| {
+                interval.min(options.commit_time_threshold)
+            }),
         running,
         channel_manager,
+        terminated: terminated_sender,
+        metrics: Arc::new(NodeMetrics::new(&[], None)),
     };
 
     (source_sender_node, source_listener_node)
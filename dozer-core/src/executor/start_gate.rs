@@ -0,0 +1,115 @@
+use std::sync::{Condvar, Mutex};
+
+/// Holds the node threads back until every one of them is running, so sources only emit once all
+/// the nodes downstream of them consume their input, however small the channels are.
+///
+/// Unlike a [`std::sync::Barrier`], the gate can be aborted when the DAG fails to start, so the
+/// threads already spawned quit instead of waiting for the others forever.
+#[derive(Debug)]
+pub(crate) struct StartGate {
+    state: Mutex<GateState>,
+    condvar: Condvar,
+}
+
+#[derive(Debug)]
+struct GateState {
+    /// Number of threads yet to arrive.
+    waiting_for: usize,
+    aborted: bool,
+}
+
+impl StartGate {
+    /// Creates a gate for `threads` node threads.
+    pub fn new(threads: usize) -> Self {
+        Self {
+            state: Mutex::new(GateState {
+                waiting_for: threads,
+                aborted: false,
+            }),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// Called by every node thread once running. Waits for all the others, and returns whether
+    /// the thread should run its node, `false` if the start was aborted.
+    pub fn wait(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.waiting_for = state.waiting_for.saturating_sub(1);
+        if state.waiting_for == 0 {
+            self.condvar.notify_all();
+        }
+        let state = self
+            .condvar
+            .wait_while(state, |state| state.waiting_for > 0 && !state.aborted)
+            .unwrap();
+        !state.aborted
+    }
+
+    /// Releases the threads waiting, and those yet to arrive, without running their nodes.
+    pub fn abort(&self) {
+        self.state.lock().unwrap().aborted = true;
+        self.condvar.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn gate_opens_once_every_thread_arrived() {
+        let gate = Arc::new(StartGate::new(3));
+        let arrived = Arc::new(AtomicUsize::new(0));
+
+        // Stands for a source, arriving first.
+        let source = {
+            let gate = gate.clone();
+            let arrived = arrived.clone();
+            thread::spawn(move || {
+                arrived.fetch_add(1, Ordering::SeqCst);
+                let open = gate.wait();
+                (open, arrived.load(Ordering::SeqCst))
+            })
+        };
+        // Stand for slow starting processors and sinks.
+        let others = (0..2)
+            .map(|_| {
+                let gate = gate.clone();
+                let arrived = arrived.clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(50));
+                    arrived.fetch_add(1, Ordering::SeqCst);
+                    gate.wait()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(source.join().unwrap(), (true, 3));
+        for other in others {
+            assert!(other.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn aborted_gate_releases_threads_without_running_them() {
+        let gate = Arc::new(StartGate::new(3));
+        let waiting = (0..2)
+            .map(|_| {
+                let gate = gate.clone();
+                thread::spawn(move || gate.wait())
+            })
+            .collect::<Vec<_>>();
+
+        // The third thread never gets spawned.
+        gate.abort();
+        for thread in waiting {
+            assert!(!thread.join().unwrap());
+        }
+        assert!(!gate.wait());
+    }
+}
@@ -5,13 +5,17 @@ use crate::errors::ExecutionError::InvalidPortHandle;
 use crate::node::PortHandle;
 use crate::record_store::RecordWriter;
 
-use crossbeam::channel::Sender;
-use dozer_types::epoch::{Epoch, ExecutorOperation};
+use crossbeam::channel::{SendError, Sender, TrySendError};
+use dozer_types::chrono::{DateTime, FixedOffset};
+use dozer_types::epoch::{Epoch, ExecutorOperation, OpOrigin, OpTags};
 use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind};
-use dozer_types::log::debug;
-use dozer_types::node::NodeHandle;
-use dozer_types::types::Operation;
+use dozer_types::log::{debug, warn};
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::tracing::debug_span;
+use dozer_types::types::{Operation, Record};
 use std::collections::HashMap;
+use std::mem::take;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -38,97 +42,272 @@ impl StateWriter {
     }
 }
 
+/// The sending end of the channel to a downstream node.
+#[derive(Debug, Clone)]
+pub(crate) struct NodeSender {
+    sender: Sender<ExecutorOperation>,
+    /// Set if operations are dropped rather than waited on when the channel is full, counting
+    /// them, see [`ExecutorOptions::drop_when_full`].
+    ///
+    /// [`ExecutorOptions::drop_when_full`]: crate::executor::ExecutorOptions::drop_when_full
+    dropped: Option<Arc<AtomicU64>>,
+}
+
+impl NodeSender {
+    pub fn new(sender: Sender<ExecutorOperation>, dropped: Option<Arc<AtomicU64>>) -> Self {
+        Self { sender, dropped }
+    }
+
+    /// Sends `op`, waiting for room in the channel unless it's an operation and the receiver
+    /// drops operations when full. Commits and other control messages are always sent.
+    fn send(&self, op: ExecutorOperation) -> Result<(), SendError<ExecutorOperation>> {
+        let Some(dropped) = &self.dropped else {
+            return self.sender.send(op);
+        };
+        let count = match &op {
+            ExecutorOperation::Op { .. } => 1,
+            ExecutorOperation::Batch { ops } => ops.len() as u64,
+            _ => return self.sender.send(op),
+        };
+        match self.sender.try_send(op) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                dropped.fetch_add(count, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(op)) => Err(SendError(op)),
+        }
+    }
+}
+
+impl From<Sender<ExecutorOperation>> for NodeSender {
+    fn from(sender: Sender<ExecutorOperation>) -> Self {
+        Self::new(sender, None)
+    }
+}
+
 #[derive(Debug)]
 struct ChannelManager {
     owner: NodeHandle,
-    senders: HashMap<PortHandle, Vec<Sender<ExecutorOperation>>>,
+    senders: HashMap<PortHandle, Vec<NodeSender>>,
     state_writer: StateWriter,
     stateful: bool,
+    /// Origin of the operation being handled, attached to every operation sent.
+    origin: Option<OpOrigin>,
+    /// Whether to stop sending to a node that quit instead of failing, see
+    /// [`ExecutorOptions::continue_on_failure`].
+    ///
+    /// [`ExecutorOptions::continue_on_failure`]: crate::executor::ExecutorOptions::continue_on_failure
+    drop_disconnected: bool,
+    /// Number of operations sent on a port as one message, see
+    /// [`ExecutorOptions::batch_size`].
+    ///
+    /// [`ExecutorOptions::batch_size`]: crate::executor::ExecutorOptions::batch_size
+    batch_size: usize,
+    /// Operations not sent yet on each port, fewer than `batch_size`.
+    batches: HashMap<PortHandle, Vec<(Operation, Option<OpOrigin>)>>,
 }
 
 impl ChannelManager {
     #[inline]
-    fn send_op(&mut self, mut op: Operation, port_id: PortHandle) -> Result<(), ExecutionError> {
+    fn send_op(&mut self, op: Operation, port_id: PortHandle) -> Result<(), ExecutionError> {
+        let origin = self.origin.clone();
+        self.send_op_from(op, port_id, origin)
+    }
+
+    /// Sends `op` with the given `origin` rather than that of the operation being handled.
+    fn send_op_from(
+        &mut self,
+        mut op: Operation,
+        port_id: PortHandle,
+        origin: Option<OpOrigin>,
+    ) -> Result<(), ExecutionError> {
         if self.stateful {
             op = self.state_writer.store_op(op, &port_id)?;
         }
 
         let senders = self
             .senders
-            .get(&port_id)
+            .get_mut(&port_id)
             .ok_or(InvalidPortHandle(port_id))?;
 
-        let exec_op = ExecutorOperation::Op { op };
+        if self.batch_size <= 1 {
+            let exec_op = ExecutorOperation::Op { op, origin };
+            return send_to_all(&self.owner, senders, exec_op, self.drop_disconnected);
+        }
 
-        if let Some((last_sender, senders)) = senders.split_last() {
-            for sender in senders {
-                sender.send(exec_op.clone())?;
-            }
-            last_sender.send(exec_op)?;
+        let batch = self.batches.entry(port_id).or_default();
+        batch.push((op, origin));
+        if batch.len() < self.batch_size {
+            return Ok(());
         }
+        let ops = take(batch);
+        send_to_all(
+            &self.owner,
+            senders,
+            ExecutorOperation::Batch { ops },
+            self.drop_disconnected,
+        )
+    }
 
-        Ok(())
+    /// Sends the operations batched on `port_id`, if any.
+    fn flush_batch(&mut self, port_id: PortHandle) -> Result<(), ExecutionError> {
+        let Some(batch) = self
+            .batches
+            .get_mut(&port_id)
+            .filter(|batch| !batch.is_empty())
+        else {
+            return Ok(());
+        };
+        let ops = take(batch);
+        let senders = self
+            .senders
+            .get_mut(&port_id)
+            .ok_or(InvalidPortHandle(port_id))?;
+        send_to_all(
+            &self.owner,
+            senders,
+            ExecutorOperation::Batch { ops },
+            self.drop_disconnected,
+        )
     }
 
-    fn send_terminate(&self) -> Result<(), ExecutionError> {
-        for senders in self.senders.values() {
-            for sender in senders {
-                sender.send(ExecutorOperation::Terminate)?;
+    /// Sends `op` on every port, or on `port_id` only if given, after the operations batched on
+    /// these ports.
+    fn broadcast(
+        &mut self,
+        op: ExecutorOperation,
+        port_id: Option<PortHandle>,
+    ) -> Result<(), ExecutionError> {
+        match port_id {
+            Some(port_id) => {
+                self.flush_batch(port_id)?;
+                let senders = self
+                    .senders
+                    .get_mut(&port_id)
+                    .ok_or(InvalidPortHandle(port_id))?;
+                send_to_all(&self.owner, senders, op, self.drop_disconnected)
+            }
+            None => {
+                let ports = self.batches.keys().copied().collect::<Vec<_>>();
+                for port_id in ports {
+                    self.flush_batch(port_id)?;
+                }
+                for senders in self.senders.values_mut() {
+                    send_to_all(&self.owner, senders, op.clone(), self.drop_disconnected)?;
+                }
+                Ok(())
             }
         }
+    }
 
-        Ok(())
+    fn send_terminate(&mut self) -> Result<(), ExecutionError> {
+        self.broadcast(ExecutorOperation::Terminate, None)
     }
 
-    fn send_snapshotting_done(&self) -> Result<(), ExecutionError> {
-        for senders in self.senders.values() {
-            for sender in senders {
-                sender.send(ExecutorOperation::SnapshottingDone {})?;
-            }
-        }
+    fn send_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        self.broadcast(ExecutorOperation::SnapshottingDone {}, None)
+    }
 
-        Ok(())
+    fn send_watermark(
+        &mut self,
+        timestamp: DateTime<FixedOffset>,
+        port_id: Option<PortHandle>,
+    ) -> Result<(), ExecutionError> {
+        self.broadcast(ExecutorOperation::Watermark { timestamp }, port_id)
     }
 
     fn store_and_send_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError> {
         debug!("[{}] Checkpointing - {}", self.owner, &epoch);
         self.state_writer.store_commit_info(epoch)?;
 
-        for senders in &self.senders {
-            for sender in senders.1 {
-                sender.send(ExecutorOperation::Commit {
-                    epoch: epoch.clone(),
-                })?;
-            }
-        }
-
-        Ok(())
+        self.broadcast(
+            ExecutorOperation::Commit {
+                epoch: epoch.clone(),
+            },
+            None,
+        )
     }
     fn new(
         owner: NodeHandle,
-        senders: HashMap<PortHandle, Vec<Sender<ExecutorOperation>>>,
+        senders: HashMap<PortHandle, Vec<NodeSender>>,
         state_writer: StateWriter,
         stateful: bool,
+        drop_disconnected: bool,
+        batch_size: usize,
     ) -> Self {
         Self {
             owner,
             senders,
             state_writer,
             stateful,
+            origin: None,
+            drop_disconnected,
+            batch_size,
+            batches: HashMap::new(),
         }
     }
 }
 
+/// Sends `op` to every sender in `senders`.
+///
+/// If a receiver has quit, fails unless `drop_disconnected`, in which case its sender is removed
+/// so that the remaining receivers keep being served.
+fn send_to_all(
+    owner: &NodeHandle,
+    senders: &mut Vec<NodeSender>,
+    op: ExecutorOperation,
+    drop_disconnected: bool,
+) -> Result<(), ExecutionError> {
+    let mut disconnected = vec![];
+    if let Some((last_sender, others)) = senders.split_last() {
+        for (index, sender) in others.iter().enumerate() {
+            if sender.send(op.clone()).is_err() {
+                disconnected.push(index);
+            }
+        }
+        if last_sender.send(op).is_err() {
+            disconnected.push(others.len());
+        }
+    }
+
+    if disconnected.is_empty() {
+        return Ok(());
+    }
+    if !drop_disconnected {
+        return Err(ExecutionError::CannotSendToChannel);
+    }
+    warn!(
+        "[{}] Dropping {} disconnected output(s)",
+        owner,
+        disconnected.len()
+    );
+    for index in disconnected.into_iter().rev() {
+        senders.remove(index);
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 pub(crate) struct SourceChannelManager {
-    source_handle: NodeHandle,
+    source_handle: Arc<NodeHandle>,
     manager: ChannelManager,
     curr_txid: u64,
     curr_seq_in_tx: u64,
+    /// Position of the last operation committed.
+    last_committed: Option<OpIdentifier>,
+    /// Number of epochs committed.
+    commits: u64,
     commit_sz: u32,
     num_uncommitted_ops: u32,
     max_duration_between_commits: Duration,
     last_commit_instant: Instant,
+    /// Longest time between two epochs, see [`ExecutorOptions::commit_interval`].
+    ///
+    /// [`ExecutorOptions::commit_interval`]: crate::executor::ExecutorOptions::commit_interval
+    commit_interval: Option<Duration>,
+    /// When the last epoch was committed.
+    last_epoch_instant: Instant,
     epoch_manager: Arc<EpochManager>,
 }
 
@@ -136,43 +315,76 @@ impl SourceChannelManager {
     #![allow(clippy::too_many_arguments)]
     pub fn new(
         owner: NodeHandle,
-        senders: HashMap<PortHandle, Vec<Sender<ExecutorOperation>>>,
+        senders: HashMap<PortHandle, Vec<NodeSender>>,
         state_writer: StateWriter,
         stateful: bool,
         commit_sz: u32,
         max_duration_between_commits: Duration,
+        commit_interval: Option<Duration>,
         epoch_manager: Arc<EpochManager>,
+        drop_disconnected: bool,
+        batch_size: usize,
     ) -> Self {
         Self {
-            manager: ChannelManager::new(owner.clone(), senders, state_writer, stateful),
+            manager: ChannelManager::new(
+                owner.clone(),
+                senders,
+                state_writer,
+                stateful,
+                drop_disconnected,
+                batch_size,
+            ),
             // FIXME: Read curr_txid and curr_seq_in_tx from persisted state.
             curr_txid: 0,
             curr_seq_in_tx: 0,
-            source_handle: owner,
+            last_committed: None,
+            commits: 0,
+            source_handle: Arc::new(owner),
             commit_sz,
             num_uncommitted_ops: 0,
             max_duration_between_commits,
             last_commit_instant: Instant::now(),
+            commit_interval,
+            last_epoch_instant: Instant::now(),
             epoch_manager,
         }
     }
 
+    fn is_commit_interval_elapsed(&self) -> bool {
+        self.commit_interval.map_or(false, |interval| {
+            self.last_epoch_instant.elapsed() >= interval
+        })
+    }
+
     fn should_participate_in_commit(&self) -> bool {
         self.num_uncommitted_ops >= self.commit_sz
             || self.last_commit_instant.elapsed() >= self.max_duration_between_commits
+            || self.is_commit_interval_elapsed()
     }
 
     fn commit(&mut self, request_termination: bool) -> Result<bool, ExecutionError> {
+        let request_commit = self.num_uncommitted_ops > 0 || self.is_commit_interval_elapsed();
         let (terminating, epoch, decision_instant) = self
             .epoch_manager
-            .wait_for_epoch_close(request_termination, self.num_uncommitted_ops > 0);
+            .wait_for_epoch_close(request_termination, request_commit);
         if let Some(epoch_id) = epoch {
+            self.last_epoch_instant = decision_instant;
+            let _span = debug_span!(
+                "commit",
+                epoch = epoch_id,
+                txid = self.curr_txid,
+                seq_in_tx = self.curr_seq_in_tx,
+                ops = self.num_uncommitted_ops
+            )
+            .entered();
             self.manager.store_and_send_commit(&Epoch::from(
                 epoch_id,
-                self.source_handle.clone(),
+                self.source_handle.as_ref().clone(),
                 self.curr_txid,
                 self.curr_seq_in_tx,
             ))?;
+            self.last_committed = Some(OpIdentifier::new(self.curr_txid, self.curr_seq_in_tx));
+            self.commits += 1;
         }
         self.num_uncommitted_ops = 0;
         self.last_commit_instant = decision_instant;
@@ -193,12 +405,18 @@ impl SourceChannelManager {
     pub fn send_and_trigger_commit_if_needed(
         &mut self,
         message: IngestionMessage,
+        tags: Option<OpTags>,
         port: PortHandle,
         request_termination: bool,
     ) -> Result<bool, ExecutionError> {
         //
         self.curr_txid = message.identifier.txid;
         self.curr_seq_in_tx = message.identifier.seq_in_tx;
+        self.manager.origin = Some(OpOrigin {
+            source: self.source_handle.clone(),
+            id: message.identifier,
+            tags,
+        });
         match message.kind {
             IngestionMessageKind::OperationEvent(op) => {
                 self.manager.send_op(op, port)?;
@@ -214,46 +432,399 @@ impl SourceChannelManager {
                 // TODO "implement handle for snapshotting started"
                 Ok(true)
             }
+            IngestionMessageKind::Watermark(timestamp) => {
+                self.manager.send_watermark(timestamp, Some(port))?;
+                self.trigger_commit_if_needed(request_termination)
+            }
         }
     }
 
     pub fn terminate(&mut self) -> Result<(), ExecutionError> {
         self.manager.send_terminate()
     }
+
+    /// Position of the last operation of the source committed so far.
+    pub fn last_committed(&self) -> Option<OpIdentifier> {
+        self.last_committed
+    }
+
+    /// Number of epochs committed so far.
+    pub fn commits(&self) -> u64 {
+        self.commits
+    }
+}
+
+/// Holds back the output of an accumulating processor until it is flushed, merging successive
+/// changes to the same record on the same port.
+///
+/// A change is merged into an earlier one when its old record is the earlier change's new record,
+/// e.g. `Insert(a)` followed by `Update(a -> b)` becomes `Insert(b)`, and `Insert(a)` followed by
+/// `Delete(a)` cancels out.
+///
+/// Each change keeps the origin of the operation being handled when it was pushed, that of the
+/// latest change for merged ones, since it is sent later than it was produced.
+#[derive(Debug, Default)]
+struct OperationBuffer {
+    ops: Vec<Option<(Operation, PortHandle, Option<OpOrigin>)>>,
+    // Position in `ops` of the buffered change producing a record on a port.
+    by_new_record: HashMap<(PortHandle, Record), usize>,
+}
+
+impl OperationBuffer {
+    fn push(&mut self, op: Operation, port: PortHandle, origin: Option<OpOrigin>) {
+        let merge_into = match &op {
+            Operation::Insert { .. } => None,
+            Operation::Update { old, .. } | Operation::Delete { old } => {
+                self.by_new_record.remove(&(port, old.clone()))
+            }
+        };
+
+        let Some(index) = merge_into else {
+            self.insert_at(self.ops.len(), op, port, origin);
+            return;
+        };
+
+        let (previous, _, _) = self.ops[index]
+            .take()
+            .expect("Indexed operations are never removed");
+        let merged = match (previous, op) {
+            (Operation::Insert { .. }, Operation::Update { new, .. }) => {
+                Some(Operation::Insert { new })
+            }
+            (Operation::Insert { .. }, Operation::Delete { .. }) => None,
+            (Operation::Update { old, .. }, Operation::Update { new, .. }) => {
+                Some(Operation::Update { old, new })
+            }
+            (Operation::Update { old, .. }, Operation::Delete { .. }) => {
+                Some(Operation::Delete { old })
+            }
+            (Operation::Delete { .. }, _) | (_, Operation::Insert { .. }) => {
+                unreachable!("Deletes are never indexed and inserts are never merged")
+            }
+        };
+        if let Some(merged) = merged {
+            self.insert_at(index, merged, port, origin);
+        }
+    }
+
+    fn insert_at(
+        &mut self,
+        index: usize,
+        op: Operation,
+        port: PortHandle,
+        origin: Option<OpOrigin>,
+    ) {
+        match &op {
+            Operation::Insert { new } | Operation::Update { new, .. } => {
+                self.by_new_record.insert((port, new.clone()), index);
+            }
+            Operation::Delete { .. } => {}
+        }
+        if index == self.ops.len() {
+            self.ops.push(Some((op, port, origin)));
+        } else {
+            self.ops[index] = Some((op, port, origin));
+        }
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = (Operation, PortHandle, Option<OpOrigin>)> + '_ {
+        self.by_new_record.clear();
+        self.ops.drain(..).flatten()
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct ProcessorChannelManager {
     manager: ChannelManager,
+    buffer: Option<OperationBuffer>,
 }
 
 impl ProcessorChannelManager {
     pub fn new(
         owner: NodeHandle,
-        senders: HashMap<PortHandle, Vec<Sender<ExecutorOperation>>>,
+        senders: HashMap<PortHandle, Vec<NodeSender>>,
         state_writer: StateWriter,
         stateful: bool,
+        accumulating: bool,
+        drop_disconnected: bool,
+        batch_size: usize,
     ) -> Self {
         Self {
-            manager: ChannelManager::new(owner, senders, state_writer, stateful),
+            manager: ChannelManager::new(
+                owner,
+                senders,
+                state_writer,
+                stateful,
+                drop_disconnected,
+                batch_size,
+            ),
+            buffer: accumulating.then(OperationBuffer::default),
+        }
+    }
+
+    /// Sets the origin attached to operations sent from now on, i.e. that of the operation the
+    /// processor is about to handle.
+    pub fn set_origin(&mut self, origin: Option<OpOrigin>) {
+        self.manager.origin = origin;
+    }
+
+    fn flush(&mut self) -> Result<(), ExecutionError> {
+        if let Some(buffer) = &mut self.buffer {
+            for (op, port, origin) in buffer.drain() {
+                self.manager.send_op_from(op, port, origin)?;
+            }
         }
+        Ok(())
     }
 
     pub fn store_and_send_commit(&mut self, epoch: &Epoch) -> Result<(), ExecutionError> {
+        self.flush()?;
         self.manager.store_and_send_commit(epoch)
     }
 
-    pub fn send_terminate(&self) -> Result<(), ExecutionError> {
+    pub fn send_terminate(&mut self) -> Result<(), ExecutionError> {
+        self.flush()?;
         self.manager.send_terminate()
     }
 
-    pub fn send_snapshotting_done(&self) -> Result<(), ExecutionError> {
+    pub fn send_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        self.flush()?;
         self.manager.send_snapshotting_done()
     }
+
+    pub fn send_watermark(
+        &mut self,
+        timestamp: DateTime<FixedOffset>,
+    ) -> Result<(), ExecutionError> {
+        self.flush()?;
+        self.manager.send_watermark(timestamp, None)
+    }
 }
 
 impl ProcessorChannelForwarder for ProcessorChannelManager {
     fn send(&mut self, op: Operation, port: PortHandle) -> Result<(), ExecutionError> {
-        self.manager.send_op(op, port)
+        match &mut self.buffer {
+            Some(buffer) => {
+                buffer.push(op, port, self.manager.origin.clone());
+                Ok(())
+            }
+            None => self.manager.send_op(op, port),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossbeam::channel::unbounded;
+    use dozer_types::types::Field;
+
+    use crate::DEFAULT_PORT_HANDLE;
+
+    use super::*;
+
+    fn record(id: i64, value: i64) -> Record {
+        Record::new(None, vec![Field::Int(id), Field::Int(value)])
+    }
+
+    fn run(accumulating: bool, ops: Vec<Operation>) -> Vec<ExecutorOperation> {
+        run_batched(accumulating, 1, ops)
+    }
+
+    fn run_batched(
+        accumulating: bool,
+        batch_size: usize,
+        ops: Vec<Operation>,
+    ) -> Vec<ExecutorOperation> {
+        let (sender, receiver) = unbounded();
+        let mut manager = ProcessorChannelManager::new(
+            NodeHandle::new(None, "processor".to_string()),
+            [(DEFAULT_PORT_HANDLE, vec![sender.into()])]
+                .into_iter()
+                .collect(),
+            StateWriter::new(HashMap::new()),
+            false,
+            accumulating,
+            false,
+            batch_size,
+        );
+        for op in ops {
+            manager.send(op, DEFAULT_PORT_HANDLE).unwrap();
+        }
+        manager
+            .store_and_send_commit(&Epoch::new(0, Default::default()))
+            .unwrap();
+        receiver.try_iter().collect()
+    }
+
+    fn ops() -> Vec<Operation> {
+        vec![
+            Operation::Insert { new: record(1, 1) },
+            Operation::Insert { new: record(2, 1) },
+            Operation::Update {
+                old: record(1, 1),
+                new: record(1, 2),
+            },
+            Operation::Update {
+                old: record(2, 1),
+                new: record(2, 2),
+            },
+            Operation::Update {
+                old: record(1, 2),
+                new: record(1, 3),
+            },
+            Operation::Insert { new: record(3, 1) },
+            Operation::Delete { old: record(3, 1) },
+        ]
+    }
+
+    #[test]
+    fn non_accumulating_processor_forwards_every_op() {
+        let received = run(false, ops());
+        assert_eq!(received.len(), 8);
+        assert!(matches!(received[7], ExecutorOperation::Commit { .. }));
+    }
+
+    #[test]
+    fn accumulating_processor_merges_ops_until_commit() {
+        let received = run(true, ops());
+        assert_eq!(
+            received[..2],
+            [
+                ExecutorOperation::Op {
+                    op: Operation::Insert { new: record(1, 3) },
+                    origin: None,
+                },
+                ExecutorOperation::Op {
+                    op: Operation::Insert { new: record(2, 2) },
+                    origin: None,
+                },
+            ]
+        );
+        assert_eq!(received.len(), 3);
+        assert!(matches!(received[2], ExecutorOperation::Commit { .. }));
+    }
+
+    #[test]
+    fn accumulating_processor_merges_updates_of_committed_records() {
+        let received = run(
+            true,
+            vec![
+                Operation::Update {
+                    old: record(1, 1),
+                    new: record(1, 2),
+                },
+                Operation::Update {
+                    old: record(1, 2),
+                    new: record(1, 3),
+                },
+                Operation::Update {
+                    old: record(2, 1),
+                    new: record(2, 2),
+                },
+                Operation::Delete { old: record(2, 2) },
+            ],
+        );
+        assert_eq!(
+            received[..2],
+            [
+                ExecutorOperation::Op {
+                    op: Operation::Update {
+                        old: record(1, 1),
+                        new: record(1, 3)
+                    },
+                    origin: None,
+                },
+                ExecutorOperation::Op {
+                    op: Operation::Delete { old: record(2, 1) },
+                    origin: None,
+                },
+            ]
+        );
+        assert_eq!(received.len(), 3);
+    }
+
+    #[test]
+    fn accumulating_processor_sends_each_op_with_its_own_origin() {
+        let (sender, receiver) = unbounded();
+        let mut manager = ProcessorChannelManager::new(
+            NodeHandle::new(None, "processor".to_string()),
+            [(DEFAULT_PORT_HANDLE, vec![sender.into()])]
+                .into_iter()
+                .collect(),
+            StateWriter::new(HashMap::new()),
+            false,
+            true,
+            false,
+            1,
+        );
+        let origin = |seq_in_tx| {
+            Some(OpOrigin {
+                source: Arc::new(NodeHandle::new(None, "source".to_string())),
+                id: OpIdentifier::new(0, seq_in_tx),
+                tags: None,
+            })
+        };
+
+        manager.set_origin(origin(0));
+        manager
+            .send(Operation::Insert { new: record(1, 1) }, DEFAULT_PORT_HANDLE)
+            .unwrap();
+        manager.set_origin(origin(1));
+        manager
+            .send(Operation::Insert { new: record(2, 1) }, DEFAULT_PORT_HANDLE)
+            .unwrap();
+        manager.set_origin(origin(2));
+        manager
+            .send(
+                Operation::Update {
+                    old: record(1, 1),
+                    new: record(1, 2),
+                },
+                DEFAULT_PORT_HANDLE,
+            )
+            .unwrap();
+        // Emitted on flush, after the last operation was handled.
+        manager.set_origin(None);
+        manager
+            .send(Operation::Insert { new: record(3, 1) }, DEFAULT_PORT_HANDLE)
+            .unwrap();
+        manager
+            .store_and_send_commit(&Epoch::new(0, Default::default()))
+            .unwrap();
+
+        let received = receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(
+            received[..3],
+            [
+                ExecutorOperation::Op {
+                    op: Operation::Insert { new: record(1, 2) },
+                    origin: origin(2),
+                },
+                ExecutorOperation::Op {
+                    op: Operation::Insert { new: record(2, 1) },
+                    origin: origin(1),
+                },
+                ExecutorOperation::Op {
+                    op: Operation::Insert { new: record(3, 1) },
+                    origin: None,
+                },
+            ]
+        );
+        assert_eq!(received.len(), 4);
+    }
+
+    #[test]
+    fn batching_processor_sends_full_batches_then_the_rest_before_commit() {
+        let ops = ops();
+        let received = run_batched(false, 3, ops.clone());
+        let batch = |ops: &[Operation]| ExecutorOperation::Batch {
+            ops: ops.iter().cloned().map(|op| (op, None)).collect(),
+        };
+        assert_eq!(
+            received[..3],
+            [batch(&ops[..3]), batch(&ops[3..6]), batch(&ops[6..])]
+        );
+        assert_eq!(received.len(), 4);
+        assert!(matches!(received[3], ExecutorOperation::Commit { .. }));
     }
 }
@@ -11,8 +11,10 @@ pub mod errors;
 pub mod executor;
 pub mod forwarder;
 mod hash_map_to_vec;
+pub mod metrics;
 pub mod node;
 pub mod record_store;
+pub mod stats;
 
 #[cfg(test)]
 pub mod tests;
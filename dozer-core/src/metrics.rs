@@ -0,0 +1,356 @@
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dozer_types::node::NodeHandle;
+
+use crate::node::PortHandle;
+
+/// Metrics of one node of a running DAG, updated by the node's thread and readable from any
+/// other.
+#[derive(Debug)]
+pub struct NodeMetrics {
+    records: AtomicU64,
+    commits: AtomicU64,
+    state_size: AtomicU64,
+    has_state_size: AtomicBool,
+    /// Operations waiting in each input channel, measured whenever the node reads from it.
+    channel_occupancy: Vec<(PortHandle, AtomicUsize)>,
+    /// Operations dropped because an input channel was full, if the node drops them.
+    dropped: Option<Arc<AtomicU64>>,
+    /// Time taken to process each operation, for processors and sinks.
+    latency: LatencyHistogram,
+}
+
+impl NodeMetrics {
+    pub(crate) fn new(input_ports: &[PortHandle], dropped: Option<Arc<AtomicU64>>) -> Self {
+        Self {
+            records: AtomicU64::new(0),
+            commits: AtomicU64::new(0),
+            state_size: AtomicU64::new(0),
+            has_state_size: AtomicBool::new(false),
+            channel_occupancy: input_ports
+                .iter()
+                .map(|port| (*port, AtomicUsize::new(0)))
+                .collect(),
+            dropped,
+            latency: LatencyHistogram::default(),
+        }
+    }
+
+    /// Operations processed by the node, or sent by it for a source.
+    pub fn records(&self) -> u64 {
+        self.records.load(Ordering::Relaxed)
+    }
+
+    /// Epochs committed by the node.
+    pub fn commits(&self) -> u64 {
+        self.commits.load(Ordering::Relaxed)
+    }
+
+    /// Size of the state of the node, as of the last commit, if it reports one.
+    pub fn state_size(&self) -> Option<u64> {
+        self.has_state_size
+            .load(Ordering::Relaxed)
+            .then(|| self.state_size.load(Ordering::Relaxed))
+    }
+
+    /// Operations dropped on the way to the node because its input channels were full, if it's
+    /// one of [`ExecutorOptions::drop_when_full`].
+    ///
+    /// [`ExecutorOptions::drop_when_full`]: crate::executor::ExecutorOptions::drop_when_full
+    pub fn dropped(&self) -> Option<u64> {
+        self.dropped
+            .as_ref()
+            .map(|dropped| dropped.load(Ordering::Relaxed))
+    }
+
+    /// Time processors and sinks took to process each operation. Empty for sources.
+    pub fn latency(&self) -> &LatencyHistogram {
+        &self.latency
+    }
+
+    pub(crate) fn record_op(&self) {
+        self.records.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records an operation processed in `elapsed`.
+    pub(crate) fn record_latency(&self, elapsed: Duration) {
+        self.latency.record(elapsed);
+    }
+
+    pub(crate) fn record_commit(&self) {
+        self.commits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_commits(&self, commits: u64) {
+        self.commits.store(commits, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_state_size(&self, size: usize) {
+        self.state_size.store(size as u64, Ordering::Relaxed);
+        self.has_state_size.store(true, Ordering::Relaxed);
+    }
+
+    /// Sets the occupancy of the input channel at `index`.
+    pub(crate) fn set_channel_occupancy(&self, index: usize, len: usize) {
+        self.channel_occupancy[index]
+            .1
+            .store(len, Ordering::Relaxed);
+    }
+}
+
+/// Upper bounds of the buckets of [`LatencyHistogram`], in nanoseconds. Larger durations fall in
+/// an extra, unbounded bucket.
+const LATENCY_BUCKETS: [u64; 16] = [
+    1_000,
+    5_000,
+    10_000,
+    50_000,
+    100_000,
+    500_000,
+    1_000_000,
+    5_000_000,
+    10_000_000,
+    50_000_000,
+    100_000_000,
+    500_000_000,
+    1_000_000_000,
+    5_000_000_000,
+    10_000_000_000,
+    60_000_000_000,
+];
+
+/// Histogram of durations with fixed buckets, from 1µs to 1 minute. Recording is a couple of
+/// relaxed atomic adds, cheap enough to do for every operation.
+#[derive(Debug, Default)]
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+    count: AtomicU64,
+    sum_nanos: AtomicU64,
+    max_nanos: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub(crate) fn record(&self, elapsed: Duration) {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        let bucket = LATENCY_BUCKETS.partition_point(|bound| *bound < nanos);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    /// Number of durations recorded.
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Sum of the durations recorded.
+    pub fn sum(&self) -> Duration {
+        Duration::from_nanos(self.sum_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Estimate of the `quantile` (between 0 and 1) of the durations recorded: the upper bound of
+    /// the bucket holding it, or the longest duration recorded if smaller. `None` if nothing was
+    /// recorded.
+    pub fn percentile(&self, quantile: f64) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * count as f64).ceil() as u64).max(1);
+        let max = self.max_nanos.load(Ordering::Relaxed);
+        let mut seen = 0;
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            seen += bucket.load(Ordering::Relaxed);
+            if seen >= rank {
+                let bound = LATENCY_BUCKETS.get(index).copied().unwrap_or(u64::MAX);
+                return Some(Duration::from_nanos(bound.min(max)));
+            }
+        }
+        // Concurrent recording may have bumped the count before the bucket.
+        Some(Duration::from_nanos(max))
+    }
+
+    /// Cumulative counts of the buckets, with their upper bounds in seconds, `None` for the
+    /// unbounded one.
+    fn cumulative_buckets(&self) -> impl Iterator<Item = (Option<f64>, u64)> + '_ {
+        let mut seen = 0;
+        self.buckets.iter().enumerate().map(move |(index, bucket)| {
+            seen += bucket.load(Ordering::Relaxed);
+            let bound = LATENCY_BUCKETS.get(index).map(|nanos| *nanos as f64 / 1e9);
+            (bound, seen)
+        })
+    }
+}
+
+/// Metrics of all the nodes of a running DAG.
+#[derive(Debug, Default)]
+pub struct DagMetrics {
+    nodes: Vec<(NodeHandle, Arc<NodeMetrics>)>,
+}
+
+impl DagMetrics {
+    pub(crate) fn add(&mut self, handle: NodeHandle, metrics: Arc<NodeMetrics>) {
+        self.nodes.push((handle, metrics));
+    }
+
+    pub fn node(&self, handle: &NodeHandle) -> Option<&NodeMetrics> {
+        self.nodes
+            .iter()
+            .find(|(node, _)| node == handle)
+            .map(|(_, metrics)| metrics.as_ref())
+    }
+
+    /// Renders the current value of every metric in the Prometheus text exposition format, each
+    /// series labelled with its node.
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::with_capacity(256 + self.nodes.len() * 256);
+
+        write_header(
+            &mut out,
+            "dozer_node_records_total",
+            "counter",
+            "Operations processed by the node, or sent by it for a source.",
+        );
+        for (handle, metrics) in &self.nodes {
+            write_series(
+                &mut out,
+                "dozer_node_records_total",
+                handle,
+                None,
+                metrics.records(),
+            );
+        }
+
+        write_header(
+            &mut out,
+            "dozer_node_commits_total",
+            "counter",
+            "Epochs committed by the node.",
+        );
+        for (handle, metrics) in &self.nodes {
+            write_series(
+                &mut out,
+                "dozer_node_commits_total",
+                handle,
+                None,
+                metrics.commits(),
+            );
+        }
+
+        write_header(
+            &mut out,
+            "dozer_node_dropped_total",
+            "counter",
+            "Operations dropped because the input channels of the node were full.",
+        );
+        for (handle, metrics) in &self.nodes {
+            if let Some(dropped) = metrics.dropped() {
+                write_series(&mut out, "dozer_node_dropped_total", handle, None, dropped);
+            }
+        }
+
+        write_header(
+            &mut out,
+            "dozer_channel_occupancy",
+            "gauge",
+            "Operations waiting in the input channel of the node.",
+        );
+        for (handle, metrics) in &self.nodes {
+            for (port, len) in &metrics.channel_occupancy {
+                write_series(
+                    &mut out,
+                    "dozer_channel_occupancy",
+                    handle,
+                    Some(*port),
+                    len.load(Ordering::Relaxed) as u64,
+                );
+            }
+        }
+
+        write_header(
+            &mut out,
+            "dozer_node_state_size",
+            "gauge",
+            "Size of the state of the node as of its last commit.",
+        );
+        for (handle, metrics) in &self.nodes {
+            if let Some(size) = metrics.state_size() {
+                write_series(&mut out, "dozer_node_state_size", handle, None, size);
+            }
+        }
+
+        write_header(
+            &mut out,
+            "dozer_node_process_seconds",
+            "histogram",
+            "Time the node took to process an operation.",
+        );
+        for (handle, metrics) in &self.nodes {
+            let latency = metrics.latency();
+            if latency.count() == 0 {
+                continue;
+            }
+            for (bound, count) in latency.cumulative_buckets() {
+                out.push_str("dozer_node_process_seconds_bucket{node=\"");
+                let _ = write!(EscapedLabel(&mut out), "{handle}");
+                let _ = match bound {
+                    Some(bound) => writeln!(out, "\",le=\"{bound}\"}} {count}"),
+                    None => writeln!(out, "\",le=\"+Inf\"}} {count}"),
+                };
+            }
+            out.push_str("dozer_node_process_seconds_sum{node=\"");
+            let _ = write!(EscapedLabel(&mut out), "{handle}");
+            let _ = writeln!(out, "\"}} {}", latency.sum().as_secs_f64());
+            write_series(
+                &mut out,
+                "dozer_node_process_seconds_count",
+                handle,
+                None,
+                latency.count(),
+            );
+        }
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, typ: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {typ}");
+}
+
+fn write_series(
+    out: &mut String,
+    name: &str,
+    node: &NodeHandle,
+    port: Option<PortHandle>,
+    value: u64,
+) {
+    out.push_str(name);
+    out.push_str("{node=\"");
+    let _ = write!(EscapedLabel(out), "{node}");
+    out.push('"');
+    if let Some(port) = port {
+        let _ = write!(out, ",port=\"{port}\"");
+    }
+    let _ = writeln!(out, "}} {value}");
+}
+
+/// Writes a label value, escaping it as the exposition format requires.
+struct EscapedLabel<'a>(&'a mut String);
+
+impl Write for EscapedLabel<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '\\' => self.0.push_str("\\\\"),
+                '"' => self.0.push_str("\\\""),
+                '\n' => self.0.push_str("\\n"),
+                c => self.0.push(c),
+            }
+        }
+        Ok(())
+    }
+}
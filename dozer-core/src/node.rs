@@ -1,13 +1,74 @@
 use crate::channels::{ProcessorChannelForwarder, SourceChannelForwarder};
 use crate::errors::ExecutionError;
+use crate::stats::ColumnStats;
+use crate::DEFAULT_PORT_HANDLE;
 
-use dozer_types::epoch::Epoch;
-use dozer_types::types::{Operation, Schema};
+use dozer_types::chrono::{DateTime, FixedOffset};
+use dozer_types::epoch::{Epoch, OpOrigin};
+use dozer_types::types::{
+    FieldDefinition, FieldType, Operation, Schema, SchemaRef, SourceDefinition,
+};
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
+use std::time::{Duration, Instant};
 
 pub type PortHandle = u16;
 
+/// A port, named rather than given as a raw [`PortHandle`].
+///
+/// Nodes with a single port use [`Port::Default`]. Nodes with several ports number them
+/// explicitly, and must not use `DEFAULT_PORT_HANDLE` for any of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Port {
+    Default,
+    Numbered(PortHandle),
+}
+
+impl Port {
+    /// An explicitly numbered port, for declaring named port constants.
+    ///
+    /// Panics if `handle` is `DEFAULT_PORT_HANDLE`, at compile time when used in a `const`.
+    pub const fn numbered(handle: PortHandle) -> Self {
+        assert!(
+            handle != DEFAULT_PORT_HANDLE,
+            "DEFAULT_PORT_HANDLE can't be used as a numbered port"
+        );
+        Port::Numbered(handle)
+    }
+
+    pub const fn handle(self) -> PortHandle {
+        match self {
+            Port::Default => DEFAULT_PORT_HANDLE,
+            Port::Numbered(handle) => handle,
+        }
+    }
+}
+
+impl From<PortHandle> for Port {
+    fn from(handle: PortHandle) -> Self {
+        if handle == DEFAULT_PORT_HANDLE {
+            Port::Default
+        } else {
+            Port::Numbered(handle)
+        }
+    }
+}
+
+impl From<Port> for PortHandle {
+    fn from(port: Port) -> Self {
+        port.handle()
+    }
+}
+
+impl Display for Port {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Port::Default => f.write_str("default"),
+            Port::Numbered(handle) => write!(f, "{handle}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum OutputPortType {
     Stateless,
@@ -25,6 +86,32 @@ impl Display for OutputPortType {
     }
 }
 
+/// How a processor or sink with several input ports picks the next port to read from.
+///
+/// Whatever the policy, the operations of a port are processed in the order they were sent, and
+/// commits are barriers: a port that delivered the commit of an epoch isn't read again until all
+/// ports have. Only the interleaving of different ports varies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputSelectionPolicy {
+    /// Read from any port that has data, as picked by the channel selector.
+    /// A port that is always busy can delay the others.
+    #[default]
+    Ready,
+    /// Serve ports with pending data in turn, so a busy port cannot starve the others.
+    RoundRobin,
+    /// Process in rounds everything pending on the ports when the round starts, one operation of
+    /// each port in turn. Operations arriving during a round wait for the next one, so a burst
+    /// on a port, or a port that is slow to process, delays the others by at most one round.
+    Drain,
+    /// Read the ports strictly in turn, waiting for the next one to have data even if others
+    /// do, so the interleaving doesn't depend on timing. For tests only, as a port without data
+    /// stalls all the others. See [`ExecutorOptions::deterministic_selection`].
+    ///
+    /// [`ExecutorOptions::deterministic_selection`]: crate::executor::ExecutorOptions::deterministic_selection
+    #[cfg(any(test, feature = "deterministic-select"))]
+    Sequential,
+}
+
 #[derive(Debug, Clone)]
 pub struct OutputPortDef {
     pub handle: PortHandle,
@@ -55,6 +142,17 @@ pub trait Source: Send + Sync + Debug {
         fw: &mut dyn SourceChannelForwarder,
         last_checkpoint: Option<(u64, u64)>,
     ) -> Result<(), ExecutionError>;
+
+    /// Called once the pipeline has terminated and `start` has returned, with the position of
+    /// the last operation of this source that was committed, if any.
+    ///
+    /// Sources that keep their own position record it here, so that a restart resumes right
+    /// after it. `start` may return early, or with an error, when the pipeline stops, so it
+    /// can't know which of the operations it sent were committed.
+    fn on_terminate(&self, last_committed: Option<(u64, u64)>) -> Result<(), ExecutionError> {
+        let _ = last_committed;
+        Ok(())
+    }
 }
 
 pub trait ProcessorFactory<T>: Send + Sync + Debug {
@@ -70,6 +168,60 @@ pub trait ProcessorFactory<T>: Send + Sync + Debug {
         input_schemas: HashMap<PortHandle, Schema>,
         output_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Processor>, ExecutionError>;
+
+    /// Checks the input schemas and returns the schema of every output port.
+    ///
+    /// Called once per processor when the DAG's schemas are populated, before anything is built,
+    /// so a processor that can't handle its inputs fails validation rather than at runtime. The
+    /// result must hold a schema for each port of [`ProcessorFactory::get_output_ports`]. By
+    /// default, it asks [`ProcessorFactory::get_output_schema`] for each of them.
+    fn prepare(
+        &self,
+        input_schemas: &HashMap<PortHandle, (Schema, T)>,
+    ) -> Result<HashMap<PortHandle, (Schema, T)>, ExecutionError> {
+        self.get_output_ports()
+            .into_iter()
+            .map(|port| {
+                let schema = self.get_output_schema(&port.handle, input_schemas)?;
+                Ok((port.handle, schema))
+            })
+            .collect()
+    }
+
+    /// Whether the processor's output is only meaningful at commit boundaries, as with
+    /// aggregations. The output of an accumulating processor is held back until the next commit,
+    /// and successive changes to the same record are merged before being sent downstream.
+    fn is_accumulating(&self) -> bool {
+        false
+    }
+
+    /// The output port that receives operations [`Processor::process`] fails on, if any.
+    ///
+    /// Every record of a failed operation is sent there with the error message appended, so the
+    /// port's schema should be built with [`dead_letter_schema`]. The port must be one of
+    /// [`ProcessorFactory::get_output_ports`]. Without a connected dead-letter port, failed
+    /// operations are logged and dropped.
+    fn dead_letter_port(&self) -> Option<PortHandle> {
+        None
+    }
+}
+
+/// Name of the field holding the error message on a dead-letter port.
+pub const DEAD_LETTER_ERROR_FIELD: &str = "error";
+
+/// Returns the schema of a dead-letter port for operations read from `input_schema`.
+pub fn dead_letter_schema(input_schema: &Schema) -> Schema {
+    let mut schema = input_schema.clone();
+    schema.field(
+        FieldDefinition::new(
+            DEAD_LETTER_ERROR_FIELD.to_string(),
+            FieldType::String,
+            false,
+            SourceDefinition::Dynamic,
+        ),
+        false,
+    );
+    schema
 }
 
 pub trait Processor: Send + Sync + Debug {
@@ -80,6 +232,72 @@ pub trait Processor: Send + Sync + Debug {
         op: Operation,
         fw: &mut dyn ProcessorChannelForwarder,
     ) -> Result<(), ExecutionError>;
+
+    /// How this processor reads from its input ports.
+    fn input_selection_policy(&self) -> InputSelectionPolicy {
+        InputSelectionPolicy::Ready
+    }
+
+    /// Called when all input ports have advanced their watermark to at least `timestamp`.
+    /// The watermark itself is forwarded downstream after this returns.
+    fn on_watermark(
+        &mut self,
+        _timestamp: DateTime<FixedOffset>,
+        _fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    /// Called when a source upstream is done sending its initial snapshot, and only streams
+    /// changes from then on, e.g. to flush results held back during the snapshot. The signal
+    /// itself is forwarded downstream after this returns.
+    fn on_source_snapshotting_done(
+        &mut self,
+        _fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    /// Called when the input on `port` is done and won't send anything anymore, e.g. to send
+    /// operations held back waiting for it. Terminate is forwarded downstream once all inputs
+    /// are done.
+    fn on_input_terminated(
+        &mut self,
+        _port: PortHandle,
+        _fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    /// Called at every commit boundary, before [`Processor::commit`], to send operations the
+    /// processor is holding back.
+    fn flush(&mut self, _fw: &mut dyn ProcessorChannelForwarder) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    /// How often [`Processor::on_tick`] is called, never if `None`.
+    fn tick_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Called every [`Processor::tick_interval`], whether or not input arrives, to fire timers,
+    /// close windows or evict state. `now` is when the tick fired.
+    ///
+    /// Ticks are handled between operations, so a tick may be late while the processor is busy,
+    /// and a late tick doesn't make up for the ones it missed.
+    fn on_tick(
+        &mut self,
+        _now: Instant,
+        _fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    /// Number of entries the processor holds in its state, reported as a metric at every commit.
+    /// `None` if the processor doesn't keep state or doesn't report it.
+    fn state_size(&self) -> Option<usize> {
+        None
+    }
 }
 
 pub trait SinkFactory<T>: Send + Sync + Debug {
@@ -95,8 +313,58 @@ pub trait SinkFactory<T>: Send + Sync + Debug {
 }
 
 pub trait Sink: Send + Sync + Debug {
-    fn commit(&mut self) -> Result<(), ExecutionError>;
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), ExecutionError>;
     fn process(&mut self, from_port: PortHandle, op: Operation) -> Result<(), ExecutionError>;
 
+    /// Like [`Sink::process`], also given the source `op` was ingested from and its position in
+    /// that source, when the executor knows them.
+    fn process_with_origin(
+        &mut self,
+        from_port: PortHandle,
+        op: Operation,
+        _origin: Option<&OpOrigin>,
+    ) -> Result<(), ExecutionError> {
+        self.process(from_port, op)
+    }
+
+    /// Like [`Sink::process_with_origin`], also given the schema of the records received on
+    /// `from_port`, so that `op` can be rendered with its field names, e.g. when logging it or
+    /// dead-lettering it.
+    fn process_with_schema(
+        &mut self,
+        from_port: PortHandle,
+        op: Operation,
+        origin: Option<&OpOrigin>,
+        _schema: &SchemaRef,
+    ) -> Result<(), ExecutionError> {
+        self.process_with_origin(from_port, op, origin)
+    }
+
     fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError>;
+
+    /// How this sink reads from its input ports.
+    fn input_selection_policy(&self) -> InputSelectionPolicy {
+        InputSelectionPolicy::Ready
+    }
+
+    /// Called when all input ports have advanced their watermark to at least `timestamp`.
+    fn on_source_watermark(
+        &mut self,
+        _timestamp: DateTime<FixedOffset>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    /// Whether the executor should collect per-column statistics of the records this sink
+    /// receives and pass them to [`Sink::on_stats`]. Off by default, as it costs a comparison
+    /// per field.
+    fn collects_stats(&self) -> bool {
+        false
+    }
+
+    /// Called before every commit with the statistics of the records received since the
+    /// previous commit, if [`Sink::collects_stats`] returns true.
+    fn on_stats(&mut self, _stats: Vec<ColumnStats>) -> Result<(), ExecutionError> {
+        Ok(())
+    }
 }
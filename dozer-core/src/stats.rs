@@ -0,0 +1,53 @@
+use dozer_types::types::{Field, Operation, Record};
+
+/// Statistics of one column over a batch of records.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnStats {
+    /// Smallest non-null value, by the ordering of `Field`.
+    pub min: Option<Field>,
+    /// Largest non-null value, by the ordering of `Field`.
+    pub max: Option<Field>,
+    pub null_count: u64,
+}
+
+/// Accumulates per-column statistics of the records written by operations.
+#[derive(Debug, Clone, Default)]
+pub struct StatsCollector {
+    columns: Vec<ColumnStats>,
+}
+
+impl StatsCollector {
+    /// Adds the records `op` writes, that is the new records of inserts and updates. Deletes
+    /// write nothing and are ignored.
+    pub fn observe(&mut self, op: &Operation) {
+        match op {
+            Operation::Insert { new } | Operation::Update { new, .. } => self.observe_record(new),
+            Operation::Delete { .. } => {}
+        }
+    }
+
+    pub fn observe_record(&mut self, record: &Record) {
+        if self.columns.len() < record.values.len() {
+            self.columns
+                .resize_with(record.values.len(), ColumnStats::default);
+        }
+
+        for (stats, value) in self.columns.iter_mut().zip(&record.values) {
+            if value == &Field::Null {
+                stats.null_count += 1;
+                continue;
+            }
+            if stats.min.as_ref().map_or(true, |min| value < min) {
+                stats.min = Some(value.clone());
+            }
+            if stats.max.as_ref().map_or(true, |max| value > max) {
+                stats.max = Some(value.clone());
+            }
+        }
+    }
+
+    /// Returns the statistics gathered since the last call, indexed by column.
+    pub fn take(&mut self) -> Vec<ColumnStats> {
+        std::mem::take(&mut self.columns)
+    }
+}
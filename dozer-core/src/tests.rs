@@ -3,8 +3,23 @@ mod checkpoint_ns;
 mod dag_base_create_errors;
 mod dag_base_errors;
 mod dag_base_run;
+mod dag_batching;
+mod dag_dead_letter;
+mod dag_deterministic_selection;
+mod dag_drop_when_full;
+mod dag_epoch_alignment;
+mod dag_limits;
+mod dag_metrics;
+mod dag_op_origin;
 mod dag_ports;
+mod dag_processor_tick;
 mod dag_schemas;
+mod dag_sink_schema;
+mod dag_snapshotting_done;
+mod dag_source_terminate;
+mod dag_threads;
+mod dag_tracing;
 pub mod processors;
 pub mod sinks;
 pub mod sources;
+mod stats;
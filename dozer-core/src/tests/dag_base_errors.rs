@@ -19,7 +19,7 @@ use dozer_types::types::{
 use std::collections::HashMap;
 use std::panic;
 
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use crate::tests::app::NoneContext;
@@ -452,7 +452,7 @@ pub(crate) struct ErrSink {
     panic: bool,
 }
 impl Sink for ErrSink {
-    fn commit(&mut self) -> Result<(), ExecutionError> {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
         Ok(())
     }
 
@@ -558,3 +558,61 @@ fn test_run_dag_sink_err_panic() {
         .join()
         .unwrap();
 }
+
+#[test]
+fn test_run_dag_sink_err_continue_on_failure() {
+    let count: u64 = 100_000;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let failing_source_handle = NodeHandle::new(None, 1.to_string());
+    let failing_sink_handle = NodeHandle::new(Some(1), 2.to_string());
+    let source_handle = NodeHandle::new(None, 3.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 4.to_string());
+
+    dag.add_source(
+        failing_source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_sink(
+        failing_sink_handle.clone(),
+        Arc::new(ErrSinkFactory::new(10_000, false)),
+    );
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(count, latch.clone())),
+    );
+
+    dag.connect(
+        Endpoint::new(failing_source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(failing_sink_handle.clone(), COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let options = ExecutorOptions {
+        continue_on_failure: true,
+        ..Default::default()
+    };
+    let errors = DagExecutor::new(dag, options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join_all()
+        .unwrap_err();
+
+    // The counting sink stops the sources once it has seen every record of its branch.
+    assert!(!latch.load(Ordering::Relaxed));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, failing_sink_handle);
+    assert!(matches!(errors[0].1, ExecutionError::InvalidOperation(_)));
+}
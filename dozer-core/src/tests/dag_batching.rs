@@ -0,0 +1,140 @@
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{PortHandle, Sink, SinkFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::dag_base_run::NoopProcessorFactory;
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::{Epoch, OpOrigin};
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::types::{Operation, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+type Received = Arc<Mutex<Vec<(Operation, Option<OpIdentifier>)>>>;
+
+#[derive(Debug)]
+struct RecordingSinkFactory {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    received: Received,
+}
+
+impl SinkFactory<NoneContext> for RecordingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(RecordingSink {
+            expected: self.expected,
+            running: self.running.clone(),
+            received: self.received.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct RecordingSink {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    received: Received,
+}
+
+impl Sink for RecordingSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(&mut self, from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        self.process_with_origin(from_port, op, None)
+    }
+
+    fn process_with_origin(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        origin: Option<&OpOrigin>,
+    ) -> Result<(), ExecutionError> {
+        let mut received = self.received.lock().unwrap();
+        received.push((op, origin.map(|origin| origin.id)));
+        if received.len() == self.expected {
+            self.running.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+/// Runs `count` operations from a source through a processor into a sink, sending them in
+/// batches of `batch_size`, and returns what the sink received.
+fn run(count: u64, batch_size: usize) -> Vec<(Operation, Option<OpIdentifier>)> {
+    let latch = Arc::new(AtomicBool::new(true));
+    let received = Received::default();
+
+    let mut dag = Dag::new();
+    let source_handle = NodeHandle::new(Some(1), "source".to_string());
+    let proc_handle = NodeHandle::new(Some(1), "proc".to_string());
+    let sink_handle = NodeHandle::new(Some(1), "sink".to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(proc_handle.clone(), Arc::new(NoopProcessorFactory {}));
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(RecordingSinkFactory {
+            expected: count as usize,
+            running: latch,
+            received: received.clone(),
+        }),
+    );
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    let options = ExecutorOptions {
+        batch_size,
+        ..Default::default()
+    };
+    DagExecutor::new(dag, options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let mut received = received.lock().unwrap();
+    std::mem::take(&mut *received)
+}
+
+#[test]
+fn test_batched_and_unbatched_runs_deliver_the_same_operations() {
+    // Not a multiple of the batch size, so the last batch is only sent on commit.
+    let count = 1_000;
+    let unbatched = run(count, 1);
+    assert_eq!(unbatched.len(), count as usize);
+    assert_eq!(run(count, 64), unbatched);
+}
@@ -0,0 +1,262 @@
+use crate::channels::ProcessorChannelForwarder;
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{
+    dead_letter_schema, OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory,
+    Sink, SinkFactory, DEAD_LETTER_ERROR_FIELD,
+};
+use crate::tests::app::NoneContext;
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::Epoch;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{Field, Operation, Record, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+const VALID_OUTPUT_PORT: PortHandle = 1;
+const DEAD_LETTER_OUTPUT_PORT: PortHandle = 2;
+
+/// Fails on every record whose key number is a multiple of 10.
+#[derive(Debug)]
+struct FailingProcessorFactory {
+    dead_letter_port: Option<PortHandle>,
+}
+
+impl ProcessorFactory<NoneContext> for FailingProcessorFactory {
+    fn get_output_schema(
+        &self,
+        output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        let (schema, ctx) = input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap().clone();
+        if *output_port == DEAD_LETTER_OUTPUT_PORT {
+            Ok((dead_letter_schema(&schema), ctx))
+        } else {
+            Ok((schema, ctx))
+        }
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![
+            OutputPortDef::new(VALID_OUTPUT_PORT, OutputPortType::Stateless),
+            OutputPortDef::new(DEAD_LETTER_OUTPUT_PORT, OutputPortType::Stateless),
+        ]
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(FailingProcessor {}))
+    }
+
+    fn dead_letter_port(&self) -> Option<PortHandle> {
+        self.dead_letter_port
+    }
+}
+
+#[derive(Debug)]
+struct FailingProcessor {}
+
+impl Processor for FailingProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        let Operation::Insert { new } = &op else {
+            return Err(ExecutionError::InvalidOperation(
+                "Expected insert".to_string(),
+            ));
+        };
+        if key_number(new) % 10 == 0 {
+            return Err(ExecutionError::InvalidOperation(format!(
+                "Rejected {:?}",
+                new.values[0]
+            )));
+        }
+        fw.send(op, VALID_OUTPUT_PORT)
+    }
+}
+
+fn key_number(record: &Record) -> u64 {
+    let Field::String(key) = &record.values[0] else {
+        panic!("Generated keys are strings");
+    };
+    key.trim_start_matches("key_").parse().unwrap()
+}
+
+#[derive(Debug)]
+struct PortRecordingSinkFactory {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    records: Arc<Mutex<HashMap<PortHandle, Vec<Record>>>>,
+}
+
+impl SinkFactory<NoneContext> for PortRecordingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![VALID_OUTPUT_PORT, DEAD_LETTER_OUTPUT_PORT]
+    }
+
+    fn prepare(
+        &self,
+        input_schemas: HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(), ExecutionError> {
+        let dead_letter_schema = &input_schemas[&DEAD_LETTER_OUTPUT_PORT].0;
+        assert_eq!(
+            dead_letter_schema.fields.last().unwrap().name,
+            DEAD_LETTER_ERROR_FIELD
+        );
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(PortRecordingSink {
+            expected: self.expected,
+            running: self.running.clone(),
+            records: self.records.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct PortRecordingSink {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    records: Arc<Mutex<HashMap<PortHandle, Vec<Record>>>>,
+}
+
+impl Sink for PortRecordingSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(&mut self, from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        let Operation::Insert { new } = op else {
+            panic!("Only inserts are generated");
+        };
+        let mut records = self.records.lock().unwrap();
+        records.entry(from_port).or_default().push(new);
+        if records.values().map(Vec::len).sum::<usize>() == self.expected {
+            self.running.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+fn build_dag(
+    count: u64,
+    dead_letter_port: Option<PortHandle>,
+    latch: Arc<AtomicBool>,
+    records: Arc<Mutex<HashMap<PortHandle, Vec<Record>>>>,
+) -> Dag<NoneContext> {
+    let mut dag = Dag::new();
+
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Arc::new(FailingProcessorFactory { dead_letter_port }),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(PortRecordingSinkFactory {
+            expected: count as usize,
+            running: latch,
+            records,
+        }),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+    for port in [VALID_OUTPUT_PORT, DEAD_LETTER_OUTPUT_PORT] {
+        dag.connect(
+            Endpoint::new(proc_handle.clone(), port),
+            Endpoint::new(sink_handle.clone(), port),
+        )
+        .unwrap();
+    }
+    dag
+}
+
+#[test]
+fn test_failed_records_are_sent_to_dead_letter_port() {
+    let count: u64 = 1_000;
+    let latch = Arc::new(AtomicBool::new(true));
+    let records = Arc::new(Mutex::new(HashMap::new()));
+    let dag = build_dag(
+        count,
+        Some(DEAD_LETTER_OUTPUT_PORT),
+        latch.clone(),
+        records.clone(),
+    );
+
+    DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let records = records.lock().unwrap();
+    let valid = &records[&VALID_OUTPUT_PORT];
+    let failed = &records[&DEAD_LETTER_OUTPUT_PORT];
+    assert_eq!(valid.len() as u64, count - count / 10);
+    assert_eq!(failed.len() as u64, count / 10);
+
+    for record in valid {
+        assert_ne!(key_number(record) % 10, 0);
+        assert_eq!(record.values.len(), 2);
+    }
+    for record in failed {
+        assert_eq!(key_number(record) % 10, 0);
+        let Some(Field::String(error)) = record.values.get(2) else {
+            panic!("Dead-letter records carry the error message");
+        };
+        assert!(error.contains("Rejected"), "{error}");
+    }
+}
+
+#[test]
+fn test_dead_letter_port_must_be_an_output_port() {
+    let dag = build_dag(
+        1,
+        Some(3),
+        Arc::new(AtomicBool::new(true)),
+        Arc::new(Mutex::new(HashMap::new())),
+    );
+
+    assert!(matches!(
+        DagExecutor::validate(dag),
+        Err(ExecutionError::InvalidPortHandle(3))
+    ));
+}
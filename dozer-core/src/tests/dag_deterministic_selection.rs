@@ -0,0 +1,169 @@
+use crate::channels::ProcessorChannelForwarder;
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::dag_base_run::{NOOP_JOIN_LEFT_INPUT_PORT, NOOP_JOIN_RIGHT_INPUT_PORT};
+use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::Epoch;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{Field, Operation, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type Log = Arc<Mutex<Vec<(PortHandle, Field)>>>;
+
+/// A two-input processor logging the port and key of every operation it forwards.
+#[derive(Debug)]
+struct LoggingJoinProcessorFactory {
+    log: Log,
+}
+
+impl ProcessorFactory<NoneContext> for LoggingJoinProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        Ok(input_schemas
+            .get(&NOOP_JOIN_LEFT_INPUT_PORT)
+            .unwrap()
+            .clone())
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![NOOP_JOIN_LEFT_INPUT_PORT, NOOP_JOIN_RIGHT_INPUT_PORT]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(LoggingJoinProcessor {
+            log: self.log.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct LoggingJoinProcessor {
+    log: Log,
+}
+
+impl Processor for LoggingJoinProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        if let Operation::Insert { new } = &op {
+            self.log
+                .lock()
+                .unwrap()
+                .push((from_port, new.values[0].clone()));
+        }
+        fw.send(op, DEFAULT_PORT_HANDLE)
+    }
+}
+
+/// Runs two sources of `count` records each into a [`LoggingJoinProcessor`] and returns its log.
+fn run_logged_join(count: u64) -> Vec<(PortHandle, Field)> {
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let log = Log::default();
+
+    let source1_handle = NodeHandle::new(None, 1.to_string());
+    let source2_handle = NodeHandle::new(None, 2.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+
+    for source_handle in [&source1_handle, &source2_handle] {
+        dag.add_source(
+            source_handle.clone(),
+            Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+        );
+    }
+    dag.add_processor(
+        proc_handle.clone(),
+        Arc::new(LoggingJoinProcessorFactory { log: log.clone() }),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(count * 2, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source1_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), NOOP_JOIN_LEFT_INPUT_PORT),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(source2_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), NOOP_JOIN_RIGHT_INPUT_PORT),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    // Commits are barriers that change the interleaving, so only commit once all is sent.
+    let options = ExecutorOptions {
+        commit_sz: u32::MAX,
+        commit_time_threshold: Duration::from_secs(3600),
+        deterministic_selection: true,
+        ..Default::default()
+    };
+    DagExecutor::new(dag, options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let log = log.lock().unwrap().clone();
+    log
+}
+
+#[test]
+fn test_deterministic_selection_fixes_interleaving() {
+    let count: u64 = 1_000;
+
+    let log = run_logged_join(count);
+    assert_eq!(log.len() as u64, count * 2);
+    // The ports are read strictly in turn, each delivering its records in order.
+    let first_port = log[0].0;
+    for (index, (port, key)) in log.iter().enumerate() {
+        if index % 2 == 0 {
+            assert_eq!(*port, first_port, "{index}");
+        } else {
+            assert_ne!(*port, first_port, "{index}");
+        }
+        assert_eq!(
+            *key,
+            Field::String(format!("key_{}", index / 2 + 1)),
+            "{index}"
+        );
+    }
+
+    assert_eq!(run_logged_join(count), log);
+}
@@ -0,0 +1,127 @@
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{PortHandle, Sink, SinkFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use crossbeam::channel::{unbounded, Receiver};
+use dozer_types::epoch::Epoch;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{Operation, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A sink that doesn't read anything until it is released.
+#[derive(Debug)]
+struct StalledSinkFactory {
+    release: Receiver<()>,
+}
+
+impl SinkFactory<NoneContext> for StalledSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(StalledSink {
+            release: self.release.clone(),
+            released: false,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct StalledSink {
+    release: Receiver<()>,
+    released: bool,
+}
+
+impl Sink for StalledSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(&mut self, _from_port: PortHandle, _op: Operation) -> Result<(), ExecutionError> {
+        if !self.released {
+            let _ = self.release.recv();
+            self.released = true;
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stalled_sink_drops_operations_instead_of_blocking() {
+    let count: u64 = 1_000;
+    let latch = Arc::new(AtomicBool::new(true));
+    let (release, released) = unbounded();
+
+    let mut dag = Dag::new();
+    let source_handle = NodeHandle::new(Some(1), "source".to_string());
+    let sink_handle = NodeHandle::new(Some(1), "sink".to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(StalledSinkFactory { release: released }),
+    );
+    dag.connect(
+        Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    // Commits still wait for room, so keep them out of the way of the stalled sink.
+    let options = ExecutorOptions {
+        channel_buffer_sz: 4,
+        commit_sz: count as u32 + 1,
+        commit_time_threshold: Duration::from_secs(60),
+        drop_when_full: [sink_handle.clone()].into_iter().collect(),
+        ..Default::default()
+    };
+    let handle = DagExecutor::new(dag, options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap();
+    let metrics = handle.metrics();
+
+    // The source gets through all its operations although the sink reads none of them.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while metrics.node(&source_handle).unwrap().records() < count {
+        assert!(Instant::now() < deadline, "the source is blocked");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let dropped = metrics.node(&sink_handle).unwrap().dropped().unwrap();
+    assert!(dropped > 0);
+    assert_eq!(metrics.node(&source_handle).unwrap().dropped(), None);
+
+    release.send(()).unwrap();
+    latch.store(false, Ordering::Relaxed);
+    handle.join().unwrap();
+
+    let sink_metrics = metrics.node(&sink_handle).unwrap();
+    assert_eq!(sink_metrics.dropped(), Some(dropped));
+    assert_eq!(sink_metrics.records() + dropped, count);
+    assert!(metrics.render_prometheus().contains(&format!(
+        "dozer_node_dropped_total{{node=\"{sink_handle}\"}} {dropped}\n"
+    )));
+}
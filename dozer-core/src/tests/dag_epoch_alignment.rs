@@ -0,0 +1,203 @@
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{PortHandle, Sink, SinkFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::dag_base_run::{
+    NoopJoinProcessorFactory, NOOP_JOIN_LEFT_INPUT_PORT, NOOP_JOIN_RIGHT_INPUT_PORT,
+};
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::Epoch;
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::types::{Operation, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug)]
+struct EpochRecordingSinkFactory {
+    expected: u64,
+    running: Arc<AtomicBool>,
+    epochs: Arc<Mutex<Vec<Epoch>>>,
+}
+
+impl SinkFactory<NoneContext> for EpochRecordingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(EpochRecordingSink {
+            expected: self.expected,
+            current: 0,
+            running: self.running.clone(),
+            epochs: self.epochs.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct EpochRecordingSink {
+    expected: u64,
+    current: u64,
+    running: Arc<AtomicBool>,
+    epochs: Arc<Mutex<Vec<Epoch>>>,
+}
+
+impl Sink for EpochRecordingSink {
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        self.epochs.lock().unwrap().push(epoch_details.clone());
+        Ok(())
+    }
+
+    fn process(&mut self, _from_port: PortHandle, _op: Operation) -> Result<(), ExecutionError> {
+        self.current += 1;
+        if self.current == self.expected {
+            self.running.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sink_commits_epochs_aligned_across_sources() {
+    let count: u64 = 5_000;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let epochs = Arc::new(Mutex::new(vec![]));
+
+    let source1_handle = NodeHandle::new(None, 1.to_string());
+    let source2_handle = NodeHandle::new(None, 2.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+
+    for source_handle in [&source1_handle, &source2_handle] {
+        dag.add_source(
+            source_handle.clone(),
+            Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+        );
+    }
+    dag.add_processor(proc_handle.clone(), Arc::new(NoopJoinProcessorFactory {}));
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(EpochRecordingSinkFactory {
+            expected: count * 2,
+            running: latch,
+            epochs: epochs.clone(),
+        }),
+    );
+
+    dag.connect(
+        Endpoint::new(source1_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), NOOP_JOIN_LEFT_INPUT_PORT),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(source2_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), NOOP_JOIN_RIGHT_INPUT_PORT),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    let options = ExecutorOptions {
+        commit_sz: 100,
+        commit_time_threshold: Duration::from_millis(5),
+        ..Default::default()
+    };
+    DagExecutor::new(dag, options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let epochs = epochs.lock().unwrap();
+    assert!(!epochs.is_empty());
+    for (expected_id, epoch) in epochs.iter().enumerate() {
+        // Every commit reaching the sink closes the same epoch on both sources.
+        assert_eq!(epoch.id, expected_id as u64);
+        assert_eq!(epoch.details.len(), 2, "{epoch}");
+        assert!(epoch.details.contains_key(&source1_handle), "{epoch}");
+        assert!(epoch.details.contains_key(&source2_handle), "{epoch}");
+    }
+
+    let last = epochs.last().unwrap();
+    for source_handle in [&source1_handle, &source2_handle] {
+        assert_eq!(last.details[source_handle], OpIdentifier::new(count, 0));
+    }
+}
+
+#[test]
+fn test_idle_source_commits_every_interval() {
+    let interval = Duration::from_millis(20);
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let epochs = Arc::new(Mutex::new(vec![]));
+
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 1.to_string());
+
+    // The source sends a single record, then idles until `latch` is cleared.
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(1, latch.clone(), false)),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(EpochRecordingSinkFactory {
+            expected: u64::MAX,
+            running: latch.clone(),
+            epochs: epochs.clone(),
+        }),
+    );
+    dag.connect(
+        Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle, DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    let options = ExecutorOptions {
+        commit_time_threshold: Duration::from_secs(60),
+        commit_interval: Some(interval),
+        ..Default::default()
+    };
+    let join_handle = DagExecutor::new(dag, options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap();
+    std::thread::sleep(interval * 25);
+    latch.store(false, Ordering::Relaxed);
+    join_handle.join().unwrap();
+
+    // Without the interval, the time threshold would hold back every commit until termination.
+    let record_position = OpIdentifier::new(1, 0);
+    let idle_commits = epochs
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|epoch| epoch.details[&source_handle] == record_position)
+        .count();
+    assert!(idle_commits >= 5, "only {idle_commits} commits");
+}
@@ -0,0 +1,109 @@
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::tests::app::NoneContext;
+use crate::tests::dag_base_run::NoopProcessorFactory;
+use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, DagLimits, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::node::NodeHandle;
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// A source feeding `fan_out` chains of `chain_len` processors, each ending in a sink.
+fn build_dag(fan_out: usize, chain_len: usize) -> Dag<NoneContext> {
+    let latch = Arc::new(AtomicBool::new(true));
+    let mut dag = Dag::new();
+    let source_handle = NodeHandle::new(None, "source".to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(1, latch.clone(), false)),
+    );
+
+    for branch in 0..fan_out {
+        let mut from = Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT);
+        for step in 0..chain_len {
+            let proc_handle = NodeHandle::new(None, format!("proc_{branch}_{step}"));
+            dag.add_processor(proc_handle.clone(), Arc::new(NoopProcessorFactory {}));
+            dag.connect(
+                from,
+                Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+            )
+            .unwrap();
+            from = Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE);
+        }
+        let sink_handle = NodeHandle::new(None, format!("sink_{branch}"));
+        dag.add_sink(
+            sink_handle.clone(),
+            Arc::new(CountingSinkFactory::new(1, latch.clone())),
+        );
+        dag.connect(from, Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT))
+            .unwrap();
+    }
+    dag
+}
+
+fn options(limits: DagLimits) -> ExecutorOptions {
+    ExecutorOptions {
+        limits,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_dag_within_limits_is_accepted() {
+    // 1 source, 3 chains of 2 processors and a sink: 10 nodes, 4 deep.
+    let limits = DagLimits {
+        max_nodes: Some(10),
+        max_edges_per_port: Some(3),
+        max_depth: Some(4),
+    };
+    assert!(build_dag(3, 2).validate(&limits).is_ok());
+    assert!(DagExecutor::new(build_dag(3, 2), options(limits)).is_ok());
+}
+
+#[test]
+fn test_dag_over_node_limit_is_rejected() {
+    let limits = DagLimits {
+        max_nodes: Some(9),
+        ..Default::default()
+    };
+    assert!(matches!(
+        DagExecutor::new(build_dag(3, 2), options(limits)),
+        Err(ExecutionError::TooManyNodes {
+            count: 10,
+            limit: 9
+        })
+    ));
+}
+
+#[test]
+fn test_dag_over_fan_out_limit_is_rejected() {
+    let limits = DagLimits {
+        max_edges_per_port: Some(2),
+        ..Default::default()
+    };
+    let Err(ExecutionError::TooManyEdgesFromPort {
+        node,
+        port,
+        count,
+        limit,
+    }) = DagExecutor::new(build_dag(3, 2), options(limits))
+    else {
+        panic!("the fan-out of the source must be rejected");
+    };
+    assert_eq!(node, NodeHandle::new(None, "source".to_string()));
+    assert_eq!((port, count, limit), (GENERATOR_SOURCE_OUTPUT_PORT, 3, 2));
+}
+
+#[test]
+fn test_dag_over_depth_limit_is_rejected() {
+    let limits = DagLimits {
+        max_depth: Some(4),
+        ..Default::default()
+    };
+    assert!(matches!(
+        build_dag(1, 3).validate(&limits),
+        Err(ExecutionError::DagTooDeep { depth: 5, limit: 4 })
+    ));
+}
@@ -0,0 +1,232 @@
+use crate::channels::ProcessorChannelForwarder;
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::dag_base_run::NoopProcessorFactory;
+use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::Epoch;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{Operation, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_metrics_snapshot_renders_node_series() {
+    let count: u64 = 1_000;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(proc_handle.clone(), Arc::new(NoopProcessorFactory {}));
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(count, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle.clone(), COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let handle = DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap();
+    // A running pipeline can be scraped.
+    assert!(handle
+        .metrics_snapshot()
+        .contains("# TYPE dozer_node_records_total counter"));
+    let metrics = handle.metrics();
+    handle.join().unwrap();
+
+    for node in [&source_handle, &proc_handle, &sink_handle] {
+        let node_metrics = metrics.node(node).unwrap();
+        assert_eq!(node_metrics.records(), count);
+        assert!(node_metrics.commits() > 0);
+    }
+
+    let text = metrics.render_prometheus();
+    for name in [
+        "dozer_node_records_total",
+        "dozer_node_commits_total",
+        "dozer_channel_occupancy",
+        "dozer_node_state_size",
+    ] {
+        assert!(text.contains(&format!("# HELP {name} ")), "{text}");
+    }
+    assert!(
+        text.contains(&format!(
+            "dozer_node_records_total{{node=\"{proc_handle}\"}} {count}\n"
+        )),
+        "{text}"
+    );
+    assert!(
+        text.contains(&format!(
+            "dozer_channel_occupancy{{node=\"{sink_handle}\",port=\"{COUNTING_SINK_INPUT_PORT}\"}} "
+        )),
+        "{text}"
+    );
+}
+
+const SLOW_PROCESSOR_DELAY: Duration = Duration::from_millis(2);
+
+/// Forwards every operation after sleeping for [`SLOW_PROCESSOR_DELAY`].
+#[derive(Debug)]
+struct SlowProcessorFactory;
+
+impl ProcessorFactory<NoneContext> for SlowProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        Ok(input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap().clone())
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(SlowProcessor))
+    }
+}
+
+#[derive(Debug)]
+struct SlowProcessor;
+
+impl Processor for SlowProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        std::thread::sleep(SLOW_PROCESSOR_DELAY);
+        fw.send(op, DEFAULT_PORT_HANDLE)
+    }
+}
+
+#[test]
+fn test_latency_histogram_tells_slow_processors_apart() {
+    let count: u64 = 50;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let fast_handle = NodeHandle::new(Some(1), 2.to_string());
+    let slow_handle = NodeHandle::new(Some(1), 3.to_string());
+    let fast_sink_handle = NodeHandle::new(Some(1), 4.to_string());
+    let slow_sink_handle = NodeHandle::new(Some(1), 5.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(fast_handle.clone(), Arc::new(NoopProcessorFactory {}));
+    dag.add_processor(slow_handle.clone(), Arc::new(SlowProcessorFactory));
+    dag.add_sink(
+        fast_sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(count, latch.clone())),
+    );
+    dag.add_sink(
+        slow_sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(count, latch)),
+    );
+
+    for (processor, sink) in [
+        (&fast_handle, fast_sink_handle),
+        (&slow_handle, slow_sink_handle),
+    ] {
+        dag.connect(
+            Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+            Endpoint::new(processor.clone(), DEFAULT_PORT_HANDLE),
+        )
+        .unwrap();
+        dag.connect(
+            Endpoint::new(processor.clone(), DEFAULT_PORT_HANDLE),
+            Endpoint::new(sink, COUNTING_SINK_INPUT_PORT),
+        )
+        .unwrap();
+    }
+
+    let handle = DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap();
+    let metrics = handle.metrics();
+    handle.join().unwrap();
+
+    let fast = metrics.node(&fast_handle).unwrap().latency();
+    let slow = metrics.node(&slow_handle).unwrap().latency();
+    assert_eq!(fast.count(), count);
+    assert_eq!(slow.count(), count);
+    assert!(slow.sum() >= SLOW_PROCESSOR_DELAY * count as u32);
+    // Sources don't process anything.
+    assert_eq!(metrics.node(&source_handle).unwrap().latency().count(), 0);
+
+    let (fast_p50, slow_p50) = (fast.percentile(0.5).unwrap(), slow.percentile(0.5).unwrap());
+    assert!(slow_p50 >= SLOW_PROCESSOR_DELAY, "{slow_p50:?}");
+    assert!(fast_p50 < slow_p50, "{fast_p50:?} vs {slow_p50:?}");
+    assert!(slow.percentile(0.99).unwrap() >= slow_p50);
+
+    let text = metrics.render_prometheus();
+    assert!(
+        text.contains("# TYPE dozer_node_process_seconds histogram"),
+        "{text}"
+    );
+    assert!(
+        text.contains(&format!(
+            "dozer_node_process_seconds_bucket{{node=\"{slow_handle}\",le=\"+Inf\"}} {count}\n"
+        )),
+        "{text}"
+    );
+    assert!(
+        text.contains(&format!(
+            "dozer_node_process_seconds_count{{node=\"{slow_handle}\"}} {count}\n"
+        )),
+        "{text}"
+    );
+    assert!(
+        !text.contains(&format!(
+            "dozer_node_process_seconds_count{{node=\"{source_handle}\"}}"
+        )),
+        "{text}"
+    );
+}
@@ -0,0 +1,223 @@
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{OutputPortDef, PortHandle, Processor, ProcessorFactory, Sink, SinkFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::dag_base_run::NoopProcessorFactory;
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::{Epoch, OpOrigin};
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::types::{Field, Operation, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+const ORIGIN_SINK_INPUT_PORT_1: PortHandle = 1;
+const ORIGIN_SINK_INPUT_PORT_2: PortHandle = 2;
+
+#[derive(Debug)]
+struct OriginRecordingSinkFactory {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    origins: Arc<Mutex<Vec<(Operation, Option<OpOrigin>)>>>,
+}
+
+impl SinkFactory<NoneContext> for OriginRecordingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![ORIGIN_SINK_INPUT_PORT_1, ORIGIN_SINK_INPUT_PORT_2]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(OriginRecordingSink {
+            expected: self.expected,
+            running: self.running.clone(),
+            origins: self.origins.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct OriginRecordingSink {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    origins: Arc<Mutex<Vec<(Operation, Option<OpOrigin>)>>>,
+}
+
+impl Sink for OriginRecordingSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(&mut self, from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        self.process_with_origin(from_port, op, None)
+    }
+
+    fn process_with_origin(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        origin: Option<&OpOrigin>,
+    ) -> Result<(), ExecutionError> {
+        let mut origins = self.origins.lock().unwrap();
+        origins.push((op, origin.cloned()));
+        if origins.len() == self.expected {
+            self.running.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+/// Forwards every operation, with its output held back until commit.
+#[derive(Debug)]
+struct AccumulatingProcessorFactory;
+
+impl ProcessorFactory<NoneContext> for AccumulatingProcessorFactory {
+    fn get_output_schema(
+        &self,
+        output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        NoopProcessorFactory {}.get_output_schema(output_port, input_schemas)
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        NoopProcessorFactory {}.get_input_ports()
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        NoopProcessorFactory {}.get_output_ports()
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        NoopProcessorFactory {}.build(input_schemas, output_schemas)
+    }
+
+    fn is_accumulating(&self) -> bool {
+        true
+    }
+}
+
+/// Runs `count` operations from each of two sources through a processor built by `factory` to a
+/// sink, returning the source handles and the operations the sink received with their origins.
+fn run_two_sources(
+    count: u64,
+    factory: impl Fn() -> Arc<dyn ProcessorFactory<NoneContext>>,
+) -> (Vec<NodeHandle>, Vec<(Operation, Option<OpOrigin>)>) {
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let origins = Arc::new(Mutex::new(vec![]));
+
+    let sink_handle = NodeHandle::new(Some(1), "sink".to_string());
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(OriginRecordingSinkFactory {
+            expected: 2 * count as usize,
+            running: latch.clone(),
+            origins: origins.clone(),
+        }),
+    );
+
+    let mut source_handles = vec![];
+    for (index, sink_port) in [ORIGIN_SINK_INPUT_PORT_1, ORIGIN_SINK_INPUT_PORT_2]
+        .into_iter()
+        .enumerate()
+    {
+        let source_handle = NodeHandle::new(Some(1), format!("source_{index}"));
+        let proc_handle = NodeHandle::new(Some(1), format!("proc_{index}"));
+        dag.add_source(
+            source_handle.clone(),
+            Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+        );
+        dag.add_processor(proc_handle.clone(), factory());
+        dag.connect(
+            Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+            Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+        )
+        .unwrap();
+        dag.connect(
+            Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+            Endpoint::new(sink_handle.clone(), sink_port),
+        )
+        .unwrap();
+        source_handles.push(source_handle);
+    }
+
+    DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let origins = origins.lock().unwrap().clone();
+    (source_handles, origins)
+}
+
+#[test]
+fn test_sink_observes_increasing_positions_per_source() {
+    let count: u64 = 1_000;
+    let (source_handles, origins) = run_two_sources(count, || Arc::new(NoopProcessorFactory {}));
+
+    // Operations of each source arrive in order, through the processors.
+    let mut last_seen: HashMap<NodeHandle, OpIdentifier> = HashMap::new();
+    let mut seen_per_source: HashMap<NodeHandle, u64> = HashMap::new();
+    for (_, origin) in origins.iter() {
+        let origin = origin.as_ref().expect("origin must be known");
+        if let Some(last) = last_seen.get(origin.source.as_ref()) {
+            assert!(origin.id >= *last, "{:?} after {:?}", origin.id, last);
+        }
+        last_seen.insert(origin.source.as_ref().clone(), origin.id);
+        *seen_per_source
+            .entry(origin.source.as_ref().clone())
+            .or_default() += 1;
+    }
+    for source_handle in source_handles {
+        assert_eq!(seen_per_source[&source_handle], count);
+    }
+}
+
+#[test]
+fn test_accumulating_processor_keeps_the_origin_of_each_operation() {
+    let count: u64 = 1_000;
+    let (source_handles, origins) =
+        run_two_sources(count, || Arc::new(AccumulatingProcessorFactory));
+
+    // Output held back until commit still carries the position of the operation it came from,
+    // the generator writing record `key_n` at transaction `n`.
+    let mut seen_per_source: HashMap<NodeHandle, u64> = HashMap::new();
+    for (op, origin) in origins.iter() {
+        let origin = origin.as_ref().expect("origin must be known");
+        let Operation::Insert { new } = op else {
+            panic!("unexpected operation {op:?}");
+        };
+        assert_eq!(
+            new.values[0],
+            Field::String(format!("key_{}", origin.id.txid))
+        );
+        *seen_per_source
+            .entry(origin.source.as_ref().clone())
+            .or_default() += 1;
+    }
+    for source_handle in source_handles {
+        assert_eq!(seen_per_source[&source_handle], count);
+    }
+}
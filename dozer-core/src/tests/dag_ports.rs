@@ -1,9 +1,10 @@
 use crate::errors::ExecutionError;
 use crate::node::{
-    OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory, Source, SourceFactory,
+    OutputPortDef, OutputPortType, Port, PortHandle, Processor, ProcessorFactory, Source,
+    SourceFactory,
 };
 use crate::tests::app::NoneContext;
-use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use crate::{Dag, Edge, Endpoint, DEFAULT_PORT_HANDLE};
 use dozer_types::{node::NodeHandle, types::Schema};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -147,3 +148,140 @@ test_ports!(
     2,
     false
 );
+
+#[test]
+fn test_multi_port_processor_rejects_default_port() {
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 1.to_string());
+
+    let mut dag = Dag::new();
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(DynPortsSourceFactory::new(vec![DEFAULT_PORT_HANDLE])),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Arc::new(DynPortsProcessorFactory::new(
+            vec![DEFAULT_PORT_HANDLE, 1],
+            vec![DEFAULT_PORT_HANDLE],
+        )),
+    );
+
+    let res = dag.connect(
+        Endpoint::new(source_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    );
+    assert!(matches!(
+        res,
+        Err(ExecutionError::DefaultPortOnMultiPortNode { count: 2, .. })
+    ));
+
+    // The explicitly numbered port can still be connected.
+    dag.connect(
+        Endpoint::new(source_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(proc_handle, 1),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_connect_all_chains_single_ports() {
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let first_handle = NodeHandle::new(Some(1), 1.to_string());
+    let second_handle = NodeHandle::new(Some(1), 2.to_string());
+
+    let mut dag = Dag::new();
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(DynPortsSourceFactory::new(vec![1])),
+    );
+    dag.add_processor(
+        first_handle.clone(),
+        Arc::new(DynPortsProcessorFactory::new(vec![2], vec![3])),
+    );
+    dag.add_processor(
+        second_handle.clone(),
+        Arc::new(DynPortsProcessorFactory::new(
+            vec![4],
+            vec![DEFAULT_PORT_HANDLE],
+        )),
+    );
+
+    dag.connect_all(&[
+        source_handle.clone(),
+        first_handle.clone(),
+        second_handle.clone(),
+    ])
+    .unwrap();
+
+    let mut edges = dag.edge_handles();
+    edges.sort_by_key(|edge| edge.from.port);
+    assert_eq!(
+        edges,
+        vec![
+            Edge::new(
+                Endpoint::new(source_handle, 1),
+                Endpoint::new(first_handle.clone(), 2)
+            ),
+            Edge::new(
+                Endpoint::new(first_handle, 3),
+                Endpoint::new(second_handle, 4)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_connect_all_rejects_ambiguous_ports() {
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let join_handle = NodeHandle::new(Some(1), 1.to_string());
+    let last_handle = NodeHandle::new(Some(1), 2.to_string());
+
+    let mut dag = Dag::new();
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(DynPortsSourceFactory::new(vec![1])),
+    );
+    dag.add_processor(
+        join_handle.clone(),
+        Arc::new(DynPortsProcessorFactory::new(vec![2, 3], vec![4])),
+    );
+    dag.add_processor(
+        last_handle.clone(),
+        Arc::new(DynPortsProcessorFactory::new(vec![5], vec![6])),
+    );
+
+    let res = dag.connect_all(&[source_handle.clone(), join_handle.clone(), last_handle]);
+    assert!(matches!(
+        res,
+        Err(ExecutionError::AmbiguousPorts {
+            node,
+            direction: "input",
+            count: 2,
+        }) if node == join_handle
+    ));
+    assert!(dag.edge_handles().is_empty());
+
+    // The node can still be connected explicitly.
+    dag.connect(
+        Endpoint::new(source_handle, 1),
+        Endpoint::new(join_handle, 2),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_port_conversions() {
+    const LEFT: Port = Port::numbered(0);
+
+    assert_eq!(Port::from(DEFAULT_PORT_HANDLE), Port::Default);
+    assert_eq!(Port::from(0), LEFT);
+    assert_eq!(PortHandle::from(Port::Default), DEFAULT_PORT_HANDLE);
+    assert_eq!(LEFT.handle(), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_numbered_port_rejects_default_handle() {
+    Port::numbered(DEFAULT_PORT_HANDLE);
+}
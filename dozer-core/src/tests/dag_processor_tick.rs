@@ -0,0 +1,162 @@
+use crate::channels::ProcessorChannelForwarder;
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::Epoch;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{Field, Operation, Record, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Emits a record on its third tick, and records when it did.
+#[derive(Debug)]
+struct TimerProcessorFactory {
+    fired_at: Arc<Mutex<Option<Instant>>>,
+}
+
+impl ProcessorFactory<NoneContext> for TimerProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        Ok(input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap().clone())
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(TimerProcessor {
+            ticks: 0,
+            fired_at: self.fired_at.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct TimerProcessor {
+    ticks: u64,
+    fired_at: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Processor for TimerProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        fw.send(op, DEFAULT_PORT_HANDLE)
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        Some(TICK_INTERVAL)
+    }
+
+    fn on_tick(
+        &mut self,
+        now: Instant,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        self.ticks += 1;
+        if self.ticks == 3 {
+            *self.fired_at.lock().unwrap() = Some(now);
+            fw.send(
+                Operation::Insert {
+                    new: Record::new(
+                        None,
+                        vec![
+                            Field::String("timer".to_string()),
+                            Field::String("fired".to_string()),
+                        ],
+                    ),
+                },
+                DEFAULT_PORT_HANDLE,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_processor_ticks_without_input() {
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let fired_at = Arc::new(Mutex::new(None));
+
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    // The source sends nothing, and the sink stops it once the timer record arrives.
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(0, latch.clone(), false)),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Arc::new(TimerProcessorFactory {
+            fired_at: fired_at.clone(),
+        }),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(1, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let started_at = Instant::now();
+    DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let elapsed = fired_at
+        .lock()
+        .unwrap()
+        .expect("the third tick fired")
+        .duration_since(started_at);
+    assert!(elapsed >= 3 * TICK_INTERVAL, "fired after {elapsed:?}");
+    assert!(
+        elapsed < 3 * TICK_INTERVAL + Duration::from_secs(1),
+        "fired after {elapsed:?}"
+    );
+}
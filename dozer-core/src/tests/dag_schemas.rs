@@ -1,5 +1,6 @@
 use crate::dag_schemas::{DagHaveSchemas, DagSchemas};
 use crate::errors::ExecutionError;
+use crate::executor::DagExecutor;
 use crate::node::{
     OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory, SinkFactory, Source,
     SourceFactory,
@@ -253,3 +254,164 @@ fn test_extract_dag_schemas() {
         5
     );
 }
+
+/// Passes its input through, but only if it has a `country_name` field.
+#[derive(Debug)]
+struct TestCountryNameProcessorFactory {}
+
+impl ProcessorFactory<NoneContext> for TestCountryNameProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        let (schema, _) = input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap();
+        Ok((schema.clone(), NoneContext {}))
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn prepare(
+        &self,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<HashMap<PortHandle, (Schema, NoneContext)>, ExecutionError> {
+        let (schema, _) = input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap();
+        if !schema.fields.iter().any(|f| f.name == "country_name") {
+            return Err(ExecutionError::InvalidOperation(
+                "Input has no country_name field".to_string(),
+            ));
+        }
+        Ok(HashMap::from([(
+            DEFAULT_PORT_HANDLE,
+            self.get_output_schema(&DEFAULT_PORT_HANDLE, input_schemas)?,
+        )]))
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        todo!()
+    }
+}
+
+fn country_name_dag(source: Arc<dyn SourceFactory<NoneContext>>) -> Dag<NoneContext> {
+    let mut dag = Dag::new();
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(source_handle.clone(), source);
+    dag.add_processor(
+        proc_handle.clone(),
+        Arc::new(TestCountryNameProcessorFactory {}),
+    );
+    dag.add_sink(sink_handle.clone(), Arc::new(TestSinkFactory {}));
+
+    chk!(dag.connect(
+        Endpoint::new(source_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    ));
+    chk!(dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, DEFAULT_PORT_HANDLE),
+    ));
+    dag
+}
+
+#[test]
+fn test_processor_prepare_rejects_input_schema() {
+    let dag = country_name_dag(Arc::new(TestCountriesSourceFactory {}));
+    chk!(DagExecutor::validate(dag));
+
+    let dag = country_name_dag(Arc::new(TestUsersSourceFactory {}));
+    assert!(matches!(
+        DagExecutor::validate(dag),
+        Err(ExecutionError::InvalidOperation(_))
+    ));
+}
+
+/// Declares two output ports but only prepares a schema for one of them.
+#[derive(Debug)]
+struct TestMissingPortProcessorFactory {}
+
+impl ProcessorFactory<NoneContext> for TestMissingPortProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        let (schema, _) = input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap();
+        Ok((schema.clone(), NoneContext {}))
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![
+            OutputPortDef::new(DEFAULT_PORT_HANDLE, OutputPortType::Stateless),
+            OutputPortDef::new(1, OutputPortType::Stateless),
+        ]
+    }
+
+    fn prepare(
+        &self,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<HashMap<PortHandle, (Schema, NoneContext)>, ExecutionError> {
+        Ok(HashMap::from([(
+            DEFAULT_PORT_HANDLE,
+            self.get_output_schema(&DEFAULT_PORT_HANDLE, input_schemas)?,
+        )]))
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        todo!()
+    }
+}
+
+#[test]
+fn test_processor_prepare_must_cover_every_output_port() {
+    let mut dag = Dag::new();
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(source_handle.clone(), Arc::new(TestUsersSourceFactory {}));
+    dag.add_processor(
+        proc_handle.clone(),
+        Arc::new(TestMissingPortProcessorFactory {}),
+    );
+    dag.add_sink(sink_handle.clone(), Arc::new(TestSinkFactory {}));
+
+    chk!(dag.connect(
+        Endpoint::new(source_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    ));
+    chk!(dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, DEFAULT_PORT_HANDLE),
+    ));
+
+    assert!(matches!(
+        DagSchemas::new(dag),
+        Err(ExecutionError::MissingOutputSchema { port: 1, .. })
+    ));
+}
@@ -0,0 +1,130 @@
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{PortHandle, Sink, SinkFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::dag_base_run::NoopProcessorFactory;
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::{Epoch, OpOrigin};
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{format_operation, Operation, Schema, SchemaRef};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Renders every operation it receives with the schema attached to it.
+#[derive(Debug)]
+struct RenderingSinkFactory {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    rendered: Arc<Mutex<Vec<String>>>,
+}
+
+impl SinkFactory<NoneContext> for RenderingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        // Deliberately ignores the schemas given at build time.
+        Ok(Box::new(RenderingSink {
+            expected: self.expected,
+            running: self.running.clone(),
+            rendered: self.rendered.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct RenderingSink {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    rendered: Arc<Mutex<Vec<String>>>,
+}
+
+impl Sink for RenderingSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(&mut self, _from_port: PortHandle, _op: Operation) -> Result<(), ExecutionError> {
+        unreachable!("the executor always calls process_with_schema")
+    }
+
+    fn process_with_schema(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        _origin: Option<&OpOrigin>,
+        schema: &SchemaRef,
+    ) -> Result<(), ExecutionError> {
+        let mut rendered = self.rendered.lock().unwrap();
+        rendered.push(format_operation(&op, schema));
+        if rendered.len() == self.expected {
+            self.running.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sink_renders_operations_with_attached_schema() {
+    let count: u64 = 10;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let rendered = Arc::new(Mutex::new(vec![]));
+
+    let source_handle = NodeHandle::new(Some(1), "source".to_string());
+    let proc_handle = NodeHandle::new(Some(1), "proc".to_string());
+    let sink_handle = NodeHandle::new(Some(1), "sink".to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(proc_handle.clone(), Arc::new(NoopProcessorFactory {}));
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(RenderingSinkFactory {
+            expected: count as usize,
+            running: latch,
+            rendered: rendered.clone(),
+        }),
+    );
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let rendered = rendered.lock().unwrap();
+    assert_eq!(rendered.len(), count as usize);
+    assert_eq!(rendered[0], r#"INSERT {id: "key_1", value: "value_1"}"#);
+}
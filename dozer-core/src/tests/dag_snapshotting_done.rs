@@ -0,0 +1,273 @@
+use crate::channels::{ProcessorChannelForwarder, SourceChannelForwarder};
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{
+    OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory, Sink, SinkFactory,
+    Source, SourceFactory,
+};
+use crate::tests::app::NoneContext;
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::Epoch;
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+/// What a node saw, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    Op,
+    SnapshottingDone,
+}
+
+type Events = Arc<Mutex<Vec<Event>>>;
+
+/// Sends `snapshot` operations, signals the end of its snapshot, then sends `stream` operations.
+#[derive(Debug)]
+struct SnapshottingSourceFactory {
+    snapshot: u64,
+    stream: u64,
+}
+
+impl SourceFactory<NoneContext> for SnapshottingSourceFactory {
+    fn get_output_schema(
+        &self,
+        _port: &PortHandle,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        Ok((
+            Schema::empty()
+                .field(
+                    FieldDefinition::new(
+                        "id".to_string(),
+                        FieldType::UInt,
+                        false,
+                        SourceDefinition::Dynamic,
+                    ),
+                    true,
+                )
+                .clone(),
+            NoneContext {},
+        ))
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, ExecutionError> {
+        Ok(Box::new(SnapshottingSource {
+            snapshot: self.snapshot,
+            stream: self.stream,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct SnapshottingSource {
+    snapshot: u64,
+    stream: u64,
+}
+
+impl Source for SnapshottingSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
+        Ok(false)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        _last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), ExecutionError> {
+        let insert = |n: u64| {
+            IngestionMessage::new_op(
+                n,
+                0,
+                Operation::Insert {
+                    new: Record::new(None, vec![Field::UInt(n)]),
+                },
+            )
+        };
+        for n in 0..self.snapshot {
+            fw.send(insert(n), DEFAULT_PORT_HANDLE)?;
+        }
+        fw.send(
+            IngestionMessage::new_snapshotting_done(self.snapshot, 0),
+            DEFAULT_PORT_HANDLE,
+        )?;
+        for n in self.snapshot + 1..=self.snapshot + self.stream {
+            fw.send(insert(n), DEFAULT_PORT_HANDLE)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct EventLoggingProcessorFactory {
+    events: Events,
+}
+
+impl ProcessorFactory<NoneContext> for EventLoggingProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        Ok(input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap().clone())
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(EventLoggingProcessor {
+            events: self.events.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct EventLoggingProcessor {
+    events: Events,
+}
+
+impl Processor for EventLoggingProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        self.events.lock().unwrap().push(Event::Op);
+        fw.send(op, DEFAULT_PORT_HANDLE)
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        self.events.lock().unwrap().push(Event::SnapshottingDone);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct EventLoggingSinkFactory {
+    events: Events,
+}
+
+impl SinkFactory<NoneContext> for EventLoggingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(EventLoggingSink {
+            events: self.events.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct EventLoggingSink {
+    events: Events,
+}
+
+impl Sink for EventLoggingSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(&mut self, _from_port: PortHandle, _op: Operation) -> Result<(), ExecutionError> {
+        self.events.lock().unwrap().push(Event::Op);
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        self.events.lock().unwrap().push(Event::SnapshottingDone);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_snapshotting_done_propagates_through_processors() {
+    let (snapshot, stream): (u64, u64) = (20, 10);
+
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+    let processor_events = Events::default();
+    let sink_events = Events::default();
+
+    let mut dag = Dag::new();
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(SnapshottingSourceFactory { snapshot, stream }),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Arc::new(EventLoggingProcessorFactory {
+            events: processor_events.clone(),
+        }),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(EventLoggingSinkFactory {
+            events: sink_events.clone(),
+        }),
+    );
+    dag.connect_all(&[source_handle, proc_handle, sink_handle])
+        .unwrap();
+
+    DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    // Both nodes see the signal between the snapshot and the stream.
+    let expected = std::iter::repeat(Event::Op)
+        .take(snapshot as usize)
+        .chain([Event::SnapshottingDone])
+        .chain(std::iter::repeat(Event::Op).take(stream as usize))
+        .collect::<Vec<_>>();
+    assert_eq!(*processor_events.lock().unwrap(), expected);
+    assert_eq!(*sink_events.lock().unwrap(), expected);
+}
@@ -0,0 +1,251 @@
+use crate::channels::SourceChannelForwarder;
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{
+    OutputPortDef, OutputPortType, PortHandle, Sink, SinkFactory, Source, SourceFactory,
+};
+use crate::tests::app::NoneContext;
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::{Epoch, OpOrigin};
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Where the source persists its position, surviving the pipeline.
+type OffsetStore = Arc<Mutex<Option<(u64, u64)>>>;
+
+/// A source that resumes after the position in its store rather than the executor's checkpoint,
+/// and sends until the pipeline stops.
+#[derive(Debug)]
+struct ResumingSourceFactory {
+    store: OffsetStore,
+}
+
+impl SourceFactory<NoneContext> for ResumingSourceFactory {
+    fn get_output_schema(
+        &self,
+        _port: &PortHandle,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        Ok((
+            Schema::empty()
+                .field(
+                    FieldDefinition::new(
+                        "id".to_string(),
+                        FieldType::UInt,
+                        false,
+                        SourceDefinition::Dynamic,
+                    ),
+                    true,
+                )
+                .clone(),
+            NoneContext {},
+        ))
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, ExecutionError> {
+        Ok(Box::new(ResumingSource {
+            store: self.store.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct ResumingSource {
+    store: OffsetStore,
+}
+
+impl Source for ResumingSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
+        Ok(false)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        _last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), ExecutionError> {
+        let start = self.store.lock().unwrap().map_or(0, |(txid, _)| txid + 1);
+        for txid in start.. {
+            fw.send(
+                IngestionMessage::new_op(
+                    txid,
+                    0,
+                    Operation::Insert {
+                        new: Record::new(None, vec![Field::UInt(txid)]),
+                    },
+                ),
+                DEFAULT_PORT_HANDLE,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn on_terminate(&self, last_committed: Option<(u64, u64)>) -> Result<(), ExecutionError> {
+        *self.store.lock().unwrap() = last_committed;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Observed {
+    first: Option<OpIdentifier>,
+    ops: usize,
+    committed: Option<OpIdentifier>,
+}
+
+/// Stops the pipeline after `stop_after` operations, recording what it saw.
+#[derive(Debug)]
+struct StoppingSinkFactory {
+    stop_after: usize,
+    running: Arc<AtomicBool>,
+    observed: Arc<Mutex<Observed>>,
+}
+
+impl SinkFactory<NoneContext> for StoppingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(StoppingSink {
+            stop_after: self.stop_after,
+            running: self.running.clone(),
+            observed: self.observed.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct StoppingSink {
+    stop_after: usize,
+    running: Arc<AtomicBool>,
+    observed: Arc<Mutex<Observed>>,
+}
+
+impl Sink for StoppingSink {
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        let mut observed = self.observed.lock().unwrap();
+        observed.committed = epoch_details.details.values().max().copied();
+        Ok(())
+    }
+
+    fn process(&mut self, from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        self.process_with_origin(from_port, op, None)
+    }
+
+    fn process_with_origin(
+        &mut self,
+        _from_port: PortHandle,
+        _op: Operation,
+        origin: Option<&OpOrigin>,
+    ) -> Result<(), ExecutionError> {
+        let mut observed = self.observed.lock().unwrap();
+        if observed.first.is_none() {
+            observed.first = origin.map(|origin| origin.id);
+        }
+        observed.ops += 1;
+        if observed.ops == self.stop_after {
+            self.running.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+/// Runs the source into a sink until the sink stops the pipeline.
+fn run(store: &OffsetStore) -> Observed {
+    let running = Arc::new(AtomicBool::new(true));
+    let observed = Arc::new(Mutex::new(Observed::default()));
+
+    let mut dag = Dag::new();
+    let source_handle = NodeHandle::new(Some(1), "source".to_string());
+    let sink_handle = NodeHandle::new(Some(1), "sink".to_string());
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(ResumingSourceFactory {
+            store: store.clone(),
+        }),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(StoppingSinkFactory {
+            stop_after: 100,
+            running: running.clone(),
+            observed: observed.clone(),
+        }),
+    );
+    dag.connect(
+        Endpoint::new(source_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(running)
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let mut observed = observed.lock().unwrap();
+    std::mem::take(&mut *observed)
+}
+
+#[test]
+fn test_source_records_last_committed_position_on_terminate() {
+    let store = OffsetStore::default();
+
+    let first_run = run(&store);
+    assert_eq!(first_run.first, Some(OpIdentifier::new(0, 0)));
+    let committed = first_run
+        .committed
+        .expect("the pipeline must commit on terminate");
+    // The sender thread outlives the executor, so wait for the hook to run.
+    let persisted = wait_for_persisted(&store);
+    assert_eq!(persisted, (committed.txid, committed.seq_in_tx));
+
+    let second_run = run(&store);
+    assert_eq!(
+        second_run.first,
+        Some(OpIdentifier::new(committed.txid + 1, 0))
+    );
+}
+
+fn wait_for_persisted(store: &OffsetStore) -> (u64, u64) {
+    for _ in 0..100 {
+        if let Some(persisted) = *store.lock().unwrap() {
+            return persisted;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    panic!("the source never recorded its position");
+}
@@ -0,0 +1,133 @@
+use crate::channels::ProcessorChannelForwarder;
+use crate::errors::ExecutionError;
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory};
+use crate::tests::app::NoneContext;
+use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::epoch::Epoch;
+use dozer_types::node::NodeHandle;
+use dozer_types::types::{Operation, Schema};
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Forwards operations, recording the name of the thread processing them.
+#[derive(Debug)]
+struct ThreadNameProcessorFactory {
+    names: Arc<Mutex<HashSet<Option<String>>>>,
+}
+
+impl ProcessorFactory<NoneContext> for ThreadNameProcessorFactory {
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, NoneContext)>,
+    ) -> Result<(Schema, NoneContext), ExecutionError> {
+        Ok(input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap().clone())
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(ThreadNameProcessor {
+            names: self.names.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct ThreadNameProcessor {
+    names: Arc<Mutex<HashSet<Option<String>>>>,
+}
+
+impl Processor for ThreadNameProcessor {
+    fn commit(&self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        self.names
+            .lock()
+            .unwrap()
+            .insert(thread::current().name().map(ToString::to_string));
+        fw.send(op, DEFAULT_PORT_HANDLE)
+    }
+}
+
+#[test]
+fn test_node_threads_are_named_after_nodes() {
+    let count: u64 = 100;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let names = Arc::new(Mutex::new(HashSet::new()));
+
+    let source_handle = NodeHandle::new(Some(1), "source".to_string());
+    let proc_handle = NodeHandle::new(Some(1), "orders".to_string());
+    let sink_handle = NodeHandle::new(Some(1), "sink".to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Arc::new(ThreadNameProcessorFactory {
+            names: names.clone(),
+        }),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(count, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    // Pinning is best effort, so this runs even where core 0 can't be used.
+    let options = ExecutorOptions {
+        core_affinity: [(proc_handle.clone(), 0)].into_iter().collect(),
+        ..Default::default()
+    };
+    DagExecutor::new(dag, options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(
+        *names.lock().unwrap(),
+        HashSet::from([Some(format!("processor:{proc_handle}"))])
+    );
+}
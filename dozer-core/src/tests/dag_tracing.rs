@@ -0,0 +1,121 @@
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::tests::dag_base_run::NoopProcessorFactory;
+use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
+use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_types::node::NodeHandle;
+use dozer_types::tracing::field::{Field, Visit};
+use dozer_types::tracing::span::{Attributes, Id, Record};
+use dozer_types::tracing::{dispatcher, Dispatch, Event, Metadata, Subscriber};
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Records the name and `node` field of every span opened.
+#[derive(Debug, Default)]
+struct SpanRecorder {
+    next_id: AtomicU64,
+    spans: Arc<Mutex<Vec<(String, Option<String>)>>>,
+}
+
+#[derive(Default)]
+struct NodeFieldVisitor(Option<String>);
+
+impl Visit for NodeFieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "node" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+impl Subscriber for SpanRecorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut visitor = NodeFieldVisitor::default();
+        span.record(&mut visitor);
+        self.spans
+            .lock()
+            .unwrap()
+            .push((span.metadata().name().to_string(), visitor.0));
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn test_run_dag_opens_a_span_per_node() {
+    let count: u64 = 1_000;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(count, latch.clone(), false)),
+    );
+    dag.add_processor(proc_handle.clone(), Arc::new(NoopProcessorFactory {}));
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(count, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    dag.connect(
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle.clone(), COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let recorder = SpanRecorder::default();
+    let spans = recorder.spans.clone();
+    dispatcher::with_default(&Dispatch::new(recorder), || {
+        DagExecutor::new(dag, ExecutorOptions::default())
+            .unwrap()
+            .start(Arc::new(AtomicBool::new(true)))
+            .unwrap()
+            .join()
+            .unwrap();
+    });
+
+    let spans = spans.lock().unwrap();
+    for (name, handle) in [
+        ("source_sender", &source_handle),
+        ("source_listener", &source_handle),
+        ("processor", &proc_handle),
+        ("sink", &sink_handle),
+    ] {
+        let opened = spans
+            .iter()
+            .filter(|(span_name, node)| {
+                span_name == name && node.as_deref() == Some(handle.to_string().as_str())
+            })
+            .count();
+        assert_eq!(opened, 1, "expected one {name} span for node {handle}");
+    }
+
+    // Commits are traced too.
+    assert!(spans.iter().any(|(name, _)| name == "commit"));
+}
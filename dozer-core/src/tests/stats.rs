@@ -0,0 +1,68 @@
+use crate::stats::{ColumnStats, StatsCollector};
+use dozer_types::types::{Field, Operation, Record};
+
+fn record(id: i64, name: Option<&str>) -> Record {
+    Record::new(
+        None,
+        vec![
+            Field::Int(id),
+            name.map_or(Field::Null, |name| Field::String(name.to_string())),
+        ],
+    )
+}
+
+#[test]
+fn test_stats_collector_tracks_min_max_and_nulls() {
+    let mut collector = StatsCollector::default();
+    for op in [
+        Operation::Insert {
+            new: record(3, Some("b")),
+        },
+        Operation::Insert {
+            new: record(1, None),
+        },
+        Operation::Update {
+            old: record(1, None),
+            new: record(7, Some("a")),
+        },
+        // Deletes write no values.
+        Operation::Delete {
+            old: record(-5, Some("z")),
+        },
+        Operation::Insert {
+            new: record(2, None),
+        },
+    ] {
+        collector.observe(&op);
+    }
+
+    assert_eq!(
+        collector.take(),
+        vec![
+            ColumnStats {
+                min: Some(Field::Int(1)),
+                max: Some(Field::Int(7)),
+                null_count: 0,
+            },
+            ColumnStats {
+                min: Some(Field::String("a".to_string())),
+                max: Some(Field::String("b".to_string())),
+                null_count: 2,
+            },
+        ]
+    );
+
+    // Taking resets the collector for the next commit.
+    assert!(collector.take().is_empty());
+    collector.observe(&Operation::Insert {
+        new: record(9, None),
+    });
+    assert_eq!(
+        collector.take()[1],
+        ColumnStats {
+            min: None,
+            max: None,
+            null_count: 1,
+        }
+    );
+}
@@ -18,7 +18,7 @@ pub fn map_executor_operation<'a, C: Context<'a>>(
     let result = cx.empty_object();
 
     match op {
-        ExecutorOperation::Op { op } => {
+        ExecutorOperation::Op { op, .. } => {
             let op = map_operation(op, schema, cx)?;
             let typ = cx.string("op");
             result.set(cx, "type", typ)?;
@@ -36,6 +36,11 @@ pub fn map_executor_operation<'a, C: Context<'a>>(
             let typ = cx.string("terminate");
             result.set(cx, "type", typ)?;
         }
+        ExecutorOperation::Watermark { .. } => {
+            let typ = cx.string("watermark");
+            result.set(cx, "type", typ)?;
+        }
+        ExecutorOperation::Batch { .. } => unreachable!("Batches are never persisted"),
     }
 
     Ok(result)
@@ -15,7 +15,7 @@ pub fn map_executor_operation(
     let result = PyDict::new(py);
 
     match op {
-        ExecutorOperation::Op { op } => {
+        ExecutorOperation::Op { op, .. } => {
             result.set_item("type", "op")?;
             result.set_item("op", map_op(op, schema, py)?)?;
         }
@@ -28,6 +28,10 @@ pub fn map_executor_operation(
         ExecutorOperation::Terminate => {
             result.set_item("type", "terminate")?;
         }
+        ExecutorOperation::Watermark { .. } => {
+            result.set_item("type", "watermark")?;
+        }
+        ExecutorOperation::Batch { .. } => unreachable!("Batches are never persisted"),
     }
 
     Ok(result.into())
@@ -0,0 +1,128 @@
+use std::fmt::Debug;
+use std::io::{ErrorKind, Read};
+
+use dozer_types::bincode;
+use dozer_types::epoch::ExecutorOperation;
+
+use crate::errors::{EncodingError, ReaderError};
+
+/// The payload codec of a log frame.
+///
+/// A frame is `[len: u64 LE][FRAME_MAGIC: u8][format: u8][payload]`, where `len` counts
+/// everything after it. Readers pick the decoder from the format byte, so a log can mix formats.
+///
+/// Logs written before formats existed hold `[len: u64 LE][bincode payload]` frames. These are
+/// still read, see [`decode_frame_body`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum LogFormat {
+    #[default]
+    Bincode = 0,
+    MessagePack = 1,
+}
+
+impl LogFormat {
+    pub fn encoder(self) -> &'static dyn LogEncoder {
+        match self {
+            LogFormat::Bincode => &BincodeEncoder,
+            LogFormat::MessagePack => &MessagePackEncoder,
+        }
+    }
+}
+
+impl TryFrom<u8> for LogFormat {
+    type Error = EncodingError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(LogFormat::Bincode),
+            1 => Ok(LogFormat::MessagePack),
+            _ => Err(EncodingError::UnknownFormat(value)),
+        }
+    }
+}
+
+/// First byte of the body of a frame carrying a format byte.
+///
+/// The body of a legacy frame is a bare bincode [`ExecutorOperation`], which starts with its
+/// variant index as a `u32` LE, so its first byte is never this one.
+pub const FRAME_MAGIC: u8 = 0xD0;
+
+pub trait LogEncoder: Send + Sync + Debug {
+    fn encode(&self, op: &ExecutorOperation) -> Result<Vec<u8>, EncodingError>;
+    fn decode(&self, bytes: &[u8]) -> Result<ExecutorOperation, EncodingError>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BincodeEncoder;
+
+impl LogEncoder for BincodeEncoder {
+    fn encode(&self, op: &ExecutorOperation) -> Result<Vec<u8>, EncodingError> {
+        Ok(bincode::serialize(op)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<ExecutorOperation, EncodingError> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePackEncoder;
+
+impl LogEncoder for MessagePackEncoder {
+    fn encode(&self, op: &ExecutorOperation) -> Result<Vec<u8>, EncodingError> {
+        Ok(rmp_serde::to_vec_named(op)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<ExecutorOperation, EncodingError> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+}
+
+/// Encodes `op` as a whole frame, length prefix included.
+pub fn encode_frame(op: &ExecutorOperation, format: LogFormat) -> Result<Vec<u8>, EncodingError> {
+    let payload = format.encoder().encode(op)?;
+    let mut frame = Vec::with_capacity(8 + 2 + payload.len());
+    frame.extend_from_slice(&(payload.len() as u64 + 2).to_le_bytes());
+    frame.push(FRAME_MAGIC);
+    frame.push(format as u8);
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// Decodes a frame without its length prefix, either one written by [`encode_frame`] or a legacy
+/// bincode one.
+pub fn decode_frame_body(body: &[u8]) -> Result<ExecutorOperation, EncodingError> {
+    match body {
+        [] => Err(EncodingError::EmptyFrame),
+        [FRAME_MAGIC, format, payload @ ..] => {
+            LogFormat::try_from(*format)?.encoder().decode(payload)
+        }
+        [FRAME_MAGIC] => Err(EncodingError::EmptyFrame),
+        legacy => BincodeEncoder.decode(legacy),
+    }
+}
+
+/// Reads and decodes the next frame from `reader`, or returns `None` at the end of the log.
+///
+/// Only one frame is held in memory at a time. A frame cut short by the end of the log, as left
+/// by a writer that hasn't flushed yet or by a crash, counts as the end.
+pub fn read_frame(reader: &mut impl Read) -> Result<Option<ExecutorOperation>, ReaderError> {
+    let mut len = [0; 8];
+    if !read_exact_or_eof(reader, &mut len)? {
+        return Ok(None);
+    }
+    let mut body = vec![0; u64::from_le_bytes(len) as usize];
+    if !read_exact_or_eof(reader, &mut body)? {
+        return Ok(None);
+    }
+    Ok(Some(decode_frame_body(&body)?))
+}
+
+fn read_exact_or_eof(reader: &mut impl Read, buf: &mut [u8]) -> Result<bool, ReaderError> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(ReaderError::ReadError(e)),
+    }
+}
@@ -14,7 +14,21 @@ pub enum ReaderError {
     #[error("Error seeking file log: {0},pos: {1}, error: {2}")]
     SeekError(String, u64, #[source] std::io::Error),
     #[error("Error deserializing log: {0}")]
-    DeserializationError(#[from] bincode::Error),
+    DeserializationError(#[from] EncodingError),
+}
+
+#[derive(Error, Debug)]
+pub enum EncodingError {
+    #[error("Empty log frame")]
+    EmptyFrame,
+    #[error("Unknown log format {0}")]
+    UnknownFormat(u8),
+    #[error("Bincode error: {0}")]
+    Bincode(#[from] bincode::Error),
+    #[error("MessagePack encoding error: {0}")]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    #[error("MessagePack decoding error: {0}")]
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
 }
 
 #[derive(Debug, Error)]
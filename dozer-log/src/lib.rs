@@ -1,3 +1,4 @@
+pub mod encoding;
 pub mod errors;
 pub mod home_dir;
 pub mod reader;
@@ -1,9 +1,10 @@
 use std::{io::SeekFrom, path::Path, time::Duration};
 
 use super::errors::ReaderError;
+use crate::encoding::decode_frame_body;
 use dozer_types::epoch::ExecutorOperation;
 use dozer_types::indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use dozer_types::{bincode, log::trace};
+use dozer_types::log::trace;
 use tokio::{
     fs::{File, OpenOptions},
     io::{AsyncReadExt, AsyncSeekExt, BufReader},
@@ -88,7 +89,7 @@ async fn read_msg(reader: &mut BufReader<File>) -> Result<(ExecutorOperation, u6
         .read_exact(&mut buf)
         .await
         .map_err(ReaderError::ReadError)?;
-    let msg = bincode::deserialize(&buf).map_err(ReaderError::DeserializationError)?;
+    let msg = decode_frame_body(&buf)?;
     Ok((msg, len + 8))
 }
 
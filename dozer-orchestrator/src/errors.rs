@@ -3,7 +3,7 @@
 use std::path::PathBuf;
 
 use dozer_api::errors::{ApiError, GenerationError, GrpcError};
-use dozer_cache::dozer_log::errors::SchemaError;
+use dozer_cache::dozer_log::errors::{ReaderError, SchemaError};
 use dozer_cache::errors::CacheError;
 use dozer_core::errors::ExecutionError;
 use dozer_ingestion::errors::ConnectorError;
@@ -20,6 +20,10 @@ pub enum OrchestrationError {
     FailedToCreateMigration(PathBuf, #[source] std::io::Error),
     #[error("Failed to write schema: {0}")]
     FailedToWriteSchema(#[source] SchemaError),
+    #[error("Failed to load schema: {0}")]
+    FailedToLoadSchema(#[source] SchemaError),
+    #[error("Failed to read log {0:?}: {1}")]
+    FailedToReadLog(PathBuf, #[source] ReaderError),
     #[error("Failed to generate proto files: {0:?}")]
     FailedToGenerateProtoFiles(#[from] GenerationError),
     #[error("File system error {0:?}: {1}")]
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use dozer_cache::dozer_log::encoding::read_frame;
+use dozer_cache::dozer_log::home_dir::HomeDir;
+use dozer_cache::dozer_log::schemas::load_schema;
+use dozer_core::{Dag, NodeKind};
+use dozer_types::epoch::ExecutorOperation;
+use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
+use dozer_types::types::Schema;
+
+use crate::errors::OrchestrationError;
+
+/// Checkpoint state of a pipeline, as committed to the logs of its endpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointReport {
+    /// Consistency of the sinks reachable from every source.
+    pub sources: HashMap<NodeHandle, SourceConsistency>,
+    /// Schema stored with the latest migration of every sink that has one.
+    pub schemas: HashMap<NodeHandle, Schema>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceConsistency {
+    /// Every sink reachable from the source committed up to this position.
+    FullyConsistent(OpIdentifier),
+    /// The sinks reachable from the source committed up to different positions, or none of them
+    /// committed anything from it.
+    PartiallyConsistent {
+        /// The furthest position committed by any of the sinks, `None` if none committed.
+        latest: Option<OpIdentifier>,
+        /// The sinks behind `latest`, with the position they committed.
+        lagging: Vec<(NodeHandle, Option<OpIdentifier>)>,
+    },
+}
+
+/// Inspects the checkpoints of the pipeline `dag`, whose sinks write the endpoint logs under
+/// `home_dir`. Sinks are matched to endpoints by node id.
+///
+/// Only the latest migration of each endpoint is read. A frame cut short by a crash ends the
+/// log, as it would for the cache reading it.
+pub fn inspect_checkpoints<T>(
+    home_dir: &HomeDir,
+    dag: &Dag<T>,
+) -> Result<CheckpointReport, OrchestrationError> {
+    let mut committed = HashMap::new();
+    let mut schemas = HashMap::new();
+    for (handle, _) in dag.sinks() {
+        let migration_path = home_dir
+            .find_latest_migration_path(&handle.id)
+            .map_err(|(path, e)| OrchestrationError::FileSystem(path, e))?;
+        let Some(migration_path) = migration_path else {
+            committed.insert(handle.clone(), SourceStates::new());
+            continue;
+        };
+
+        if migration_path.schema_path.exists() {
+            let schema = load_schema(&migration_path.schema_path)
+                .map_err(OrchestrationError::FailedToLoadSchema)?;
+            schemas.insert(handle.clone(), schema);
+        }
+        committed.insert(handle.clone(), read_committed(&migration_path.log_path)?);
+    }
+
+    let sources = dag
+        .sources()
+        .map(|(source, _)| {
+            let positions = dag
+                .bfs(source)
+                .filter(|handle| matches!(dag.node_kind_from_handle(handle), NodeKind::Sink(_)))
+                .map(|sink| (sink.clone(), committed[sink].get(source).copied()))
+                .collect::<Vec<_>>();
+            (source.clone(), consistency(positions))
+        })
+        .collect();
+
+    Ok(CheckpointReport { sources, schemas })
+}
+
+fn consistency(positions: Vec<(NodeHandle, Option<OpIdentifier>)>) -> SourceConsistency {
+    let latest = positions.iter().filter_map(|(_, position)| *position).max();
+    match latest {
+        Some(latest)
+            if positions
+                .iter()
+                .all(|(_, position)| *position == Some(latest)) =>
+        {
+            SourceConsistency::FullyConsistent(latest)
+        }
+        _ => SourceConsistency::PartiallyConsistent {
+            latest,
+            lagging: positions
+                .into_iter()
+                .filter(|(_, position)| *position < latest)
+                .collect(),
+        },
+    }
+}
+
+/// Returns the highest position of every source committed in the log at `log_path`.
+fn read_committed(log_path: &Path) -> Result<SourceStates, OrchestrationError> {
+    let mut committed = SourceStates::new();
+    let file = match File::open(log_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(committed),
+        Err(e) => return Err(OrchestrationError::FileSystem(log_path.to_path_buf(), e)),
+    };
+
+    let mut reader = BufReader::new(file);
+    while let Some(op) = read_frame(&mut reader)
+        .map_err(|e| OrchestrationError::FailedToReadLog(log_path.to_path_buf(), e))?
+    {
+        if let ExecutorOperation::Commit { epoch } = op {
+            for (source, position) in epoch.details {
+                let entry = committed.entry(source).or_default();
+                *entry = (*entry).max(position);
+            }
+        }
+    }
+    Ok(committed)
+}
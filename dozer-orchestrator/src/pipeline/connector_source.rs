@@ -174,7 +174,12 @@ impl SourceFactory<SchemaSQLContext> for ConnectorSourceFactory {
 
         let mut schema_port_map = HashMap::new();
         for table in &self.tables {
-            let schema_id = get_schema_id(table.schema.identifier)?;
+            let schema_id = get_schema_id(table.schema.identifier, || {
+                format!(
+                    "table {} of connection {}",
+                    table.name, self.connection_name
+                )
+            })?;
             schema_port_map.insert(schema_id, (table.port, table.name.clone()));
         }
 
@@ -265,18 +270,25 @@ impl Source for ConnectorSource {
                 );
                 let _enter = span.enter();
 
+                let describe = || {
+                    format!(
+                        "operation {}:{} of connection {}",
+                        identifier.txid, identifier.seq_in_tx, self.connection_name
+                    )
+                };
                 let schema_id = match &kind {
                     IngestionMessageKind::OperationEvent(Operation::Delete { old }) => {
-                        Some(get_schema_id(old.schema_id)?)
+                        Some(get_schema_id(old.schema_id, describe)?)
                     }
                     IngestionMessageKind::OperationEvent(Operation::Insert { new }) => {
-                        Some(get_schema_id(new.schema_id)?)
+                        Some(get_schema_id(new.schema_id, describe)?)
                     }
                     IngestionMessageKind::OperationEvent(Operation::Update { old: _, new }) => {
-                        Some(get_schema_id(new.schema_id)?)
+                        Some(get_schema_id(new.schema_id, describe)?)
                     }
                     IngestionMessageKind::SnapshottingDone
-                    | IngestionMessageKind::SnapshottingStarted => None,
+                    | IngestionMessageKind::SnapshottingStarted
+                    | IngestionMessageKind::Watermark(_) => None,
                 };
                 if let Some(schema_id) = schema_id {
                     let (port, table_name) =
@@ -303,11 +315,23 @@ impl Source for ConnectorSource {
                                 source: table_name.clone(),
                                 r#type: "source".to_string(),
                                 count: *schema_counter as i64,
+                                column_stats: vec![],
                             };
                             let _ = notifier.2.try_send(status_update);
                         }
                     }
                     fw.send(IngestionMessage { identifier, kind }, *port)?
+                } else if let IngestionMessageKind::Watermark(timestamp) = kind {
+                    for (port, _) in self.schema_port_map.values() {
+                        fw.send(
+                            IngestionMessage::new_watermark(
+                                identifier.txid,
+                                identifier.seq_in_tx,
+                                timestamp,
+                            ),
+                            *port,
+                        )?
+                    }
                 } else {
                     for (port, _) in self.schema_port_map.values() {
                         fw.send(
@@ -332,8 +356,12 @@ impl Source for ConnectorSource {
     }
 }
 
-fn get_schema_id(op_schema_id: Option<SchemaIdentifier>) -> Result<u32, ExecutionError> {
-    Ok(op_schema_id
-        .map_or(Err(ExecutionError::SchemaNotInitialized), Ok)?
-        .id)
+/// Returns the id of `op_schema_id`, or an error naming what `describe` returns if it is unset.
+pub(crate) fn get_schema_id(
+    op_schema_id: Option<SchemaIdentifier>,
+    describe: impl FnOnce() -> String,
+) -> Result<u32, ExecutionError> {
+    op_schema_id
+        .map(|identifier| identifier.id)
+        .ok_or_else(|| ExecutionError::SchemaNotInitialized(describe()))
 }
@@ -0,0 +1,250 @@
+use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::log::info;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How a CSV file is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvSettings {
+    pub delimiter: u8,
+    /// Whether the first row holds column names rather than data.
+    pub has_headers: bool,
+}
+
+impl Default for CsvSettings {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_headers: true,
+        }
+    }
+}
+
+/// Reads a CSV file and emits every row as an insert on `DEFAULT_PORT_HANDLE`.
+///
+/// If no schema is given, column names come from the header row (or `column_{i}` without one)
+/// and each column gets the narrowest of `Int`, `Float`, `Boolean` and `String` that parses all
+/// of its cells. Empty cells make a column nullable.
+#[derive(Debug)]
+pub struct CsvSourceFactory {
+    path: PathBuf,
+    settings: CsvSettings,
+    schema: Schema,
+}
+
+impl CsvSourceFactory {
+    pub fn new(
+        name: String,
+        path: PathBuf,
+        settings: CsvSettings,
+        schema: Option<Schema>,
+    ) -> Result<Self, ExecutionError> {
+        let mut schema = match schema {
+            Some(schema) => schema,
+            None => infer_schema(&path, settings)?,
+        };
+        for field in &mut schema.fields {
+            field.source = SourceDefinition::Alias { name: name.clone() };
+        }
+
+        Ok(Self {
+            path,
+            settings,
+            schema,
+        })
+    }
+}
+
+impl SourceFactory<SchemaSQLContext> for CsvSourceFactory {
+    fn get_output_schema(
+        &self,
+        port: &PortHandle,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        if *port != DEFAULT_PORT_HANDLE {
+            return Err(ExecutionError::PortNotFoundInSource(*port));
+        }
+
+        info!(
+            "Source: Initializing input schema: {}\n{}",
+            self.path.display(),
+            self.schema.print()
+        );
+
+        Ok((self.schema.clone(), SchemaSQLContext::default()))
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, ExecutionError> {
+        Ok(Box::new(CsvSource {
+            path: self.path.clone(),
+            settings: self.settings,
+            schema: self.schema.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct CsvSource {
+    path: PathBuf,
+    settings: CsvSettings,
+    schema: Schema,
+}
+
+impl CsvSource {
+    pub fn new(path: PathBuf, settings: CsvSettings, schema: Schema) -> Self {
+        Self {
+            path,
+            settings,
+            schema,
+        }
+    }
+}
+
+impl Source for CsvSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
+        Ok(true)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), ExecutionError> {
+        // Rows are numbered from 1, so a checkpoint at row `n` resumes from row `n + 1`.
+        let start = last_checkpoint.map_or(0, |(txid, _)| txid);
+
+        let mut reader = open_reader(&self.path, self.settings)?;
+        for (index, row) in reader.records().enumerate() {
+            let row_number = index as u64 + 1;
+            let row = row.map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+            if row_number <= start {
+                continue;
+            }
+
+            if row.len() != self.schema.fields.len() {
+                return Err(ExecutionError::InternalStringError(format!(
+                    "{}: row {row_number} has {} columns, expected {}",
+                    self.path.display(),
+                    row.len(),
+                    self.schema.fields.len()
+                )));
+            }
+
+            let values = row
+                .iter()
+                .zip(&self.schema.fields)
+                .map(|(cell, field)| {
+                    Field::from_str(cell, field.typ, field.nullable).map_err(|e| {
+                        ExecutionError::InternalStringError(format!(
+                            "{}: row {row_number}, column {}: {e}",
+                            self.path.display(),
+                            field.name
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            fw.send(
+                IngestionMessage::new_op(
+                    row_number,
+                    0,
+                    Operation::Insert {
+                        new: Record::new(None, values),
+                    },
+                ),
+                DEFAULT_PORT_HANDLE,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn open_reader(
+    path: &Path,
+    settings: CsvSettings,
+) -> Result<csv::Reader<std::fs::File>, ExecutionError> {
+    csv::ReaderBuilder::new()
+        .delimiter(settings.delimiter)
+        .has_headers(settings.has_headers)
+        .from_path(path)
+        .map_err(|e| ExecutionError::InternalError(Box::new(e)))
+}
+
+const INFERRED_TYPES: [FieldType; 4] = [
+    FieldType::Int,
+    FieldType::Float,
+    FieldType::Boolean,
+    FieldType::String,
+];
+
+fn infer_schema(path: &Path, settings: CsvSettings) -> Result<Schema, ExecutionError> {
+    let mut reader = open_reader(path, settings)?;
+
+    let mut names = if settings.has_headers {
+        reader
+            .headers()
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?
+            .iter()
+            .map(str::to_string)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    // Index into `INFERRED_TYPES` of the narrowest type seen so far, per column.
+    let mut candidates: Vec<usize> = vec![0; names.len()];
+    let mut nullable = vec![false; names.len()];
+    for row in reader.records() {
+        let row = row.map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        if row.len() > candidates.len() {
+            candidates.resize(row.len(), 0);
+            nullable.resize(row.len(), false);
+        }
+
+        for (i, cell) in row.iter().enumerate() {
+            if cell.is_empty() {
+                nullable[i] = true;
+                continue;
+            }
+            while Field::from_str(cell, INFERRED_TYPES[candidates[i]], false).is_err() {
+                candidates[i] += 1;
+            }
+        }
+    }
+
+    for i in names.len()..candidates.len() {
+        names.push(format!("column_{i}"));
+    }
+
+    let mut schema = Schema::empty();
+    for ((name, candidate), nullable) in names.into_iter().zip(candidates).zip(nullable) {
+        schema.field(
+            FieldDefinition::new(
+                name,
+                INFERRED_TYPES[candidate],
+                nullable,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        );
+    }
+    Ok(schema)
+}
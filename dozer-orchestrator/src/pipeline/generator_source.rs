@@ -0,0 +1,252 @@
+use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::chrono::{DateTime, NaiveDate, NaiveDateTime, Offset, Utc};
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::json_types::JsonValue;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{
+    DozerDuration, DozerPoint, Field, FieldType, Operation, Record, Schema, TimeUnit,
+};
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How many records a `GeneratorSource` emits and how fast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorSettings {
+    /// Number of records to emit before the source quits.
+    pub count: u64,
+    /// Seed for field values. The same seed and schema always produce the same records.
+    pub seed: u64,
+    /// Maximum number of records per second, unlimited if `None`.
+    pub rate: Option<u64>,
+    /// Number of records sharing a transaction id. Every transaction boundary gives the executor
+    /// a position it can commit at.
+    pub records_per_commit: u64,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            count: 1000,
+            seed: 0,
+            rate: None,
+            records_per_commit: 100,
+        }
+    }
+}
+
+/// Emits synthetic inserts matching a schema on `DEFAULT_PORT_HANDLE`, for load testing.
+///
+/// Primary key fields are derived from the record number so keys never collide. Other fields
+/// are drawn from a generator seeded with `GeneratorSettings::seed`.
+#[derive(Debug)]
+pub struct GeneratorSourceFactory {
+    schema: Schema,
+    settings: GeneratorSettings,
+}
+
+impl GeneratorSourceFactory {
+    pub fn new(schema: Schema, settings: GeneratorSettings) -> Self {
+        Self { schema, settings }
+    }
+}
+
+impl SourceFactory<SchemaSQLContext> for GeneratorSourceFactory {
+    fn get_output_schema(
+        &self,
+        port: &PortHandle,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        if *port != DEFAULT_PORT_HANDLE {
+            return Err(ExecutionError::PortNotFoundInSource(*port));
+        }
+        Ok((self.schema.clone(), SchemaSQLContext::default()))
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, ExecutionError> {
+        Ok(Box::new(GeneratorSource::new(
+            self.schema.clone(),
+            self.settings,
+        )))
+    }
+}
+
+#[derive(Debug)]
+pub struct GeneratorSource {
+    schema: Schema,
+    settings: GeneratorSettings,
+}
+
+impl GeneratorSource {
+    pub fn new(schema: Schema, settings: GeneratorSettings) -> Self {
+        Self { schema, settings }
+    }
+
+    /// Returns the `n`th record, counting from 0.
+    pub fn record(&self, n: u64) -> Record {
+        let mut rng = SplitMix64::new(self.settings.seed ^ n.wrapping_mul(GOLDEN_GAMMA));
+        let values = self
+            .schema
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                if self.schema.primary_index.contains(&index) {
+                    key_field(field.typ, &field.name, n)
+                } else if field.nullable && rng.next_u64() % 10 == 0 {
+                    Field::Null
+                } else {
+                    random_field(field.typ, &field.name, &mut rng)
+                }
+            })
+            .collect();
+        Record::new(None, values)
+    }
+
+    fn op_identifier(&self, n: u64) -> (u64, u64) {
+        let records_per_commit = self.settings.records_per_commit.max(1);
+        (n / records_per_commit + 1, n % records_per_commit)
+    }
+}
+
+impl Source for GeneratorSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
+        Ok(true)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), ExecutionError> {
+        let start = match last_checkpoint {
+            Some((txid, seq_in_tx)) => {
+                txid.saturating_sub(1) * self.settings.records_per_commit.max(1) + seq_in_tx + 1
+            }
+            None => 0,
+        };
+
+        let started_at = Instant::now();
+        for n in start..self.settings.count {
+            if let Some(rate) = self.settings.rate.filter(|rate| *rate > 0) {
+                let due = Duration::from_secs_f64((n - start) as f64 / rate as f64);
+                if let Some(wait) = due.checked_sub(started_at.elapsed()) {
+                    thread::sleep(wait);
+                }
+            }
+
+            let (txid, seq_in_tx) = self.op_identifier(n);
+            fw.send(
+                IngestionMessage::new_op(
+                    txid,
+                    seq_in_tx,
+                    Operation::Insert {
+                        new: self.record(n),
+                    },
+                ),
+                DEFAULT_PORT_HANDLE,
+            )?;
+        }
+
+        // Returning disconnects the source, which makes the executor commit and terminate.
+        Ok(())
+    }
+}
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A small, fast generator. Reproducibility matters more here than statistical quality.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(GOLDEN_GAMMA);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Seconds since the Unix epoch of 2023-01-01.
+const BASE_TIMESTAMP: i64 = 1_672_531_200;
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+fn timestamp_field(secs: i64) -> Field {
+    let naive = NaiveDateTime::from_timestamp_opt(BASE_TIMESTAMP + secs, 0)
+        .expect("generated timestamps are in range");
+    Field::Timestamp(DateTime::from_utc(naive, Utc.fix()))
+}
+
+fn date_field(days: i64) -> Field {
+    let base = NaiveDate::from_ymd_opt(2023, 1, 1).expect("valid date");
+    Field::Date(base + dozer_types::chrono::Duration::days(days))
+}
+
+fn key_field(typ: FieldType, name: &str, n: u64) -> Field {
+    match typ {
+        FieldType::UInt => Field::UInt(n),
+        FieldType::U128 => Field::U128(n as u128),
+        FieldType::Int => Field::Int(n as i64),
+        FieldType::I128 => Field::I128(n as i128),
+        FieldType::Float => Field::Float(OrderedFloat(n as f64)),
+        FieldType::Boolean => Field::Boolean(n % 2 == 1),
+        FieldType::String => Field::String(format!("{name}_{n}")),
+        FieldType::Text => Field::Text(format!("{name}_{n}")),
+        FieldType::Binary => Field::Binary(n.to_be_bytes().to_vec()),
+        FieldType::Decimal => Field::Decimal(Decimal::from(n)),
+        FieldType::Timestamp => timestamp_field(n as i64),
+        FieldType::Date => date_field(n as i64),
+        FieldType::Json => Field::Json(JsonValue::Number(OrderedFloat(n as f64))),
+        FieldType::Point => Field::Point(DozerPoint::from((n as f64, 0.0))),
+        FieldType::Duration => Field::Duration(DozerDuration(
+            Duration::from_nanos(n),
+            TimeUnit::Nanoseconds,
+        )),
+    }
+}
+
+fn random_field(typ: FieldType, name: &str, rng: &mut SplitMix64) -> Field {
+    let value = rng.next_u64();
+    match typ {
+        FieldType::UInt => Field::UInt(value % 1_000_000),
+        FieldType::U128 => Field::U128((value % 1_000_000) as u128),
+        FieldType::Int => Field::Int((value % 2_000_000) as i64 - 1_000_000),
+        FieldType::I128 => Field::I128((value % 2_000_000) as i128 - 1_000_000),
+        FieldType::Float => Field::Float(OrderedFloat((value % 1_000_000) as f64 / 100.0)),
+        FieldType::Boolean => Field::Boolean(value % 2 == 1),
+        FieldType::String => Field::String(format!("{name}_{}", value % 1000)),
+        FieldType::Text => Field::Text(format!("{name}_{}", value % 1000)),
+        FieldType::Binary => Field::Binary(value.to_be_bytes().to_vec()),
+        FieldType::Decimal => Field::Decimal(Decimal::new((value % 1_000_000) as i64, 2)),
+        FieldType::Timestamp => timestamp_field((value % SECONDS_PER_YEAR) as i64),
+        FieldType::Date => date_field((value % 365) as i64),
+        FieldType::Json => Field::Json(JsonValue::String(format!("{name}_{}", value % 1000))),
+        FieldType::Point => Field::Point(DozerPoint::from((
+            (value % 360) as f64 - 180.0,
+            (rng.next_u64() % 180) as f64 - 90.0,
+        ))),
+        FieldType::Duration => Field::Duration(DozerDuration(
+            Duration::from_millis(value % 1_000_000),
+            TimeUnit::Milliseconds,
+        )),
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use dozer_api::grpc::types_helper::{map_field_definitions, map_pipeline_operation};
+use dozer_core::{
+    epoch::Epoch,
+    errors::ExecutionError,
+    node::{PortHandle, Sink, SinkFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::crossbeam::channel::Sender;
+use dozer_types::grpc_types::types::{FieldDefinition, Operation as GrpcOperation};
+use dozer_types::types::{Operation, Schema};
+
+/// Builds [`GrpcStreamSink`]s that push every operation onto `sender`.
+///
+/// `sender` should be bounded. The gRPC endpoint drains the other end; while no client is
+/// attached the channel fills up and the sink blocks, holding back the pipeline.
+#[derive(Debug, Clone)]
+pub struct GrpcStreamSinkFactory {
+    endpoint_name: String,
+    sender: Sender<GrpcOperation>,
+}
+
+impl GrpcStreamSinkFactory {
+    pub fn new(endpoint_name: String, sender: Sender<GrpcOperation>) -> Self {
+        Self {
+            endpoint_name,
+            sender,
+        }
+    }
+}
+
+impl SinkFactory<SchemaSQLContext> for GrpcStreamSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        debug_assert!(input_schemas.len() == 1);
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        let schema = input_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        Ok(Box::new(GrpcStreamSink::new(
+            self.endpoint_name.clone(),
+            schema,
+            self.sender.clone(),
+        )))
+    }
+}
+
+#[derive(Debug)]
+pub struct GrpcStreamSink {
+    endpoint_name: String,
+    /// Definitions of the streamed values, in the order they appear in each record.
+    fields: Vec<FieldDefinition>,
+    sender: Sender<GrpcOperation>,
+}
+
+impl GrpcStreamSink {
+    pub fn new(endpoint_name: String, schema: Schema, sender: Sender<GrpcOperation>) -> Self {
+        Self {
+            endpoint_name,
+            fields: map_field_definitions(schema.fields),
+            sender,
+        }
+    }
+
+    /// Names and types of the streamed values, for clients to decode records with.
+    pub fn fields(&self) -> &[FieldDefinition] {
+        &self.fields
+    }
+}
+
+impl Sink for GrpcStreamSink {
+    fn process(&mut self, _from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        let op = map_pipeline_operation(self.endpoint_name.clone(), op);
+        for record in op.old.iter().chain(op.new.iter()) {
+            if record.values.len() != self.fields.len() {
+                return Err(ExecutionError::InternalStringError(format!(
+                    "Record for endpoint {} has {} values, expected {}",
+                    self.endpoint_name,
+                    record.values.len(),
+                    self.fields.len()
+                )));
+            }
+        }
+        self.sender.send(op)?;
+        Ok(())
+    }
+
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
@@ -1,31 +1,81 @@
 use std::{
     collections::HashMap,
     fs::File,
-    io::{BufWriter, Write},
+    io::{BufWriter, ErrorKind, IsTerminal, Write},
     path::PathBuf,
+    time::{Duration, Instant},
 };
 
 use dozer_api::grpc::internal::internal_pipeline_server::PipelineEventSenders;
+use dozer_api::grpc::types_helper::field_to_prost_value;
+use dozer_cache::dozer_log::encoding::{encode_frame, LogFormat};
 use dozer_core::{
     epoch::Epoch,
-    errors::ExecutionError,
+    errors::{ExecutionError, SinkError},
     node::{PortHandle, Sink, SinkFactory},
+    stats::ColumnStats,
     DEFAULT_PORT_HANDLE,
 };
 use dozer_sql::pipeline::builder::SchemaSQLContext;
 use dozer_types::indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use dozer_types::log::{error, info};
+use dozer_types::serde_json;
 use dozer_types::{
-    bytes::{BufMut, BytesMut},
-    types::{Operation, Schema},
+    epoch::{ExecutorOperation, OpOrigin},
+    grpc_types::internal::{ColumnStats as GrpcColumnStats, StatusUpdate},
+};
+use dozer_types::{
+    node::SourceStates,
+    types::{Field, FieldType, Operation, Record, Schema},
 };
-use dozer_types::{epoch::ExecutorOperation, grpc_types::internal::StatusUpdate};
 use std::fs::OpenOptions;
 
 #[derive(Debug, Clone)]
 pub struct LogSinkSettings {
     pub file_buffer_capacity: u64,
+    /// Check every record against the input schema before writing it. Off by default for performance.
+    pub validate_records: bool,
+    /// Persist the committed source positions next to the log and skip replayed operations
+    /// after a restart.
+    pub exactly_once: bool,
+    /// Draw a progress bar. When off, or when stdout is not a terminal, progress is logged
+    /// periodically instead.
+    pub show_progress: bool,
+    /// Report per-column min/max/null counts of every commit through the notifier.
+    pub collect_stats: bool,
+    /// Payload codec of the log frames.
+    pub format: LogFormat,
+    /// Write an `Insert` whose primary key is already present as an `Update` of the existing row,
+    /// as expected from the output of aggregations.
+    pub upsert: bool,
+    /// Write the origin and tags of every operation to a JSON lines file next to the log, to
+    /// trace output rows back to the source events they were derived from.
+    pub lineage: bool,
+    /// Which commits flush the log to disk.
+    pub commit_policy: CommitPolicy,
 }
 
+/// Which commits a [`LogSink`] flushes the log on, syncing it if exactly-once. Commits that
+/// aren't flushed are still written to the log buffer, and become durable with the next flush.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitPolicy {
+    /// Flush on every commit.
+    #[default]
+    EveryCommit,
+    /// Flush on every `n`th commit.
+    EveryNCommits(u64),
+    /// Flush on the first commit after at least `n` operations were written since the last flush.
+    EveryNRecords(u64),
+    /// Flush on the first commit at least this long after the last flush.
+    EveryDuration(Duration),
+}
+
+/// Number of operations between progress log lines when there is no progress bar.
+const PROGRESS_LOG_INTERVAL: usize = 100_000;
+
+/// Buffer capacity of the lineage file, whose lines are much smaller than log frames.
+const LINEAGE_BUFFER_CAPACITY: u64 = 64 * 1024;
+
 #[derive(Debug, Clone)]
 pub struct LogSinkFactory {
     log_path: PathBuf,
@@ -68,28 +118,243 @@ impl SinkFactory<SchemaSQLContext> for LogSinkFactory {
 
     fn build(
         &self,
-        _input_schemas: HashMap<PortHandle, Schema>,
+        mut input_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Sink>, ExecutionError> {
-        Ok(Box::new(LogSink::new(
-            Some(self.multi_pb.clone()),
+        let multi_pb = (self.settings.show_progress && std::io::stdout().is_terminal())
+            .then(|| self.multi_pb.clone());
+        let mut sink = LogSink::new(
+            multi_pb,
             self.log_path.clone(),
             self.settings.file_buffer_capacity,
             self.endpoint_name.clone(),
             self.notifier.clone(),
-        )?))
+        )?;
+        if self.settings.validate_records || self.settings.collect_stats || self.settings.upsert {
+            let schema = input_schemas
+                .remove(&DEFAULT_PORT_HANDLE)
+                .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+            if self.settings.collect_stats {
+                sink = sink.with_stats(&schema);
+            }
+            if self.settings.upsert {
+                sink = sink.with_upsert(&schema);
+            }
+            if self.settings.validate_records {
+                sink = sink.with_validation(schema);
+            }
+        }
+        sink = sink.with_format(self.settings.format);
+        if self.settings.exactly_once {
+            sink = sink.with_exactly_once(self.log_path.with_extension("seq"))?;
+        }
+        if self.settings.lineage {
+            sink = sink.with_lineage(self.log_path.with_extension("lineage"))?;
+        }
+        sink = sink.with_commit_policy(self.settings.commit_policy);
+        Ok(Box::new(sink))
     }
 }
 
 #[derive(Debug)]
 pub struct LogSink {
-    pb: ProgressBar,
+    /// `None` if progress is logged rather than drawn.
+    pb: Option<ProgressBar>,
     buffered_file: BufWriter<File>,
     counter: usize,
     notifier: Option<PipelineEventSenders>,
     endpoint_name: String,
+    /// When set, records are checked against this schema before being written.
+    schema: Option<Schema>,
+    /// When set, operations are held until their commit and dropped if it was already written.
+    exactly_once: Option<ExactlyOnce>,
+    /// When set, per-column statistics are reported for these column names on every commit.
+    stats_columns: Option<Vec<String>>,
+    format: LogFormat,
+    /// When set, inserts of existing primary keys are written as updates.
+    upsert: Option<Upsert>,
+    /// When set, the origin of every operation written to the log is written here too.
+    lineage: Option<Lineage>,
+    flush_schedule: FlushSchedule,
+    /// Number of times the log was flushed on commit.
+    flushes: usize,
+    /// Id of the last epoch whose commit was written to the log.
+    written_epoch: Option<u64>,
+    /// Id of the last epoch whose commit was flushed to disk.
+    durable_epoch: Option<u64>,
+}
+
+#[derive(Debug)]
+struct FlushSchedule {
+    policy: CommitPolicy,
+    /// Commits since the last flush.
+    commits: u64,
+    /// Operations written since the last flush.
+    records: u64,
+    last_flush: Instant,
+}
+
+impl FlushSchedule {
+    fn new(policy: CommitPolicy) -> Self {
+        Self {
+            policy,
+            commits: 0,
+            records: 0,
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Counts a commit, and returns if the log should be flushed with it.
+    fn on_commit(&mut self) -> bool {
+        self.commits += 1;
+        let due = match self.policy {
+            CommitPolicy::EveryCommit => true,
+            CommitPolicy::EveryNCommits(n) => self.commits >= n,
+            CommitPolicy::EveryNRecords(n) => self.records >= n,
+            CommitPolicy::EveryDuration(interval) => self.last_flush.elapsed() >= interval,
+        };
+        if due {
+            self.commits = 0;
+            self.records = 0;
+            self.last_flush = Instant::now();
+        }
+        due
+    }
+}
+
+#[derive(Debug)]
+struct Lineage {
+    file: BufWriter<File>,
+    /// Number of operations written to the log so far.
+    ops: u64,
+}
+
+impl Lineage {
+    /// Writes a line for `msg` if it's an operation, identifying it by its index among the
+    /// operations of the log. Operations of unknown origin get no line.
+    fn write(&mut self, msg: &ExecutorOperation) -> Result<(), ExecutionError> {
+        let ExecutorOperation::Op { origin, .. } = msg else {
+            return Ok(());
+        };
+        let index = self.ops;
+        self.ops += 1;
+        let Some(origin) = origin else {
+            return Ok(());
+        };
+
+        let line = serde_json::json!({
+            "op": index,
+            "source": origin.source.to_string(),
+            "txid": origin.id.txid,
+            "seq_in_tx": origin.id.seq_in_tx,
+            "tags": origin.tags.as_deref(),
+        });
+        serde_json::to_writer(&mut self.file, &line)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        self.file
+            .write_all(b"\n")
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))
+    }
+}
+
+#[derive(Debug)]
+struct Upsert {
+    primary_index: Vec<usize>,
+    /// The current row of every primary key, by encoded key.
+    rows: HashMap<Vec<u8>, Record>,
+}
+
+impl Upsert {
+    /// Tracks the row `op` writes, and turns it into an `Update` if it inserts an existing key.
+    fn apply(&mut self, op: Operation) -> Operation {
+        match op {
+            Operation::Insert { new } => {
+                match self
+                    .rows
+                    .insert(new.get_key(&self.primary_index), new.clone())
+                {
+                    Some(old) => Operation::Update { old, new },
+                    None => Operation::Insert { new },
+                }
+            }
+            Operation::Update { old, new } => {
+                self.rows.remove(&old.get_key(&self.primary_index));
+                self.rows
+                    .insert(new.get_key(&self.primary_index), new.clone());
+                Operation::Update { old, new }
+            }
+            Operation::Delete { old } => {
+                self.rows.remove(&old.get_key(&self.primary_index));
+                Operation::Delete { old }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ExactlyOnce {
+    /// Sidecar file storing `committed`.
+    path: PathBuf,
+    /// The highest position of each source whose operations are written to the log. Only
+    /// durable once persisted.
+    committed: SourceStates,
+    /// Operations received since the last commit.
+    pending: Vec<ExecutorOperation>,
+}
+
+impl ExactlyOnce {
+    fn open(path: PathBuf) -> Result<Self, ExecutionError> {
+        let committed = match std::fs::read(&path) {
+            Ok(bytes) => dozer_types::bincode::deserialize(&bytes)
+                .map_err(|e| ExecutionError::InternalError(Box::new(e)))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => SourceStates::new(),
+            Err(e) => return Err(ExecutionError::InternalError(Box::new(e))),
+        };
+        Ok(Self {
+            path,
+            committed,
+            pending: vec![],
+        })
+    }
+
+    /// Returns if an operation from `origin` is already in the log.
+    fn is_committed(&self, origin: &OpOrigin) -> bool {
+        self.committed
+            .get(origin.source.as_ref())
+            .map_or(false, |committed| origin.id <= *committed)
+    }
+
+    /// Returns if every source in `epoch` is at or before its committed position, i.e. the
+    /// operations of this epoch are a replay of ones already in the log.
+    fn is_replay(&self, epoch: &Epoch) -> bool {
+        !epoch.details.is_empty()
+            && epoch.details.iter().all(|(source, position)| {
+                self.committed
+                    .get(source)
+                    .map_or(false, |committed| position <= committed)
+            })
+    }
+
+    fn record(&mut self, epoch: &Epoch) {
+        for (source, position) in &epoch.details {
+            let committed = self.committed.entry(source.clone()).or_default();
+            *committed = (*committed).max(*position);
+        }
+    }
+
+    /// Stores the recorded positions in the sidecar file, once the log is synced.
+    fn persist(&self) -> Result<(), ExecutionError> {
+        // Write to a temporary file first so a crash never leaves a torn sidecar behind.
+        let bytes = dozer_types::bincode::serialize(&self.committed)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, bytes)
+            .and_then(|_| std::fs::rename(&tmp_path, &self.path))
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))
+    }
 }
 
 impl LogSink {
+    /// Draws progress in `multi_pb`, or logs it if `multi_pb` is `None`.
     pub fn new(
         multi_pb: Option<MultiProgress>,
         log_path: PathBuf,
@@ -97,17 +362,13 @@ impl LogSink {
         endpoint_name: String,
         notifier: Option<PipelineEventSenders>,
     ) -> Result<Self, ExecutionError> {
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .append(true)
-            .open(log_path)
-            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
-
-        let buffered_file = std::io::BufWriter::with_capacity(file_buffer_capacity as usize, file);
+        let buffered_file = open_log_file(log_path, file_buffer_capacity)?;
 
-        let pb = attach_progress(multi_pb);
-        pb.set_message(endpoint_name.clone());
+        let pb = multi_pb.map(|multi_pb| {
+            let pb = attach_progress(Some(multi_pb));
+            pb.set_message(endpoint_name.clone());
+            pb
+        });
 
         Ok(Self {
             pb,
@@ -115,54 +376,365 @@ impl LogSink {
             counter: 0,
             notifier,
             endpoint_name,
+            schema: None,
+            exactly_once: None,
+            stats_columns: None,
+            format: LogFormat::default(),
+            upsert: None,
+            lineage: None,
+            flush_schedule: FlushSchedule::new(CommitPolicy::default()),
+            flushes: 0,
+            written_epoch: None,
+            durable_epoch: None,
         })
     }
+
+    /// Number of operations processed so far.
+    pub fn count(&self) -> usize {
+        self.counter
+    }
+
+    /// Number of times the log was flushed on commit.
+    pub fn flush_count(&self) -> usize {
+        self.flushes
+    }
+
+    /// Id of the last epoch whose commit was flushed to disk, and synced if exactly-once.
+    pub fn durable_epoch(&self) -> Option<u64> {
+        self.durable_epoch
+    }
+
+    /// Flushes the log on the commits picked by `policy` rather than on every commit.
+    pub fn with_commit_policy(mut self, policy: CommitPolicy) -> Self {
+        self.flush_schedule = FlushSchedule::new(policy);
+        self
+    }
+
+    /// Encodes log frames with `format` instead of bincode.
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Rejects records whose fields don't match `schema` instead of writing them.
+    pub fn with_validation(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Reports the min, max and null count of every column of `schema` through the notifier on
+    /// every commit.
+    pub fn with_stats(mut self, schema: &Schema) -> Self {
+        self.stats_columns = Some(
+            schema
+                .fields
+                .iter()
+                .map(|field| field.name.clone())
+                .collect(),
+        );
+        self
+    }
+
+    /// Writes an `Insert` whose primary key, per `schema`, is already present as an `Update` of
+    /// the existing row, so that the log holds a single row per key.
+    ///
+    /// The current row of every key is kept in memory. Does nothing if `schema` has no primary key.
+    pub fn with_upsert(mut self, schema: &Schema) -> Self {
+        if !schema.primary_index.is_empty() {
+            self.upsert = Some(Upsert {
+                primary_index: schema.primary_index.clone(),
+                rows: HashMap::new(),
+            });
+        }
+        self
+    }
+
+    /// Makes writes effectively-once across restarts, by recording the source positions of every
+    /// commit in the sidecar file at `path`.
+    ///
+    /// Operations are buffered until their commit. Operations whose origin is at or before the
+    /// recorded position of their source were already written by a previous run and are dropped.
+    /// When origins are unknown, a whole commit is dropped if its positions are all at or before
+    /// the recorded ones.
+    pub fn with_exactly_once(mut self, path: PathBuf) -> Result<Self, ExecutionError> {
+        self.exactly_once = Some(ExactlyOnce::open(path)?);
+        Ok(self)
+    }
+
+    /// Writes the origin and tags of every operation written to the log as a JSON line of the
+    /// file at `path`, identifying the operation by its index among the operations of the log.
+    ///
+    /// The file is appended to, so indexes only match a log this sink wrote from its start.
+    pub fn with_lineage(mut self, path: PathBuf) -> Result<Self, ExecutionError> {
+        self.lineage = Some(Lineage {
+            file: open_log_file(path, LINEAGE_BUFFER_CAPACITY)?,
+            ops: 0,
+        });
+        Ok(self)
+    }
+
+    fn write_msg(&mut self, msg: ExecutorOperation) -> Result<(), ExecutionError> {
+        match &mut self.exactly_once {
+            Some(exactly_once) => {
+                exactly_once.pending.push(msg);
+                Ok(())
+            }
+            None => write_to_log(
+                &mut self.buffered_file,
+                &mut self.lineage,
+                &msg,
+                self.format,
+            ),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), ExecutionError> {
+        self.buffered_file.flush()?;
+        if let Some(lineage) = &mut self.lineage {
+            lineage.file.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the log, then syncs it and persists the committed positions if exactly-once, which
+    /// makes the commits written so far durable.
+    fn flush_durably(&mut self) -> Result<(), ExecutionError> {
+        self.flush()?;
+        if let Some(exactly_once) = &self.exactly_once {
+            self.buffered_file.get_ref().sync_data()?;
+            exactly_once.persist()?;
+        }
+        self.durable_epoch = self.written_epoch;
+        Ok(())
+    }
+
+    fn validate(&self, op: &Operation) -> Result<(), ExecutionError> {
+        let Some(schema) = &self.schema else {
+            return Ok(());
+        };
+        match op {
+            Operation::Delete { old } => validate_record(schema, old),
+            Operation::Insert { new } => validate_record(schema, new),
+            Operation::Update { old, new } => {
+                validate_record(schema, old)?;
+                validate_record(schema, new)
+            }
+        }
+    }
 }
 
 impl Sink for LogSink {
-    fn process(&mut self, _from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
-        let msg = ExecutorOperation::Op { op };
+    fn process(&mut self, from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        self.process_with_origin(from_port, op, None)
+    }
+
+    fn process_with_origin(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        origin: Option<&OpOrigin>,
+    ) -> Result<(), ExecutionError> {
+        self.validate(&op)?;
+        if let (Some(exactly_once), Some(origin)) = (&self.exactly_once, origin) {
+            if exactly_once.is_committed(origin) {
+                return Ok(());
+            }
+        }
+        let op = match &mut self.upsert {
+            Some(upsert) => upsert.apply(op),
+            None => op,
+        };
+        let msg = ExecutorOperation::Op {
+            op,
+            origin: origin.cloned(),
+        };
         self.counter += 1;
-        self.pb.set_position(self.counter as u64);
+        self.flush_schedule.records += 1;
+        match &self.pb {
+            Some(pb) => pb.set_position(self.counter as u64),
+            None if self.counter % PROGRESS_LOG_INTERVAL == 0 => {
+                info!(
+                    "[{}] Processed {} operations",
+                    self.endpoint_name, self.counter
+                )
+            }
+            None => {}
+        }
         if self.counter % 1000 == 0 {
             try_send(&self.notifier, self.counter, &self.endpoint_name);
         }
-        write_msg_to_file(&mut self.buffered_file, &msg)
+        self.write_msg(msg)
     }
 
-    fn commit(&mut self) -> Result<(), ExecutionError> {
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), ExecutionError> {
         let msg = ExecutorOperation::Commit {
-            epoch: Epoch::new(0, Default::default()),
+            epoch: epoch_details.clone(),
         };
 
         try_send(&self.notifier, self.counter, &self.endpoint_name);
-        write_msg_to_file(&mut self.buffered_file, &msg)?;
-        self.buffered_file.flush()?;
+        if let Some(exactly_once) = &mut self.exactly_once {
+            if exactly_once.is_replay(epoch_details) {
+                exactly_once.pending.clear();
+                return Ok(());
+            }
+            for pending in exactly_once.pending.drain(..) {
+                write_to_log(
+                    &mut self.buffered_file,
+                    &mut self.lineage,
+                    &pending,
+                    self.format,
+                )?;
+            }
+            exactly_once.record(epoch_details);
+        }
+        write_msg_to_file(&mut self.buffered_file, &msg, self.format)?;
+        self.written_epoch = Some(epoch_details.id);
+
+        if self.flush_schedule.on_commit() {
+            self.flush_durably()?;
+            self.flushes += 1;
+        }
         Ok(())
     }
 
     fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
-        let msg = ExecutorOperation::SnapshottingDone {};
-        write_msg_to_file(&mut self.buffered_file, &msg)
+        self.write_msg(ExecutorOperation::SnapshottingDone {})
+    }
+
+    fn collects_stats(&self) -> bool {
+        self.stats_columns.is_some()
+    }
+
+    fn on_stats(&mut self, stats: Vec<ColumnStats>) -> Result<(), ExecutionError> {
+        let (Some(names), Some(notifier)) = (&self.stats_columns, &self.notifier) else {
+            return Ok(());
+        };
+        if stats.is_empty() {
+            return Ok(());
+        }
+
+        let column_stats = names
+            .iter()
+            .zip(stats)
+            .map(|(name, stats)| GrpcColumnStats {
+                name: name.clone(),
+                min: stats.min.map(field_to_prost_value),
+                max: stats.max.map(field_to_prost_value),
+                null_count: stats.null_count as i64,
+            })
+            .collect();
+        let status_update = StatusUpdate {
+            source: self.endpoint_name.clone(),
+            r#type: "stats".to_string(),
+            count: self.counter as i64,
+            column_stats,
+        };
+        let _ = notifier.2.try_send(status_update);
+        Ok(())
+    }
+}
+
+/// Flushes the operations written since the last flush, so that a pipeline torn down without a
+/// final commit, or between the flushes of its commit policy, doesn't lose them. If exactly-once,
+/// also syncs the log and persists the positions of the commits written. Operations held for an
+/// exactly-once commit are not written: they were never committed and will be replayed.
+impl Drop for LogSink {
+    fn drop(&mut self) {
+        // Errors can't be returned from here, and panicking while unwinding would abort.
+        if let Err(e) = self.flush_durably() {
+            error!("[{}] Failed to flush the log: {}", self.endpoint_name, e);
+        }
+    }
+}
+
+pub(super) fn validate_record(schema: &Schema, record: &Record) -> Result<(), ExecutionError> {
+    if schema.fields.len() != record.values.len() {
+        return Err(SinkError::RecordFieldCountMismatch {
+            expected: schema.fields.len(),
+            actual: record.values.len(),
+        }
+        .into());
+    }
+
+    for (field, value) in schema.fields.iter().zip(record.values.iter()) {
+        if value == &Field::Null && field.nullable {
+            continue;
+        }
+        if !is_of_type(value, field.typ) {
+            return Err(SinkError::RecordFieldTypeMismatch {
+                field: field.name.clone(),
+                expected: field.typ,
+                actual: value.clone(),
+            }
+            .into());
+        }
     }
+    Ok(())
+}
+
+fn is_of_type(value: &Field, typ: FieldType) -> bool {
+    match typ {
+        FieldType::UInt => value.as_uint().is_some(),
+        FieldType::U128 => value.as_u128().is_some(),
+        FieldType::Int => value.as_int().is_some(),
+        FieldType::I128 => value.as_i128().is_some(),
+        FieldType::Float => value.as_float().is_some(),
+        FieldType::Boolean => value.as_boolean().is_some(),
+        FieldType::String => value.as_string().is_some(),
+        FieldType::Text => value.as_text().is_some(),
+        FieldType::Binary => value.as_binary().is_some(),
+        FieldType::Decimal => value.as_decimal().is_some(),
+        FieldType::Timestamp => value.as_timestamp().is_some(),
+        FieldType::Date => value.as_date().is_some(),
+        FieldType::Json => value.as_json().is_some(),
+        FieldType::Point => value.as_point().is_some(),
+        FieldType::Duration => value.as_duration().is_some(),
+    }
+}
+
+pub(super) fn open_log_file(
+    log_path: PathBuf,
+    file_buffer_capacity: u64,
+) -> Result<BufWriter<File>, ExecutionError> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+
+    Ok(BufWriter::with_capacity(
+        file_buffer_capacity as usize,
+        file,
+    ))
 }
 
-fn write_msg_to_file(
+/// Writes `msg` to the log, and its lineage if tracked.
+fn write_to_log(
     file: &mut BufWriter<File>,
+    lineage: &mut Option<Lineage>,
     msg: &ExecutorOperation,
+    format: LogFormat,
 ) -> Result<(), ExecutionError> {
-    let msg = dozer_types::bincode::serialize(msg)
-        .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
-
-    let mut buf = BytesMut::with_capacity(msg.len() + 4);
-    buf.put_u64_le(msg.len() as u64);
-    buf.put_slice(&msg);
+    write_msg_to_file(file, msg, format)?;
+    match lineage {
+        Some(lineage) => lineage.write(msg),
+        None => Ok(()),
+    }
+}
 
-    file.write_all(&buf)
+pub(super) fn write_msg_to_file(
+    file: &mut BufWriter<File>,
+    msg: &ExecutorOperation,
+    format: LogFormat,
+) -> Result<(), ExecutionError> {
+    let frame =
+        encode_frame(msg, format).map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+    file.write_all(&frame)
         .map_err(|e| ExecutionError::InternalError(Box::new(e)))
 }
 
-fn attach_progress(multi_pb: Option<MultiProgress>) -> ProgressBar {
+pub(super) fn attach_progress(multi_pb: Option<MultiProgress>) -> ProgressBar {
     let pb = ProgressBar::new_spinner();
     multi_pb.as_ref().map(|m| m.add(pb.clone()));
     pb.set_style(
@@ -183,12 +755,17 @@ fn attach_progress(multi_pb: Option<MultiProgress>) -> ProgressBar {
     pb
 }
 
-fn try_send(notifier: &Option<PipelineEventSenders>, progress: usize, endpoint_name: &str) {
+pub(super) fn try_send(
+    notifier: &Option<PipelineEventSenders>,
+    progress: usize,
+    endpoint_name: &str,
+) {
     if let Some(n) = notifier {
         let status_update = StatusUpdate {
             source: endpoint_name.to_string(),
             r#type: "sink".to_string(),
             count: progress as i64,
+            column_stats: vec![],
         };
 
         let _ = n.2.try_send(status_update);
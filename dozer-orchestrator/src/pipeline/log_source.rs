@@ -0,0 +1,155 @@
+use dozer_cache::dozer_log::encoding::read_frame;
+use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::epoch::ExecutorOperation;
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::log::info;
+use dozer_types::node::OpIdentifier;
+use dozer_types::types::Schema;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+/// Replays a log written by [`LogSink`] as a source, emitting its operations on
+/// `DEFAULT_PORT_HANDLE`.
+///
+/// Only committed transactions are replayed: operations after the last commit of the log are
+/// dropped. The operations between two commits of the log form a transaction, numbered from 0
+/// in the order of the log, and the message at index `i` of transaction `t` is emitted at
+/// position `(t, i)`. A source restarting from a checkpoint skips the messages at or before it.
+/// The source stops at the end of the log.
+///
+/// [`LogSink`]: crate::pipeline::LogSink
+#[derive(Debug)]
+pub struct LogSourceFactory {
+    log_path: PathBuf,
+    schema: Schema,
+    start: Option<OpIdentifier>,
+}
+
+impl LogSourceFactory {
+    /// `schema` is that of the records of the log.
+    pub fn new(log_path: PathBuf, schema: Schema) -> Self {
+        Self {
+            log_path,
+            schema,
+            start: None,
+        }
+    }
+
+    /// Skips the messages at or before `position` when the pipeline has no checkpoint to resume
+    /// from.
+    pub fn with_start(mut self, position: OpIdentifier) -> Self {
+        self.start = Some(position);
+        self
+    }
+}
+
+impl SourceFactory<SchemaSQLContext> for LogSourceFactory {
+    fn get_output_schema(
+        &self,
+        port: &PortHandle,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        if *port != DEFAULT_PORT_HANDLE {
+            return Err(ExecutionError::PortNotFoundInSource(*port));
+        }
+
+        info!(
+            "Source: Initializing input schema: {}\n{}",
+            self.log_path.display(),
+            self.schema.print()
+        );
+
+        Ok((self.schema.clone(), SchemaSQLContext::default()))
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, ExecutionError> {
+        Ok(Box::new(LogSource {
+            log_path: self.log_path.clone(),
+            start: self.start,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct LogSource {
+    log_path: PathBuf,
+    start: Option<OpIdentifier>,
+}
+
+impl Source for LogSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
+        Ok(true)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), ExecutionError> {
+        let start = last_checkpoint
+            .map(|(txid, seq_in_tx)| OpIdentifier::new(txid, seq_in_tx))
+            .or(self.start);
+
+        let file =
+            File::open(&self.log_path).map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        let mut reader = BufReader::new(file);
+
+        let mut txid = 0;
+        let mut transaction = vec![];
+        while let Some(msg) =
+            read_frame(&mut reader).map_err(|e| ExecutionError::InternalError(Box::new(e)))?
+        {
+            match msg {
+                ExecutorOperation::Op { op, .. } => {
+                    transaction.push(IngestionMessage::new_op(txid, transaction.len() as u64, op));
+                }
+                ExecutorOperation::SnapshottingDone {} => {
+                    transaction.push(IngestionMessage::new_snapshotting_done(
+                        txid,
+                        transaction.len() as u64,
+                    ));
+                }
+                ExecutorOperation::Watermark { timestamp } => {
+                    transaction.push(IngestionMessage::new_watermark(
+                        txid,
+                        transaction.len() as u64,
+                        timestamp,
+                    ));
+                }
+                ExecutorOperation::Commit { .. } => {
+                    for message in transaction.drain(..) {
+                        if start.map_or(true, |start| message.identifier > start) {
+                            fw.send(message, DEFAULT_PORT_HANDLE)?;
+                        }
+                    }
+                    txid += 1;
+                }
+                ExecutorOperation::Terminate => break,
+                ExecutorOperation::Batch { .. } => {
+                    // Batches are never persisted, so this isn't a log the log sink wrote.
+                    return Err(ExecutionError::InvalidOperation(format!(
+                        "unexpected batch in log {}",
+                        self.log_path.display()
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,19 @@
 mod builder;
+mod checkpoint;
 pub mod connector_source;
+pub mod csv_source;
+pub mod generator_source;
+pub mod grpc_stream_sink;
 mod log_sink;
+pub mod log_source;
+mod sharded_log_sink;
 pub mod source_builder;
 pub mod validate;
 
 pub use builder::PipelineBuilder;
-pub use log_sink::{LogSink, LogSinkFactory, LogSinkSettings};
+pub use checkpoint::{inspect_checkpoints, CheckpointReport, SourceConsistency};
+pub use log_sink::{CommitPolicy, LogSink, LogSinkFactory, LogSinkSettings};
+pub use sharded_log_sink::{ShardedLogSink, ShardedLogSinkFactory};
 
 #[cfg(test)]
 mod tests;
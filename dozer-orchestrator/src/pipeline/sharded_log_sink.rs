@@ -0,0 +1,275 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use dozer_api::grpc::internal::internal_pipeline_server::PipelineEventSenders;
+use dozer_cache::dozer_log::encoding::LogFormat;
+use dozer_core::{
+    epoch::Epoch,
+    errors::ExecutionError,
+    node::{PortHandle, Sink, SinkFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_sql::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use dozer_types::epoch::ExecutorOperation;
+use dozer_types::indicatif::{MultiProgress, ProgressBar};
+use dozer_types::types::{Field, Operation, Record, Schema};
+
+use super::log_sink::{
+    attach_progress, open_log_file, try_send, validate_record, write_msg_to_file, LogSinkSettings,
+};
+
+#[derive(Debug, Clone)]
+pub struct ShardedLogSinkFactory {
+    partition: Expression,
+    shard_paths: Vec<(Field, PathBuf)>,
+    default_shard_path: PathBuf,
+    settings: LogSinkSettings,
+    endpoint_name: String,
+    multi_pb: MultiProgress,
+    notifier: Option<PipelineEventSenders>,
+}
+
+impl ShardedLogSinkFactory {
+    pub fn new(
+        partition: Expression,
+        shard_paths: Vec<(Field, PathBuf)>,
+        default_shard_path: PathBuf,
+        settings: LogSinkSettings,
+        endpoint_name: String,
+        multi_pb: MultiProgress,
+        notifier: Option<PipelineEventSenders>,
+    ) -> Self {
+        Self {
+            partition,
+            shard_paths,
+            default_shard_path,
+            settings,
+            endpoint_name,
+            multi_pb,
+            notifier,
+        }
+    }
+}
+
+impl SinkFactory<SchemaSQLContext> for ShardedLogSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        debug_assert!(input_schemas.len() == 1);
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        let schema = input_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        let mut sink = ShardedLogSink::new(
+            Some(self.multi_pb.clone()),
+            self.partition.clone(),
+            schema,
+            self.shard_paths.clone(),
+            self.default_shard_path.clone(),
+            self.settings.file_buffer_capacity,
+            self.endpoint_name.clone(),
+            self.notifier.clone(),
+        )?;
+        if self.settings.validate_records {
+            sink = sink.with_validation();
+        }
+        Ok(Box::new(sink.with_format(self.settings.format)))
+    }
+}
+
+/// A log sink that splits its output across several files.
+///
+/// The file of each record is picked by evaluating the partition expression on it. Records whose
+/// partition value is NULL or has no shard of its own go to the default shard.
+#[derive(Debug)]
+pub struct ShardedLogSink {
+    partition: Expression,
+    schema: Schema,
+    validate_records: bool,
+    /// Partition value to index in `shards`.
+    shard_indexes: HashMap<Field, usize>,
+    /// One shard per partition value, followed by the default shard.
+    shards: Vec<Shard>,
+    notifier: Option<PipelineEventSenders>,
+    format: LogFormat,
+}
+
+#[derive(Debug)]
+struct Shard {
+    name: String,
+    pb: ProgressBar,
+    buffered_file: BufWriter<File>,
+    counter: usize,
+}
+
+impl Shard {
+    fn new(
+        multi_pb: Option<MultiProgress>,
+        log_path: PathBuf,
+        file_buffer_capacity: u64,
+        name: String,
+    ) -> Result<Self, ExecutionError> {
+        let buffered_file = open_log_file(log_path, file_buffer_capacity)?;
+
+        let pb = attach_progress(multi_pb);
+        pb.set_message(name.clone());
+
+        Ok(Self {
+            name,
+            pb,
+            buffered_file,
+            counter: 0,
+        })
+    }
+}
+
+impl ShardedLogSink {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        multi_pb: Option<MultiProgress>,
+        partition: Expression,
+        schema: Schema,
+        shard_paths: Vec<(Field, PathBuf)>,
+        default_shard_path: PathBuf,
+        file_buffer_capacity: u64,
+        endpoint_name: String,
+        notifier: Option<PipelineEventSenders>,
+    ) -> Result<Self, ExecutionError> {
+        let mut shard_indexes = HashMap::new();
+        let mut shards = vec![];
+        for (value, log_path) in shard_paths {
+            let name = format!("{endpoint_name}[{value}]");
+            shard_indexes.insert(value, shards.len());
+            shards.push(Shard::new(
+                multi_pb.clone(),
+                log_path,
+                file_buffer_capacity,
+                name,
+            )?);
+        }
+        shards.push(Shard::new(
+            multi_pb,
+            default_shard_path,
+            file_buffer_capacity,
+            format!("{endpoint_name}[default]"),
+        )?);
+
+        Ok(Self {
+            partition,
+            schema,
+            validate_records: false,
+            shard_indexes,
+            shards,
+            notifier,
+            format: LogFormat::default(),
+        })
+    }
+
+    /// Rejects records whose fields don't match the input schema instead of writing them.
+    pub fn with_validation(mut self) -> Self {
+        self.validate_records = true;
+        self
+    }
+
+    /// Encodes log frames with `format` instead of bincode.
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn shard_of(&self, record: &Record) -> Result<usize, ExecutionError> {
+        if self.validate_records {
+            validate_record(&self.schema, record)?;
+        }
+        let value = self
+            .partition
+            .evaluate(record, &self.schema)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        let default_shard = self.shards.len() - 1;
+        Ok(match value {
+            Field::Null => default_shard,
+            value => self
+                .shard_indexes
+                .get(&value)
+                .copied()
+                .unwrap_or(default_shard),
+        })
+    }
+
+    fn write_op(&mut self, index: usize, op: Operation) -> Result<(), ExecutionError> {
+        let shard = &mut self.shards[index];
+        shard.counter += 1;
+        shard.pb.set_position(shard.counter as u64);
+        if shard.counter % 1000 == 0 {
+            try_send(&self.notifier, shard.counter, &shard.name);
+        }
+        write_msg_to_file(
+            &mut shard.buffered_file,
+            &ExecutorOperation::Op { op, origin: None },
+            self.format,
+        )
+    }
+}
+
+impl Sink for ShardedLogSink {
+    fn process(&mut self, _from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        match op {
+            Operation::Delete { old } => {
+                let index = self.shard_of(&old)?;
+                self.write_op(index, Operation::Delete { old })
+            }
+            Operation::Insert { new } => {
+                let index = self.shard_of(&new)?;
+                self.write_op(index, Operation::Insert { new })
+            }
+            Operation::Update { old, new } => {
+                let old_index = self.shard_of(&old)?;
+                let new_index = self.shard_of(&new)?;
+                if old_index == new_index {
+                    self.write_op(new_index, Operation::Update { old, new })
+                } else {
+                    // The record moved to another partition.
+                    self.write_op(old_index, Operation::Delete { old })?;
+                    self.write_op(new_index, Operation::Insert { new })
+                }
+            }
+        }
+    }
+
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        let msg = ExecutorOperation::Commit {
+            epoch: epoch_details.clone(),
+        };
+
+        for shard in &mut self.shards {
+            try_send(&self.notifier, shard.counter, &shard.name);
+            write_msg_to_file(&mut shard.buffered_file, &msg, self.format)?;
+            shard.buffered_file.flush()?;
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        let msg = ExecutorOperation::SnapshottingDone {};
+        for shard in &mut self.shards {
+            write_msg_to_file(&mut shard.buffered_file, &msg, self.format)?;
+        }
+        Ok(())
+    }
+}
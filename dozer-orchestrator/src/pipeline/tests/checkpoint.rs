@@ -0,0 +1,158 @@
+use crate::pipeline::generator_source::{GeneratorSettings, GeneratorSourceFactory};
+use crate::pipeline::{
+    inspect_checkpoints, CommitPolicy, LogSinkFactory, LogSinkSettings, SourceConsistency,
+};
+use dozer_cache::dozer_log::encoding::{encode_frame, LogFormat};
+use dozer_cache::dozer_log::home_dir::HomeDir;
+use dozer_core::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::epoch::{Epoch, ExecutorOperation};
+use dozer_types::indicatif::MultiProgress;
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::serde_json;
+use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
+use std::sync::Arc;
+use tempdir::TempDir;
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .clone()
+}
+
+fn log_sink_factory(temp_dir: &TempDir, endpoint_name: &str) -> Arc<LogSinkFactory> {
+    Arc::new(LogSinkFactory::new(
+        temp_dir.path().join(endpoint_name),
+        LogSinkSettings {
+            file_buffer_capacity: 1024,
+            validate_records: false,
+            exactly_once: false,
+            show_progress: false,
+            collect_stats: false,
+            format: LogFormat::Bincode,
+            upsert: false,
+            lineage: false,
+            commit_policy: CommitPolicy::default(),
+        },
+        endpoint_name.to_string(),
+        MultiProgress::new(),
+        None,
+    ))
+}
+
+/// Writes commits of `source` at each of `txids` to a new migration of `endpoint_name`.
+fn write_commits(home_dir: &HomeDir, endpoint_name: &str, source: &NodeHandle, txids: &[u64]) {
+    let migration_path = home_dir.create_new_migration(endpoint_name).unwrap();
+    let mut bytes = vec![];
+    for (id, txid) in txids.iter().enumerate() {
+        let epoch = Epoch::from(id as u64, source.clone(), *txid, 0);
+        bytes.extend(
+            encode_frame(&ExecutorOperation::Commit { epoch }, LogFormat::Bincode).unwrap(),
+        );
+    }
+    std::fs::write(&migration_path.log_path, bytes).unwrap();
+}
+
+#[test]
+fn test_inspect_checkpoints() {
+    let temp_dir = TempDir::new("test_inspect_checkpoints").unwrap();
+    let home_dir = HomeDir::new(temp_dir.path(), temp_dir.path().join("cache"));
+
+    // `users` feeds `all_users` and `active_users`, `orders` feeds `all_orders`.
+    let users = NodeHandle::new(None, "users".to_string());
+    let orders = NodeHandle::new(None, "orders".to_string());
+    let all_users = NodeHandle::new(None, "all_users".to_string());
+    let active_users = NodeHandle::new(None, "active_users".to_string());
+    let all_orders = NodeHandle::new(None, "all_orders".to_string());
+
+    let mut dag = Dag::<SchemaSQLContext>::new();
+    for source in [&users, &orders] {
+        dag.add_source(
+            source.clone(),
+            Arc::new(GeneratorSourceFactory::new(
+                get_schema(),
+                GeneratorSettings::default(),
+            )),
+        );
+    }
+    for (source, sink) in [
+        (&users, &all_users),
+        (&users, &active_users),
+        (&orders, &all_orders),
+    ] {
+        dag.add_sink(sink.clone(), log_sink_factory(&temp_dir, &sink.id));
+        dag.connect(
+            Endpoint::new(source.clone(), DEFAULT_PORT_HANDLE),
+            Endpoint::new(sink.clone(), DEFAULT_PORT_HANDLE),
+        )
+        .unwrap();
+    }
+
+    write_commits(&home_dir, "all_users", &users, &[1, 2, 5]);
+    write_commits(&home_dir, "active_users", &users, &[1, 3]);
+    write_commits(&home_dir, "all_orders", &orders, &[7]);
+    // A migration newer than the first one of `all_orders` is the one inspected.
+    write_commits(&home_dir, "all_orders", &orders, &[4, 9]);
+    let schema_path = home_dir
+        .find_latest_migration_path("all_orders")
+        .unwrap()
+        .unwrap()
+        .schema_path;
+    std::fs::write(schema_path, serde_json::to_string(&get_schema()).unwrap()).unwrap();
+
+    let report = inspect_checkpoints(&home_dir, &dag).unwrap();
+    assert_eq!(
+        report.sources[&users],
+        SourceConsistency::PartiallyConsistent {
+            latest: Some(OpIdentifier::new(5, 0)),
+            lagging: vec![(active_users, Some(OpIdentifier::new(3, 0)))],
+        }
+    );
+    assert_eq!(
+        report.sources[&orders],
+        SourceConsistency::FullyConsistent(OpIdentifier::new(9, 0))
+    );
+    assert_eq!(report.schemas.len(), 1);
+    assert_eq!(report.schemas[&all_orders], get_schema());
+}
+
+#[test]
+fn test_inspect_checkpoints_without_logs() {
+    let temp_dir = TempDir::new("test_inspect_checkpoints_without_logs").unwrap();
+    let home_dir = HomeDir::new(temp_dir.path(), temp_dir.path().join("cache"));
+
+    let source = NodeHandle::new(None, "users".to_string());
+    let sink = NodeHandle::new(None, "all_users".to_string());
+    let mut dag = Dag::<SchemaSQLContext>::new();
+    dag.add_source(
+        source.clone(),
+        Arc::new(GeneratorSourceFactory::new(
+            get_schema(),
+            GeneratorSettings::default(),
+        )),
+    );
+    dag.add_sink(sink.clone(), log_sink_factory(&temp_dir, &sink.id));
+    dag.connect(
+        Endpoint::new(source.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink, DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    let report = inspect_checkpoints(&home_dir, &dag).unwrap();
+    assert_eq!(
+        report.sources[&source],
+        SourceConsistency::PartiallyConsistent {
+            latest: None,
+            lagging: vec![],
+        }
+    );
+    assert!(report.schemas.is_empty());
+}
@@ -0,0 +1,21 @@
+use crate::pipeline::connector_source::get_schema_id;
+use dozer_core::errors::ExecutionError;
+use dozer_types::types::SchemaIdentifier;
+
+#[test]
+fn test_get_schema_id() {
+    let identifier = SchemaIdentifier { id: 3, version: 1 };
+    let id = get_schema_id(Some(identifier), || unreachable!()).unwrap();
+    assert_eq!(id, 3);
+}
+
+#[test]
+fn test_operation_without_schema_names_its_origin() {
+    let error =
+        get_schema_id(None, || "operation 2:0 of connection users".to_string()).unwrap_err();
+    assert!(matches!(error, ExecutionError::SchemaNotInitialized(_)));
+    assert_eq!(
+        error.to_string(),
+        "Schema not initialized for operation 2:0 of connection users"
+    );
+}
@@ -0,0 +1,188 @@
+use crate::pipeline::csv_source::{CsvSettings, CsvSourceFactory};
+use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, SourceFactory};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use std::collections::HashMap;
+use tempdir::TempDir;
+
+#[derive(Debug, Default)]
+struct TestSourceForwarder {
+    messages: Vec<(IngestionMessage, PortHandle)>,
+}
+
+impl SourceChannelForwarder for TestSourceForwarder {
+    fn send(&mut self, message: IngestionMessage, port: PortHandle) -> Result<(), ExecutionError> {
+        self.messages.push((message, port));
+        Ok(())
+    }
+}
+
+fn run(factory: &CsvSourceFactory) -> Result<Vec<(u64, Record)>, ExecutionError> {
+    let source = factory.build(HashMap::new())?;
+    let mut fw = TestSourceForwarder::default();
+    source.start(&mut fw, None)?;
+
+    Ok(fw
+        .messages
+        .into_iter()
+        .map(|(message, port)| {
+            assert_eq!(port, DEFAULT_PORT_HANDLE);
+            let IngestionMessageKind::OperationEvent(Operation::Insert { new }) = message.kind
+            else {
+                panic!("Expected an insert, got {:?}", message.kind);
+            };
+            (message.identifier.txid, new)
+        })
+        .collect())
+}
+
+#[test]
+fn test_csv_source_infers_schema_and_emits_rows() {
+    let temp_dir = TempDir::new("test_csv_source_infers_schema_and_emits_rows").unwrap();
+    let path = temp_dir.path().join("users.csv");
+    std::fs::write(
+        &path,
+        "id;name;score;active\n1;alice;1.5;true\n2;;2;false\n",
+    )
+    .unwrap();
+
+    let factory = CsvSourceFactory::new(
+        "users".to_string(),
+        path,
+        CsvSettings {
+            delimiter: b';',
+            has_headers: true,
+        },
+        None,
+    )
+    .unwrap();
+
+    let (schema, _) = factory.get_output_schema(&DEFAULT_PORT_HANDLE).unwrap();
+    let types = schema
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.typ, field.nullable))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![
+            ("id", FieldType::Int, false),
+            ("name", FieldType::String, true),
+            ("score", FieldType::Float, false),
+            ("active", FieldType::Boolean, false),
+        ]
+    );
+
+    assert_eq!(
+        run(&factory).unwrap(),
+        vec![
+            (
+                1,
+                Record::new(
+                    None,
+                    vec![
+                        Field::Int(1),
+                        Field::String("alice".to_string()),
+                        Field::Float(1.5.into()),
+                        Field::Boolean(true),
+                    ]
+                )
+            ),
+            (
+                2,
+                Record::new(
+                    None,
+                    vec![
+                        Field::Int(2),
+                        Field::String("".to_string()),
+                        Field::Float(2.0.into()),
+                        Field::Boolean(false),
+                    ]
+                )
+            ),
+        ]
+    );
+}
+
+fn get_declared_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "amount".to_string(),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+#[test]
+fn test_csv_source_parses_declared_schema() {
+    let temp_dir = TempDir::new("test_csv_source_parses_declared_schema").unwrap();
+    let path = temp_dir.path().join("orders.csv");
+    std::fs::write(&path, "7,100\n8,\n").unwrap();
+
+    let factory = CsvSourceFactory::new(
+        "orders".to_string(),
+        path,
+        CsvSettings {
+            delimiter: b',',
+            has_headers: false,
+        },
+        Some(get_declared_schema()),
+    )
+    .unwrap();
+
+    let (schema, _) = factory.get_output_schema(&DEFAULT_PORT_HANDLE).unwrap();
+    assert_eq!(schema.primary_index, vec![0]);
+    assert_eq!(
+        schema.fields[0].source,
+        SourceDefinition::Alias {
+            name: "orders".to_string()
+        }
+    );
+
+    assert_eq!(
+        run(&factory).unwrap(),
+        vec![
+            (1, Record::new(None, vec![Field::UInt(7), Field::Int(100)])),
+            (2, Record::new(None, vec![Field::UInt(8), Field::Null])),
+        ]
+    );
+}
+
+#[test]
+fn test_csv_source_rejects_unparseable_cells() {
+    let temp_dir = TempDir::new("test_csv_source_rejects_unparseable_cells").unwrap();
+    let path = temp_dir.path().join("orders.csv");
+    std::fs::write(&path, "id,amount\n1,abc\n").unwrap();
+
+    let factory = CsvSourceFactory::new(
+        "orders".to_string(),
+        path,
+        CsvSettings::default(),
+        Some(get_declared_schema()),
+    )
+    .unwrap();
+
+    assert!(matches!(
+        run(&factory),
+        Err(ExecutionError::InternalStringError(_))
+    ));
+}
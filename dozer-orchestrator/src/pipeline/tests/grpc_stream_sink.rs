@@ -0,0 +1,121 @@
+use crate::pipeline::grpc_stream_sink::{GrpcStreamSink, GrpcStreamSinkFactory};
+use dozer_core::node::SinkFactory;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::crossbeam::channel::bounded;
+use dozer_types::grpc_types::types::{value, OperationType, Type, Value};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use std::collections::HashMap;
+use std::thread;
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+fn record(id: i64, name: &str) -> Record {
+    Record::new(None, vec![Field::Int(id), Field::String(name.to_string())])
+}
+
+fn values(id: i64, name: &str) -> Vec<Value> {
+    vec![
+        Value {
+            value: Some(value::Value::IntValue(id)),
+        },
+        Value {
+            value: Some(value::Value::StringValue(name.to_string())),
+        },
+    ]
+}
+
+#[test]
+fn test_grpc_stream_sink_streams_operations() {
+    // Capacity 1 so the sink has to wait for the receiver to drain.
+    let (sender, receiver) = bounded(1);
+    let factory = GrpcStreamSinkFactory::new("users".to_string(), sender);
+    let mut sink = factory
+        .build(HashMap::from([(DEFAULT_PORT_HANDLE, get_schema())]))
+        .unwrap();
+
+    let ops = vec![
+        Operation::Insert {
+            new: record(1, "a"),
+        },
+        Operation::Update {
+            old: record(1, "a"),
+            new: record(1, "b"),
+        },
+        Operation::Delete {
+            old: record(1, "b"),
+        },
+    ];
+
+    let client = thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+    for op in ops {
+        sink.process(DEFAULT_PORT_HANDLE, op).unwrap();
+    }
+    drop(sink);
+    drop(factory);
+    let streamed = client.join().unwrap();
+
+    let streamed = streamed
+        .into_iter()
+        .map(|op| {
+            assert_eq!(op.endpoint_name, "users");
+            (
+                op.typ,
+                op.old.map(|record| record.values),
+                op.new.unwrap().values,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        streamed,
+        vec![
+            (OperationType::Insert as i32, None, values(1, "a")),
+            (
+                OperationType::Update as i32,
+                Some(values(1, "a")),
+                values(1, "b")
+            ),
+            (OperationType::Delete as i32, None, values(1, "b")),
+        ]
+    );
+}
+
+#[test]
+fn test_grpc_stream_sink_exposes_field_definitions() {
+    let (sender, _receiver) = bounded(1);
+    let sink = GrpcStreamSink::new("users".to_string(), get_schema(), sender);
+    let fields = sink
+        .fields()
+        .iter()
+        .map(|field| (field.name.as_str(), field.typ, field.nullable))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("id", Type::Int as i32, false),
+            ("name", Type::String as i32, true),
+        ]
+    );
+}
@@ -0,0 +1,641 @@
+use std::path::Path;
+
+use crate::pipeline::{CommitPolicy, LogSink, ShardedLogSink};
+use dozer_api::grpc::types_helper::field_to_prost_value;
+use dozer_cache::dozer_log::encoding::{read_frame, LogFormat};
+use dozer_cache::dozer_log::reader::LogReader;
+use dozer_core::errors::{ExecutionError, SinkError};
+use dozer_core::node::Sink;
+use dozer_core::stats::StatsCollector;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql::pipeline::expression::execution::Expression;
+use dozer_types::crossbeam::channel::unbounded;
+use dozer_types::epoch::{Epoch, ExecutorOperation, OpOrigin};
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use dozer_types::{bincode, serde_json};
+use std::sync::Arc;
+use std::time::Duration;
+use tempdir::TempDir;
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+fn insert(values: Vec<Field>) -> Operation {
+    Operation::Insert {
+        new: Record::new(None, values),
+    }
+}
+
+#[test]
+fn test_log_sink_rejects_mismatched_records() {
+    let temp_dir = TempDir::new("test_log_sink_rejects_mismatched_records").unwrap();
+    let mut sink = LogSink::new(
+        None,
+        temp_dir.path().join("log"),
+        1024,
+        "endpoint".to_string(),
+        None,
+    )
+    .unwrap()
+    .with_validation(get_schema());
+
+    sink.process(
+        DEFAULT_PORT_HANDLE,
+        insert(vec![Field::Int(1), Field::String("a".to_string())]),
+    )
+    .unwrap();
+    sink.process(
+        DEFAULT_PORT_HANDLE,
+        insert(vec![Field::Int(2), Field::Null]),
+    )
+    .unwrap();
+
+    assert!(matches!(
+        sink.process(DEFAULT_PORT_HANDLE, insert(vec![Field::Int(3)])),
+        Err(ExecutionError::SinkError(
+            SinkError::RecordFieldCountMismatch {
+                expected: 2,
+                actual: 1
+            }
+        ))
+    ));
+    assert!(matches!(
+        sink.process(
+            DEFAULT_PORT_HANDLE,
+            insert(vec![Field::String("4".to_string()), Field::Null])
+        ),
+        Err(ExecutionError::SinkError(
+            SinkError::RecordFieldTypeMismatch {
+                expected: FieldType::Int,
+                ..
+            }
+        ))
+    ));
+    assert!(matches!(
+        sink.process(DEFAULT_PORT_HANDLE, insert(vec![Field::Null, Field::Null])),
+        Err(ExecutionError::SinkError(
+            SinkError::RecordFieldTypeMismatch { .. }
+        ))
+    ));
+}
+
+#[test]
+fn test_log_sink_does_not_validate_by_default() {
+    let temp_dir = TempDir::new("test_log_sink_does_not_validate_by_default").unwrap();
+    let mut sink = LogSink::new(
+        None,
+        temp_dir.path().join("log"),
+        1024,
+        "endpoint".to_string(),
+        None,
+    )
+    .unwrap();
+
+    sink.process(DEFAULT_PORT_HANDLE, insert(vec![Field::Int(3)]))
+        .unwrap();
+}
+
+fn read_ops(path: &Path) -> Vec<ExecutorOperation> {
+    let mut reader = std::io::BufReader::new(std::fs::File::open(path).unwrap());
+    let mut ops = vec![];
+    while let Some(op) = read_frame(&mut reader).unwrap() {
+        ops.push(op);
+    }
+    ops
+}
+
+fn inserted_ids(path: &Path) -> Vec<Field> {
+    let ops = read_ops(path);
+    assert!(matches!(ops.last(), Some(ExecutorOperation::Commit { .. })));
+    ops.into_iter()
+        .filter_map(|op| match op {
+            ExecutorOperation::Op {
+                op: Operation::Insert { new },
+                ..
+            } => Some(new.values[0].clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_sharded_log_sink_routes_records_by_partition() {
+    let temp_dir = TempDir::new("test_sharded_log_sink_routes_records_by_partition").unwrap();
+    let eu_path = temp_dir.path().join("eu");
+    let us_path = temp_dir.path().join("us");
+    let default_path = temp_dir.path().join("default");
+
+    let mut sink = ShardedLogSink::new(
+        None,
+        Expression::Column { index: 1 },
+        get_schema(),
+        vec![
+            (Field::String("eu".to_string()), eu_path.clone()),
+            (Field::String("us".to_string()), us_path.clone()),
+        ],
+        default_path.clone(),
+        1024,
+        "endpoint".to_string(),
+        None,
+    )
+    .unwrap();
+
+    for (id, region) in [(1, "eu"), (2, "us"), (3, "eu"), (4, "asia")] {
+        sink.process(
+            DEFAULT_PORT_HANDLE,
+            insert(vec![Field::Int(id), Field::String(region.to_string())]),
+        )
+        .unwrap();
+    }
+    sink.process(
+        DEFAULT_PORT_HANDLE,
+        insert(vec![Field::Int(5), Field::Null]),
+    )
+    .unwrap();
+    sink.commit(&Epoch::new(0, Default::default())).unwrap();
+
+    assert_eq!(inserted_ids(&eu_path), vec![Field::Int(1), Field::Int(3)]);
+    assert_eq!(inserted_ids(&us_path), vec![Field::Int(2)]);
+    assert_eq!(
+        inserted_ids(&default_path),
+        vec![Field::Int(4), Field::Int(5)]
+    );
+}
+
+#[test]
+fn test_log_sink_without_progress_bar_counts_operations() {
+    let temp_dir = TempDir::new("test_log_sink_without_progress_bar_counts_operations").unwrap();
+    let mut sink = LogSink::new(
+        None,
+        temp_dir.path().join("log"),
+        1024,
+        "endpoint".to_string(),
+        None,
+    )
+    .unwrap();
+
+    for id in 0..3 {
+        sink.process(
+            DEFAULT_PORT_HANDLE,
+            insert(vec![Field::Int(id), Field::Null]),
+        )
+        .unwrap();
+    }
+    sink.commit(&Epoch::new(0, Default::default())).unwrap();
+
+    assert_eq!(sink.count(), 3);
+}
+
+#[test]
+fn test_log_sink_reports_column_stats() {
+    let temp_dir = TempDir::new("test_log_sink_reports_column_stats").unwrap();
+    let (alias_sender, _alias_receiver) = unbounded();
+    let (operation_sender, _operation_receiver) = unbounded();
+    let (status_sender, status_receiver) = unbounded();
+    let mut sink = LogSink::new(
+        None,
+        temp_dir.path().join("log"),
+        1024,
+        "endpoint".to_string(),
+        Some((alias_sender, operation_sender, status_sender)),
+    )
+    .unwrap()
+    .with_stats(&get_schema());
+    assert!(sink.collects_stats());
+
+    let mut collector = StatsCollector::default();
+    for (id, name) in [(2, Some("b")), (1, None), (3, Some("a")), (4, None)] {
+        let op = insert(vec![
+            Field::Int(id),
+            name.map_or(Field::Null, |name| Field::String(name.to_string())),
+        ]);
+        collector.observe(&op);
+        sink.process(DEFAULT_PORT_HANDLE, op).unwrap();
+    }
+    sink.on_stats(collector.take()).unwrap();
+
+    let update = status_receiver
+        .try_iter()
+        .find(|update| update.r#type == "stats")
+        .unwrap();
+    assert_eq!(update.source, "endpoint");
+    assert_eq!(update.count, 4);
+    let column_stats = update
+        .column_stats
+        .into_iter()
+        .map(|stats| (stats.name, stats.min, stats.max, stats.null_count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        column_stats,
+        vec![
+            (
+                "id".to_string(),
+                Some(field_to_prost_value(Field::Int(1))),
+                Some(field_to_prost_value(Field::Int(4))),
+                0
+            ),
+            (
+                "name".to_string(),
+                Some(field_to_prost_value(Field::String("a".to_string()))),
+                Some(field_to_prost_value(Field::String("b".to_string()))),
+                2
+            ),
+        ]
+    );
+}
+
+fn read_log(path: &Path, count: usize) -> Vec<ExecutorOperation> {
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let mut reader = LogReader::new(path, "log", 0, None).await.unwrap();
+        let mut ops = vec![];
+        for _ in 0..count {
+            ops.push(reader.next_op().await);
+        }
+        ops
+    })
+}
+
+#[test]
+fn test_log_sink_round_trips_every_format() {
+    for format in [LogFormat::Bincode, LogFormat::MessagePack] {
+        let temp_dir = TempDir::new("test_log_sink_round_trips_every_format").unwrap();
+        let path = temp_dir.path().join("log");
+        let mut sink = LogSink::new(None, path.clone(), 1024, "endpoint".to_string(), None)
+            .unwrap()
+            .with_format(format);
+
+        let ops = vec![
+            insert(vec![Field::Int(1), Field::String("a".to_string())]),
+            Operation::Update {
+                old: Record::new(None, vec![Field::Int(1), Field::String("a".to_string())]),
+                new: Record::new(None, vec![Field::Int(1), Field::Null]),
+            },
+            Operation::Delete {
+                old: Record::new(None, vec![Field::Int(1), Field::Null]),
+            },
+        ];
+        for op in ops.clone() {
+            sink.process(DEFAULT_PORT_HANDLE, op).unwrap();
+        }
+        let epoch = Epoch::from(1, NodeHandle::new(None, "source".to_string()), 1, 0);
+        sink.commit(&epoch).unwrap();
+
+        let mut expected = ops
+            .into_iter()
+            .map(|op| ExecutorOperation::Op { op, origin: None })
+            .collect::<Vec<_>>();
+        expected.push(ExecutorOperation::Commit { epoch });
+        assert_eq!(read_log(&path, expected.len()), expected, "{format:?}");
+    }
+}
+
+#[test]
+fn test_log_reader_reads_legacy_bincode_frames() {
+    let temp_dir = TempDir::new("test_log_reader_reads_legacy_bincode_frames").unwrap();
+    let path = temp_dir.path().join("log");
+
+    // Frames written before the format byte was introduced: a length and a bare bincode payload.
+    let ops = vec![
+        ExecutorOperation::Op {
+            op: insert(vec![Field::Int(1), Field::String("a".to_string())]),
+            origin: None,
+        },
+        ExecutorOperation::Commit {
+            epoch: Epoch::from(1, NodeHandle::new(None, "source".to_string()), 1, 0),
+        },
+    ];
+    let mut bytes = vec![];
+    for op in &ops {
+        let payload = bincode::serialize(op).unwrap();
+        bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+    }
+    std::fs::write(&path, bytes).unwrap();
+
+    assert_eq!(read_log(&path, ops.len()), ops);
+}
+
+#[test]
+fn test_log_sink_upserts_existing_primary_keys() {
+    let temp_dir = TempDir::new("test_log_sink_upserts_existing_primary_keys").unwrap();
+    let path = temp_dir.path().join("log");
+    let mut sink = LogSink::new(None, path.clone(), 1024, "endpoint".to_string(), None)
+        .unwrap()
+        .with_upsert(&get_schema());
+
+    // An aggregation re-emitting the group with id 1.
+    let first = Record::new(None, vec![Field::Int(1), Field::String("a".to_string())]);
+    let second = Record::new(None, vec![Field::Int(1), Field::String("b".to_string())]);
+    sink.process(
+        DEFAULT_PORT_HANDLE,
+        Operation::Insert { new: first.clone() },
+    )
+    .unwrap();
+    sink.process(
+        DEFAULT_PORT_HANDLE,
+        Operation::Insert {
+            new: second.clone(),
+        },
+    )
+    .unwrap();
+    sink.commit(&Epoch::new(0, Default::default())).unwrap();
+
+    let ops = read_ops(&path)
+        .into_iter()
+        .filter_map(|op| match op {
+            ExecutorOperation::Op { op, .. } => Some(op),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ops,
+        vec![
+            Operation::Insert { new: first.clone() },
+            Operation::Update {
+                old: first,
+                new: second
+            },
+        ]
+    );
+}
+
+fn open_exactly_once_sink(dir: &Path) -> LogSink {
+    LogSink::new(None, dir.join("log"), 1024, "endpoint".to_string(), None)
+        .unwrap()
+        .with_exactly_once(dir.join("log.seq"))
+        .unwrap()
+}
+
+/// Inserts ids `ids` and commits them as transaction `txid` of the source.
+fn write_transaction(sink: &mut LogSink, ids: std::ops::Range<i64>, txid: u64) {
+    for id in ids {
+        sink.process(
+            DEFAULT_PORT_HANDLE,
+            insert(vec![Field::Int(id), Field::Null]),
+        )
+        .unwrap();
+    }
+    let source = NodeHandle::new(None, "source".to_string());
+    sink.commit(&Epoch::from(txid, source, txid, 0)).unwrap();
+}
+
+#[test]
+fn test_log_sink_skips_replayed_operations() {
+    let temp_dir = TempDir::new("test_log_sink_skips_replayed_operations").unwrap();
+
+    let mut sink = open_exactly_once_sink(temp_dir.path());
+    write_transaction(&mut sink, 0..3, 1);
+    write_transaction(&mut sink, 3..6, 2);
+    // Not committed, so lost on restart and replayed.
+    sink.process(
+        DEFAULT_PORT_HANDLE,
+        insert(vec![Field::Int(6), Field::Null]),
+    )
+    .unwrap();
+    drop(sink);
+
+    // The source restarts from an earlier checkpoint and replays its log.
+    let mut sink = open_exactly_once_sink(temp_dir.path());
+    write_transaction(&mut sink, 0..3, 1);
+    write_transaction(&mut sink, 3..6, 2);
+    write_transaction(&mut sink, 6..8, 3);
+
+    let log_path = temp_dir.path().join("log");
+    assert_eq!(
+        inserted_ids(&log_path),
+        (0..8).map(Field::Int).collect::<Vec<_>>()
+    );
+    let commits = read_ops(&log_path)
+        .into_iter()
+        .filter(|op| matches!(op, ExecutorOperation::Commit { .. }))
+        .count();
+    assert_eq!(commits, 3);
+}
+
+#[test]
+fn test_log_sink_skips_operations_from_committed_origins() {
+    let temp_dir = TempDir::new("test_log_sink_skips_operations_from_committed_origins").unwrap();
+    let source = Arc::new(NodeHandle::new(None, "source".to_string()));
+    // Inserts each id as the operation at `txid == id` of the source, then commits.
+    let write = |sink: &mut LogSink, ids: std::ops::Range<u64>| {
+        for id in ids.clone() {
+            let origin = OpOrigin {
+                source: source.clone(),
+                id: OpIdentifier::new(id, 0),
+                tags: None,
+            };
+            sink.process_with_origin(
+                DEFAULT_PORT_HANDLE,
+                insert(vec![Field::Int(id as i64), Field::Null]),
+                Some(&origin),
+            )
+            .unwrap();
+        }
+        let epoch = Epoch::from(0, source.as_ref().clone(), ids.end - 1, 0);
+        sink.commit(&epoch).unwrap();
+    };
+
+    let mut sink = open_exactly_once_sink(temp_dir.path());
+    write(&mut sink, 0..3);
+    drop(sink);
+
+    // The replay overlaps the committed operations and goes past them.
+    let mut sink = open_exactly_once_sink(temp_dir.path());
+    write(&mut sink, 1..6);
+
+    assert_eq!(
+        inserted_ids(&temp_dir.path().join("log")),
+        (0..6).map(Field::Int).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_log_sink_writes_lineage_of_operations() {
+    let temp_dir = TempDir::new("test_log_sink_writes_lineage_of_operations").unwrap();
+    let lineage_path = temp_dir.path().join("log.lineage");
+    let mut sink = LogSink::new(
+        None,
+        temp_dir.path().join("log"),
+        1024,
+        "test".to_string(),
+        None,
+    )
+    .unwrap()
+    .with_lineage(lineage_path.clone())
+    .unwrap();
+
+    let source = Arc::new(NodeHandle::new(None, "source".to_string()));
+    let tags = Arc::new(
+        [("batch".to_string(), "b1".to_string())]
+            .into_iter()
+            .collect(),
+    );
+    let origin = OpOrigin {
+        source: source.clone(),
+        id: OpIdentifier::new(7, 2),
+        tags: Some(tags),
+    };
+    sink.process(
+        DEFAULT_PORT_HANDLE,
+        insert(vec![Field::Int(0), Field::Null]),
+    )
+    .unwrap();
+    sink.process_with_origin(
+        DEFAULT_PORT_HANDLE,
+        insert(vec![Field::Int(1), Field::Null]),
+        Some(&origin),
+    )
+    .unwrap();
+    sink.commit(&Epoch::from(0, source.as_ref().clone(), 7, 2))
+        .unwrap();
+
+    // The operation of unknown origin has no line, but still counts.
+    let lines = std::fs::read_to_string(&lineage_path).unwrap();
+    let lines = lines
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![serde_json::json!({
+            "op": 1,
+            "source": source.to_string(),
+            "txid": 7,
+            "seq_in_tx": 2,
+            "tags": {"batch": "b1"},
+        })]
+    );
+}
+
+#[test]
+fn test_log_sink_flushes_uncommitted_operations_on_drop() {
+    let temp_dir = TempDir::new("test_log_sink_flushes_uncommitted_operations_on_drop").unwrap();
+    let log_path = temp_dir.path().join("log");
+    let mut sink = LogSink::new(
+        None,
+        log_path.clone(),
+        1024 * 1024,
+        "endpoint".to_string(),
+        None,
+    )
+    .unwrap();
+
+    sink.process(
+        DEFAULT_PORT_HANDLE,
+        insert(vec![Field::Int(1), Field::Null]),
+    )
+    .unwrap();
+    assert!(read_ops(&log_path).is_empty());
+    drop(sink);
+
+    let ops = read_ops(&log_path);
+    assert_eq!(ops.len(), 1);
+    assert!(matches!(
+        &ops[0],
+        ExecutorOperation::Op {
+            op: Operation::Insert { new },
+            ..
+        } if new.values == vec![Field::Int(1), Field::Null]
+    ));
+}
+
+/// A sink whose buffer is large enough that the log is only written to disk when flushed.
+fn open_sink_with_commit_policy(dir: &Path, policy: CommitPolicy) -> LogSink {
+    LogSink::new(
+        None,
+        dir.join("log"),
+        1024 * 1024,
+        "endpoint".to_string(),
+        None,
+    )
+    .unwrap()
+    .with_commit_policy(policy)
+}
+
+fn commits_on_disk(path: &Path) -> usize {
+    read_ops(path)
+        .into_iter()
+        .filter(|op| matches!(op, ExecutorOperation::Commit { .. }))
+        .count()
+}
+
+#[test]
+fn test_log_sink_flushes_every_n_commits() {
+    let temp_dir = TempDir::new("test_log_sink_flushes_every_n_commits").unwrap();
+    let log_path = temp_dir.path().join("log");
+    let mut sink = open_sink_with_commit_policy(temp_dir.path(), CommitPolicy::EveryNCommits(3));
+
+    let mut flushes = vec![];
+    for txid in 1..=7 {
+        write_transaction(&mut sink, 0..1, txid);
+        flushes.push(sink.flush_count());
+    }
+    assert_eq!(flushes, vec![0, 0, 1, 1, 1, 2, 2]);
+    assert_eq!(sink.durable_epoch(), Some(6));
+    assert_eq!(commits_on_disk(&log_path), 6);
+}
+
+#[test]
+fn test_log_sink_flushes_every_n_records() {
+    let temp_dir = TempDir::new("test_log_sink_flushes_every_n_records").unwrap();
+    let log_path = temp_dir.path().join("log");
+    let mut sink = open_sink_with_commit_policy(temp_dir.path(), CommitPolicy::EveryNRecords(5));
+
+    // Two operations per commit, so every third commit crosses the threshold.
+    let mut flushes = vec![];
+    for txid in 1..=5 {
+        write_transaction(&mut sink, 0..2, txid);
+        flushes.push(sink.flush_count());
+    }
+    assert_eq!(flushes, vec![0, 0, 1, 1, 1]);
+    assert_eq!(sink.durable_epoch(), Some(3));
+    assert_eq!(commits_on_disk(&log_path), 3);
+}
+
+#[test]
+fn test_log_sink_flushes_every_duration() {
+    let temp_dir = TempDir::new("test_log_sink_flushes_every_duration").unwrap();
+    let log_path = temp_dir.path().join("log");
+    let mut sink = open_sink_with_commit_policy(
+        temp_dir.path(),
+        CommitPolicy::EveryDuration(Duration::from_millis(100)),
+    );
+
+    write_transaction(&mut sink, 0..1, 1);
+    assert_eq!(sink.flush_count(), 0);
+    assert_eq!(sink.durable_epoch(), None);
+
+    std::thread::sleep(Duration::from_millis(150));
+    write_transaction(&mut sink, 0..1, 2);
+    write_transaction(&mut sink, 0..1, 3);
+    assert_eq!(sink.flush_count(), 1);
+    assert_eq!(sink.durable_epoch(), Some(2));
+    assert_eq!(commits_on_disk(&log_path), 2);
+
+    // Commits that weren't flushed yet are flushed when the sink is dropped.
+    drop(sink);
+    assert_eq!(commits_on_disk(&log_path), 3);
+}
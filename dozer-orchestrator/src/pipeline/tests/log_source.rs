@@ -0,0 +1,225 @@
+use crate::pipeline::log_source::LogSourceFactory;
+use crate::pipeline::LogSink;
+use dozer_cache::dozer_log::encoding::{encode_frame, LogFormat};
+use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::ExecutionError;
+use dozer_core::executor::{DagExecutor, ExecutorOptions};
+use dozer_core::node::{PortHandle, Sink, SinkFactory, SourceFactory};
+use dozer_core::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::epoch::ExecutorOperation;
+use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind};
+use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use tempdir::TempDir;
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .to_owned()
+}
+
+fn record(id: i64) -> Record {
+    Record::new(None, vec![Field::Int(id)])
+}
+
+/// Writes `transactions` to a log at `path`, committing after each, then `uncommitted`.
+fn write_log(path: &Path, transactions: &[Vec<Operation>], uncommitted: &[Operation]) {
+    let mut sink = LogSink::new(None, path.to_path_buf(), 1024, "log".to_string(), None).unwrap();
+    for (id, ops) in transactions.iter().enumerate() {
+        for op in ops {
+            sink.process(DEFAULT_PORT_HANDLE, op.clone()).unwrap();
+        }
+        sink.commit(&Epoch::new(id as u64, Default::default()))
+            .unwrap();
+    }
+    for op in uncommitted {
+        sink.process(DEFAULT_PORT_HANDLE, op.clone()).unwrap();
+    }
+    // Dropping the sink flushes its buffer.
+}
+
+fn transactions() -> Vec<Vec<Operation>> {
+    vec![
+        vec![
+            Operation::Insert { new: record(1) },
+            Operation::Insert { new: record(2) },
+        ],
+        vec![
+            Operation::Update {
+                old: record(1),
+                new: record(3),
+            },
+            Operation::Delete { old: record(2) },
+        ],
+    ]
+}
+
+#[derive(Debug)]
+struct VecSinkFactory {
+    ops: Arc<Mutex<Vec<Operation>>>,
+}
+
+impl SinkFactory<SchemaSQLContext> for VecSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(VecSink {
+            ops: self.ops.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct VecSink {
+    ops: Arc<Mutex<Vec<Operation>>>,
+}
+
+impl Sink for VecSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(&mut self, _from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        self.ops.lock().unwrap().push(op);
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_log_source_replays_committed_operations() {
+    let temp_dir = TempDir::new("test_log_source_replays_committed_operations").unwrap();
+    let log_path = temp_dir.path().join("log");
+    let transactions = transactions();
+    write_log(
+        &log_path,
+        &transactions,
+        &[Operation::Insert { new: record(4) }],
+    );
+
+    let ops = Arc::new(Mutex::new(vec![]));
+    let source = NodeHandle::new(None, "log".to_string());
+    let sink = NodeHandle::new(None, "sink".to_string());
+    let mut dag = Dag::<SchemaSQLContext>::new();
+    dag.add_source(
+        source.clone(),
+        Arc::new(LogSourceFactory::new(log_path, get_schema())),
+    );
+    dag.add_sink(sink.clone(), Arc::new(VecSinkFactory { ops: ops.clone() }));
+    dag.connect(
+        Endpoint::new(source, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink, DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    // The operation written after the last commit is not replayed.
+    assert_eq!(*ops.lock().unwrap(), transactions.concat());
+}
+
+#[derive(Debug, Default)]
+struct TestSourceForwarder {
+    messages: Vec<IngestionMessage>,
+}
+
+impl SourceChannelForwarder for TestSourceForwarder {
+    fn send(&mut self, message: IngestionMessage, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.messages.push(message);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_log_source_resumes_after_checkpoint() {
+    let temp_dir = TempDir::new("test_log_source_resumes_after_checkpoint").unwrap();
+    let log_path = temp_dir.path().join("log");
+    write_log(&log_path, &transactions(), &[]);
+
+    let replay = |factory: LogSourceFactory, checkpoint| {
+        let source = factory.build(HashMap::new()).unwrap();
+        let mut fw = TestSourceForwarder::default();
+        source.start(&mut fw, checkpoint).unwrap();
+        fw.messages
+            .into_iter()
+            .map(|message| {
+                assert!(matches!(
+                    message.kind,
+                    IngestionMessageKind::OperationEvent(_)
+                ));
+                message.identifier
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        replay(
+            LogSourceFactory::new(log_path.clone(), get_schema()),
+            Some((0, 1))
+        ),
+        vec![OpIdentifier::new(1, 0), OpIdentifier::new(1, 1)]
+    );
+    // A checkpoint takes precedence over the start position.
+    let factory = LogSourceFactory::new(log_path, get_schema()).with_start(OpIdentifier::new(0, 0));
+    assert_eq!(replay(factory, Some((1, 0))), vec![OpIdentifier::new(1, 1)]);
+}
+
+#[test]
+fn test_log_source_fails_on_corrupt_frames() {
+    let temp_dir = TempDir::new("test_log_source_fails_on_corrupt_frames").unwrap();
+    let log_path = temp_dir.path().join("log");
+    let mut bytes = encode_frame(
+        &ExecutorOperation::Op {
+            op: Operation::Insert { new: record(1) },
+            origin: None,
+        },
+        LogFormat::Bincode,
+    )
+    .unwrap();
+    // A format no version knows.
+    bytes[9] = u8::MAX;
+    std::fs::write(&log_path, bytes).unwrap();
+
+    let source = LogSourceFactory::new(log_path, get_schema())
+        .build(HashMap::new())
+        .unwrap();
+    let mut fw = TestSourceForwarder::default();
+    assert!(source.start(&mut fw, None).is_err());
+    assert!(fw.messages.is_empty());
+}
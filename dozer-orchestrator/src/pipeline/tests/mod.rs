@@ -1 +1,8 @@
 mod builder;
+mod checkpoint;
+mod connector_source;
+mod csv_source;
+mod generator_source;
+mod grpc_stream_sink;
+mod log_sink;
+mod log_source;
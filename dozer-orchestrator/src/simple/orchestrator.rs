@@ -1,7 +1,7 @@
 use super::executor::Executor;
 use crate::console_helper::get_colored_text;
 use crate::errors::OrchestrationError;
-use crate::pipeline::{LogSinkSettings, PipelineBuilder};
+use crate::pipeline::{CommitPolicy, LogSinkSettings, PipelineBuilder};
 use crate::shutdown::ShutdownReceiver;
 use crate::simple::helper::validate_config;
 use crate::utils::{
@@ -14,6 +14,7 @@ use dozer_api::auth::{Access, Authorizer};
 use dozer_api::generator::protoc::generator::ProtoGenerator;
 use dozer_api::{grpc, rest, CacheEndpoint};
 use dozer_cache::cache::LmdbRwCacheManager;
+use dozer_cache::dozer_log::encoding::LogFormat;
 use dozer_cache::dozer_log::home_dir::HomeDir;
 use dozer_cache::dozer_log::schemas::write_schema;
 use dozer_core::app::AppPipeline;
@@ -187,6 +188,14 @@ impl Orchestrator for SimpleOrchestrator {
         )?;
         let settings = LogSinkSettings {
             file_buffer_capacity: get_file_buffer_capacity(&self.config),
+            validate_records: false,
+            exactly_once: false,
+            show_progress: true,
+            collect_stats: false,
+            format: LogFormat::default(),
+            upsert: false,
+            lineage: false,
+            commit_policy: CommitPolicy::default(),
         };
         let dag_executor = executor.create_dag_executor(
             self.runtime.clone(),
@@ -277,6 +286,14 @@ impl Orchestrator for SimpleOrchestrator {
         );
         let settings = LogSinkSettings {
             file_buffer_capacity: get_file_buffer_capacity(&self.config),
+            validate_records: false,
+            exactly_once: false,
+            show_progress: true,
+            collect_stats: false,
+            format: LogFormat::default(),
+            upsert: false,
+            lineage: false,
+            commit_policy: CommitPolicy::default(),
         };
         let dag = builder.build(self.runtime.clone(), settings, None)?;
         // Populate schemas.
@@ -0,0 +1,93 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use dozer_sql::pipeline::aggregation::aggregator::Aggregator;
+use dozer_sql::pipeline::aggregation::max::MaxAggregator;
+use dozer_sql::pipeline::aggregation::min::MinAggregator;
+use dozer_sql::pipeline::aggregation::sum::SumAggregator;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+
+fn sum(c: &mut Criterion, typ: FieldType, fields: &[Field]) {
+    c.bench_with_input(
+        BenchmarkId::new(format!("sum_{typ}_per_row"), fields.len()),
+        fields,
+        |b, fields| {
+            b.iter(|| {
+                let mut aggregator = SumAggregator::new();
+                aggregator.init(typ);
+                for field in fields {
+                    aggregator.insert(std::slice::from_ref(field)).unwrap();
+                }
+            })
+        },
+    );
+
+    c.bench_with_input(
+        BenchmarkId::new(format!("sum_{typ}_batch"), fields.len()),
+        fields,
+        |b, fields| {
+            b.iter(|| {
+                let mut aggregator = SumAggregator::new();
+                aggregator.init(typ);
+                aggregator.insert(fields).unwrap()
+            })
+        },
+    );
+}
+
+/// Deletes and reinserts the current extreme of a group of `group_size` distinct values. The time
+/// per operation should grow with the logarithm of the group size, not linearly.
+fn churn_extreme<A: Aggregator>(
+    c: &mut Criterion,
+    name: &str,
+    mut aggregator: A,
+    extreme: i64,
+    group_size: i64,
+) {
+    aggregator.init(FieldType::Int);
+    for i in 0..group_size {
+        aggregator.insert(&[Field::Int(i)]).unwrap();
+    }
+
+    let extreme = [Field::Int(extreme)];
+    c.bench_function(&format!("{name}_churn_{group_size}"), |b| {
+        b.iter(|| {
+            aggregator.delete(&extreme).unwrap();
+            aggregator.insert(&extreme).unwrap()
+        })
+    });
+}
+
+fn aggregation(c: &mut Criterion) {
+    let size = std::env::var("AGGREGATION_BENCH_BATCH_SIZE").unwrap_or("".to_string());
+    let size: i64 = size.parse().unwrap_or(1_000_000);
+
+    let ints = (0..size)
+        .map(|i| {
+            if i % 10 == 0 {
+                Field::Null
+            } else {
+                Field::Int(i)
+            }
+        })
+        .collect::<Vec<_>>();
+    sum(c, FieldType::Int, &ints);
+
+    let floats = (0..size)
+        .map(|i| {
+            if i % 10 == 0 {
+                Field::Null
+            } else {
+                Field::Float(OrderedFloat(i as f64))
+            }
+        })
+        .collect::<Vec<_>>();
+    sum(c, FieldType::Float, &floats);
+
+    for group_size in [1_000, 100_000] {
+        churn_extreme(c, "min", MinAggregator::new(), 0, group_size);
+        churn_extreme(c, "max", MaxAggregator::new(), group_size - 1, group_size);
+    }
+}
+
+criterion_group!(benches, aggregation);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use dozer_sql::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use dozer_sql::pipeline::expression::operator::BinaryOperatorType;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+fn schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "a".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "b".to_string(),
+                FieldType::Float,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn binary(left: Expression, operator: BinaryOperatorType, right: Expression) -> Expression {
+    Expression::BinaryOperator {
+        left: Box::new(left),
+        operator,
+        right: Box::new(right),
+    }
+}
+
+fn expression(c: &mut Criterion) {
+    let schema = schema();
+
+    let size = std::env::var("EXPRESSION_BENCH_BATCH_SIZE").unwrap_or("".to_string());
+    let size: usize = size.parse().unwrap_or(10000);
+    let records = (0..size)
+        .map(|i| {
+            Record::new(
+                None,
+                vec![Field::Int(i as i64), Field::Float(OrderedFloat(i as f64))],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // (a * 2 + b) > 100
+    let expression = binary(
+        binary(
+            binary(
+                Expression::Column { index: 0 },
+                BinaryOperatorType::Mul,
+                Expression::Literal(Field::Int(2)),
+            ),
+            BinaryOperatorType::Add,
+            Expression::Column { index: 1 },
+        ),
+        BinaryOperatorType::Gt,
+        Expression::Literal(Field::Int(100)),
+    );
+
+    c.bench_with_input(
+        BenchmarkId::new("expression_evaluate", size),
+        &records,
+        |b, records| {
+            b.iter(|| {
+                records
+                    .iter()
+                    .map(|record| expression.evaluate(record, &schema).unwrap())
+                    .collect::<Vec<_>>()
+            })
+        },
+    );
+
+    c.bench_with_input(
+        BenchmarkId::new("expression_evaluate_batch", size),
+        &records,
+        |b, records| b.iter(|| expression.evaluate_batch(records, &schema).unwrap()),
+    );
+}
+
+criterion_group!(benches, expression);
+criterion_main!(benches);
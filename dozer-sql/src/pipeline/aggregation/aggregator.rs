@@ -1,7 +1,11 @@
 #![allow(clippy::enum_variant_names)]
 
+use crate::pipeline::aggregation::approx_count_distinct::{
+    get_precision, ApproxCountDistinctAggregator,
+};
 use crate::pipeline::aggregation::avg::AvgAggregator;
 use crate::pipeline::aggregation::count::CountAggregator;
+use crate::pipeline::aggregation::custom::CustomAggregator;
 use crate::pipeline::aggregation::max::MaxAggregator;
 use crate::pipeline::aggregation::min::MinAggregator;
 use crate::pipeline::aggregation::sum::SumAggregator;
@@ -12,6 +16,7 @@ use std::collections::BTreeMap;
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::execution::Expression;
 
+use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::types::{Field, FieldType, Schema};
 use std::fmt::{Debug, Display, Formatter};
 
@@ -26,11 +31,13 @@ pub trait Aggregator: Send + Sync {
 #[enum_dispatch(Aggregator)]
 #[derive(Debug)]
 pub enum AggregatorEnum {
+    ApproxCountDistinctAggregator,
     AvgAggregator,
     MinAggregator,
     MaxAggregator,
     SumAggregator,
     CountAggregator,
+    CustomAggregator,
 }
 
 impl Debug for dyn Aggregator {
@@ -39,34 +46,47 @@ impl Debug for dyn Aggregator {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum AggregatorType {
+    ApproxCountDistinct {
+        precision: u8,
+    },
     Avg,
     Count,
     Max,
     Min,
     Sum,
+    /// A function registered with [`register_aggregate_function`], by lowercase name.
+    ///
+    /// [`register_aggregate_function`]: crate::pipeline::aggregation::custom::register_aggregate_function
+    Custom(String),
 }
 
 impl Display for AggregatorType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            AggregatorType::ApproxCountDistinct { .. } => f.write_str("approx_count_distinct"),
             AggregatorType::Avg => f.write_str("avg"),
             AggregatorType::Count => f.write_str("count"),
             AggregatorType::Max => f.write_str("max"),
             AggregatorType::Min => f.write_str("min"),
             AggregatorType::Sum => f.write_str("sum"),
+            AggregatorType::Custom(name) => f.write_str(name),
         }
     }
 }
 
-pub fn get_aggregator_from_aggregator_type(typ: AggregatorType) -> AggregatorEnum {
+pub fn get_aggregator_from_aggregator_type(typ: &AggregatorType) -> AggregatorEnum {
     match typ {
+        AggregatorType::ApproxCountDistinct { precision } => {
+            ApproxCountDistinctAggregator::new(*precision).into()
+        }
         AggregatorType::Avg => AvgAggregator::new().into(),
         AggregatorType::Count => CountAggregator::new().into(),
         AggregatorType::Max => MaxAggregator::new().into(),
         AggregatorType::Min => MinAggregator::new().into(),
         AggregatorType::Sum => SumAggregator::new().into(),
+        AggregatorType::Custom(name) => CustomAggregator::new(name).into(),
     }
 }
 
@@ -135,10 +155,35 @@ pub fn get_aggregator_type_from_aggregation_expression(
                 .clone()],
             AggregatorType::Count,
         )),
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::ApproxCountDistinct,
+            args,
+        } => Ok((
+            vec![args
+                .get(0)
+                .ok_or_else(|| {
+                    PipelineError::NotEnoughArguments(
+                        AggregateFunctionType::ApproxCountDistinct.to_string(),
+                    )
+                })?
+                .clone()],
+            AggregatorType::ApproxCountDistinct {
+                precision: get_precision(args)?,
+            },
+        )),
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::Custom(name),
+            args,
+        } => Ok((args.clone(), AggregatorType::Custom(name.clone()))),
         _ => Err(PipelineError::InvalidFunction(e.to_string(schema))),
     }
 }
 
+/// Adds `val_delta` to, or subtracts it from, the count of each non-`NULL` field in `fields`.
+///
+/// Only the entries of `fields` are looked up, once each, so the cost is logarithmic in the size
+/// of `field_map`. An entry whose count drops to zero is removed, and decrementing a field that
+/// isn't counted does nothing.
 pub fn update_map(
     fields: &[Field],
     val_delta: u64,
@@ -150,25 +195,20 @@ pub fn update_map(
             continue;
         }
 
-        let get_prev_count = field_map.get(field);
-        let prev_count = match get_prev_count {
-            Some(v) => *v,
-            None => 0_u64,
-        };
-        let mut new_count = prev_count;
-        if decr {
-            new_count = new_count.wrapping_sub(val_delta);
-        } else {
-            new_count = new_count.wrapping_add(val_delta);
-        }
-        if new_count < 1 {
-            field_map.remove(field);
-        } else if field_map.contains_key(field) {
-            if let Some(val) = field_map.get_mut(field) {
-                *val = new_count;
+        match field_map.get_mut(field) {
+            Some(count) if decr => {
+                *count = count.saturating_sub(val_delta);
+                if *count == 0 {
+                    field_map.remove(field);
+                }
+            }
+            Some(count) => *count += val_delta,
+            None if decr => {}
+            None => {
+                if val_delta > 0 {
+                    field_map.insert(field.clone(), val_delta);
+                }
             }
-        } else {
-            field_map.insert(field.clone(), new_count);
         }
     }
 }
@@ -183,24 +223,40 @@ macro_rules! deserialize_u8 {
     };
 }
 
-#[macro_export]
-macro_rules! check_nan_f64 {
-    ($stmt:expr) => {
-        if $stmt.is_nan() {
-            0_f64
-        } else {
-            $stmt
+/// What an aggregation outputs when its value is a float NaN, e.g. `SUM` of `+inf` and `-inf`.
+/// Decimals have no NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// Output NaN.
+    #[default]
+    Keep,
+    /// Output `0`.
+    Zero,
+    /// Output `NULL`.
+    Null,
+    /// Fail with [`PipelineError::NanResult`].
+    Error,
+}
+
+impl NanPolicy {
+    /// Returns what `aggregator` outputs instead of NaN.
+    pub fn on_nan(self, aggregator: &AggregatorType) -> Result<Field, PipelineError> {
+        match self {
+            NanPolicy::Keep => Ok(Field::Float(OrderedFloat(f64::NAN))),
+            NanPolicy::Zero => Ok(Field::Float(OrderedFloat(0.0))),
+            NanPolicy::Null => Ok(Field::Null),
+            NanPolicy::Error => Err(PipelineError::NanResult(aggregator.to_string())),
         }
-    };
+    }
 }
 
+/// Returns `$value` if it isn't a float NaN, what `$policy` says `$aggr` outputs otherwise.
 #[macro_export]
-macro_rules! check_nan_decimal {
-    ($stmt:expr) => {
-        if $stmt.is_nan() {
-            dozer_types::rust_decimal::Decimal::zero()
-        } else {
-            $stmt
+macro_rules! check_nan_f64 {
+    ($value:expr, $policy:expr, $aggr:expr) => {
+        match $value {
+            dozer_types::types::Field::Float(f) if f.is_nan() => $policy.on_nan($aggr)?,
+            value => value,
         }
     };
 }
@@ -0,0 +1,141 @@
+use crate::argv;
+use crate::pipeline::aggregation::aggregator::Aggregator;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::aggregate::AggregateFunctionType::ApproxCountDistinct;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+use dozer_types::types::{Field, FieldType, Schema, SourceDefinition};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Precision used when `APPROX_COUNT_DISTINCT` is called without one. 2^12 registers give a
+/// standard error of about 1.6%.
+pub const DEFAULT_PRECISION: u8 = 12;
+pub const MIN_PRECISION: u8 = 4;
+pub const MAX_PRECISION: u8 = 16;
+
+pub fn validate_approx_count_distinct(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    argv!(args, 0, ApproxCountDistinct)?.get_type(schema)?;
+    if args.len() > 2 {
+        return Err(PipelineError::TooManyArguments(
+            ApproxCountDistinct.to_string(),
+        ));
+    }
+    get_precision(args)?;
+
+    Ok(ExpressionType::new(
+        FieldType::UInt,
+        false,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// Reads the optional precision literal passed as the second argument.
+pub fn get_precision(args: &[Expression]) -> Result<u8, PipelineError> {
+    match args.get(1) {
+        None => Ok(DEFAULT_PRECISION),
+        Some(Expression::Literal(Field::Int(precision)))
+            if (MIN_PRECISION as i64..=MAX_PRECISION as i64).contains(precision) =>
+        {
+            Ok(*precision as u8)
+        }
+        Some(Expression::Literal(field)) => Err(PipelineError::InvalidFunctionArgument(
+            ApproxCountDistinct.to_string(),
+            field.clone(),
+            1,
+        )),
+        Some(_) => Err(PipelineError::InvalidArgument(format!(
+            "precision of {ApproxCountDistinct}() must be an integer literal between {MIN_PRECISION} and {MAX_PRECISION}"
+        ))),
+    }
+}
+
+/// Estimates the number of distinct non-NULL values with a HyperLogLog sketch.
+///
+/// A sketch cannot forget a value once it has been added, so this aggregator only supports
+/// inserts. Deletes and updates are rejected, which makes it suitable for append-only sources.
+#[derive(Debug)]
+pub struct ApproxCountDistinctAggregator {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl ApproxCountDistinctAggregator {
+    pub fn new(precision: u8) -> Self {
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    fn add(&mut self, field: &Field) {
+        let mut hasher = DefaultHasher::new();
+        field.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // The first `precision` bits pick the register, the position of the first set bit in
+        // the rest is the register's candidate value.
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        // Small cardinalities are better estimated by linear counting.
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+impl Aggregator for ApproxCountDistinctAggregator {
+    fn init(&mut self, _return_type: FieldType) {}
+
+    fn update(&mut self, _old: &[Field], _new: &[Field]) -> Result<Field, PipelineError> {
+        Err(PipelineError::UnsupportedRetraction(
+            ApproxCountDistinct.to_string(),
+        ))
+    }
+
+    fn delete(&mut self, _old: &[Field]) -> Result<Field, PipelineError> {
+        Err(PipelineError::UnsupportedRetraction(
+            ApproxCountDistinct.to_string(),
+        ))
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        for field in new {
+            if field != &Field::Null {
+                self.add(field);
+            }
+        }
+        Ok(Field::UInt(self.estimate()))
+    }
+}
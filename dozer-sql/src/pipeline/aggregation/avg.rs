@@ -1,19 +1,18 @@
 use crate::argv;
 use crate::pipeline::aggregation::aggregator::Aggregator;
-use crate::pipeline::aggregation::sum::{get_sum, SumState};
+use crate::pipeline::aggregation::sum::{checked, get_sum, SumState};
 use crate::pipeline::errors::PipelineError::InvalidValue;
-use crate::pipeline::errors::{FieldTypes, PipelineError};
+use crate::pipeline::errors::{FieldTypes, OperationError, PipelineError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::aggregate::AggregateFunctionType::Avg;
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
 use dozer_core::errors::ExecutionError::InvalidType;
-use dozer_types::arrow::datatypes::ArrowNativeTypeOp;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::{DozerDuration, Field, FieldType, Schema, SourceDefinition, TimeUnit};
 use num_traits::FromPrimitive;
 
-use std::ops::Div;
+use std::time::Duration;
 
 pub fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionType, PipelineError> {
     let arg = &argv!(args, 0, AggregateFunctionType::Avg)?.get_type(schema)?;
@@ -59,6 +58,9 @@ pub fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
     ))
 }
 
+/// Keeps the sum and the count of the values of a group, so that inserting or deleting a value
+/// is exact and doesn't depend on the size of the group. The average is computed from them on
+/// every change.
 #[derive(Debug)]
 pub struct AvgAggregator {
     current_state: SumState,
@@ -95,133 +97,100 @@ impl Aggregator for AvgAggregator {
     }
 
     fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
-        self.current_count -= old.len() as u64;
-        get_average(
-            old,
-            &mut self.current_state,
-            &mut self.current_count,
-            self.return_type,
-            true,
-        )
+        let count = checked(
+            self.current_count.checked_sub(old.len() as u64),
+            OperationError::SubtractionOverflow,
+        )?;
+        let sum = get_sum(old, &mut self.current_state, self.return_type, true)?;
+        self.current_count = count;
+        get_average(&sum, self.current_count, self.return_type)
     }
 
     fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
-        self.current_count += new.len() as u64;
-        get_average(
-            new,
-            &mut self.current_state,
-            &mut self.current_count,
-            self.return_type,
-            false,
-        )
+        let count = checked(
+            self.current_count.checked_add(new.len() as u64),
+            OperationError::AdditionOverflow,
+        )?;
+        let sum = get_sum(new, &mut self.current_state, self.return_type, false)?;
+        self.current_count = count;
+        get_average(&sum, self.current_count, self.return_type)
     }
 }
 
+/// Divides `sum` by `count`, or returns `Null` for an empty group.
 fn get_average(
-    field: &[Field],
-    current_sum: &mut SumState,
-    current_count: &mut u64,
+    sum: &Field,
+    count: u64,
     return_type: Option<FieldType>,
-    decr: bool,
 ) -> Result<Field, PipelineError> {
-    let sum = get_sum(field, current_sum, return_type, decr)?;
-
-    match return_type {
-        Some(typ) => match typ {
-            FieldType::UInt => {
-                if *current_count == 0 {
-                    return Ok(Field::Null);
-                }
-                let u_sum = sum
-                    .to_uint()
-                    .ok_or(InvalidValue(sum.to_string().unwrap()))
-                    .unwrap();
-                Ok(Field::UInt(u_sum.div_wrapping(*current_count)))
-            }
-            FieldType::U128 => {
-                if *current_count == 0 {
-                    return Ok(Field::Null);
-                }
-                let u_sum = sum
-                    .to_u128()
-                    .ok_or(InvalidValue(sum.to_string().unwrap()))
-                    .unwrap();
-                Ok(Field::U128(u_sum.wrapping_div(*current_count as u128)))
-            }
-            FieldType::Int => {
-                if *current_count == 0 {
-                    return Ok(Field::Null);
-                }
-                let i_sum = sum
-                    .to_int()
-                    .ok_or(InvalidValue(sum.to_string().unwrap()))
-                    .unwrap();
-                Ok(Field::Int(i_sum.div_wrapping(*current_count as i64)))
-            }
-            FieldType::I128 => {
-                if *current_count == 0 {
-                    return Ok(Field::Null);
-                }
-                let i_sum = sum
-                    .to_i128()
-                    .ok_or(InvalidValue(sum.to_string().unwrap()))
-                    .unwrap();
-                Ok(Field::I128(i_sum.div_wrapping(*current_count as i128)))
-            }
-            FieldType::Float => {
-                if *current_count == 0 {
-                    return Ok(Field::Null);
-                }
-                let f_sum = sum
-                    .to_float()
-                    .ok_or(InvalidValue(sum.to_string().unwrap()))
-                    .unwrap();
-                Ok(Field::Float(OrderedFloat(
-                    f_sum.div_wrapping(*current_count as f64),
-                )))
-            }
-            FieldType::Decimal => {
-                if *current_count == 0 {
-                    return Ok(Field::Null);
-                }
-                let d_sum = sum
-                    .to_decimal()
-                    .ok_or(InvalidValue(sum.to_string().unwrap()))
-                    .unwrap();
-                Ok(Field::Decimal(d_sum.div(Decimal::from(*current_count))))
-            }
-            FieldType::Duration => {
-                if *current_count == 0 {
-                    return Ok(Field::Null);
-                }
-                let str_dur = sum.to_duration()?.unwrap().to_string();
-                let d_sum = sum
-                    .to_duration()?
-                    .ok_or(InvalidValue(str_dur.clone()))
-                    .unwrap();
-
-                Ok(Field::Duration(DozerDuration(
-                    d_sum
-                        .0
-                        .checked_div((*current_count) as u32)
-                        .ok_or(InvalidValue(str_dur))
-                        .unwrap(),
-                    TimeUnit::Nanoseconds,
-                )))
-            }
-            FieldType::Boolean
-            | FieldType::String
-            | FieldType::Text
-            | FieldType::Date
-            | FieldType::Timestamp
-            | FieldType::Binary
-            | FieldType::Json
-            | FieldType::Point => Err(PipelineError::InternalExecutionError(InvalidType(format!(
-                "Not supported return type {typ} for {Avg}"
-            )))),
-        },
-        None => Err(PipelineError::InternalExecutionError(InvalidType(format!(
+    let Some(typ) = return_type else {
+        return Err(PipelineError::InternalExecutionError(InvalidType(format!(
             "Not supported None return type for {Avg}"
-        )))),
+        ))));
+    };
+    if count == 0 {
+        return match typ {
+            FieldType::UInt
+            | FieldType::U128
+            | FieldType::Int
+            | FieldType::I128
+            | FieldType::Float
+            | FieldType::Decimal
+            | FieldType::Duration => Ok(Field::Null),
+            _ => Err(unsupported_return_type(typ)),
+        };
+    }
+
+    let invalid = || InvalidValue(format!("{sum}"));
+    let division = || PipelineError::SqlError(OperationError::DivisionByZeroOrOverflow.into());
+    match typ {
+        FieldType::UInt => {
+            let u_sum = sum.to_uint().ok_or_else(invalid)?;
+            Ok(Field::UInt(u_sum / count))
+        }
+        FieldType::U128 => {
+            let u_sum = sum.to_u128().ok_or_else(invalid)?;
+            Ok(Field::U128(u_sum / count as u128))
+        }
+        FieldType::Int => {
+            let i_sum = sum.to_int().ok_or_else(invalid)?;
+            let count = i64::try_from(count).map_err(|_| division())?;
+            Ok(Field::Int(i_sum / count))
+        }
+        FieldType::I128 => {
+            let i_sum = sum.to_i128().ok_or_else(invalid)?;
+            Ok(Field::I128(i_sum / count as i128))
+        }
+        FieldType::Float => {
+            let f_sum = sum.to_float().ok_or_else(invalid)?;
+            Ok(Field::Float(OrderedFloat(f_sum / count as f64)))
+        }
+        FieldType::Decimal => {
+            let d_sum = sum.to_decimal().ok_or_else(invalid)?;
+            let average = d_sum
+                .checked_div(Decimal::from(count))
+                .ok_or_else(division)?;
+            Ok(Field::Decimal(average))
+        }
+        FieldType::Duration => {
+            let d_sum = sum.to_duration()?.ok_or_else(invalid)?;
+            // `Duration` only divides by `u32`, so divide the nanoseconds instead.
+            let nanos = d_sum.0.as_nanos() / count as u128;
+            let average = Duration::new(
+                (nanos / 1_000_000_000) as u64,
+                (nanos % 1_000_000_000) as u32,
+            );
+            Ok(Field::Duration(DozerDuration(
+                average,
+                TimeUnit::Nanoseconds,
+            )))
+        }
+        typ => Err(unsupported_return_type(typ)),
     }
 }
+
+fn unsupported_return_type(typ: FieldType) -> PipelineError {
+    PipelineError::InternalExecutionError(InvalidType(format!(
+        "Not supported return type {typ} for {Avg}"
+    )))
+}
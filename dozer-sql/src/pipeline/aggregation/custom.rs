@@ -0,0 +1,114 @@
+use crate::pipeline::aggregation::aggregator::Aggregator;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::aggregate::AggregateFunctionType;
+use crate::pipeline::expression::execution::ExpressionType;
+use crate::pipeline::expression::udf::{get_scalar_udf, is_builtin_function};
+use dozer_types::types::{Field, FieldType, SourceDefinition};
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+
+type AggregatorBuilder = Arc<dyn Fn() -> Box<dyn Aggregator> + Send + Sync>;
+
+struct CustomAggregate {
+    return_type: FieldType,
+    build: AggregatorBuilder,
+}
+
+/// Aggregate functions registered with [`register_aggregate_function`], by lowercase name.
+static CUSTOM_AGGREGATES: RwLock<BTreeMap<String, CustomAggregate>> = RwLock::new(BTreeMap::new());
+
+/// Registers an aggregate function `name`, whose state is created by `build` for every group and
+/// which returns values of type `return_type`, possibly `NULL`.
+///
+/// SQL calls `name` case-insensitively, with any number of arguments, all of which are passed to
+/// the aggregator. Built-in functions take precedence: registering the name of a built-in
+/// function, or a name registered already as an aggregate or scalar function, fails.
+/// Registration applies to pipelines built afterwards.
+pub fn register_aggregate_function(
+    name: &str,
+    return_type: FieldType,
+    build: impl Fn() -> Box<dyn Aggregator> + Send + Sync + 'static,
+) -> Result<(), PipelineError> {
+    let name = name.to_lowercase();
+    if AggregateFunctionType::new(&name).is_ok()
+        || is_builtin_function(&name)
+        || get_scalar_udf(&name).is_some()
+    {
+        return Err(PipelineError::DuplicateFunction(name));
+    }
+
+    let mut aggregates = CUSTOM_AGGREGATES.write().unwrap();
+    if aggregates.contains_key(&name) {
+        return Err(PipelineError::DuplicateFunction(name));
+    }
+    aggregates.insert(
+        name,
+        CustomAggregate {
+            return_type,
+            build: Arc::new(build),
+        },
+    );
+    Ok(())
+}
+
+/// Returns whether `name`, in lowercase, is a registered aggregate function.
+pub(crate) fn is_custom_aggregate(name: &str) -> bool {
+    CUSTOM_AGGREGATES.read().unwrap().contains_key(name)
+}
+
+pub(crate) fn validate_custom(name: &str) -> Result<ExpressionType, PipelineError> {
+    let aggregates = CUSTOM_AGGREGATES.read().unwrap();
+    let aggregate = aggregates
+        .get(name)
+        .ok_or_else(|| PipelineError::InvalidFunction(name.to_string()))?;
+    Ok(ExpressionType::new(
+        aggregate.return_type,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// A registered aggregate function, wrapped to be held in an `AggregatorEnum`.
+pub struct CustomAggregator(Box<dyn Aggregator>);
+
+impl CustomAggregator {
+    /// Builds the aggregator of the registered function `name`.
+    ///
+    /// Panics if `name` isn't registered. Functions are never unregistered, so this can't happen
+    /// to a name that planning resolved.
+    pub fn new(name: &str) -> Self {
+        let build = CUSTOM_AGGREGATES
+            .read()
+            .unwrap()
+            .get(name)
+            .map(|aggregate| aggregate.build.clone())
+            .unwrap_or_else(|| panic!("aggregate function {name} is not registered"));
+        Self(build())
+    }
+}
+
+impl Debug for CustomAggregator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CustomAggregator").field(&self.0).finish()
+    }
+}
+
+impl Aggregator for CustomAggregator {
+    fn init(&mut self, return_type: FieldType) {
+        self.0.init(return_type)
+    }
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        self.0.update(old, new)
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        self.0.delete(old)
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        self.0.insert(new)
+    }
+}
@@ -1,6 +1,7 @@
+use crate::pipeline::aggregation::aggregator::NanPolicy;
 use crate::pipeline::aggregation::processor::AggregationProcessor;
 use crate::pipeline::builder::SchemaSQLContext;
-use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::planner::projection::{AggregateNaming, CommonPlanner};
 use crate::pipeline::projection::processor::ProjectionProcessor;
 use dozer_core::{
     errors::ExecutionError,
@@ -8,12 +9,18 @@ use dozer_core::{
     DEFAULT_PORT_HANDLE,
 };
 use dozer_types::types::Schema;
-use sqlparser::ast::Select;
+use sqlparser::ast::{OrderByExpr, Select};
 use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct AggregationProcessorFactory {
     projection: Select,
+    order_by: Vec<OrderByExpr>,
+    sum_promote_on_overflow: bool,
+    aggregate_naming: AggregateNaming,
+    sorted_output: bool,
+    nan_policy: NanPolicy,
+    max_groups: Option<usize>,
     _stateful: bool,
 }
 
@@ -21,15 +28,64 @@ impl AggregationProcessorFactory {
     pub fn new(projection: Select, stateful: bool) -> Self {
         Self {
             projection,
+            order_by: vec![],
+            sum_promote_on_overflow: false,
+            aggregate_naming: AggregateNaming::default(),
+            sorted_output: false,
+            nan_policy: NanPolicy::default(),
+            max_groups: None,
             _stateful: stateful,
         }
     }
 
+    /// Computes integer `SUM`s as `Decimal` if `promote` is set, instead of failing on overflow.
+    pub fn with_sum_promote_on_overflow(mut self, promote: bool) -> Self {
+        self.sum_promote_on_overflow = promote;
+        self
+    }
+
+    /// Names the output fields of unaliased aggregations with `naming`.
+    pub fn with_aggregate_naming(mut self, naming: AggregateNaming) -> Self {
+        self.aggregate_naming = naming;
+        self
+    }
+
+    /// Emits the changed groups in group key order at every commit if `sorted` is set, see
+    /// [`AggregationProcessor::with_sorted_output`].
+    pub fn with_sorted_output(mut self, sorted: bool) -> Self {
+        self.sorted_output = sorted;
+        self
+    }
+
+    /// Makes aggregations whose value is a float NaN output what `policy` says.
+    pub fn with_nan_policy(mut self, policy: NanPolicy) -> Self {
+        self.nan_policy = policy;
+        self
+    }
+
+    /// Caps the number of groups held in memory if `max_groups` is set, see
+    /// [`AggregationProcessor::with_max_groups`].
+    pub fn with_max_groups(mut self, max_groups: Option<usize>) -> Self {
+        self.max_groups = max_groups;
+        self
+    }
+
+    /// Lets the planner pick sorted aggregation if `order_by` matches the `GROUP BY` key.
+    pub fn with_order_by(mut self, order_by: Vec<OrderByExpr>) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
     fn get_planner(&self, input_schema: Schema) -> Result<CommonPlanner, ExecutionError> {
-        let mut projection_planner = CommonPlanner::new(input_schema);
+        let mut projection_planner = CommonPlanner::new(input_schema)
+            .with_sum_promote_on_overflow(self.sum_promote_on_overflow)
+            .with_aggregate_naming(self.aggregate_naming);
         projection_planner
             .plan(self.projection.clone())
             .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        projection_planner
+            .plan_order_by(&self.order_by)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
         Ok(projection_planner)
     }
 }
@@ -70,24 +126,43 @@ impl ProcessorFactory<SchemaSQLContext> for AggregationProcessorFactory {
 
         let planner = self.get_planner(input_schema.clone())?;
 
-        let is_projection = planner.aggregation_output.is_empty() && planner.groupby.is_empty();
+        let is_projection = planner.aggregation_output.is_empty()
+            && planner.groupby.is_empty()
+            && planner.grouping_sets.is_empty();
         let processor: Box<dyn Processor> = if is_projection {
             Box::new(ProjectionProcessor::new(
                 input_schema.clone(),
                 planner.projection_output,
             ))
         } else {
-            Box::new(
-                AggregationProcessor::new(
-                    planner.groupby,
-                    planner.aggregation_output,
-                    planner.projection_output,
-                    planner.having,
-                    input_schema.clone(),
-                    planner.post_aggregation_schema,
-                )
-                .map_err(|e| ExecutionError::InternalError(Box::new(e)))?,
+            let mut processor = AggregationProcessor::new(
+                planner.groupby,
+                planner.aggregation_output,
+                planner.projection_output,
+                planner.having,
+                input_schema.clone(),
+                planner.post_aggregation_schema,
             )
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?
+            .with_strategy(planner.aggregation_strategy)
+            .with_nan_policy(self.nan_policy);
+            if self.sum_promote_on_overflow {
+                processor = processor.with_sum_promote_on_overflow();
+            }
+            if self.sorted_output {
+                processor = processor
+                    .with_sorted_output()
+                    .with_null_ordering(planner.null_ordering);
+            }
+            if let Some(max_groups) = self.max_groups {
+                processor = processor.with_max_groups(max_groups);
+            }
+            if !planner.grouping_sets.is_empty() {
+                processor = processor
+                    .with_grouping_sets(planner.grouping_sets)
+                    .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+            }
+            Box::new(processor)
         };
         Ok(processor)
     }
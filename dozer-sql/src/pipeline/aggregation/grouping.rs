@@ -0,0 +1,71 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use crate::pipeline::planner::pruning::replace_expressions;
+use dozer_types::types::Field;
+
+/// Rewrites `expression`, planned against all the `GROUP BY` `dimensions`, for the rows of the
+/// grouping set made of the dimensions at `grouped`: the other dimensions are `NULL`, and
+/// `GROUPING` calls are replaced by their value.
+///
+/// `GROUPING(d1, ..., dn)` has bit `n - i` set if `di` is not grouped, so a single argument gives
+/// 1 on the rows where it is aggregated away and 0 otherwise. Its arguments must be dimensions.
+pub(crate) fn rewrite_for_grouping_set(
+    expression: Expression,
+    dimensions: &[Expression],
+    grouped: &[usize],
+) -> Result<Expression, PipelineError> {
+    let mut error = None;
+    let expression = replace_expressions(expression, &mut |expression| {
+        if let Expression::ScalarFunction {
+            fun: ScalarFunctionType::Grouping,
+            args,
+        } = expression
+        {
+            let mut value = 0;
+            for arg in args {
+                value <<= 1;
+                match dimensions.iter().position(|dimension| dimension == arg) {
+                    Some(index) if grouped.contains(&index) => {}
+                    Some(_) => value |= 1,
+                    None => {
+                        error.get_or_insert(PipelineError::InvalidQuery(
+                            "GROUPING arguments must be GROUP BY expressions".to_string(),
+                        ));
+                    }
+                }
+            }
+            return Some(Expression::Literal(Field::UInt(value)));
+        }
+
+        dimensions
+            .iter()
+            .position(|dimension| dimension == expression)
+            .filter(|index| !grouped.contains(index))
+            .map(|_| Expression::Literal(Field::Null))
+    });
+    error.map_or(Ok(expression), Err)
+}
+
+/// Returns whether `expression` reads one of the `dimensions` at `indexes`, outside of `GROUPING`
+/// calls.
+pub(crate) fn reads_dimensions(
+    expression: &Expression,
+    dimensions: &[Expression],
+    indexes: &[usize],
+) -> bool {
+    let mut found = false;
+    replace_expressions(expression.clone(), &mut |expression| match expression {
+        Expression::ScalarFunction {
+            fun: ScalarFunctionType::Grouping,
+            ..
+        } => Some(expression.clone()),
+        _ => {
+            found |= indexes
+                .iter()
+                .any(|index| &dimensions[*index] == expression);
+            None
+        }
+    });
+    found
+}
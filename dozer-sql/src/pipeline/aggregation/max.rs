@@ -22,10 +22,10 @@ pub fn validate_max(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
         FieldType::Timestamp => FieldType::Timestamp,
         FieldType::Date => FieldType::Date,
         FieldType::Duration => FieldType::Duration,
+        FieldType::Binary => FieldType::Binary,
         FieldType::Boolean
         | FieldType::String
         | FieldType::Text
-        | FieldType::Binary
         | FieldType::Json
         | FieldType::Point => {
             return Err(PipelineError::InvalidFunctionArgumentType(
@@ -41,6 +41,7 @@ pub fn validate_max(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
                     FieldType::Timestamp,
                     FieldType::Date,
                     FieldType::Duration,
+                    FieldType::Binary,
                 ]),
                 0,
             ));
@@ -54,6 +55,10 @@ pub fn validate_max(args: &[Expression], schema: &Schema) -> Result<ExpressionTy
     ))
 }
 
+/// Counts the occurrences of each value in a `BTreeMap`, whose last key is the maximum.
+///
+/// Inserts and deletes only touch the count of their own values, so each operation costs
+/// `O(log n)` in the number of distinct values, and removing the current maximum doesn't rescan.
 #[derive(Debug)]
 pub struct MaxAggregator {
     current_state: BTreeMap<Field, u64>,
@@ -97,7 +102,7 @@ fn get_max(
     if field_map.is_empty() {
         Ok(Field::Null)
     } else {
-        let val = calculate_err!(field_map.keys().max(), Max).clone();
+        let val = calculate_err!(field_map.last_key_value().map(|(key, _)| key), Max).clone();
         match return_type {
             Some(typ) => match typ {
                 FieldType::UInt => Ok(Field::UInt(calculate_err_field!(val.to_uint(), Max, val))),
@@ -125,10 +130,12 @@ fn get_max(
                     Max,
                     val
                 ))),
+                FieldType::Binary => Ok(Field::Binary(
+                    calculate_err_field!(val.to_binary(), Max, val).to_vec(),
+                )),
                 FieldType::Boolean
                 | FieldType::String
                 | FieldType::Text
-                | FieldType::Binary
                 | FieldType::Json
                 | FieldType::Point => Err(PipelineError::InternalExecutionError(InvalidType(
                     format!("Not supported return type {typ} for {Max}"),
@@ -1,7 +1,10 @@
 pub mod aggregator;
+pub mod approx_count_distinct;
 pub mod avg;
 pub mod count;
+pub mod custom;
 pub mod factory;
+pub(crate) mod grouping;
 pub mod max;
 pub mod min;
 pub mod processor;
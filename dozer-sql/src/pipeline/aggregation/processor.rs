@@ -1,5 +1,8 @@
 #![allow(clippy::too_many_arguments)]
 
+use crate::check_nan_f64;
+use crate::pipeline::aggregation::grouping::rewrite_for_grouping_set;
+use crate::pipeline::aggregation::sum::promoted_sum_type;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::execution::ExpressionExecutor;
 use crate::pipeline::{aggregation::aggregator::Aggregator, expression::execution::Expression};
@@ -8,12 +11,14 @@ use dozer_core::errors::ExecutionError;
 use dozer_core::errors::ExecutionError::InternalError;
 use dozer_core::node::{PortHandle, Processor};
 use dozer_core::DEFAULT_PORT_HANDLE;
-use dozer_types::types::{Field, FieldType, Operation, Record, Schema};
+use dozer_types::types::{Field, FieldType, NullOrdering, Operation, Record, Schema};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 
 use crate::pipeline::aggregation::aggregator::{
     get_aggregator_from_aggregator_type, get_aggregator_type_from_aggregation_expression,
-    AggregatorEnum, AggregatorType,
+    AggregatorEnum, AggregatorType, NanPolicy,
 };
 use ahash::AHasher;
 use dozer_core::epoch::Epoch;
@@ -32,7 +37,7 @@ impl AggregationState {
     pub fn new(types: &[AggregatorType], ret_types: &[FieldType]) -> Self {
         let mut states: Vec<AggregatorEnum> = Vec::new();
         for (idx, typ) in types.iter().enumerate() {
-            let mut aggr = get_aggregator_from_aggregator_type(*typ);
+            let mut aggr = get_aggregator_from_aggregator_type(typ);
             aggr.init(ret_types[idx]);
             states.push(aggr);
         }
@@ -45,6 +50,184 @@ impl AggregationState {
     }
 }
 
+/// How an `AggregationProcessor` stores the state of its groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AggregationStrategy {
+    /// Groups are kept in a hash map, keyed by a hash of the group key.
+    #[default]
+    Hash,
+    /// Groups are kept in a `BTreeMap` ordered by the group key, for output that must be sorted
+    /// by it.
+    Sorted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GroupKey {
+    Hash(u64),
+    Sorted(Vec<Field>),
+}
+
+#[derive(Debug)]
+enum GroupStates {
+    Hash(HashMap<u64, AggregationState>),
+    Sorted(BTreeMap<Vec<Field>, AggregationState>),
+}
+
+impl GroupStates {
+    fn new(strategy: AggregationStrategy) -> Self {
+        match strategy {
+            AggregationStrategy::Hash => GroupStates::Hash(HashMap::new()),
+            AggregationStrategy::Sorted => GroupStates::Sorted(BTreeMap::new()),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            GroupStates::Hash(states) => states.len(),
+            GroupStates::Sorted(states) => states.len(),
+        }
+    }
+
+    fn contains_key(&self, key: &GroupKey) -> bool {
+        match (self, key) {
+            (GroupStates::Hash(states), GroupKey::Hash(key)) => states.contains_key(key),
+            (GroupStates::Sorted(states), GroupKey::Sorted(key)) => states.contains_key(key),
+            _ => unreachable!("group key doesn't match the aggregation strategy"),
+        }
+    }
+
+    fn get(&self, key: &GroupKey) -> Option<&AggregationState> {
+        match (self, key) {
+            (GroupStates::Hash(states), GroupKey::Hash(key)) => states.get(key),
+            (GroupStates::Sorted(states), GroupKey::Sorted(key)) => states.get(key),
+            _ => unreachable!("group key doesn't match the aggregation strategy"),
+        }
+    }
+
+    fn get_mut(&mut self, key: &GroupKey) -> Option<&mut AggregationState> {
+        match (self, key) {
+            (GroupStates::Hash(states), GroupKey::Hash(key)) => states.get_mut(key),
+            (GroupStates::Sorted(states), GroupKey::Sorted(key)) => states.get_mut(key),
+            _ => unreachable!("group key doesn't match the aggregation strategy"),
+        }
+    }
+
+    fn get_or_insert_with(
+        &mut self,
+        key: GroupKey,
+        default: impl FnOnce() -> AggregationState,
+    ) -> &mut AggregationState {
+        match (self, key) {
+            (GroupStates::Hash(states), GroupKey::Hash(key)) => {
+                states.entry(key).or_insert_with(default)
+            }
+            (GroupStates::Sorted(states), GroupKey::Sorted(key)) => {
+                states.entry(key).or_insert_with(default)
+            }
+            _ => unreachable!("group key doesn't match the aggregation strategy"),
+        }
+    }
+
+    fn remove(&mut self, key: &GroupKey) {
+        match (self, key) {
+            (GroupStates::Hash(states), GroupKey::Hash(key)) => {
+                states.remove(key);
+            }
+            (GroupStates::Sorted(states), GroupKey::Sorted(key)) => {
+                states.remove(key);
+            }
+            _ => unreachable!("group key doesn't match the aggregation strategy"),
+        }
+    }
+}
+
+/// The change to the row of a group since it was last emitted, see
+/// [`AggregationProcessor::with_sorted_output`].
+#[derive(Debug)]
+struct PendingRow {
+    /// The row as last emitted, if any.
+    emitted: Option<Record>,
+    /// The row now, if any.
+    current: Option<Record>,
+}
+
+impl PendingRow {
+    fn new(op: Operation) -> Self {
+        let (emitted, current) = match op {
+            Operation::Insert { new } => (None, Some(new)),
+            Operation::Update { old, new } => (Some(old), Some(new)),
+            Operation::Delete { old } => (Some(old), None),
+        };
+        Self { emitted, current }
+    }
+
+    fn apply(&mut self, op: Operation) {
+        self.current = match op {
+            Operation::Insert { new } | Operation::Update { new, .. } => Some(new),
+            Operation::Delete { .. } => None,
+        };
+    }
+
+    /// The operation taking the row from `emitted` to `current`, if it changed.
+    fn into_operation(self) -> Option<Operation> {
+        match (self.emitted, self.current) {
+            (None, Some(new)) => Some(Operation::Insert { new }),
+            (Some(old), Some(new)) if old != new => Some(Operation::Update { old, new }),
+            (Some(old), None) => Some(Operation::Delete { old }),
+            _ => None,
+        }
+    }
+}
+
+/// A group, identified by the index of its grouping set and its key.
+type Group = (usize, Vec<Field>);
+
+/// Compares group keys column by column, placing `NULL`s in each column as `nulls` says, or
+/// last past its end.
+fn cmp_group_keys(a: &[Field], b: &[Field], nulls: &[NullOrdering]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .enumerate()
+        .map(|(i, (a, b))| a.cmp_with_nulls(b, nulls.get(i).copied().unwrap_or_default()))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+/// The groups of one grouping set, and how their rows are projected.
+#[derive(Debug)]
+struct GroupingSet {
+    /// The dimensions the rows are grouped by.
+    dimensions: Vec<Expression>,
+    /// The projections of the processor, with the other dimensions `NULL`.
+    projections: Vec<Expression>,
+    /// The `HAVING` of the processor, with the other dimensions `NULL`.
+    having: Option<Expression>,
+    states: GroupStates,
+}
+
+impl GroupingSet {
+    fn new(
+        dimensions: &[Expression],
+        grouped: Vec<usize>,
+        projections: &[Expression],
+        having: Option<&Expression>,
+        strategy: AggregationStrategy,
+    ) -> Result<Self, PipelineError> {
+        let rewrite = |expression: &Expression| {
+            rewrite_for_grouping_set(expression.clone(), dimensions, &grouped)
+        };
+        Ok(Self {
+            projections: projections.iter().map(rewrite).collect::<Result<_, _>>()?,
+            having: having.map(rewrite).transpose()?,
+            dimensions: grouped
+                .iter()
+                .map(|index| dimensions[*index].clone())
+                .collect(),
+            states: GroupStates::new(strategy),
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct AggregationProcessor {
     dimensions: Vec<Expression>,
@@ -55,9 +238,19 @@ pub struct AggregationProcessor {
     having: Option<Expression>,
     input_schema: Schema,
     aggregation_schema: Schema,
-    states: HashMap<u64, AggregationState>,
+    strategy: AggregationStrategy,
+    /// A single set of all the dimensions, unless `GROUP BY` has grouping sets.
+    grouping_sets: Vec<GroupingSet>,
     default_segment_key: u64,
     having_eval_schema: Schema,
+    max_groups: Option<usize>,
+    /// The changes to the rows of every group since the last flush, if output is held back,
+    /// see [`AggregationProcessor::with_sorted_output`].
+    pending: Option<BTreeMap<Group, PendingRow>>,
+    /// Where `NULL`s go in each leading column of the group key of the sorted output, see
+    /// [`AggregationProcessor::with_null_ordering`].
+    null_ordering: Vec<NullOrdering>,
+    nan_policy: NanPolicy,
 }
 
 enum AggregatorOperation {
@@ -93,12 +286,21 @@ impl AggregationProcessor {
         let mut having_eval_schema_fields = input_schema.fields.clone();
         having_eval_schema_fields.extend(aggregation_schema.fields.clone());
 
+        let grouping_set = GroupingSet::new(
+            &dimensions,
+            (0..dimensions.len()).collect(),
+            &projections,
+            having.as_ref(),
+            AggregationStrategy::Hash,
+        )?;
+
         Ok(Self {
             dimensions,
             projections,
             input_schema,
             aggregation_schema,
-            states: HashMap::new(),
+            strategy: AggregationStrategy::Hash,
+            grouping_sets: vec![grouping_set],
             measures: aggr_measures,
             having,
             measures_types: aggr_types,
@@ -109,15 +311,109 @@ impl AggregationProcessor {
                 primary_index: vec![],
                 identifier: None,
             },
+            max_groups: None,
+            pending: None,
+            null_ordering: vec![],
+            nan_policy: NanPolicy::default(),
         })
     }
 
+    /// Caps the number of groups whose state is held in memory. Inserting a record for a new
+    /// group once the cap is reached fails with `PipelineError::StateLimitExceeded` instead of
+    /// growing the state without bound.
+    pub fn with_max_groups(mut self, max_groups: usize) -> Self {
+        self.max_groups = Some(max_groups);
+        self
+    }
+
+    /// Computes integer `SUM`s as `Decimal`, as planned with
+    /// [`CommonPlanner::with_sum_promote_on_overflow`].
+    ///
+    /// [`CommonPlanner::with_sum_promote_on_overflow`]: crate::pipeline::planner::projection::CommonPlanner::with_sum_promote_on_overflow
+    pub fn with_sum_promote_on_overflow(mut self) -> Self {
+        for (typ, return_type) in self
+            .measures_types
+            .iter()
+            .zip(&mut self.measures_return_types)
+        {
+            if *typ == AggregatorType::Sum {
+                *return_type = promoted_sum_type(*return_type);
+            }
+        }
+        self
+    }
+
+    /// Makes aggregations whose value is a float NaN output what `policy` says.
+    pub fn with_nan_policy(mut self, policy: NanPolicy) -> Self {
+        self.nan_policy = policy;
+        self
+    }
+
+    /// Stores group states as `strategy` says. Must be called before any record is processed.
+    pub fn with_strategy(mut self, strategy: AggregationStrategy) -> Self {
+        self.strategy = strategy;
+        for grouping_set in &mut self.grouping_sets {
+            grouping_set.states = GroupStates::new(strategy);
+        }
+        self
+    }
+
+    /// Aggregates every record into one group per grouping set, each given by the indexes of the
+    /// dimensions it groups by, as planned for `GROUP BY` with `ROLLUP`, `CUBE` or
+    /// `GROUPING SETS`. Rows of a set have the dimensions it doesn't group by `NULL`.
+    ///
+    /// Fails if a `GROUPING` call has arguments that aren't dimensions. Must be called before any
+    /// record is processed.
+    pub fn with_grouping_sets(mut self, sets: Vec<Vec<usize>>) -> Result<Self, PipelineError> {
+        self.grouping_sets = sets
+            .into_iter()
+            .map(|grouped| {
+                GroupingSet::new(
+                    &self.dimensions,
+                    grouped,
+                    &self.projections,
+                    self.having.as_ref(),
+                    self.strategy,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(self)
+    }
+
+    /// Holds back the output until the next commit, then emits the net change of the row of
+    /// every changed group ordered by grouping set and group key, instead of emitting changes as
+    /// they happen in the order of the input. The output is then the same for the same input
+    /// transactions whatever the order of the records within them.
+    pub fn with_sorted_output(mut self) -> Self {
+        self.pending = Some(BTreeMap::new());
+        self
+    }
+
+    /// Places the `NULL`s of the sorted output as `nulls` says, one policy per leading column of
+    /// the group key, as given by `NULLS FIRST` or `NULLS LAST` in `ORDER BY`. `NULL`s sort last
+    /// in the other columns.
+    pub fn with_null_ordering(mut self, nulls: Vec<NullOrdering>) -> Self {
+        self.null_ordering = nulls;
+        self
+    }
+
+    pub fn strategy(&self) -> AggregationStrategy {
+        self.strategy
+    }
+
+    /// Number of groups currently held in memory, across grouping sets.
+    pub fn groups_count(&self) -> usize {
+        self.grouping_sets
+            .iter()
+            .map(|grouping_set| grouping_set.states.len())
+            .sum()
+    }
+
     fn calc_and_fill_measures(
         curr_state: &mut AggregationState,
         deleted_record: Option<&Record>,
         inserted_record: Option<&Record>,
         out_rec_delete: &mut Vec<Field>,
-        out_rec_insert: &mut Vec<Field>,
         op: AggregatorOperation,
         measures: &Vec<Vec<Expression>>,
         input_schema: &Schema,
@@ -166,23 +462,39 @@ impl AggregationProcessor {
                     curr_aggr.update(&deleted_fields, &inserted_fields)?
                 }
             };
-            out_rec_insert.push(new_val.clone());
             new_fields.push(new_val);
         }
         Ok(new_fields)
     }
 
-    fn agg_delete(&mut self, old: &mut Record) -> Result<Vec<Operation>, PipelineError> {
+    /// Replaces the float NaNs in the values of the measures as `nan_policy` says.
+    ///
+    /// Called once the new values of all the measures are in the aggregators, so that if
+    /// `nan_policy` fails the state of the group still holds the operation. Its `values` are left
+    /// as they were, the row last sent downstream, which later operations then update.
+    fn replace_nans(
+        values: &[Field],
+        nan_policy: NanPolicy,
+        measures_types: &[AggregatorType],
+    ) -> Result<Vec<Field>, PipelineError> {
+        values
+            .iter()
+            .zip(measures_types)
+            .map(|(value, typ)| Ok(check_nan_f64!(value.clone(), nan_policy, typ)))
+            .collect()
+    }
+
+    fn agg_delete(
+        &mut self,
+        set: usize,
+        old: &mut Record,
+    ) -> Result<Vec<Operation>, PipelineError> {
         let mut out_rec_delete: Vec<Field> = Vec::with_capacity(self.measures.len());
-        let mut out_rec_insert: Vec<Field> = Vec::with_capacity(self.measures.len());
 
-        let key = if !self.dimensions.is_empty() {
-            get_key(&self.input_schema, old, &self.dimensions)?
-        } else {
-            self.default_segment_key
-        };
+        let key = self.get_key(set, old)?;
 
-        let curr_state_opt = self.states.get_mut(&key);
+        let grouping_set = &mut self.grouping_sets[set];
+        let curr_state_opt = grouping_set.states.get_mut(&key);
         assert!(
             curr_state_opt.is_some(),
             "Unable to find aggregator state during DELETE operation"
@@ -194,39 +506,51 @@ impl AggregationProcessor {
             Some(old),
             None,
             &mut out_rec_delete,
-            &mut out_rec_insert,
             AggregatorOperation::Delete,
             &self.measures,
             &self.input_schema,
         )?;
-
-        let (out_rec_delete_having_satisfied, out_rec_insert_having_satisfied) = match &self.having
-        {
-            None => (true, true),
-            Some(having) => (
-                Self::having_is_satisfied(
-                    &self.having_eval_schema,
-                    old,
-                    having,
-                    &mut out_rec_delete,
-                )?,
-                Self::having_is_satisfied(
-                    &self.having_eval_schema,
-                    old,
-                    having,
-                    &mut out_rec_insert,
-                )?,
-            ),
+        // The row of the group wasn't sent yet if every operation on it failed.
+        let emitted = curr_state.values.is_some();
+        let removed = curr_state.count == 1;
+        let mut out_rec_insert = if removed {
+            // The new values of a removed group are never output.
+            new_values
+        } else {
+            curr_state.count -= 1;
+            let values = Self::replace_nans(&new_values, self.nan_policy, &self.measures_types)?;
+            curr_state.values = Some(values.clone());
+            values
         };
 
-        let res = if curr_state.count == 1 {
-            self.states.remove(&key);
+        let (out_rec_delete_having_satisfied, out_rec_insert_having_satisfied) =
+            match &grouping_set.having {
+                None => (true, true),
+                Some(having) => (
+                    Self::having_is_satisfied(
+                        &self.having_eval_schema,
+                        old,
+                        having,
+                        &mut out_rec_delete,
+                    )?,
+                    Self::having_is_satisfied(
+                        &self.having_eval_schema,
+                        old,
+                        having,
+                        &mut out_rec_insert,
+                    )?,
+                ),
+            };
+        let out_rec_delete_having_satisfied = emitted && out_rec_delete_having_satisfied;
+
+        let res = if removed {
+            grouping_set.states.remove(&key);
             if out_rec_delete_having_satisfied {
                 vec![Operation::Delete {
                     old: Self::build_projection(
                         old,
                         out_rec_delete,
-                        &self.projections,
+                        &grouping_set.projections,
                         &self.aggregation_schema,
                     )?,
                 }]
@@ -234,16 +558,13 @@ impl AggregationProcessor {
                 vec![]
             }
         } else {
-            curr_state.count -= 1;
-            curr_state.values = Some(new_values);
-
             Self::generate_op_for_existing_segment(
                 out_rec_delete_having_satisfied,
                 out_rec_insert_having_satisfied,
                 out_rec_delete,
                 out_rec_insert,
                 old,
-                &self.projections,
+                &grouping_set.projections,
                 &self.aggregation_schema,
             )?
         };
@@ -251,58 +572,63 @@ impl AggregationProcessor {
         Ok(res)
     }
 
-    fn agg_insert(&mut self, new: &mut Record) -> Result<Vec<Operation>, PipelineError> {
+    fn agg_insert(
+        &mut self,
+        set: usize,
+        new: &mut Record,
+    ) -> Result<Vec<Operation>, PipelineError> {
         let mut out_rec_delete: Vec<Field> = Vec::with_capacity(self.measures.len());
-        let mut out_rec_insert: Vec<Field> = Vec::with_capacity(self.measures.len());
 
-        let key = if !self.dimensions.is_empty() {
-            get_key(&self.input_schema, new, &self.dimensions)?
-        } else {
-            self.default_segment_key
-        };
+        let key = self.get_key(set, new)?;
 
-        let curr_state = self.states.entry(key).or_insert(AggregationState::new(
-            &self.measures_types,
-            &self.measures_return_types,
-        ));
+        let grouping_set = &mut self.grouping_sets[set];
+        let curr_state = grouping_set.states.get_or_insert_with(key, || {
+            AggregationState::new(&self.measures_types, &self.measures_return_types)
+        });
 
         let new_values = Self::calc_and_fill_measures(
             curr_state,
             None,
             Some(new),
             &mut out_rec_delete,
-            &mut out_rec_insert,
             AggregatorOperation::Insert,
             &self.measures,
             &self.input_schema,
         )?;
+        // The row of the group wasn't sent yet if every operation on it failed.
+        let emitted = curr_state.values.is_some();
+        curr_state.count += 1;
+        let mut out_rec_insert =
+            Self::replace_nans(&new_values, self.nan_policy, &self.measures_types)?;
+        curr_state.values = Some(out_rec_insert.clone());
 
-        let (out_rec_delete_having_satisfied, out_rec_insert_having_satisfied) = match &self.having
-        {
-            None => (true, true),
-            Some(having) => (
-                Self::having_is_satisfied(
-                    &self.having_eval_schema,
-                    new,
-                    having,
-                    &mut out_rec_delete,
-                )?,
-                Self::having_is_satisfied(
-                    &self.having_eval_schema,
-                    new,
-                    having,
-                    &mut out_rec_insert,
-                )?,
-            ),
-        };
+        let (out_rec_delete_having_satisfied, out_rec_insert_having_satisfied) =
+            match &grouping_set.having {
+                None => (true, true),
+                Some(having) => (
+                    Self::having_is_satisfied(
+                        &self.having_eval_schema,
+                        new,
+                        having,
+                        &mut out_rec_delete,
+                    )?,
+                    Self::having_is_satisfied(
+                        &self.having_eval_schema,
+                        new,
+                        having,
+                        &mut out_rec_insert,
+                    )?,
+                ),
+            };
+        let out_rec_delete_having_satisfied = emitted && out_rec_delete_having_satisfied;
 
-        let res = if curr_state.count == 0 {
+        let res = if !emitted {
             if out_rec_insert_having_satisfied {
                 vec![Operation::Insert {
                     new: Self::build_projection(
                         new,
                         out_rec_insert,
-                        &self.projections,
+                        &grouping_set.projections,
                         &self.aggregation_schema,
                     )?,
                 }]
@@ -316,14 +642,11 @@ impl AggregationProcessor {
                 out_rec_delete,
                 out_rec_insert,
                 new,
-                &self.projections,
+                &grouping_set.projections,
                 &self.aggregation_schema,
             )?
         };
 
-        curr_state.count += 1;
-        curr_state.values = Some(new_values);
-
         Ok(res)
     }
 
@@ -407,14 +730,15 @@ impl AggregationProcessor {
 
     fn agg_update(
         &mut self,
+        set: usize,
         old: &mut Record,
         new: &mut Record,
-        key: u64,
+        key: GroupKey,
     ) -> Result<Vec<Operation>, PipelineError> {
         let mut out_rec_delete: Vec<Field> = Vec::with_capacity(self.measures.len());
-        let mut out_rec_insert: Vec<Field> = Vec::with_capacity(self.measures.len());
 
-        let curr_state_opt = self.states.get_mut(&key);
+        let grouping_set = &mut self.grouping_sets[set];
+        let curr_state_opt = grouping_set.states.get_mut(&key);
         assert!(
             curr_state_opt.is_some(),
             "Unable to find aggregator state during UPDATE operation"
@@ -426,30 +750,35 @@ impl AggregationProcessor {
             Some(old),
             Some(new),
             &mut out_rec_delete,
-            &mut out_rec_insert,
             AggregatorOperation::Update,
             &self.measures,
             &self.input_schema,
         )?;
+        // The row of the group wasn't sent yet if every operation on it failed.
+        let emitted = curr_state.values.is_some();
+        let mut out_rec_insert =
+            Self::replace_nans(&new_values, self.nan_policy, &self.measures_types)?;
+        curr_state.values = Some(out_rec_insert.clone());
 
-        let (out_rec_delete_having_satisfied, out_rec_insert_having_satisfied) = match &self.having
-        {
-            None => (true, true),
-            Some(having) => (
-                Self::having_is_satisfied(
-                    &self.having_eval_schema,
-                    old,
-                    having,
-                    &mut out_rec_delete,
-                )?,
-                Self::having_is_satisfied(
-                    &self.having_eval_schema,
-                    new,
-                    having,
-                    &mut out_rec_insert,
-                )?,
-            ),
-        };
+        let (out_rec_delete_having_satisfied, out_rec_insert_having_satisfied) =
+            match &grouping_set.having {
+                None => (true, true),
+                Some(having) => (
+                    Self::having_is_satisfied(
+                        &self.having_eval_schema,
+                        old,
+                        having,
+                        &mut out_rec_delete,
+                    )?,
+                    Self::having_is_satisfied(
+                        &self.having_eval_schema,
+                        new,
+                        having,
+                        &mut out_rec_insert,
+                    )?,
+                ),
+            };
+        let out_rec_delete_having_satisfied = emitted && out_rec_delete_having_satisfied;
 
         let res = match (
             out_rec_delete_having_satisfied,
@@ -459,7 +788,7 @@ impl AggregationProcessor {
                 new: Self::build_projection(
                     new,
                     out_rec_insert,
-                    &self.projections,
+                    &grouping_set.projections,
                     &self.aggregation_schema,
                 )?,
             }],
@@ -467,7 +796,7 @@ impl AggregationProcessor {
                 old: Self::build_projection(
                     old,
                     out_rec_delete,
-                    &self.projections,
+                    &grouping_set.projections,
                     &self.aggregation_schema,
                 )?,
             }],
@@ -475,20 +804,19 @@ impl AggregationProcessor {
                 new: Self::build_projection(
                     new,
                     out_rec_insert,
-                    &self.projections,
+                    &grouping_set.projections,
                     &self.aggregation_schema,
                 )?,
                 old: Self::build_projection(
                     old,
                     out_rec_delete,
-                    &self.projections,
+                    &grouping_set.projections,
                     &self.aggregation_schema,
                 )?,
             }],
             (false, false) => vec![],
         };
 
-        curr_state.values = Some(new_values);
         Ok(res)
     }
 
@@ -508,45 +836,145 @@ impl AggregationProcessor {
         Ok(Record::new(None, output))
     }
 
-    pub fn aggregate(&mut self, mut op: Operation) -> Result<Vec<Operation>, PipelineError> {
-        match op {
-            Operation::Insert { ref mut new } => Ok(self.agg_insert(new)?),
-            Operation::Delete { ref mut old } => Ok(self.agg_delete(old)?),
-            Operation::Update {
-                ref mut old,
-                ref mut new,
-            } => {
-                let (old_record_hash, new_record_hash) = if self.dimensions.is_empty() {
-                    (self.default_segment_key, self.default_segment_key)
-                } else {
-                    (
-                        get_key(&self.input_schema, old, &self.dimensions)?,
-                        get_key(&self.input_schema, new, &self.dimensions)?,
-                    )
-                };
-
-                if old_record_hash == new_record_hash {
-                    Ok(self.agg_update(old, new, old_record_hash)?)
-                } else {
-                    let mut r = Vec::with_capacity(2);
-                    r.extend(self.agg_delete(old)?);
-                    r.extend(self.agg_insert(new)?);
-                    Ok(r)
+    fn get_key(&self, set: usize, record: &Record) -> Result<GroupKey, PipelineError> {
+        let dimensions = &self.grouping_sets[set].dimensions;
+        Ok(match self.strategy {
+            AggregationStrategy::Hash if dimensions.is_empty() => {
+                GroupKey::Hash(self.default_segment_key)
+            }
+            AggregationStrategy::Hash => {
+                GroupKey::Hash(get_key(&self.input_schema, record, dimensions)?)
+            }
+            AggregationStrategy::Sorted => {
+                GroupKey::Sorted(get_key_values(&self.input_schema, record, dimensions)?)
+            }
+        })
+    }
+
+    pub fn aggregate(&mut self, op: Operation) -> Result<Vec<Operation>, PipelineError> {
+        Ok(self
+            .aggregate_groups(op, false)?
+            .into_iter()
+            .flat_map(|(_, ops)| ops)
+            .collect())
+    }
+
+    /// Aggregates `op`, returning the operations on the row of every group it changes, along
+    /// with the group if `with_groups`.
+    fn aggregate_groups(
+        &mut self,
+        mut op: Operation,
+        with_groups: bool,
+    ) -> Result<Vec<(Option<Group>, Vec<Operation>)>, PipelineError> {
+        self.check_max_groups(&op)?;
+        let mut result = vec![];
+        for set in 0..self.grouping_sets.len() {
+            match op {
+                Operation::Insert { ref mut new } => {
+                    let group = self.get_group(set, new, with_groups)?;
+                    result.push((group, self.agg_insert(set, new)?));
+                }
+                Operation::Delete { ref mut old } => {
+                    let group = self.get_group(set, old, with_groups)?;
+                    result.push((group, self.agg_delete(set, old)?));
+                }
+                Operation::Update {
+                    ref mut old,
+                    ref mut new,
+                } => {
+                    let old_key = self.get_key(set, old)?;
+                    let new_key = self.get_key(set, new)?;
+                    let old_group = self.get_group(set, old, with_groups)?;
+
+                    if old_key == new_key {
+                        result.push((old_group, self.agg_update(set, old, new, old_key)?));
+                    } else {
+                        result.push((old_group, self.agg_delete(set, old)?));
+                        let new_group = self.get_group(set, new, with_groups)?;
+                        result.push((new_group, self.agg_insert(set, new)?));
+                    }
                 }
             }
         }
+        Ok(result)
+    }
+
+    /// Fails with `PipelineError::StateLimitExceeded` if `op` would create groups past the cap
+    /// set with [`AggregationProcessor::with_max_groups`]. Checked across all the grouping sets
+    /// before any state changes, so that `op` is applied to all of them or to none.
+    fn check_max_groups(&self, op: &Operation) -> Result<(), PipelineError> {
+        let Some(max_groups) = self.max_groups else {
+            return Ok(());
+        };
+        let mut created = 0;
+        let mut removed = 0;
+        for (set, grouping_set) in self.grouping_sets.iter().enumerate() {
+            match op {
+                Operation::Insert { new } => {
+                    if !grouping_set.states.contains_key(&self.get_key(set, new)?) {
+                        created += 1;
+                    }
+                }
+                Operation::Delete { .. } => {}
+                Operation::Update { old, new } => {
+                    let old_key = self.get_key(set, old)?;
+                    let new_key = self.get_key(set, new)?;
+                    if old_key == new_key {
+                        continue;
+                    }
+                    // The record is deleted first, which removes its group if it's the last one.
+                    if grouping_set
+                        .states
+                        .get(&old_key)
+                        .map_or(false, |state| state.count == 1)
+                    {
+                        removed += 1;
+                    }
+                    if !grouping_set.states.contains_key(&new_key) {
+                        created += 1;
+                    }
+                }
+            }
+        }
+        if created > 0 && self.groups_count() + created - removed > max_groups {
+            return Err(PipelineError::StateLimitExceeded(max_groups));
+        }
+        Ok(())
+    }
+
+    fn get_group(
+        &self,
+        set: usize,
+        record: &Record,
+        with_groups: bool,
+    ) -> Result<Option<Group>, PipelineError> {
+        if !with_groups {
+            return Ok(None);
+        }
+        let dimensions = &self.grouping_sets[set].dimensions;
+        let key = get_key_values(&self.input_schema, record, dimensions)?;
+        Ok(Some((set, key)))
     }
 }
 
-fn get_key(
+fn get_key_values(
     schema: &Schema,
     record: &Record,
     dimensions: &[Expression],
-) -> Result<u64, PipelineError> {
+) -> Result<Vec<Field>, PipelineError> {
     let mut key = Vec::<Field>::with_capacity(dimensions.len());
     for dimension in dimensions.iter() {
         key.push(dimension.evaluate(record, schema)?);
     }
+    Ok(key)
+}
+
+fn get_key(
+    schema: &Schema,
+    record: &Record,
+    dimensions: &[Expression],
+) -> Result<u64, PipelineError> {
+    let key = get_key_values(schema, record, dimensions)?;
     let mut hasher = AHasher::default();
     key.hash(&mut hasher);
     let v = hasher.finish();
@@ -564,9 +992,46 @@ impl Processor for AggregationProcessor {
         op: Operation,
         fw: &mut dyn ProcessorChannelForwarder,
     ) -> Result<(), ExecutionError> {
-        let ops = self.aggregate(op).map_err(|e| InternalError(Box::new(e)))?;
-        for fop in ops {
-            fw.send(fop, DEFAULT_PORT_HANDLE)?;
+        if self.pending.is_none() {
+            let ops = self.aggregate(op).map_err(|e| InternalError(Box::new(e)))?;
+            for fop in ops {
+                fw.send(fop, DEFAULT_PORT_HANDLE)?;
+            }
+            return Ok(());
+        }
+
+        let groups = self
+            .aggregate_groups(op, true)
+            .map_err(|e| InternalError(Box::new(e)))?;
+        let pending = self.pending.as_mut().expect("Output is held back");
+        for (group, ops) in groups {
+            let group = group.expect("Groups were requested");
+            for op in ops {
+                match pending.get_mut(&group) {
+                    Some(row) => row.apply(op),
+                    None => {
+                        pending.insert(group.clone(), PendingRow::new(op));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, fw: &mut dyn ProcessorChannelForwarder) -> Result<(), ExecutionError> {
+        let Some(pending) = &mut self.pending else {
+            return Ok(());
+        };
+        let mut rows = std::mem::take(pending).into_iter().collect::<Vec<_>>();
+        rows.sort_by(|((set_a, key_a), _), ((set_b, key_b), _)| {
+            set_a
+                .cmp(set_b)
+                .then_with(|| cmp_group_keys(key_a, key_b, &self.null_ordering))
+        });
+        for (_, row) in rows {
+            if let Some(op) = row.into_operation() {
+                fw.send(op, DEFAULT_PORT_HANDLE)?;
+            }
         }
         Ok(())
     }
@@ -1,5 +1,5 @@
 use crate::pipeline::aggregation::aggregator::Aggregator;
-use crate::pipeline::errors::{FieldTypes, PipelineError};
+use crate::pipeline::errors::{FieldTypes, OperationError, PipelineError, SqlError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::aggregate::AggregateFunctionType::Sum;
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
@@ -118,12 +118,18 @@ pub fn get_sum(
                 if decr {
                     for field in fields {
                         let val = calculate_err_field!(field.to_uint(), Sum, field);
-                        current_state.uint_state -= val;
+                        current_state.uint_state = checked(
+                            current_state.uint_state.checked_sub(val),
+                            OperationError::SubtractionOverflow,
+                        )?;
                     }
                 } else {
                     for field in fields {
                         let val = calculate_err_field!(field.to_uint(), Sum, field);
-                        current_state.uint_state += val;
+                        current_state.uint_state = checked(
+                            current_state.uint_state.checked_add(val),
+                            OperationError::AdditionOverflow,
+                        )?;
                     }
                 }
                 Ok(Field::UInt(current_state.uint_state))
@@ -132,26 +138,48 @@ pub fn get_sum(
                 if decr {
                     for field in fields {
                         let val = calculate_err_field!(field.to_u128(), Sum, field);
-                        current_state.u128_state -= val;
+                        current_state.u128_state = checked(
+                            current_state.u128_state.checked_sub(val),
+                            OperationError::SubtractionOverflow,
+                        )?;
                     }
                 } else {
                     for field in fields {
                         let val = calculate_err_field!(field.to_u128(), Sum, field);
-                        current_state.u128_state += val;
+                        current_state.u128_state = checked(
+                            current_state.u128_state.checked_add(val),
+                            OperationError::AdditionOverflow,
+                        )?;
                     }
                 }
                 Ok(Field::U128(current_state.u128_state))
             }
             FieldType::Int => {
+                // Plain `Int` columns skip the per-field conversion. The per-field loop below
+                // still handles other values, and sums that don't fit in an `i64`.
+                let state = sum_ints(fields).and_then(|sum| {
+                    let state = current_state.int_state as i128;
+                    i64::try_from(if decr { state - sum } else { state + sum }).ok()
+                });
+                if let Some(state) = state {
+                    current_state.int_state = state;
+                    return Ok(Field::Int(state));
+                }
                 if decr {
                     for field in fields {
                         let val = calculate_err_field!(field.to_int(), Sum, field);
-                        current_state.int_state -= val;
+                        current_state.int_state = checked(
+                            current_state.int_state.checked_sub(val),
+                            OperationError::SubtractionOverflow,
+                        )?;
                     }
                 } else {
                     for field in fields {
                         let val = calculate_err_field!(field.to_int(), Sum, field);
-                        current_state.int_state += val;
+                        current_state.int_state = checked(
+                            current_state.int_state.checked_add(val),
+                            OperationError::AdditionOverflow,
+                        )?;
                     }
                 }
                 Ok(Field::Int(current_state.int_state))
@@ -160,17 +188,27 @@ pub fn get_sum(
                 if decr {
                     for field in fields {
                         let val = calculate_err_field!(field.to_i128(), Sum, field);
-                        current_state.i128_state -= val;
+                        current_state.i128_state = checked(
+                            current_state.i128_state.checked_sub(val),
+                            OperationError::SubtractionOverflow,
+                        )?;
                     }
                 } else {
                     for field in fields {
                         let val = calculate_err_field!(field.to_i128(), Sum, field);
-                        current_state.i128_state += val;
+                        current_state.i128_state = checked(
+                            current_state.i128_state.checked_add(val),
+                            OperationError::AdditionOverflow,
+                        )?;
                     }
                 }
                 Ok(Field::I128(current_state.i128_state))
             }
             FieldType::Float => {
+                if let Some(state) = sum_floats(fields, current_state.float_state, decr) {
+                    current_state.float_state = state;
+                    return Ok(Field::Float(OrderedFloat::from(state)));
+                }
                 if decr {
                     for field in fields {
                         let val = calculate_err_field!(field.to_float(), Sum, field);
@@ -185,15 +223,37 @@ pub fn get_sum(
                 Ok(Field::Float(OrderedFloat::from(current_state.float_state)))
             }
             FieldType::Decimal => {
+                // Sums of `Int` columns planned as `Decimal` add up the integers exactly first,
+                // rather than converting every value.
+                if let Some(sum) = sum_ints(fields).and_then(Decimal::from_i128) {
+                    current_state.decimal_state = if decr {
+                        checked(
+                            current_state.decimal_state.checked_sub(sum),
+                            OperationError::SubtractionOverflow,
+                        )?
+                    } else {
+                        checked(
+                            current_state.decimal_state.checked_add(sum),
+                            OperationError::AdditionOverflow,
+                        )?
+                    };
+                    return Ok(Field::Decimal(current_state.decimal_state));
+                }
                 if decr {
                     for field in fields {
                         let val = calculate_err_field!(field.to_decimal(), Sum, field);
-                        current_state.decimal_state -= val;
+                        current_state.decimal_state = checked(
+                            current_state.decimal_state.checked_sub(val),
+                            OperationError::SubtractionOverflow,
+                        )?;
                     }
                 } else {
                     for field in fields {
                         let val = calculate_err_field!(field.to_decimal(), Sum, field);
-                        current_state.decimal_state += val;
+                        current_state.decimal_state = checked(
+                            current_state.decimal_state.checked_add(val),
+                            OperationError::AdditionOverflow,
+                        )?;
                     }
                 }
                 Ok(Field::Decimal(current_state.decimal_state))
@@ -202,12 +262,18 @@ pub fn get_sum(
                 if decr {
                     for field in fields {
                         let val = calculate_err_field!(field.to_duration()?, Sum, field);
-                        current_state.duration_state -= val.0;
+                        current_state.duration_state = checked(
+                            current_state.duration_state.checked_sub(val.0),
+                            OperationError::SubtractionOverflow,
+                        )?;
                     }
                 } else {
                     for field in fields {
                         let val = calculate_err_field!(field.to_duration()?, Sum, field);
-                        current_state.duration_state += val.0;
+                        current_state.duration_state = checked(
+                            current_state.duration_state.checked_add(val.0),
+                            OperationError::AdditionOverflow,
+                        )?;
                     }
                 }
                 Ok(Field::Duration(DozerDuration(
@@ -231,3 +297,49 @@ pub fn get_sum(
         )))),
     }
 }
+
+/// Return type of `SUM` over `typ` when the planner promotes sums that may overflow: integer sums
+/// are computed as `Decimal`, which holds sums far past the range of the integer types.
+pub fn promoted_sum_type(typ: FieldType) -> FieldType {
+    match typ {
+        FieldType::UInt | FieldType::Int => FieldType::Decimal,
+        typ => typ,
+    }
+}
+
+pub(crate) fn checked<T>(value: Option<T>, error: OperationError) -> Result<T, PipelineError> {
+    value.ok_or(PipelineError::SqlError(SqlError::Operation(error)))
+}
+
+fn int_or_null(field: &Field) -> Option<i64> {
+    match field {
+        Field::Int(i) => Some(*i),
+        Field::Null => Some(0),
+        _ => None,
+    }
+}
+
+/// Sums `fields` exactly if they are all `Int` or `Null`, without going through
+/// `Field::to_int`. Returns `None` on any other value, leaving `get_sum` to take the per-field
+/// path.
+fn sum_ints(fields: &[Field]) -> Option<i128> {
+    fields
+        .iter()
+        .try_fold(0_i128, |sum, field| Some(sum + int_or_null(field)? as i128))
+}
+
+/// Adds `fields` to (or subtracts them from) `state` if they are all `Float` or `Null`, without
+/// going through `Field::to_float`. Returns `None` on any other value.
+///
+/// Values are accumulated one at a time, in order, so the result is rounded exactly as on the
+/// per-field path. Reordering the additions across lanes would not be.
+fn sum_floats(fields: &[Field], state: f64, decr: bool) -> Option<f64> {
+    fields.iter().try_fold(state, |state, field| {
+        let value = match field {
+            Field::Float(f) => f.0,
+            Field::Null => 0_f64,
+            _ => return None,
+        };
+        Some(if decr { state - value } else { state + value })
+    })
+}
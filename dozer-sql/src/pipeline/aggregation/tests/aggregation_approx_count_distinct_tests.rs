@@ -0,0 +1,112 @@
+use crate::output;
+use crate::pipeline::aggregation::aggregator::{
+    get_aggregator_type_from_aggregation_expression, Aggregator, AggregatorType,
+};
+use crate::pipeline::aggregation::approx_count_distinct::ApproxCountDistinctAggregator;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_field, init_input_schema, init_processor, insert_exp, insert_field, update_exp,
+    FIELD_100_INT, FIELD_200_INT, FIELD_NULL, ITALY,
+};
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::tests::utils::get_select;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::Field;
+use dozer_types::types::FieldType::Int;
+use std::collections::HashMap;
+
+fn estimate(precision: u8, cardinality: u64) -> u64 {
+    let mut aggregator = ApproxCountDistinctAggregator::new(precision);
+    let mut result = Field::Null;
+    // Every value is inserted twice, duplicates must not change the estimate.
+    for _ in 0..2 {
+        for value in 0..cardinality {
+            result = aggregator.insert(&[Field::Int(value as i64)]).unwrap();
+        }
+    }
+    result.as_uint().unwrap()
+}
+
+#[test]
+fn test_approx_count_distinct_is_within_error_bound() {
+    for (precision, cardinality) in [(12, 1_000), (12, 50_000), (14, 200_000), (10, 200_000)] {
+        // Standard error is 1.04 / sqrt(2^precision), allow three standard errors.
+        let bound = 3.0 * 1.04 / ((1u64 << precision) as f64).sqrt();
+        let estimate = estimate(precision, cardinality);
+        let error = (estimate as f64 - cardinality as f64).abs() / cardinality as f64;
+        assert!(
+            error <= bound,
+            "estimate {estimate} for {cardinality} distinct values at precision {precision} is off by {error}"
+        );
+    }
+}
+
+#[test]
+fn test_approx_count_distinct_ignores_null() {
+    let mut aggregator = ApproxCountDistinctAggregator::new(12);
+    assert_eq!(aggregator.insert(&[Field::Null]).unwrap(), Field::UInt(0));
+    assert_eq!(aggregator.insert(&[Field::Int(1)]).unwrap(), Field::UInt(1));
+    assert_eq!(aggregator.insert(&[Field::Null]).unwrap(), Field::UInt(1));
+}
+
+#[test]
+fn test_approx_count_distinct_precision_argument() {
+    let schema = init_input_schema(Int, "APPROX_COUNT_DISTINCT");
+    let get_type = |sql: &str| {
+        let mut planner = CommonPlanner::new(schema.clone());
+        planner.plan(*get_select(sql).unwrap())?;
+        get_aggregator_type_from_aggregation_expression(&planner.aggregation_output[0], &schema)
+            .map(|(_, typ)| typ)
+    };
+
+    assert_eq!(
+        get_type("SELECT APPROX_COUNT_DISTINCT(Salary) FROM Users").unwrap(),
+        AggregatorType::ApproxCountDistinct { precision: 12 }
+    );
+    assert_eq!(
+        get_type("SELECT APPROX_COUNT_DISTINCT(Salary, 14) FROM Users").unwrap(),
+        AggregatorType::ApproxCountDistinct { precision: 14 }
+    );
+    assert!(matches!(
+        get_type("SELECT APPROX_COUNT_DISTINCT(Salary, 30) FROM Users"),
+        Err(PipelineError::InvalidFunctionArgument(..))
+    ));
+}
+
+#[test]
+fn test_approx_count_distinct_aggregation_rejects_deletes() {
+    let schema = init_input_schema(Int, "APPROX_COUNT_DISTINCT");
+    let mut processor = init_processor(
+        "SELECT Country, APPROX_COUNT_DISTINCT(Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let mut out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    assert_eq!(out, vec![insert_exp(ITALY, &Field::UInt(1))]);
+
+    out = output!(processor, insert_field(ITALY, FIELD_100_INT));
+    assert_eq!(
+        out,
+        vec![update_exp(ITALY, ITALY, &Field::UInt(1), &Field::UInt(1))]
+    );
+
+    out = output!(processor, insert_field(ITALY, FIELD_200_INT));
+    assert_eq!(
+        out,
+        vec![update_exp(ITALY, ITALY, &Field::UInt(1), &Field::UInt(2))]
+    );
+
+    out = output!(processor, insert_field(ITALY, FIELD_NULL));
+    assert_eq!(
+        out,
+        vec![update_exp(ITALY, ITALY, &Field::UInt(2), &Field::UInt(2))]
+    );
+
+    assert!(matches!(
+        processor.aggregate(delete_field(ITALY, FIELD_100_INT)),
+        Err(PipelineError::UnsupportedRetraction(_))
+    ));
+}
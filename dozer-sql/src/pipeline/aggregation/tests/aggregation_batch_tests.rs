@@ -0,0 +1,86 @@
+use crate::pipeline::aggregation::aggregator::Aggregator;
+use crate::pipeline::aggregation::avg::AvgAggregator;
+use crate::pipeline::aggregation::sum::SumAggregator;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+
+fn per_row<A: Aggregator>(mut aggregator: A, typ: FieldType, fields: &[Field]) -> Field {
+    aggregator.init(typ);
+    let mut result = Field::Null;
+    for field in fields {
+        result = aggregator.insert(std::slice::from_ref(field)).unwrap();
+    }
+    result
+}
+
+fn batched<A: Aggregator>(mut aggregator: A, typ: FieldType, fields: &[Field]) -> Field {
+    aggregator.init(typ);
+    aggregator.insert(fields).unwrap()
+}
+
+fn assert_batch_matches_per_row(typ: FieldType, fields: &[Field]) {
+    assert_eq!(
+        batched(SumAggregator::new(), typ, fields),
+        per_row(SumAggregator::new(), typ, fields)
+    );
+    assert_eq!(
+        batched(AvgAggregator::new(), typ, fields),
+        per_row(AvgAggregator::new(), typ, fields)
+    );
+}
+
+#[test]
+fn test_batched_int_sum_matches_per_row() {
+    let fields = (0..1000)
+        .map(|i| match i % 7 {
+            0 => Field::Null,
+            _ => Field::Int(i * 1_000_003 - 400_000_000),
+        })
+        .collect::<Vec<_>>();
+    assert_batch_matches_per_row(FieldType::Int, &fields);
+
+    // Values near the bounds, whose running sum stays in range.
+    let fields = vec![
+        Field::Int(i64::MAX),
+        Field::Null,
+        Field::Int(i64::MIN),
+        Field::Int(i64::MAX),
+        Field::Int(-1),
+        Field::Int(i64::MIN + 1),
+    ];
+    assert_batch_matches_per_row(FieldType::Int, &fields);
+
+    // Values that aren't `Int` take the per-field path.
+    let fields = vec![Field::Int(5), Field::UInt(7), Field::Null];
+    assert_batch_matches_per_row(FieldType::Int, &fields);
+}
+
+#[test]
+fn test_batched_float_sum_matches_per_row() {
+    let fields = (0..1000)
+        .map(|i| match i % 5 {
+            0 => Field::Null,
+            _ => Field::Float(OrderedFloat(i as f64 / 3.0 - 100.0)),
+        })
+        .collect::<Vec<_>>();
+    assert_batch_matches_per_row(FieldType::Float, &fields);
+
+    let fields = vec![
+        Field::Float(OrderedFloat(1e308)),
+        Field::Float(OrderedFloat(1e308)),
+        Field::Null,
+        Field::Float(OrderedFloat(-1e308)),
+    ];
+    assert_batch_matches_per_row(FieldType::Float, &fields);
+}
+
+#[test]
+fn test_batched_delete_restores_sum() {
+    let fields = vec![Field::Int(i64::MAX), Field::Null, Field::Int(i64::MIN)];
+
+    let mut aggregator = SumAggregator::new();
+    aggregator.init(FieldType::Int);
+    aggregator.insert(&[Field::Int(42)]).unwrap();
+    assert_eq!(aggregator.insert(&fields).unwrap(), Field::Int(41));
+    assert_eq!(aggregator.delete(&fields).unwrap(), Field::Int(42));
+}
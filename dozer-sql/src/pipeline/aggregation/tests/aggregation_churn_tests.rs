@@ -0,0 +1,234 @@
+use crate::pipeline::aggregation::aggregator::Aggregator;
+use crate::pipeline::aggregation::avg::AvgAggregator;
+use crate::pipeline::aggregation::max::MaxAggregator;
+use crate::pipeline::aggregation::min::MinAggregator;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{DozerDuration, Field, FieldType, TimeUnit};
+use std::time::Duration;
+
+/// Inserts and deletes values in a pseudo-random order, checking MIN and MAX against the
+/// values currently present after every operation.
+#[test]
+fn test_min_max_follow_churn() {
+    let mut min = MinAggregator::new();
+    let mut max = MaxAggregator::new();
+    min.init(FieldType::Int);
+    max.init(FieldType::Int);
+
+    let mut present: Vec<i64> = vec![];
+    let mut state: u64 = 42;
+    for _ in 0..10_000 {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let random = state >> 33;
+
+        let (min_result, max_result) = if present.is_empty() || random % 3 != 0 {
+            let value = (random % 200) as i64 - 100;
+            present.push(value);
+            let value = Field::Int(value);
+            (
+                min.insert(std::slice::from_ref(&value)).unwrap(),
+                max.insert(std::slice::from_ref(&value)).unwrap(),
+            )
+        } else {
+            // Deleting the current extreme is the case a rescan would be needed for.
+            let index = match random % 4 {
+                0 => position_of(&present, present.iter().min()),
+                1 => position_of(&present, present.iter().max()),
+                _ => (random as usize / 4) % present.len(),
+            };
+            let value = Field::Int(present.swap_remove(index));
+            (
+                min.delete(std::slice::from_ref(&value)).unwrap(),
+                max.delete(std::slice::from_ref(&value)).unwrap(),
+            )
+        };
+
+        let expected = |extreme: Option<&i64>| extreme.map_or(Field::Null, |v| Field::Int(*v));
+        assert_eq!(min_result, expected(present.iter().min()));
+        assert_eq!(max_result, expected(present.iter().max()));
+    }
+}
+
+fn position_of(values: &[i64], value: Option<&i64>) -> usize {
+    values.iter().position(|v| Some(v) == value).unwrap()
+}
+
+#[test]
+fn test_min_max_ignore_deletes_of_missing_values() {
+    let mut min = MinAggregator::new();
+    min.init(FieldType::Int);
+
+    min.insert(&[Field::Int(5)]).unwrap();
+    assert_eq!(min.delete(&[Field::Int(1)]).unwrap(), Field::Int(5));
+    assert_eq!(min.delete(&[Field::Int(5)]).unwrap(), Field::Null);
+    assert_eq!(min.insert(&[Field::Int(7)]).unwrap(), Field::Int(7));
+}
+
+/// Inserts, deletes and updates values in a pseudo-random order, checking AVG against the
+/// average of the values currently present, computed from scratch, after every operation.
+#[test]
+fn test_avg_follows_churn() {
+    let mut avg = AvgAggregator::new();
+    // `AVG` of an `Int` column is planned as a `Decimal`.
+    avg.init(FieldType::Decimal);
+
+    let mut present: Vec<i64> = vec![];
+    let mut state: u64 = 7;
+    for _ in 0..10_000 {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let random = state >> 33;
+        let value = (random % 2_000_000) as i64 - 1_000_000;
+
+        let result = if present.is_empty() || random % 3 == 0 {
+            present.push(value);
+            avg.insert(&[Field::Int(value)]).unwrap()
+        } else {
+            let index = (random as usize / 3) % present.len();
+            if random % 2 == 0 {
+                let old = present.swap_remove(index);
+                avg.delete(&[Field::Int(old)]).unwrap()
+            } else {
+                let old = std::mem::replace(&mut present[index], value);
+                avg.update(&[Field::Int(old)], &[Field::Int(value)])
+                    .unwrap()
+            }
+        };
+
+        let expected = if present.is_empty() {
+            Field::Null
+        } else {
+            let sum: i64 = present.iter().sum();
+            Field::Decimal(Decimal::from(sum) / Decimal::from(present.len()))
+        };
+        assert_eq!(result, expected);
+    }
+}
+
+#[test]
+fn test_avg_of_empty_group_is_null() {
+    let mut avg = AvgAggregator::new();
+    avg.init(FieldType::Float);
+
+    assert_eq!(
+        avg.insert(&[Field::Float(1.5.into()), Field::Float(2.5.into())])
+            .unwrap(),
+        Field::Float(2.0.into())
+    );
+    assert_eq!(
+        avg.delete(&[Field::Float(1.5.into()), Field::Float(2.5.into())])
+            .unwrap(),
+        Field::Null
+    );
+    assert_eq!(
+        avg.insert(&[Field::Float(4.0.into())]).unwrap(),
+        Field::Float(4.0.into())
+    );
+}
+
+#[test]
+fn test_avg_duration_is_exact() {
+    let mut avg = AvgAggregator::new();
+    avg.init(FieldType::Duration);
+
+    let duration = |nanos| {
+        Field::Duration(DozerDuration(
+            Duration::from_nanos(nanos),
+            TimeUnit::Nanoseconds,
+        ))
+    };
+    avg.insert(&[duration(1_999_999_999)]).unwrap();
+    assert_eq!(avg.insert(&[duration(1)]).unwrap(), duration(1_000_000_000));
+    assert_eq!(avg.delete(&[duration(1)]).unwrap(), duration(1_999_999_999));
+}
+
+/// Churns `I128` and `Duration` values through AVG, whose running sums use checked arithmetic
+/// like the other types, checking the result against the values present after every operation.
+#[test]
+fn test_avg_i128_and_duration_follow_churn() {
+    let mut i128_avg = AvgAggregator::new();
+    let mut duration_avg = AvgAggregator::new();
+    i128_avg.init(FieldType::I128);
+    duration_avg.init(FieldType::Duration);
+    let duration = |nanos: u64| {
+        Field::Duration(DozerDuration(
+            Duration::from_nanos(nanos),
+            TimeUnit::Nanoseconds,
+        ))
+    };
+
+    let mut present: Vec<(i128, u64)> = vec![];
+    let mut state: u64 = 13;
+    for _ in 0..10_000 {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let random = state >> 33;
+        let value = (
+            (random as i128 - (1 << 30)) * 1_000_000_000_000_000_000,
+            random % 1_000_000_000_000,
+        );
+
+        let (i128_result, duration_result) = if present.is_empty() || random % 3 == 0 {
+            present.push(value);
+            (
+                i128_avg.insert(&[Field::I128(value.0)]).unwrap(),
+                duration_avg.insert(&[duration(value.1)]).unwrap(),
+            )
+        } else {
+            let index = (random as usize / 3) % present.len();
+            if random % 2 == 0 {
+                let old = present.swap_remove(index);
+                (
+                    i128_avg.delete(&[Field::I128(old.0)]).unwrap(),
+                    duration_avg.delete(&[duration(old.1)]).unwrap(),
+                )
+            } else {
+                let old = std::mem::replace(&mut present[index], value);
+                (
+                    i128_avg
+                        .update(&[Field::I128(old.0)], &[Field::I128(value.0)])
+                        .unwrap(),
+                    duration_avg
+                        .update(&[duration(old.1)], &[duration(value.1)])
+                        .unwrap(),
+                )
+            }
+        };
+
+        if present.is_empty() {
+            assert_eq!(i128_result, Field::Null);
+            assert_eq!(duration_result, Field::Null);
+        } else {
+            let count = present.len();
+            let i128_sum: i128 = present.iter().map(|(value, _)| value).sum();
+            let nanos_sum: u64 = present.iter().map(|(_, nanos)| nanos).sum();
+            assert_eq!(i128_result, Field::I128(i128_sum / count as i128));
+            assert_eq!(duration_result, duration(nanos_sum / count as u64));
+        }
+    }
+}
+
+#[test]
+fn test_avg_i128_and_duration_overflow_is_an_error() {
+    let mut avg = AvgAggregator::new();
+    avg.init(FieldType::I128);
+    avg.insert(&[Field::I128(i128::MAX)]).unwrap();
+    assert!(avg.insert(&[Field::I128(1)]).is_err());
+
+    let mut avg = AvgAggregator::new();
+    avg.init(FieldType::I128);
+    avg.insert(&[Field::I128(i128::MIN)]).unwrap();
+    assert!(avg.delete(&[Field::I128(1)]).is_err());
+
+    // A `Duration` sum can't go below zero.
+    let mut avg = AvgAggregator::new();
+    avg.init(FieldType::Duration);
+    let duration =
+        |secs| Field::Duration(DozerDuration(Duration::from_secs(secs), TimeUnit::Seconds));
+    avg.insert(&[duration(1)]).unwrap();
+    assert!(avg.update(&[duration(2)], &[duration(1)]).is_err());
+}
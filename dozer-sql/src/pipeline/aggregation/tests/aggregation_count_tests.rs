@@ -1,12 +1,19 @@
 use crate::output;
+use crate::pipeline::aggregation::factory::AggregationProcessorFactory;
 use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
     delete_exp, delete_field, get_date_field, get_decimal_field, get_duration_field, get_ts_field,
     init_input_schema, init_processor, insert_exp, insert_field, update_exp, update_field, DATE8,
     FIELD_100_FLOAT, FIELD_100_INT, FIELD_1_INT, FIELD_200_FLOAT, FIELD_200_INT, FIELD_2_INT,
     FIELD_3_INT, FIELD_50_FLOAT, FIELD_50_INT, FIELD_NULL, ITALY, SINGAPORE,
 };
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::tests::utils::get_select;
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, ProcessorFactory};
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_types::types::FieldType::{Date, Decimal, Duration, Float, Int, Timestamp};
+use dozer_types::types::Operation;
 use std::collections::HashMap;
 
 #[test]
@@ -888,3 +895,113 @@ fn test_count_aggregation_duration_null() {
     exp = vec![delete_exp(ITALY, FIELD_1_INT)];
     assert_eq!(out, exp);
 }
+
+#[test]
+fn test_count_aggregation_state_limit() {
+    let schema = init_input_schema(Int, "COUNT");
+    let mut processor = init_processor(
+        "SELECT Country, COUNT(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap()
+    .with_max_groups(1);
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100.0
+        -------------
+        COUNT = 1
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_INT);
+    let mut out = output!(processor, inp);
+    let mut exp = vec![insert_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert 100 for segment Singapore, a second group is over the limit
+    inp = insert_field(SINGAPORE, FIELD_100_INT);
+    assert!(matches!(
+        processor.aggregate(inp),
+        Err(PipelineError::StateLimitExceeded(1))
+    ));
+    assert_eq!(processor.groups_count(), 1);
+
+    // Existing groups can still be updated
+    /*
+        Italy, 100.0
+        Italy, 200.0
+        -------------
+        COUNT = 2
+    */
+    inp = insert_field(ITALY, FIELD_200_INT);
+    out = output!(processor, inp);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    // Once Italy is gone, Singapore fits
+    /*
+        Italy, 200.0
+        -------------
+        COUNT = 1
+    */
+    inp = delete_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    inp = delete_field(ITALY, FIELD_200_INT);
+    out = output!(processor, inp);
+    exp = vec![delete_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+    assert_eq!(processor.groups_count(), 0);
+
+    inp = insert_field(SINGAPORE, FIELD_100_INT);
+    out = output!(processor, inp);
+    exp = vec![insert_exp(SINGAPORE, FIELD_1_INT)];
+    assert_eq!(out, exp);
+}
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_count_aggregation_state_limit_from_factory() {
+    let schema = init_input_schema(Int, "COUNT");
+    let select = get_select("SELECT Country, COUNT(Salary) FROM Users GROUP BY Country").unwrap();
+    let mut processor = AggregationProcessorFactory::new(*select, false)
+        .with_max_groups(Some(1))
+        .build(
+            HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+            HashMap::new(),
+        )
+        .unwrap();
+
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor
+        .process(
+            DEFAULT_PORT_HANDLE,
+            insert_field(ITALY, FIELD_100_INT),
+            &mut fw,
+        )
+        .unwrap();
+    assert_eq!(fw.operations, vec![insert_exp(ITALY, FIELD_1_INT)]);
+
+    // A second group is over the limit.
+    assert!(processor
+        .process(
+            DEFAULT_PORT_HANDLE,
+            insert_field(SINGAPORE, FIELD_100_INT),
+            &mut fw,
+        )
+        .is_err());
+    assert_eq!(fw.operations.len(), 1);
+}
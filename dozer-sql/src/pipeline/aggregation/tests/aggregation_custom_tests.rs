@@ -0,0 +1,130 @@
+use crate::pipeline::aggregation::aggregator::Aggregator;
+use crate::pipeline::aggregation::custom::register_aggregate_function;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::tests::sql_runner::run_sql;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+/// Multiplies the non-`NULL` values of its first argument.
+#[derive(Debug, Default)]
+struct ProductAggregator {
+    product: f64,
+    count: u64,
+}
+
+impl ProductAggregator {
+    fn result(&self) -> Field {
+        if self.count == 0 {
+            Field::Null
+        } else {
+            Field::Float(OrderedFloat(self.product))
+        }
+    }
+}
+
+impl Aggregator for ProductAggregator {
+    fn init(&mut self, _return_type: FieldType) {
+        self.product = 1.0;
+    }
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        self.delete(old)?;
+        self.insert(new)
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        if let Some(Field::Float(value)) = old.first() {
+            self.product /= value.0;
+            self.count -= 1;
+        }
+        Ok(self.result())
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        if let Some(Field::Float(value)) = new.first() {
+            self.product *= value.0;
+            self.count += 1;
+        }
+        Ok(self.result())
+    }
+}
+
+fn product_aggregator() -> Box<dyn Aggregator> {
+    Box::<ProductAggregator>::default()
+}
+
+#[test]
+fn test_custom_aggregate_in_sql() {
+    register_aggregate_function("product_test", FieldType::Float, product_aggregator).unwrap();
+
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("country"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("spending"),
+                FieldType::Float,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let rows = [("Italy", 10.0), ("Germany", 20.0), ("Italy", 2.5)]
+        .into_iter()
+        .map(|(country, spending)| {
+            Record::new(
+                None,
+                vec![
+                    Field::String(country.to_string()),
+                    Field::Float(OrderedFloat(spending)),
+                ],
+            )
+        })
+        .collect();
+
+    let rows = run_sql(
+        "SELECT country, PRODUCT_TEST(spending) FROM input_table GROUP BY country",
+        schema,
+        rows,
+    );
+
+    let mut values = rows.into_iter().map(|row| row.values).collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(
+        values,
+        vec![
+            vec![
+                Field::String("Germany".to_string()),
+                Field::Float(OrderedFloat(20.0)),
+            ],
+            vec![
+                Field::String("Italy".to_string()),
+                Field::Float(OrderedFloat(25.0)),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_register_aggregate_function_rejects_taken_names() {
+    for name in ["sum", "SUM", "round"] {
+        assert!(matches!(
+            register_aggregate_function(name, FieldType::Float, product_aggregator),
+            Err(PipelineError::DuplicateFunction(_))
+        ));
+    }
+
+    register_aggregate_function("product_twice", FieldType::Float, product_aggregator).unwrap();
+    assert!(matches!(
+        register_aggregate_function("Product_Twice", FieldType::Float, product_aggregator),
+        Err(PipelineError::DuplicateFunction(_))
+    ));
+}
@@ -7,7 +7,8 @@ use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
 };
 use dozer_core::DEFAULT_PORT_HANDLE;
 
-use dozer_types::types::FieldType::{Date, Decimal, Duration, Float, Int, Timestamp, UInt};
+use dozer_types::types::Field;
+use dozer_types::types::FieldType::{Binary, Date, Decimal, Duration, Float, Int, Timestamp, UInt};
 use std::collections::HashMap;
 
 #[test]
@@ -1348,3 +1349,41 @@ fn test_max_aggregation_date_null() {
     exp = vec![delete_exp(ITALY, FIELD_NULL)];
     assert_eq!(out, exp);
 }
+
+#[test]
+fn test_max_aggregation_binary() {
+    let schema = init_input_schema(Binary, "MAX");
+    let mut processor = init_processor(
+        "SELECT Country, MAX(Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let short = &Field::Binary(vec![1, 2]);
+    let long = &Field::Binary(vec![1, 2, 0]);
+    let high = &Field::Binary(vec![2]);
+
+    let mut inp = insert_field(ITALY, short);
+    let mut out = output!(processor, inp);
+    let mut exp = vec![insert_exp(ITALY, short)];
+    assert_eq!(out, exp);
+
+    // A value sorts after its prefixes.
+    inp = insert_field(ITALY, long);
+    out = output!(processor, inp);
+    exp = vec![update_exp(ITALY, ITALY, short, long)];
+    assert_eq!(out, exp);
+
+    // Bytes compare before lengths.
+    inp = insert_field(ITALY, high);
+    out = output!(processor, inp);
+    exp = vec![update_exp(ITALY, ITALY, long, high)];
+    assert_eq!(out, exp);
+
+    inp = delete_field(ITALY, high);
+    out = output!(processor, inp);
+    exp = vec![update_exp(ITALY, ITALY, high, long)];
+    assert_eq!(out, exp);
+}
@@ -2,7 +2,7 @@ use crate::output;
 
 use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
     delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
-    FIELD_100_INT, FIELD_1_INT, ITALY,
+    update_exp, FIELD_100_INT, FIELD_1_INT, ITALY,
 };
 
 use dozer_core::DEFAULT_PORT_HANDLE;
@@ -89,3 +89,39 @@ fn test_sum_aggregation_del_and_insert() {
     let exp = vec![insert_exp(ITALY, FIELD_1_INT)];
     assert_eq!(out, exp);
 }
+
+#[test]
+fn test_max_aggregation_ignores_null() {
+    let schema = init_input_schema(Int, "MAX");
+    let mut processor = init_processor(
+        "SELECT Country, MAX(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100
+        -------------
+        MAX = 100
+    */
+    let inp = insert_field(ITALY, FIELD_100_INT);
+    let out = output!(processor, inp);
+    let exp = vec![insert_exp(ITALY, FIELD_100_INT)];
+    assert_eq!(out, exp);
+
+    // Insert NULL for segment Italy. NULL sorts after every value in the derived
+    // `Field` ordering, but it must not become the maximum.
+    /*
+        Italy, 100
+        Italy, NULL
+        -------------
+        MAX = 100
+    */
+    let inp = insert_field(ITALY, &Field::Null);
+    let out = output!(processor, inp);
+    let exp = vec![update_exp(ITALY, ITALY, FIELD_100_INT, FIELD_100_INT)];
+    assert_eq!(out, exp);
+}
@@ -0,0 +1,152 @@
+use crate::pipeline::aggregation::processor::AggregationProcessor;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_100_INT, FIELD_1_INT, FIELD_2_INT, FIELD_3_INT, ITALY,
+    SINGAPORE,
+};
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{Field, FieldType, NullOrdering, Operation, Record};
+use std::collections::HashMap;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+const GERMANY: &str = "Germany";
+
+fn init_sorted_processor() -> AggregationProcessor {
+    let schema = init_input_schema(FieldType::Int, "COUNT");
+    init_processor(
+        "SELECT Country, COUNT(Salary) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap()
+    .with_sorted_output()
+}
+
+/// Processes `ops`, then flushes as on commit, returning what the processor sent.
+fn process_transaction(
+    processor: &mut AggregationProcessor,
+    ops: Vec<Operation>,
+) -> Vec<Operation> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    for op in ops {
+        processor.process(DEFAULT_PORT_HANDLE, op, &mut fw).unwrap();
+    }
+    assert!(fw.operations.is_empty(), "output must be held until flush");
+    processor.flush(&mut fw).unwrap();
+    fw.operations
+}
+
+#[test]
+fn test_sorted_output_emits_groups_in_key_order() {
+    let mut processor = init_sorted_processor();
+    let out = process_transaction(
+        &mut processor,
+        vec![
+            insert_field(SINGAPORE, FIELD_100_INT),
+            insert_field(ITALY, FIELD_100_INT),
+            insert_field(GERMANY, FIELD_100_INT),
+            insert_field(ITALY, FIELD_100_INT),
+        ],
+    );
+    assert_eq!(
+        out,
+        vec![
+            insert_exp(GERMANY, FIELD_1_INT),
+            insert_exp(ITALY, FIELD_2_INT),
+            insert_exp(SINGAPORE, FIELD_1_INT),
+        ]
+    );
+
+    // Changes are netted per group, and groups left as they were emit nothing.
+    let out = process_transaction(
+        &mut processor,
+        vec![
+            insert_field(ITALY, FIELD_100_INT),
+            delete_field(SINGAPORE, FIELD_100_INT),
+            update_field(GERMANY, SINGAPORE, FIELD_100_INT, FIELD_100_INT),
+            update_field(SINGAPORE, GERMANY, FIELD_100_INT, FIELD_100_INT),
+        ],
+    );
+    assert_eq!(
+        out,
+        vec![
+            update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_3_INT),
+            delete_exp(SINGAPORE, FIELD_1_INT),
+        ]
+    );
+}
+
+#[test]
+fn test_sorted_output_does_not_depend_on_input_order() {
+    let ops = vec![
+        insert_field(SINGAPORE, FIELD_100_INT),
+        insert_field(ITALY, FIELD_100_INT),
+        insert_field(GERMANY, FIELD_100_INT),
+    ];
+    let mut reversed = ops.clone();
+    reversed.reverse();
+
+    assert_eq!(
+        process_transaction(&mut init_sorted_processor(), ops),
+        process_transaction(&mut init_sorted_processor(), reversed)
+    );
+}
+
+fn insert_null_country() -> Operation {
+    Operation::Insert {
+        new: Record::new(
+            None,
+            vec![
+                Field::Int(0),
+                Field::Null,
+                FIELD_100_INT.clone(),
+                FIELD_100_INT.clone(),
+            ],
+        ),
+    }
+}
+
+#[test]
+fn test_sorted_output_places_nulls_as_ordered() {
+    let ops = vec![
+        insert_field(ITALY, FIELD_100_INT),
+        insert_null_country(),
+        insert_field(GERMANY, FIELD_100_INT),
+    ];
+    let null_group = Operation::Insert {
+        new: Record::new(None, vec![Field::Null, FIELD_1_INT.clone()]),
+    };
+
+    let out = process_transaction(&mut init_sorted_processor(), ops.clone());
+    assert_eq!(
+        out,
+        vec![
+            insert_exp(GERMANY, FIELD_1_INT),
+            insert_exp(ITALY, FIELD_1_INT),
+            null_group.clone(),
+        ]
+    );
+
+    let mut processor = init_sorted_processor().with_null_ordering(vec![NullOrdering::First]);
+    let out = process_transaction(&mut processor, ops);
+    assert_eq!(
+        out,
+        vec![
+            null_group,
+            insert_exp(GERMANY, FIELD_1_INT),
+            insert_exp(ITALY, FIELD_1_INT),
+        ]
+    );
+}
@@ -1,4 +1,6 @@
 use crate::output;
+use crate::pipeline::aggregation::aggregator::NanPolicy;
+use crate::pipeline::aggregation::processor::AggregationProcessor;
 use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
     delete_exp, delete_field, get_decimal_field, get_duration_field, init_input_schema,
     init_processor, insert_exp, insert_field, update_exp, update_field, FIELD_0_FLOAT, FIELD_0_INT,
@@ -7,8 +9,12 @@ use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
     FIELD_350_FLOAT, FIELD_350_INT, FIELD_350_UINT, FIELD_50_FLOAT, FIELD_50_INT, FIELD_50_UINT,
     FIELD_NULL, ITALY, SINGAPORE,
 };
+use crate::pipeline::errors::{OperationError, PipelineError, SqlError};
+use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::tests::utils::get_select;
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_types::types::FieldType::{Decimal, Duration, Float, Int, UInt};
+use dozer_types::types::{Field, FieldType, Schema};
 use std::collections::HashMap;
 
 #[test]
@@ -947,3 +953,162 @@ fn test_sum_aggregation_duration_null() {
     exp = vec![delete_exp(ITALY, &get_duration_field(0))];
     assert_eq!(out, exp);
 }
+
+fn init_promoting_processor(sql: &str, schema: Schema) -> AggregationProcessor {
+    let mut planner = CommonPlanner::new(schema.clone()).with_sum_promote_on_overflow(true);
+    planner.plan(*get_select(sql).unwrap()).unwrap();
+    assert_eq!(
+        planner.post_projection_schema.fields[1].typ,
+        FieldType::Decimal
+    );
+
+    AggregationProcessor::new(
+        planner.groupby,
+        planner.aggregation_output,
+        planner.projection_output,
+        planner.having,
+        schema,
+        planner.post_aggregation_schema,
+    )
+    .unwrap()
+    .with_sum_promote_on_overflow()
+}
+
+#[test]
+fn test_sum_aggregation_int_overflow_is_an_error() {
+    let schema = init_input_schema(Int, "SUM");
+    let mut processor = init_processor(
+        "SELECT Country, SUM(Salary) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let out = output!(processor, insert_field(ITALY, &Field::Int(i64::MAX)));
+    assert_eq!(out, vec![insert_exp(ITALY, &Field::Int(i64::MAX))]);
+
+    let result = processor.aggregate(insert_field(ITALY, &Field::Int(1)));
+    assert!(matches!(
+        result,
+        Err(PipelineError::SqlError(SqlError::Operation(
+            OperationError::AdditionOverflow
+        )))
+    ));
+}
+
+#[test]
+fn test_sum_aggregation_int_promotes_to_decimal_past_overflow() {
+    let schema = init_input_schema(Int, "SUM");
+    let mut processor = init_promoting_processor(
+        "SELECT Country, SUM(Salary) FROM Users GROUP BY Country",
+        schema,
+    );
+
+    let max = dozer_types::rust_decimal::Decimal::from(i64::MAX);
+    let out = output!(processor, insert_field(ITALY, &Field::Int(i64::MAX)));
+    assert_eq!(out, vec![insert_exp(ITALY, &Field::Decimal(max))]);
+
+    let out = output!(processor, insert_field(ITALY, &Field::Int(i64::MAX)));
+    let sum = max + max;
+    assert_eq!(
+        out,
+        vec![update_exp(
+            ITALY,
+            ITALY,
+            &Field::Decimal(max),
+            &Field::Decimal(sum)
+        )]
+    );
+
+    // Deleting brings the sum back into the `Int` range, still as a `Decimal`.
+    let out = output!(processor, delete_field(ITALY, &Field::Int(i64::MAX)));
+    assert_eq!(
+        out,
+        vec![update_exp(
+            ITALY,
+            ITALY,
+            &Field::Decimal(sum),
+            &Field::Decimal(max)
+        )]
+    );
+}
+
+#[test]
+fn test_sum_nan_policy() {
+    let infinity = &Field::Float(f64::INFINITY.into());
+    let neg_infinity = &Field::Float(f64::NEG_INFINITY.into());
+    let sum_nan = |policy| {
+        let schema = init_input_schema(Float, "SUM");
+        let mut processor = init_processor(
+            "SELECT Country, SUM(Salary) \
+            FROM Users \
+            GROUP BY Country",
+            HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+        )
+        .unwrap()
+        .with_nan_policy(policy);
+        output!(processor, insert_field(ITALY, infinity));
+        processor.aggregate(insert_field(ITALY, neg_infinity))
+    };
+
+    // +inf + -inf is NaN.
+    assert_eq!(
+        sum_nan(NanPolicy::default()).unwrap(),
+        vec![update_exp(
+            ITALY,
+            ITALY,
+            infinity,
+            &Field::Float(f64::NAN.into())
+        )]
+    );
+    assert_eq!(
+        sum_nan(NanPolicy::Zero).unwrap(),
+        vec![update_exp(ITALY, ITALY, infinity, FIELD_0_FLOAT)]
+    );
+    assert_eq!(
+        sum_nan(NanPolicy::Null).unwrap(),
+        vec![update_exp(ITALY, ITALY, infinity, FIELD_NULL)]
+    );
+    assert!(matches!(
+        sum_nan(NanPolicy::Error),
+        Err(PipelineError::NanResult(aggregator)) if aggregator == "sum"
+    ));
+}
+
+#[test]
+fn test_sum_nan_error_keeps_the_group_state() {
+    let infinity = &Field::Float(f64::INFINITY.into());
+    let neg_infinity = &Field::Float(f64::NEG_INFINITY.into());
+    let schema = init_input_schema(Float, "SUM");
+    let mut processor = init_processor(
+        "SELECT Country, SUM(Salary) \
+        FROM Users \
+        GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap()
+    .with_nan_policy(NanPolicy::Error);
+
+    assert_eq!(
+        output!(processor, insert_field(ITALY, infinity)),
+        vec![insert_exp(ITALY, infinity)]
+    );
+    // The failed insert still counts in the group, whose row stays the one last sent.
+    assert!(matches!(
+        processor.aggregate(insert_field(ITALY, neg_infinity)),
+        Err(PipelineError::NanResult(_))
+    ));
+    assert_eq!(
+        output!(processor, insert_field(SINGAPORE, FIELD_100_FLOAT)),
+        vec![insert_exp(SINGAPORE, FIELD_100_FLOAT)]
+    );
+    assert!(matches!(
+        processor.aggregate(delete_field(ITALY, infinity)),
+        Err(PipelineError::NanResult(_))
+    ));
+    // Deleting the last record of the group deletes the row sent for it.
+    assert_eq!(
+        output!(processor, delete_field(ITALY, neg_infinity)),
+        vec![delete_exp(ITALY, infinity)]
+    );
+    assert_eq!(processor.groups_count(), 1);
+}
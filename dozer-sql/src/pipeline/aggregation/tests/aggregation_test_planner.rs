@@ -1,9 +1,16 @@
-use crate::pipeline::aggregation::processor::AggregationProcessor;
+use crate::pipeline::aggregation::processor::{AggregationProcessor, AggregationStrategy};
+use crate::pipeline::builder::DozerDialect;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use crate::pipeline::planner::projection::CommonPlanner;
-use crate::pipeline::tests::utils::get_select;
+use crate::pipeline::tests::utils::{get_query_select, get_select};
+use dozer_types::chrono::DateTime;
 use dozer_types::types::{
     Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
 };
+use sqlparser::ast::Statement;
+use sqlparser::parser::Parser;
 
 #[test]
 fn test_planner_with_aggregator() {
@@ -116,3 +123,412 @@ fn test_planner_with_aggregator() {
         })
         .unwrap();
 }
+
+fn plan_strategy(sql: &str) -> AggregationStrategy {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "city".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "country".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "adults_count".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let statement = Parser::parse_sql(&DozerDialect, sql).unwrap().remove(0);
+    let Statement::Query(query) = statement else {
+        panic!("Expected a query");
+    };
+
+    let mut planner = CommonPlanner::new(schema);
+    planner.plan(*get_query_select(&query)).unwrap();
+    planner.plan_order_by(&query.order_by).unwrap();
+    planner.aggregation_strategy
+}
+
+#[test]
+fn test_planner_selects_aggregation_strategy() {
+    let sorted = [
+        "SELECT country, SUM(adults_count) FROM t GROUP BY country ORDER BY country",
+        "SELECT country, city, SUM(adults_count) FROM t GROUP BY country, city ORDER BY country ASC",
+        "SELECT country AS c, SUM(adults_count) FROM t GROUP BY c ORDER BY c",
+        "SELECT country, SUM(adults_count) FROM t GROUP BY country ORDER BY country NULLS LAST",
+    ];
+    for sql in sorted {
+        assert_eq!(plan_strategy(sql), AggregationStrategy::Sorted, "{sql}");
+    }
+
+    let hash = [
+        "SELECT country, SUM(adults_count) FROM t GROUP BY country",
+        "SELECT country, SUM(adults_count) FROM t GROUP BY country ORDER BY country DESC",
+        "SELECT country, SUM(adults_count) FROM t GROUP BY country ORDER BY SUM(adults_count)",
+        "SELECT country, city, SUM(adults_count) FROM t GROUP BY country, city ORDER BY city",
+        "SELECT country, SUM(adults_count) FROM t GROUP BY country ORDER BY country, city",
+        "SELECT country, SUM(adults_count) FROM t GROUP BY ROLLUP(country) ORDER BY country",
+        "SELECT country, SUM(adults_count) FROM t GROUP BY country ORDER BY country NULLS FIRST",
+    ];
+    for sql in hash {
+        assert_eq!(plan_strategy(sql), AggregationStrategy::Hash, "{sql}");
+    }
+}
+
+#[test]
+fn test_sorted_aggregation_matches_hash_aggregation() {
+    let sql = "SELECT country, SUM(adults_count) FROM t GROUP BY country";
+    let run = |strategy| {
+        let schema = Schema::empty()
+            .field(
+                FieldDefinition::new(
+                    "country".to_string(),
+                    FieldType::String,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .field(
+                FieldDefinition::new(
+                    "adults_count".to_string(),
+                    FieldType::Int,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone();
+        let mut planner = CommonPlanner::new(schema.clone());
+        planner.plan(*get_select(sql).unwrap()).unwrap();
+        let mut processor = AggregationProcessor::new(
+            planner.groupby,
+            planner.aggregation_output,
+            planner.projection_output,
+            planner.having,
+            schema,
+            planner.post_aggregation_schema,
+        )
+        .unwrap()
+        .with_strategy(strategy);
+
+        let record = |country: &str, count| {
+            Record::new(
+                None,
+                vec![Field::String(country.to_string()), Field::Int(count)],
+            )
+        };
+        let ops = vec![
+            Operation::Insert {
+                new: record("Italy", 2),
+            },
+            Operation::Insert {
+                new: record("Singapore", 3),
+            },
+            Operation::Update {
+                old: record("Italy", 2),
+                new: record("Singapore", 4),
+            },
+            Operation::Delete {
+                old: record("Singapore", 3),
+            },
+        ];
+        let output = ops
+            .into_iter()
+            .map(|op| processor.aggregate(op).unwrap())
+            .collect::<Vec<_>>();
+        (output, processor.groups_count())
+    };
+
+    assert_eq!(
+        run(AggregationStrategy::Sorted),
+        run(AggregationStrategy::Hash)
+    );
+}
+
+fn rollup_schema() -> Schema {
+    let field = |name: &str, typ| {
+        FieldDefinition::new(name.to_string(), typ, false, SourceDefinition::Dynamic)
+    };
+    Schema::empty()
+        .field(field("country", FieldType::String), false)
+        .field(field("city", FieldType::String), false)
+        .field(field("adults_count", FieldType::Int), false)
+        .clone()
+}
+
+#[test]
+fn test_planner_expands_grouping_sets() {
+    let plan = |sql| {
+        let mut planner = CommonPlanner::new(rollup_schema());
+        planner.plan(*get_select(sql).unwrap()).unwrap();
+        (planner.groupby.len(), planner.grouping_sets)
+    };
+
+    assert_eq!(
+        plan("SELECT SUM(adults_count) FROM t GROUP BY ROLLUP(country, city)"),
+        (2, vec![vec![0, 1], vec![0], vec![]])
+    );
+    assert_eq!(
+        plan("SELECT SUM(adults_count) FROM t GROUP BY CUBE(country, city)"),
+        (2, vec![vec![0, 1], vec![0], vec![1], vec![]])
+    );
+    assert_eq!(
+        plan("SELECT SUM(adults_count) FROM t GROUP BY GROUPING SETS ((country), (city, country))"),
+        (2, vec![vec![0], vec![0, 1]])
+    );
+    assert_eq!(
+        plan("SELECT SUM(adults_count) FROM t GROUP BY country, ROLLUP(city)"),
+        (2, vec![vec![0, 1], vec![0]])
+    );
+    assert_eq!(
+        plan("SELECT SUM(adults_count) FROM t GROUP BY country, city"),
+        (2, vec![])
+    );
+}
+
+#[test]
+fn test_rollup_aggregates_every_grouping_set() {
+    let sql = "SELECT country, city, SUM(adults_count), GROUPING(country, city) \
+        FROM t GROUP BY ROLLUP(country, city)";
+    let mut planner = CommonPlanner::new(rollup_schema());
+    planner.plan(*get_select(sql).unwrap()).unwrap();
+    let fields = &planner.post_projection_schema.fields;
+    assert!(fields[0].nullable && fields[1].nullable);
+    assert!(!fields[3].nullable);
+    assert!(planner.post_projection_schema.primary_index.is_empty());
+
+    let mut processor = AggregationProcessor::new(
+        planner.groupby,
+        planner.aggregation_output,
+        planner.projection_output,
+        planner.having,
+        rollup_schema(),
+        planner.post_aggregation_schema,
+    )
+    .unwrap()
+    .with_grouping_sets(planner.grouping_sets)
+    .unwrap();
+
+    let record = |country: &str, city: &str, count| {
+        Record::new(
+            None,
+            vec![
+                Field::String(country.to_string()),
+                Field::String(city.to_string()),
+                Field::Int(count),
+            ],
+        )
+    };
+    let row = |country: Option<&str>, city: Option<&str>, sum, grouping| {
+        let string =
+            |value: Option<&str>| value.map_or(Field::Null, |v| Field::String(v.to_string()));
+        Record::new(
+            None,
+            vec![
+                string(country),
+                string(city),
+                Field::Int(sum),
+                Field::UInt(grouping),
+            ],
+        )
+    };
+
+    assert_eq!(
+        processor
+            .aggregate(Operation::Insert {
+                new: record("Italy", "Rome", 2),
+            })
+            .unwrap(),
+        vec![
+            Operation::Insert {
+                new: row(Some("Italy"), Some("Rome"), 2, 0),
+            },
+            Operation::Insert {
+                new: row(Some("Italy"), None, 2, 1),
+            },
+            Operation::Insert {
+                new: row(None, None, 2, 3),
+            },
+        ]
+    );
+    assert_eq!(
+        processor
+            .aggregate(Operation::Insert {
+                new: record("Italy", "Milan", 3),
+            })
+            .unwrap(),
+        vec![
+            Operation::Insert {
+                new: row(Some("Italy"), Some("Milan"), 3, 0),
+            },
+            Operation::Update {
+                old: row(Some("Italy"), None, 2, 1),
+                new: row(Some("Italy"), None, 5, 1),
+            },
+            Operation::Update {
+                old: row(None, None, 2, 3),
+                new: row(None, None, 5, 3),
+            },
+        ]
+    );
+    assert_eq!(processor.groups_count(), 4);
+}
+
+#[test]
+fn test_max_groups_applies_to_all_grouping_sets() {
+    let sql = "SELECT country, city, SUM(adults_count) FROM t GROUP BY ROLLUP(country, city)";
+    let mut planner = CommonPlanner::new(rollup_schema());
+    planner.plan(*get_select(sql).unwrap()).unwrap();
+    let mut processor = AggregationProcessor::new(
+        planner.groupby,
+        planner.aggregation_output,
+        planner.projection_output,
+        planner.having,
+        rollup_schema(),
+        planner.post_aggregation_schema,
+    )
+    .unwrap()
+    .with_grouping_sets(planner.grouping_sets)
+    .unwrap()
+    .with_max_groups(4);
+
+    let insert = |country: &str, city: &str| Operation::Insert {
+        new: Record::new(
+            None,
+            vec![
+                Field::String(country.to_string()),
+                Field::String(city.to_string()),
+                Field::Int(1),
+            ],
+        ),
+    };
+
+    // (Italy, Rome), (Italy) and ().
+    processor.aggregate(insert("Italy", "Rome")).unwrap();
+    assert_eq!(processor.groups_count(), 3);
+
+    // (Spain, Madrid) would fit, but not (Spain) with it, so no grouping set takes the record.
+    assert!(matches!(
+        processor.aggregate(insert("Spain", "Madrid")),
+        Err(PipelineError::StateLimitExceeded(4))
+    ));
+    assert_eq!(processor.groups_count(), 3);
+
+    // Only (Italy, Milan) is new.
+    assert_eq!(
+        processor.aggregate(insert("Italy", "Milan")).unwrap().len(),
+        3
+    );
+    assert_eq!(processor.groups_count(), 4);
+}
+
+#[test]
+fn test_grouping_requires_group_by_arguments() {
+    let mut planner = CommonPlanner::new(rollup_schema());
+    let select = get_select("SELECT country, GROUPING(city) FROM t GROUP BY country").unwrap();
+    assert!(matches!(
+        planner.plan(*select),
+        Err(PipelineError::InvalidQuery(_))
+    ));
+}
+
+fn names_schema() -> Schema {
+    let field = |name: &str, typ| {
+        FieldDefinition::new(name.to_string(), typ, false, SourceDefinition::Dynamic)
+    };
+    Schema::empty()
+        .field(field("name", FieldType::String), false)
+        .field(field("ts", FieldType::Timestamp), false)
+        .field(field("value", FieldType::Int), false)
+        .clone()
+}
+
+#[test]
+fn test_planner_stores_function_group_keys() {
+    let sql = "SELECT LOWER(name), COUNT(value) FROM t GROUP BY LOWER(name), EXTRACT(day FROM ts)";
+    let mut planner = CommonPlanner::new(names_schema());
+    planner.plan(*get_select(sql).unwrap()).unwrap();
+
+    assert_eq!(planner.groupby.len(), 2);
+    assert_eq!(
+        planner.groupby[0],
+        Expression::ScalarFunction {
+            fun: ScalarFunctionType::Lower,
+            args: vec![Expression::Column { index: 0 }],
+        }
+    );
+    assert!(matches!(
+        &planner.groupby[1],
+        Expression::DateTimeFunction { arg, .. } if **arg == Expression::Column { index: 1 }
+    ));
+}
+
+#[test]
+fn test_group_by_function_merges_equal_keys() {
+    let sql = "SELECT LOWER(name), COUNT(value) FROM t GROUP BY LOWER(name)";
+    let mut planner = CommonPlanner::new(names_schema());
+    planner.plan(*get_select(sql).unwrap()).unwrap();
+    let mut processor = AggregationProcessor::new(
+        planner.groupby,
+        planner.aggregation_output,
+        planner.projection_output,
+        planner.having,
+        names_schema(),
+        planner.post_aggregation_schema,
+    )
+    .unwrap();
+
+    let insert = |name: &str| Operation::Insert {
+        new: Record::new(
+            None,
+            vec![
+                Field::String(name.to_string()),
+                Field::Timestamp(DateTime::parse_from_rfc3339("2023-01-02T00:00:00Z").unwrap()),
+                Field::Int(1),
+            ],
+        ),
+    };
+    let row = |name: &str, count| {
+        Record::new(
+            None,
+            vec![Field::String(name.to_string()), Field::Int(count)],
+        )
+    };
+
+    assert_eq!(
+        processor.aggregate(insert("Alice")).unwrap(),
+        vec![Operation::Insert {
+            new: row("alice", 1)
+        }]
+    );
+    assert_eq!(
+        processor.aggregate(insert("ALICE")).unwrap(),
+        vec![Operation::Update {
+            old: row("alice", 1),
+            new: row("alice", 2),
+        }]
+    );
+    assert_eq!(
+        processor.aggregate(insert("Bob")).unwrap(),
+        vec![Operation::Insert { new: row("bob", 1) }]
+    );
+    assert_eq!(processor.groups_count(), 2);
+}
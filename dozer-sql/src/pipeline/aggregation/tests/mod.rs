@@ -1,8 +1,16 @@
 #[cfg(test)]
+mod aggregation_approx_count_distinct_tests;
+#[cfg(test)]
 mod aggregation_avg_tests;
 #[cfg(test)]
+mod aggregation_batch_tests;
+#[cfg(test)]
+mod aggregation_churn_tests;
+#[cfg(test)]
 mod aggregation_count_tests;
 #[cfg(test)]
+mod aggregation_custom_tests;
+#[cfg(test)]
 mod aggregation_having_tests;
 #[cfg(test)]
 mod aggregation_max_tests;
@@ -11,6 +19,8 @@ mod aggregation_min_tests;
 #[cfg(test)]
 mod aggregation_null;
 #[cfg(test)]
+mod aggregation_sorted_output_tests;
+#[cfg(test)]
 mod aggregation_sum_tests;
 #[cfg(test)]
 mod aggregation_test_planner;
@@ -1,7 +1,14 @@
+use crate::pipeline::aggregation::aggregator::NanPolicy;
 use crate::pipeline::aggregation::factory::AggregationProcessorFactory;
 use crate::pipeline::builder::PipelineError::InvalidQuery;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::builder::{ExpressionBuilder, NameOrAlias};
+use crate::pipeline::planner::having::having_over_output;
+use crate::pipeline::planner::projection::AggregateNaming;
+use crate::pipeline::planner::subquery::extract_scalar_subqueries;
+use crate::pipeline::scalar_subquery::factory::{
+    ScalarSubqueryProcessorFactory, OUTER_PORT, SUBQUERY_PORT,
+};
 use crate::pipeline::selection::factory::SelectionProcessorFactory;
 use dozer_core::app::AppPipeline;
 use dozer_core::app::PipelineEntryPoint;
@@ -12,7 +19,7 @@ use sqlparser::ast::{Join, SetOperator, SetQuantifier, TableFactor, TableWithJoi
 
 use sqlparser::{
     ast::{Query, Select, SetExpr, Statement},
-    dialect::AnsiDialect,
+    dialect::{AnsiDialect, Dialect},
     parser::Parser,
 };
 use std::collections::HashMap;
@@ -26,6 +33,24 @@ use super::product::set::set_factory::SetProcessorFactory;
 #[derive(Debug, Clone, Default)]
 pub struct SchemaSQLContext {}
 
+/// The SQL dialect of queries: ANSI SQL, with `ROLLUP`, `CUBE` and `GROUPING SETS` in `GROUP BY`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DozerDialect;
+
+impl Dialect for DozerDialect {
+    fn is_identifier_start(&self, ch: char) -> bool {
+        AnsiDialect {}.is_identifier_start(ch)
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        AnsiDialect {}.is_identifier_part(ch)
+    }
+
+    fn supports_group_by_expr(&self) -> bool {
+        true
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OutputNodeInfo {
     // Name to connect in dag
@@ -53,6 +78,30 @@ pub struct QueryContext {
 
     // Used Sources
     pub used_sources: Vec<String>,
+
+    // Options the queries are planned with
+    pub options: PlannerOptions,
+}
+
+/// Options of the SQL planner.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlannerOptions {
+    /// Plan `SUM` of integers as a `Decimal`, so that sums past the range of the integer types
+    /// are exact instead of failing with an overflow error.
+    pub sum_promote_on_overflow: bool,
+    /// Emit the output of aggregations at every commit, ordered by group key, so that it is
+    /// reproducible. Otherwise changes are emitted as they happen.
+    pub sorted_aggregation_output: bool,
+    /// How to name the output fields of aggregations without an `AS` alias.
+    pub aggregate_naming: AggregateNaming,
+    /// Evaluate `HAVING` with a selection processor after the aggregation, when it only reads
+    /// projected columns and aggregations, instead of within the aggregation.
+    pub having_as_filter: bool,
+    /// What aggregations output when their value is a float NaN.
+    pub nan_policy: NanPolicy,
+    /// Caps the number of groups each aggregation holds in memory. Records for new groups past
+    /// the cap fail instead of growing the state without bound.
+    pub max_aggregation_groups: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -65,8 +114,21 @@ pub fn statement_to_pipeline(
     pipeline: &mut AppPipeline<SchemaSQLContext>,
     override_name: Option<String>,
 ) -> Result<QueryContext, PipelineError> {
-    let dialect = AnsiDialect {};
-    let mut ctx = QueryContext::default();
+    statement_to_pipeline_with_options(sql, pipeline, override_name, PlannerOptions::default())
+}
+
+/// Like [`statement_to_pipeline`], planning the queries with `options`.
+pub fn statement_to_pipeline_with_options(
+    sql: &str,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    override_name: Option<String>,
+    options: PlannerOptions,
+) -> Result<QueryContext, PipelineError> {
+    let dialect = DozerDialect;
+    let mut ctx = QueryContext {
+        options,
+        ..Default::default()
+    };
 
     let ast = Parser::parse_sql(&dialect, sql)
         .map_err(|err| PipelineError::InternalError(Box::new(err)))?;
@@ -190,21 +252,19 @@ fn query_to_pipeline(
             set_quantifier,
             left,
             right,
-        } => match op {
-            SetOperator::Union => {
-                set_to_pipeline(
-                    table_info,
-                    left,
-                    right,
-                    set_quantifier,
-                    pipeline,
-                    query_ctx,
-                    stateful,
-                    pipeline_idx,
-                )?;
-            }
-            _ => return Err(PipelineError::InvalidOperator(op.to_string())),
-        },
+        } => {
+            set_to_pipeline(
+                table_info,
+                left,
+                right,
+                op,
+                set_quantifier,
+                pipeline,
+                query_ctx,
+                stateful,
+                pipeline_idx,
+            )?;
+        }
         _ => {
             return Err(PipelineError::UnsupportedSqlError(
                 UnsupportedSqlError::GenericError("Unsupported query body structure".to_string()),
@@ -216,7 +276,7 @@ fn query_to_pipeline(
 
 fn select_to_pipeline(
     table_info: &TableInfo,
-    select: Select,
+    mut select: Select,
     pipeline: &mut AppPipeline<SchemaSQLContext>,
     query_ctx: &mut QueryContext,
     stateful: bool,
@@ -238,6 +298,8 @@ fn select_to_pipeline(
     //     pipeline_idx,
     // )?;
 
+    let subqueries = extract_scalar_subqueries(&mut select)?;
+
     let connection_info =
         insert_from_to_pipeline(&select.from[0], pipeline, pipeline_idx, query_ctx)?;
 
@@ -246,7 +308,8 @@ fn select_to_pipeline(
 
     let gen_agg_name = format!("agg_{}", uuid::Uuid::new_v4());
     let gen_selection_name = format!("select_{}", uuid::Uuid::new_v4());
-    let (gen_product_name, product_output_port) = output_node;
+    let gen_having_name = format!("having_{}", uuid::Uuid::new_v4());
+    let (mut gen_product_name, mut product_output_port) = output_node;
 
     for (source_name, processor_name, processor_port) in input_nodes.iter() {
         if let Some(table_info) = query_ctx
@@ -266,7 +329,66 @@ fn select_to_pipeline(
         }
     }
 
-    let aggregation = AggregationProcessorFactory::new(select.clone(), stateful);
+    // Scalar subqueries, whose values are appended to the records of the FROM clause
+    for (column, query) in subqueries {
+        let subquery_name = format!("subquery_{}", uuid::Uuid::new_v4());
+        query_to_pipeline(
+            &TableInfo {
+                name: NameOrAlias(subquery_name.clone(), None),
+                is_derived: true,
+                override_name: None,
+            },
+            &query,
+            pipeline,
+            query_ctx,
+            true,
+            pipeline_idx,
+        )?;
+        let subquery_output = query_ctx
+            .pipeline_map
+            .get(&(pipeline_idx, subquery_name))
+            .cloned()
+            .ok_or_else(|| InvalidQuery("Invalid scalar subquery".to_string()))?;
+
+        let gen_subquery_name = format!("scalar_subquery_{}", uuid::Uuid::new_v4());
+        pipeline.add_processor(
+            Arc::new(ScalarSubqueryProcessorFactory::new(column)),
+            &gen_subquery_name,
+            vec![],
+        );
+        pipeline.connect_nodes(
+            &gen_product_name,
+            Some(product_output_port),
+            &gen_subquery_name,
+            Some(OUTER_PORT),
+            true,
+        )?;
+        pipeline.connect_nodes(
+            &subquery_output.node,
+            Some(subquery_output.port),
+            &gen_subquery_name,
+            Some(SUBQUERY_PORT),
+            true,
+        )?;
+        gen_product_name = gen_subquery_name;
+        product_output_port = DEFAULT_PORT_HANDLE;
+    }
+
+    let having = query_ctx
+        .options
+        .having_as_filter
+        .then(|| having_over_output(&select))
+        .flatten();
+    let mut aggregated = select.clone();
+    if having.is_some() {
+        aggregated.having = None;
+    }
+    let aggregation = AggregationProcessorFactory::new(aggregated, stateful)
+        .with_sum_promote_on_overflow(query_ctx.options.sum_promote_on_overflow)
+        .with_aggregate_naming(query_ctx.options.aggregate_naming)
+        .with_sorted_output(query_ctx.options.sorted_aggregation_output)
+        .with_nan_policy(query_ctx.options.nan_policy)
+        .with_max_groups(query_ctx.options.max_aggregation_groups);
 
     pipeline.add_processor(Arc::new(aggregation), &gen_agg_name, vec![]);
 
@@ -301,10 +423,26 @@ fn select_to_pipeline(
         )?;
     }
 
+    // Having clause, when evaluated over the output of the aggregation
+    let output_name = if let Some(having) = having {
+        let having = SelectionProcessorFactory::new(having);
+        pipeline.add_processor(Arc::new(having), &gen_having_name, vec![]);
+        pipeline.connect_nodes(
+            &gen_agg_name,
+            Some(DEFAULT_PORT_HANDLE),
+            &gen_having_name,
+            Some(DEFAULT_PORT_HANDLE),
+            true,
+        )?;
+        gen_having_name
+    } else {
+        gen_agg_name
+    };
+
     query_ctx.pipeline_map.insert(
         (pipeline_idx, table_info.name.0.to_string()),
         OutputNodeInfo {
-            node: gen_agg_name.clone(),
+            node: output_name.clone(),
             port: DEFAULT_PORT_HANDLE,
             is_derived: table_info.is_derived,
         },
@@ -319,14 +457,14 @@ fn select_to_pipeline(
         query_ctx.output_tables_map.insert(
             table_name,
             OutputNodeInfo {
-                node: gen_agg_name.clone(),
+                node: output_name.clone(),
                 port: DEFAULT_PORT_HANDLE,
                 is_derived: false,
             },
         );
     }
 
-    Ok(gen_agg_name)
+    Ok(output_name)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -334,6 +472,7 @@ fn set_to_pipeline(
     table_info: &TableInfo,
     left_select: Box<SetExpr>,
     right_select: Box<SetExpr>,
+    set_operator: SetOperator,
     set_quantifier: SetQuantifier,
     pipeline: &mut AppPipeline<SchemaSQLContext>,
     query_ctx: &mut QueryContext,
@@ -353,67 +492,22 @@ fn set_to_pipeline(
         is_derived: false,
     };
 
-    let _left_pipeline_name = match *left_select {
-        SetExpr::Select(select) => select_to_pipeline(
-            &left_table_info,
-            *select,
-            pipeline,
-            query_ctx,
-            stateful,
-            pipeline_idx,
-        )?,
-        SetExpr::SetOperation {
-            op: _,
-            set_quantifier,
-            left,
-            right,
-        } => set_to_pipeline(
-            &left_table_info,
-            left,
-            right,
-            set_quantifier,
-            pipeline,
-            query_ctx,
-            stateful,
-            pipeline_idx,
-        )?,
-        _ => {
-            return Err(PipelineError::InvalidQuery(
-                "Invalid UNION left Query".to_string(),
-            ))
-        }
-    };
-
-    let _right_pipeline_name = match *right_select {
-        SetExpr::Select(select) => select_to_pipeline(
-            &right_table_info,
-            *select,
-            pipeline,
-            query_ctx,
-            stateful,
-            pipeline_idx,
-        )?,
-        SetExpr::SetOperation {
-            op: _,
-            set_quantifier,
-            left,
-            right,
-        } => set_to_pipeline(
-            &right_table_info,
-            left,
-            right,
-            set_quantifier,
-            pipeline,
-            query_ctx,
-            stateful,
-            pipeline_idx,
-        )?,
-        _ => {
-            return Err(PipelineError::InvalidQuery(
-                "Invalid UNION right Query".to_string(),
-            ))
-        }
-    };
+    set_input_to_pipeline(
+        &left_table_info,
+        *left_select,
+        pipeline,
+        query_ctx,
+        stateful,
+        pipeline_idx,
+    )?;
+    set_input_to_pipeline(
+        &right_table_info,
+        *right_select,
+        pipeline,
+        query_ctx,
+        stateful,
+        pipeline_idx,
+    )?;
 
     let left_pipeline_output_node = match query_ctx
         .pipeline_map
@@ -422,7 +516,7 @@ fn set_to_pipeline(
         Some(pipeline) => pipeline,
         None => {
             return Err(PipelineError::InvalidQuery(
-                "Invalid UNION left Query".to_string(),
+                "Invalid set operation left Query".to_string(),
             ))
         }
     };
@@ -434,12 +528,12 @@ fn set_to_pipeline(
         Some(pipeline) => pipeline,
         None => {
             return Err(PipelineError::InvalidQuery(
-                "Invalid UNION Right Query".to_string(),
+                "Invalid set operation Right Query".to_string(),
             ))
         }
     };
 
-    let set_proc_fac = SetProcessorFactory::new(set_quantifier);
+    let set_proc_fac = SetProcessorFactory::new(set_operator, set_quantifier);
 
     let mut gen_set_name = format!("set_{}", uuid::Uuid::new_v4());
 
@@ -478,9 +572,77 @@ fn set_to_pipeline(
         },
     );
 
+    if let Some(table_name) = &table_info.override_name {
+        query_ctx.output_tables_map.insert(
+            table_name.clone(),
+            OutputNodeInfo {
+                node: gen_set_name.clone(),
+                port: DEFAULT_PORT_HANDLE,
+                is_derived: false,
+            },
+        );
+    }
+
     Ok(gen_set_name)
 }
 
+/// Plans one of the inputs of a set operation, whose output is registered under `table_info`.
+fn set_input_to_pipeline(
+    table_info: &TableInfo,
+    set_expr: SetExpr,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    query_ctx: &mut QueryContext,
+    stateful: bool,
+    pipeline_idx: usize,
+) -> Result<(), PipelineError> {
+    match set_expr {
+        SetExpr::Select(select) => {
+            select_to_pipeline(
+                table_info,
+                *select,
+                pipeline,
+                query_ctx,
+                stateful,
+                pipeline_idx,
+            )?;
+        }
+        SetExpr::Query(query) => {
+            query_to_pipeline(
+                table_info,
+                &query,
+                pipeline,
+                query_ctx,
+                stateful,
+                pipeline_idx,
+            )?;
+        }
+        SetExpr::SetOperation {
+            op,
+            set_quantifier,
+            left,
+            right,
+        } => {
+            set_to_pipeline(
+                table_info,
+                left,
+                right,
+                op,
+                set_quantifier,
+                pipeline,
+                query_ctx,
+                stateful,
+                pipeline_idx,
+            )?;
+        }
+        _ => {
+            return Err(InvalidQuery(
+                "Invalid set operation input Query".to_string(),
+            ))
+        }
+    }
+    Ok(())
+}
+
 /// Returns a vector of input port handles and relative table name
 ///
 /// # Errors
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use dozer_core::{
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_types::types::Schema;
+
+use super::processor::ChangeCoalesceProcessor;
+
+#[derive(Debug, Default)]
+pub struct ChangeCoalesceProcessorFactory {}
+
+impl ChangeCoalesceProcessorFactory {
+    /// Creates a new [`ChangeCoalesceProcessorFactory`].
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for ChangeCoalesceProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        let (schema, ctx) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        if schema.primary_index.is_empty() {
+            return Err(ExecutionError::FailedToGetPrimaryKey(
+                "change coalesce input".to_string(),
+            ));
+        }
+        Ok((schema.clone(), ctx.clone()))
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+        if schema.primary_index.is_empty() {
+            return Err(ExecutionError::FailedToGetPrimaryKey(
+                "change coalesce input".to_string(),
+            ));
+        }
+        Ok(Box::new(ChangeCoalesceProcessor::new(schema)))
+    }
+}
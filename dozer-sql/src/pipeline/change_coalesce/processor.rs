@@ -0,0 +1,90 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::chrono::{DateTime, FixedOffset};
+use dozer_types::types::{Operation, Record, Schema};
+
+/// Merges a delete immediately followed by an insert of the same primary key into a single
+/// update, to reduce churn into sinks that can apply updates.
+///
+/// A delete is held back until the next operation. A delete that isn't paired is sent as-is before
+/// that operation, or at the next commit boundary.
+#[derive(Debug)]
+pub struct ChangeCoalesceProcessor {
+    primary_index: Vec<usize>,
+    /// The last operation received, if it was a delete, along with its primary key.
+    pending_delete: Option<(Vec<u8>, Record)>,
+}
+
+impl ChangeCoalesceProcessor {
+    pub fn new(input_schema: &Schema) -> Self {
+        debug_assert!(
+            !input_schema.primary_index.is_empty(),
+            "ChangeCoalesceProcessor can only be used with a schema that has a primary key."
+        );
+        Self {
+            primary_index: input_schema.primary_index.clone(),
+            pending_delete: None,
+        }
+    }
+
+    fn send_pending_delete(
+        &mut self,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        match self.pending_delete.take() {
+            Some((_, old)) => fw.send(Operation::Delete { old }, DEFAULT_PORT_HANDLE),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Processor for ChangeCoalesceProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        match op {
+            Operation::Delete { old } => {
+                self.send_pending_delete(fw)?;
+                let key = old.get_key(&self.primary_index);
+                self.pending_delete = Some((key, old));
+                Ok(())
+            }
+            Operation::Insert { new } => match self.pending_delete.take() {
+                Some((key, old)) if key == new.get_key(&self.primary_index) => {
+                    fw.send(Operation::Update { old, new }, DEFAULT_PORT_HANDLE)
+                }
+                pending_delete => {
+                    self.pending_delete = pending_delete;
+                    self.send_pending_delete(fw)?;
+                    fw.send(Operation::Insert { new }, DEFAULT_PORT_HANDLE)
+                }
+            },
+            op => {
+                self.send_pending_delete(fw)?;
+                fw.send(op, DEFAULT_PORT_HANDLE)
+            }
+        }
+    }
+
+    fn on_watermark(
+        &mut self,
+        _timestamp: DateTime<FixedOffset>,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        self.send_pending_delete(fw)
+    }
+
+    fn flush(&mut self, fw: &mut dyn ProcessorChannelForwarder) -> Result<(), ExecutionError> {
+        self.send_pending_delete(fw)
+    }
+}
@@ -0,0 +1,131 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+
+use crate::pipeline::change_coalesce::processor::ChangeCoalesceProcessor;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+fn record(id: i64, name: &str) -> Record {
+    Record::new(None, vec![Field::Int(id), Field::String(name.to_string())])
+}
+
+fn process(processor: &mut ChangeCoalesceProcessor, ops: Vec<Operation>) -> Vec<Operation> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    for op in ops {
+        processor.process(DEFAULT_PORT_HANDLE, op, &mut fw).unwrap();
+    }
+    fw.operations
+}
+
+#[test]
+fn test_delete_then_insert_of_same_key_becomes_update() {
+    let mut processor = ChangeCoalesceProcessor::new(&get_schema());
+
+    let out = process(
+        &mut processor,
+        vec![
+            Operation::Delete {
+                old: record(1, "a"),
+            },
+            Operation::Insert {
+                new: record(1, "b"),
+            },
+        ],
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Update {
+            old: record(1, "a"),
+            new: record(1, "b")
+        }]
+    );
+
+    // Nothing is left to flush.
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor.flush(&mut fw).unwrap();
+    assert_eq!(fw.operations, vec![]);
+}
+
+#[test]
+fn test_unmatched_delete_is_sent_as_is() {
+    let mut processor = ChangeCoalesceProcessor::new(&get_schema());
+
+    // Followed by an insert of another key.
+    let out = process(
+        &mut processor,
+        vec![
+            Operation::Delete {
+                old: record(1, "a"),
+            },
+            Operation::Insert {
+                new: record(2, "a"),
+            },
+        ],
+    );
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: record(1, "a")
+            },
+            Operation::Insert {
+                new: record(2, "a")
+            }
+        ]
+    );
+
+    // Held back until the commit boundary.
+    let out = process(
+        &mut processor,
+        vec![Operation::Delete {
+            old: record(2, "a"),
+        }],
+    );
+    assert_eq!(out, vec![]);
+
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor.flush(&mut fw).unwrap();
+    assert_eq!(
+        fw.operations,
+        vec![Operation::Delete {
+            old: record(2, "a")
+        }]
+    );
+}
@@ -0,0 +1,3 @@
+pub mod factory;
+mod processor;
+mod tests;
@@ -0,0 +1,143 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{Operation, Record, Schema};
+use hashbrown::HashMap;
+
+use crate::pipeline::state_ttl::{StateTtl, TtlTracker};
+
+/// Drops repeated inserts from a change stream, using the primary key of the input schema.
+///
+/// An insert for a key that is already present is dropped when it carries the same values, and
+/// forwarded as an update of the stored record otherwise. Deleting a key forgets it, so a later
+/// insert for the same key goes through again.
+///
+/// With a TTL, records that have expired are forgotten at the next commit, and a delete is sent
+/// for each of them.
+#[derive(Debug)]
+pub struct DedupProcessor {
+    primary_index: Vec<usize>,
+    records: HashMap<Vec<u8>, Record>,
+    ttl: Option<TtlTracker<Vec<u8>>>,
+}
+
+impl DedupProcessor {
+    pub fn new(input_schema: &Schema) -> Self {
+        debug_assert!(
+            !input_schema.primary_index.is_empty(),
+            "DedupProcessor can only be used with a schema that has a primary key."
+        );
+        Self {
+            primary_index: input_schema.primary_index.clone(),
+            records: HashMap::new(),
+            ttl: None,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: StateTtl) -> Self {
+        self.ttl = Some(TtlTracker::new(ttl));
+        self
+    }
+
+    /// Number of records currently held.
+    pub fn records_count(&self) -> usize {
+        self.records.len()
+    }
+
+    fn store(&mut self, key: Vec<u8>, record: Record) -> Option<Record> {
+        if let Some(ttl) = &mut self.ttl {
+            ttl.track(key.clone(), &record);
+        }
+        self.records.insert(key, record)
+    }
+
+    fn evict_expired(
+        &mut self,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        let Some(ttl) = &mut self.ttl else {
+            return Ok(());
+        };
+        for key in ttl.take_expired() {
+            if let Some(old) = self.records.get(&key) {
+                if ttl.is_expired(old) {
+                    let old = self.records.remove(&key).expect("record was just found");
+                    fw.send(Operation::Delete { old }, DEFAULT_PORT_HANDLE)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, new: Record) -> Option<Operation> {
+        let key = new.get_key(&self.primary_index);
+        match self.store(key, new.clone()) {
+            None => Some(Operation::Insert { new }),
+            Some(old) if old.values == new.values => None,
+            Some(old) => Some(Operation::Update { old, new }),
+        }
+    }
+
+    fn delete(&mut self, old: &Record) -> Option<Operation> {
+        let key = old.get_key(&self.primary_index);
+        self.records
+            .remove(&key)
+            .map(|old| Operation::Delete { old })
+    }
+
+    fn update(&mut self, old: &Record, new: Record) -> Vec<Operation> {
+        let old_key = old.get_key(&self.primary_index);
+        let new_key = new.get_key(&self.primary_index);
+
+        match self.records.remove(&old_key) {
+            // The updated record was never seen, so this is the first time we see the new one.
+            None => self.insert(new).into_iter().collect(),
+            Some(old) if old.values == new.values => {
+                self.store(new_key, new);
+                vec![]
+            }
+            Some(old) if old_key == new_key || !self.records.contains_key(&new_key) => {
+                self.store(new_key, new.clone());
+                vec![Operation::Update { old, new }]
+            }
+            // The new key collides with another record: drop the old one and dedup the new one.
+            Some(old) => std::iter::once(Operation::Delete { old })
+                .chain(self.insert(new))
+                .collect(),
+        }
+    }
+}
+
+impl Processor for DedupProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        let ops = match op {
+            Operation::Insert { new } => self.insert(new).into_iter().collect(),
+            Operation::Delete { old } => self.delete(&old).into_iter().collect(),
+            Operation::Update { old, new } => self.update(&old, new),
+        };
+
+        for op in ops {
+            fw.send(op, DEFAULT_PORT_HANDLE)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, fw: &mut dyn ProcessorChannelForwarder) -> Result<(), ExecutionError> {
+        self.evict_expired(fw)
+    }
+
+    fn state_size(&self) -> Option<usize> {
+        Some(self.records_count())
+    }
+}
@@ -0,0 +1,311 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+
+use crate::pipeline::dedup::processor::DedupProcessor;
+use crate::pipeline::state_ttl::StateTtl;
+use dozer_types::chrono::DateTime;
+use std::time::Duration;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+fn record(id: i64, name: &str) -> Record {
+    Record::new(None, vec![Field::Int(id), Field::String(name.to_string())])
+}
+
+fn process(processor: &mut DedupProcessor, op: Operation) -> Vec<Operation> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor.process(DEFAULT_PORT_HANDLE, op, &mut fw).unwrap();
+    fw.operations
+}
+
+#[test]
+fn test_duplicate_insert_is_dropped() {
+    let mut processor = DedupProcessor::new(&get_schema());
+
+    let out = process(
+        &mut processor,
+        Operation::Insert {
+            new: record(1, "a"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: record(1, "a")
+        }]
+    );
+
+    let out = process(
+        &mut processor,
+        Operation::Insert {
+            new: record(1, "a"),
+        },
+    );
+    assert_eq!(out, vec![]);
+
+    let out = process(
+        &mut processor,
+        Operation::Insert {
+            new: record(2, "a"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: record(2, "a")
+        }]
+    );
+}
+
+#[test]
+fn test_reinsert_with_different_payload_becomes_update() {
+    let mut processor = DedupProcessor::new(&get_schema());
+
+    process(
+        &mut processor,
+        Operation::Insert {
+            new: record(1, "a"),
+        },
+    );
+    let out = process(
+        &mut processor,
+        Operation::Insert {
+            new: record(1, "b"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Update {
+            old: record(1, "a"),
+            new: record(1, "b")
+        }]
+    );
+
+    // The stored record is now the updated one.
+    let out = process(
+        &mut processor,
+        Operation::Insert {
+            new: record(1, "b"),
+        },
+    );
+    assert_eq!(out, vec![]);
+}
+
+#[test]
+fn test_reinsert_after_delete() {
+    let mut processor = DedupProcessor::new(&get_schema());
+
+    process(
+        &mut processor,
+        Operation::Insert {
+            new: record(1, "a"),
+        },
+    );
+    let out = process(
+        &mut processor,
+        Operation::Delete {
+            old: record(1, "a"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Delete {
+            old: record(1, "a")
+        }]
+    );
+
+    // Deleting an unknown key is a no-op.
+    let out = process(
+        &mut processor,
+        Operation::Delete {
+            old: record(1, "a"),
+        },
+    );
+    assert_eq!(out, vec![]);
+
+    let out = process(
+        &mut processor,
+        Operation::Insert {
+            new: record(1, "a"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: record(1, "a")
+        }]
+    );
+}
+
+#[test]
+fn test_update() {
+    let mut processor = DedupProcessor::new(&get_schema());
+
+    process(
+        &mut processor,
+        Operation::Insert {
+            new: record(1, "a"),
+        },
+    );
+    let out = process(
+        &mut processor,
+        Operation::Update {
+            old: record(1, "a"),
+            new: record(1, "b"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Update {
+            old: record(1, "a"),
+            new: record(1, "b")
+        }]
+    );
+
+    // Updating to the payload already stored is dropped.
+    let out = process(
+        &mut processor,
+        Operation::Update {
+            old: record(1, "b"),
+            new: record(1, "b"),
+        },
+    );
+    assert_eq!(out, vec![]);
+
+    // Updating an unknown record inserts the new one.
+    let out = process(
+        &mut processor,
+        Operation::Update {
+            old: record(2, "a"),
+            new: record(2, "b"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: record(2, "b")
+        }]
+    );
+}
+
+fn timed_record(id: i64, secs: u32) -> Record {
+    let time = DateTime::parse_from_rfc3339(&format!("2023-01-01T00:00:{secs:02}Z")).unwrap();
+    Record::new(None, vec![Field::Int(id), Field::Timestamp(time)])
+}
+
+fn flush(processor: &mut DedupProcessor) -> Vec<Operation> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor.flush(&mut fw).unwrap();
+    fw.operations
+}
+
+#[test]
+fn test_expired_records_are_retracted_at_commit() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "updated_at".to_string(),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+    let mut processor =
+        DedupProcessor::new(&schema).with_ttl(StateTtl::new(1, Duration::from_secs(10)));
+
+    for record in [timed_record(1, 0), timed_record(2, 5)] {
+        process(&mut processor, Operation::Insert { new: record });
+    }
+    assert_eq!(flush(&mut processor), vec![]);
+
+    // Refreshing record 2 keeps it alive past its first timestamp.
+    process(
+        &mut processor,
+        Operation::Insert {
+            new: timed_record(2, 15),
+        },
+    );
+
+    // Time moves past the TTL of record 1.
+    process(
+        &mut processor,
+        Operation::Insert {
+            new: timed_record(3, 20),
+        },
+    );
+    assert_eq!(
+        flush(&mut processor),
+        vec![Operation::Delete {
+            old: timed_record(1, 0)
+        }]
+    );
+    assert_eq!(processor.records_count(), 2);
+
+    // An evicted record is inserted again as if it was never seen.
+    let out = process(
+        &mut processor,
+        Operation::Insert {
+            new: timed_record(1, 0),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: timed_record(1, 0)
+        }]
+    );
+    assert_eq!(
+        flush(&mut processor),
+        vec![Operation::Delete {
+            old: timed_record(1, 0)
+        }]
+    );
+}
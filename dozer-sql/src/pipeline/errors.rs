@@ -35,10 +35,14 @@ pub enum PipelineError {
     InvalidInputType(String),
     #[error("Invalid function: {0}")]
     InvalidFunction(String),
+    #[error("Function {0}() is already defined")]
+    DuplicateFunction(String),
     #[error("Invalid operator: {0}")]
     InvalidOperator(String),
     #[error("Invalid expression: {0}")]
     InvalidExpression(String),
+    #[error("Column index {index} is out of range for {schema_len} columns")]
+    InvalidColumnIndex { index: usize, schema_len: usize },
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
     #[error("Invalid types on {0} and {1} for {2} operand")]
@@ -79,6 +83,18 @@ pub enum PipelineError {
     IllegalFieldIdentifier(String),
     #[error("Unable to cast {0} to {1}")]
     UnableToCast(String, String),
+    #[error("Aggregation state limit exceeded: at most {0} groups can be held in memory")]
+    StateLimitExceeded(usize),
+    #[error(
+        "{0}() does not support deletes or updates and can only aggregate append-only sources"
+    )]
+    UnsupportedRetraction(String),
+    #[error("{0}() returned NaN")]
+    NanResult(String),
+    #[error("A scalar subquery returned more than one row")]
+    ScalarSubqueryMultipleRows,
+    #[error("{construct} not yet supported: {snippet}")]
+    UnsupportedSqlConstruct { construct: String, snippet: String },
 
     #[cfg(feature = "python")]
     #[error("Python Error: {0}")]
@@ -210,6 +226,10 @@ pub enum OperationError {
 pub enum SetError {
     #[error("Invalid input schemas have been populated")]
     InvalidInputSchemas,
+    #[error("Set operation inputs must have the same number of columns, got {0} and {1}")]
+    ColumnCountMismatch(usize, usize),
+    #[error("Set operation column {0} has type {1} on the left and {2} on the right")]
+    ColumnTypeMismatch(usize, FieldType, FieldType),
     #[error("Database unavailable for SET")]
     DatabaseUnavailable,
     #[error("History unavailable for SET source [{0}]")]
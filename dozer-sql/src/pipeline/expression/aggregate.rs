@@ -1,24 +1,32 @@
+use crate::pipeline::aggregation::custom::is_custom_aggregate;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::errors::PipelineError::InvalidFunction;
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum AggregateFunctionType {
+    ApproxCountDistinct,
     Avg,
     Count,
     Max,
     Min,
     Sum,
+    /// A function registered with [`register_aggregate_function`], by lowercase name.
+    ///
+    /// [`register_aggregate_function`]: crate::pipeline::aggregation::custom::register_aggregate_function
+    Custom(String),
 }
 
 impl AggregateFunctionType {
     pub(crate) fn new(name: &str) -> Result<AggregateFunctionType, PipelineError> {
         match name {
+            "approx_count_distinct" => Ok(AggregateFunctionType::ApproxCountDistinct),
             "avg" => Ok(AggregateFunctionType::Avg),
             "count" => Ok(AggregateFunctionType::Count),
             "max" => Ok(AggregateFunctionType::Max),
             "min" => Ok(AggregateFunctionType::Min),
             "sum" => Ok(AggregateFunctionType::Sum),
+            _ if is_custom_aggregate(name) => Ok(AggregateFunctionType::Custom(name.to_string())),
             _ => Err(InvalidFunction(name.to_string())),
         }
     }
@@ -27,11 +35,13 @@ impl AggregateFunctionType {
 impl Display for AggregateFunctionType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            AggregateFunctionType::ApproxCountDistinct => f.write_str("APPROX_COUNT_DISTINCT"),
             AggregateFunctionType::Avg => f.write_str("AVG"),
             AggregateFunctionType::Count => f.write_str("COUNT"),
             AggregateFunctionType::Max => f.write_str("MAX"),
             AggregateFunctionType::Min => f.write_str("MIN"),
             AggregateFunctionType::Sum => f.write_str("SUM"),
+            AggregateFunctionType::Custom(name) => f.write_str(&name.to_uppercase()),
         }
     }
 }
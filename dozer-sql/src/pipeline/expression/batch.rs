@@ -0,0 +1,138 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use dozer_types::types::{Field, FieldDefinition, Record, Schema, SourceDefinition};
+
+/// Evaluates `expression` over every record of `records`.
+///
+/// Binary operators and scalar functions evaluate their arguments a column at a time, so
+/// literals are evaluated once per batch and each argument subtree is walked once rather than
+/// once per record. The operator itself then runs per row on the argument values, through the
+/// same code as [`ExpressionExecutor::evaluate`], which keeps results identical. Other nodes are
+/// evaluated per row.
+///
+/// Per-row evaluation may skip arguments, e.g. `AND` stops at `false`, so an argument can fail on
+/// a row whose result doesn't need it. If the columnar pass fails, the batch is evaluated again
+/// per row, which yields either the per-row results or the per-row error.
+pub(crate) fn evaluate_batch(
+    expression: &Expression,
+    records: &[Record],
+    schema: &Schema,
+) -> Result<Vec<Field>, PipelineError> {
+    evaluate_columnar(expression, records, schema)
+        .or_else(|_| evaluate_rows(expression, records, schema))
+}
+
+fn evaluate_rows(
+    expression: &Expression,
+    records: &[Record],
+    schema: &Schema,
+) -> Result<Vec<Field>, PipelineError> {
+    records
+        .iter()
+        .map(|record| expression.evaluate(record, schema))
+        .collect()
+}
+
+fn evaluate_columnar(
+    expression: &Expression,
+    records: &[Record],
+    schema: &Schema,
+) -> Result<Vec<Field>, PipelineError> {
+    match expression {
+        Expression::Literal(field) => Ok(vec![field.clone(); records.len()]),
+        Expression::Column { index } => records
+            .iter()
+            .map(|record| {
+                record
+                    .values
+                    .get(*index)
+                    .cloned()
+                    .ok_or(PipelineError::InvalidColumnIndex {
+                        index: *index,
+                        schema_len: record.values.len(),
+                    })
+            })
+            .collect(),
+        Expression::BinaryOperator {
+            left,
+            operator,
+            right,
+        } => {
+            let args = Arguments::evaluate(&[left, right], records, schema)?;
+            args.map_rows(|schema, columns, record| {
+                operator.evaluate(schema, &columns[0], &columns[1], record)
+            })
+        }
+        Expression::ScalarFunction { fun, args } => {
+            let args = Arguments::evaluate(&args.iter().collect::<Vec<_>>(), records, schema)?;
+            args.map_rows(|schema, columns, record| fun.evaluate(schema, columns, record))
+        }
+        _ => evaluate_rows(expression, records, schema),
+    }
+}
+
+/// The evaluated arguments of a node, one column per argument.
+struct Arguments {
+    /// Describes a record holding one value per argument.
+    schema: Schema,
+    /// `Column` expressions reading each argument from such a record.
+    columns: Vec<Expression>,
+    values: Vec<Vec<Field>>,
+    len: usize,
+}
+
+impl Arguments {
+    fn evaluate(
+        args: &[&Expression],
+        records: &[Record],
+        schema: &Schema,
+    ) -> Result<Self, PipelineError> {
+        let mut args_schema = Schema::empty();
+        let mut columns = Vec::with_capacity(args.len());
+        let mut values = Vec::with_capacity(args.len());
+        for (index, arg) in args.iter().enumerate() {
+            let typ = arg.get_type(schema)?;
+            args_schema.field(
+                FieldDefinition::new(
+                    index.to_string(),
+                    typ.return_type,
+                    typ.nullable,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+            columns.push(Expression::Column { index });
+            values.push(evaluate_columnar(arg, records, schema)?);
+        }
+
+        Ok(Self {
+            schema: args_schema,
+            columns,
+            values,
+            len: records.len(),
+        })
+    }
+
+    fn map_rows(
+        self,
+        f: impl Fn(&Schema, &[Expression], &Record) -> Result<Field, PipelineError>,
+    ) -> Result<Vec<Field>, PipelineError> {
+        let mut values = self
+            .values
+            .into_iter()
+            .map(Vec::into_iter)
+            .collect::<Vec<_>>();
+        let mut row = Record::new(None, Vec::with_capacity(values.len()));
+        let mut result = Vec::with_capacity(self.len);
+        for _ in 0..self.len {
+            row.values.clear();
+            row.values.extend(
+                values
+                    .iter_mut()
+                    .map(|column| column.next().expect("every column has a value per row")),
+            );
+            result.push(f(&self.schema, &self.columns, &row)?);
+        }
+        Ok(result)
+    }
+}
@@ -8,11 +8,11 @@ use sqlparser::ast::{
     Value as SqlValue,
 };
 
+use crate::pipeline::errors::PipelineError;
 use crate::pipeline::errors::PipelineError::{
-    InvalidArgument, InvalidExpression, InvalidFunction, InvalidNestedAggregationFunction,
-    InvalidOperator, InvalidValue,
+    InvalidArgument, InvalidColumnIndex, InvalidExpression, InvalidFunction,
+    InvalidNestedAggregationFunction, InvalidOperator, InvalidValue, UnsupportedSqlConstruct,
 };
-use crate::pipeline::errors::{PipelineError, SqlError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::conditional::ConditionalExpressionType;
 use crate::pipeline::expression::datetime::DateTimeFunctionType;
@@ -25,6 +25,10 @@ use crate::pipeline::expression::geo::common::GeoFunctionType;
 use crate::pipeline::expression::operator::{BinaryOperatorType, UnaryOperatorType};
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use crate::pipeline::expression::scalar::string::TrimType;
+use crate::pipeline::expression::udf::{get_scalar_udf, ScalarUdf};
+use crate::pipeline::planner::pruning::collect_columns;
+use std::collections::BTreeSet;
+use std::sync::Arc;
 
 use super::cast::CastOperatorType;
 
@@ -56,7 +60,31 @@ impl ExpressionBuilder {
         sql_expression: &SqlExpr,
         schema: &Schema,
     ) -> Result<Expression, PipelineError> {
-        self.parse_sql_expression(parse_aggregations, sql_expression, schema)
+        let expression = self.parse_sql_expression(parse_aggregations, sql_expression, schema)?;
+        self.check_columns(&expression, schema)?;
+        Ok(expression)
+    }
+
+    /// Checks that the columns of `expression` exist: the columns of `schema`, followed by the
+    /// results of the aggregations of this builder, which must not overlap them.
+    fn check_columns(&self, expression: &Expression, schema: &Schema) -> Result<(), PipelineError> {
+        let schema_len = if self.aggregations.is_empty() {
+            schema.fields.len()
+        } else if self.offset < schema.fields.len() {
+            return Err(InvalidColumnIndex {
+                index: self.offset,
+                schema_len: schema.fields.len(),
+            });
+        } else {
+            self.offset + self.aggregations.len()
+        };
+
+        let mut columns = BTreeSet::new();
+        collect_columns(expression, &mut columns);
+        match columns.last() {
+            Some(&index) if index >= schema_len => Err(InvalidColumnIndex { index, schema_len }),
+            _ => Ok(()),
+        }
     }
 
     pub(crate) fn parse_sql_expression(
@@ -122,11 +150,22 @@ impl ExpressionBuilder {
             } => {
                 self.parse_sql_interval_expression(parse_aggregations, value, leading_field, schema)
             }
-            _ => Err(InvalidExpression(format!("{expression:?}"))),
+            _ => Err(unsupported_expression(expression)),
         }
     }
 
+    /// Resolves a `[[connection.]source.]field` identifier to a column of `schema`.
+    ///
+    /// A qualifier only matches fields whose `SourceDefinition` carries the same table or alias
+    /// name (and connection, when given). Fields with a `Dynamic` source have no known origin,
+    /// so they are only considered when no field with a concrete source matches the qualifier.
     fn parse_sql_column(ident: &[Ident], schema: &Schema) -> Result<Expression, PipelineError> {
+        let full_name = ident
+            .iter()
+            .map(|e| e.value.as_str())
+            .collect::<Vec<&str>>()
+            .join(".");
+
         let (src_field, src_table_or_alias, src_connection) = match ident.len() {
             1 => (&ident[0].value, None, None),
             2 => (&ident[1].value, Some(&ident[0].value), None),
@@ -135,15 +174,7 @@ impl ExpressionBuilder {
                 Some(&ident[1].value),
                 Some(&ident[0].value),
             ),
-            _ => {
-                return Err(PipelineError::SqlError(SqlError::InvalidColumn(
-                    ident
-                        .iter()
-                        .map(|e| e.value.as_str())
-                        .collect::<Vec<&str>>()
-                        .join("."),
-                )));
-            }
+            _ => return Err(PipelineError::IllegalFieldIdentifier(full_name)),
         };
 
         let matching_by_field: Vec<(usize, &FieldDefinition)> = schema
@@ -153,72 +184,41 @@ impl ExpressionBuilder {
             .filter(|(_idx, f)| &f.name == src_field)
             .collect();
 
-        match matching_by_field.len() {
+        let matching: Vec<(usize, &FieldDefinition)> = match src_table_or_alias {
+            None => matching_by_field,
+            Some(src_table_or_alias) => {
+                let matching_by_source: Vec<(usize, &FieldDefinition)> = matching_by_field
+                    .iter()
+                    .copied()
+                    .filter(|(_idx, field)| match &field.source {
+                        SourceDefinition::Alias { name } => {
+                            src_connection.is_none() && name == src_table_or_alias
+                        }
+                        SourceDefinition::Table { name, connection } => {
+                            name == src_table_or_alias
+                                && src_connection.map_or(true, |c| c == connection)
+                        }
+                        SourceDefinition::Dynamic => false,
+                    })
+                    .collect();
+
+                if matching_by_source.is_empty() {
+                    matching_by_field
+                        .into_iter()
+                        .filter(|(_idx, field)| field.source == SourceDefinition::Dynamic)
+                        .collect()
+                } else {
+                    matching_by_source
+                }
+            }
+        };
+
+        match matching.len() {
+            0 => Err(PipelineError::UnknownFieldIdentifier(full_name)),
             1 => Ok(Expression::Column {
-                index: matching_by_field[0].0,
+                index: matching[0].0,
             }),
-            _ => match src_table_or_alias {
-                None => Err(PipelineError::SqlError(SqlError::InvalidColumn(
-                    ident
-                        .iter()
-                        .map(|e| e.value.as_str())
-                        .collect::<Vec<&str>>()
-                        .join("."),
-                ))),
-                Some(src_table_or_alias) => {
-                    let matching_by_table_or_alias: Vec<(usize, &FieldDefinition)> =
-                        matching_by_field
-                            .into_iter()
-                            .filter(|(_idx, field)| match &field.source {
-                                SourceDefinition::Alias { name } => name == src_table_or_alias,
-                                SourceDefinition::Table {
-                                    name,
-                                    connection: _,
-                                } => name == src_table_or_alias,
-                                _ => false,
-                            })
-                            .collect();
-
-                    match matching_by_table_or_alias.len() {
-                        1 => Ok(Expression::Column {
-                            index: matching_by_table_or_alias[0].0,
-                        }),
-                        _ => match src_connection {
-                            None => Err(PipelineError::SqlError(SqlError::InvalidColumn(
-                                ident
-                                    .iter()
-                                    .fold(String::new(), |a, b| a + "." + b.value.as_str()),
-                            ))),
-                            Some(src_connection) => {
-                                let matching_by_connection: Vec<(usize, &FieldDefinition)> =
-                                    matching_by_table_or_alias
-                                        .into_iter()
-                                        .filter(|(_idx, field)| match &field.source {
-                                            SourceDefinition::Table {
-                                                name: _,
-                                                connection,
-                                            } => connection == src_connection,
-                                            _ => false,
-                                        })
-                                        .collect();
-
-                                match matching_by_connection.len() {
-                                    1 => Ok(Expression::Column {
-                                        index: matching_by_connection[0].0,
-                                    }),
-                                    _ => Err(PipelineError::SqlError(SqlError::InvalidColumn(
-                                        ident
-                                            .iter()
-                                            .map(|e| e.value.as_str())
-                                            .collect::<Vec<&str>>()
-                                            .join("."),
-                                    ))),
-                                }
-                            }
-                        },
-                    }
-                }
-            },
+            _ => Err(PipelineError::AmbiguousFieldIdentifier(full_name)),
         }
     }
 
@@ -310,6 +310,26 @@ impl ExpressionBuilder {
         }
     }
 
+    /// Builds a call to `udf`, checking its arguments.
+    fn udf_check(
+        &mut self,
+        udf: Arc<ScalarUdf>,
+        parse_aggregations: bool,
+        sql_function: &Function,
+        schema: &Schema,
+    ) -> Result<Expression, PipelineError> {
+        let mut function_args: Vec<Expression> = Vec::new();
+        for arg in &sql_function.args {
+            function_args.push(self.parse_sql_function_arg(parse_aggregations, arg, schema)?);
+        }
+
+        udf.get_type(&function_args, schema)?;
+        Ok(Expression::Udf {
+            fun: udf,
+            args: function_args,
+        })
+    }
+
     fn geo_expr_check(
         &mut self,
         function_name: String,
@@ -351,6 +371,9 @@ impl ExpressionBuilder {
         }
 
         match ConditionalExpressionType::new(function_name.as_str()) {
+            Ok(ConditionalExpressionType::NullIf) => {
+                Err(unsupported_construct("NULLIF", sql_function))
+            }
             Ok(cet) => Ok(ConditionalExpression {
                 fun: cet,
                 args: function_args.clone(),
@@ -367,6 +390,10 @@ impl ExpressionBuilder {
     ) -> Result<Expression, PipelineError> {
         let function_name = sql_function.name.to_string().to_lowercase();
 
+        if sql_function.over.is_some() {
+            return Err(unsupported_construct("window functions", sql_function));
+        }
+
         #[cfg(feature = "python")]
         if function_name.starts_with("py_") {
             // The function is from python udf.
@@ -394,6 +421,10 @@ impl ExpressionBuilder {
             return scalar_check;
         }
 
+        if let Some(udf) = get_scalar_udf(&function_name) {
+            return self.udf_check(udf, parse_aggregations, sql_function, schema);
+        }
+
         let geo_check = self.geo_expr_check(
             function_name.clone(),
             parse_aggregations,
@@ -410,7 +441,10 @@ impl ExpressionBuilder {
             sql_function,
             schema,
         );
-        if conditional_check.is_ok() {
+        if matches!(
+            conditional_check,
+            Ok(_) | Err(UnsupportedSqlConstruct { .. })
+        ) {
             return conditional_check;
         }
 
@@ -455,17 +489,22 @@ impl ExpressionBuilder {
         leading_field: &Option<DateTimeField>,
         schema: &Schema,
     ) -> Result<Expression, PipelineError> {
-        let right = self.parse_sql_expression(parse_aggregations, value, schema)?;
-        if leading_field.is_some() {
-            Ok(Expression::DateTimeFunction {
-                fun: DateTimeFunctionType::Interval {
-                    field: leading_field.unwrap(),
-                },
-                arg: Box::new(right),
-            })
-        } else {
-            Err(InvalidExpression(format!("INTERVAL for {leading_field:?}")))
-        }
+        let (field, right) = match (leading_field, value) {
+            (Some(field), _) => (
+                *field,
+                self.parse_sql_expression(parse_aggregations, value, schema)?,
+            ),
+            // INTERVAL '1 day'
+            (None, Expr::Value(SqlValue::SingleQuotedString(value))) => {
+                let (count, field) = parse_interval_string(value)?;
+                (field, Expression::Literal(Field::String(count.to_string())))
+            }
+            (None, _) => return Err(InvalidExpression(format!("INTERVAL for {leading_field:?}"))),
+        };
+        Ok(Expression::DateTimeFunction {
+            fun: DateTimeFunctionType::Interval { field },
+            arg: Box::new(right),
+        })
     }
 
     fn parse_sql_unary_op(
@@ -693,3 +732,48 @@ pub fn extend_schema_source_def(schema: &Schema, name: &NameOrAlias) -> Schema {
 
     output_schema
 }
+
+/// Parses the value of an `INTERVAL` without a unit, such as `'3 days'`, into its count and unit.
+fn parse_interval_string(value: &str) -> Result<(&str, DateTimeField), PipelineError> {
+    let invalid = || InvalidValue(format!("INTERVAL '{value}'"));
+    let mut parts = value.split_whitespace();
+    let (Some(count), Some(unit), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    let unit = unit.to_lowercase();
+    let field = match unit.strip_suffix('s').unwrap_or(&unit) {
+        "nanosecond" => DateTimeField::Nanosecond,
+        "microsecond" => DateTimeField::Microsecond,
+        "millisecond" => DateTimeField::Millisecond,
+        "second" => DateTimeField::Second,
+        "minute" => DateTimeField::Minute,
+        "hour" => DateTimeField::Hour,
+        "day" => DateTimeField::Day,
+        "week" => DateTimeField::Week,
+        "month" => DateTimeField::Month,
+        "quarter" => DateTimeField::Quarter,
+        "year" => DateTimeField::Year,
+        _ => return Err(invalid()),
+    };
+    Ok((count, field))
+}
+
+fn unsupported_construct(construct: &str, snippet: impl ToString) -> PipelineError {
+    UnsupportedSqlConstruct {
+        construct: construct.to_string(),
+        snippet: snippet.to_string(),
+    }
+}
+
+/// The error for an expression the builder has no translation for, naming its kind.
+fn unsupported_expression(expression: &SqlExpr) -> PipelineError {
+    let construct = match expression {
+        SqlExpr::Case { .. } => "CASE expressions",
+        SqlExpr::InList { .. } => "IN lists",
+        SqlExpr::Between { .. } => "BETWEEN",
+        SqlExpr::IsNull(_) | SqlExpr::IsNotNull(_) => "IS NULL",
+        SqlExpr::Subquery(_) | SqlExpr::InSubquery { .. } | SqlExpr::Exists { .. } => "subqueries",
+        _ => "expression",
+    };
+    unsupported_construct(construct, expression)
+}
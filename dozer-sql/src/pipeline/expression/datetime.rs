@@ -190,31 +190,51 @@ pub(crate) fn evaluate_interval(
     record: &Record,
 ) -> Result<Field, PipelineError> {
     let value = arg.evaluate(record, schema)?;
-    let dur = value.to_duration()?.unwrap().0.as_nanos();
+    let Some(count) = interval_count(field, &value)? else {
+        return Ok(Field::Null);
+    };
+
+    let seconds = |unit: u64| {
+        count
+            .checked_mul(unit)
+            .map(|secs| {
+                Field::Duration(DozerDuration(
+                    std::time::Duration::from_secs(secs),
+                    TimeUnit::Seconds,
+                ))
+            })
+            .ok_or_else(|| InvalidValue(format!("INTERVAL '{value}' {field} is out of range")))
+    };
 
     match field {
-        DateTimeField::Second => Ok(Field::Duration(DozerDuration(
-            std::time::Duration::from_secs(dur as u64),
-            TimeUnit::Seconds,
-        ))),
+        DateTimeField::Second => seconds(1),
+        DateTimeField::Minute => seconds(60),
+        DateTimeField::Hour => seconds(60 * 60),
+        DateTimeField::Day => seconds(24 * 60 * 60),
+        DateTimeField::Week => seconds(7 * 24 * 60 * 60),
         DateTimeField::Millisecond | DateTimeField::Milliseconds => {
             Ok(Field::Duration(DozerDuration(
-                std::time::Duration::from_millis(dur as u64),
+                std::time::Duration::from_millis(count),
                 TimeUnit::Milliseconds,
             )))
         }
         DateTimeField::Microsecond | DateTimeField::Microseconds => {
             Ok(Field::Duration(DozerDuration(
-                std::time::Duration::from_micros(dur as u64),
+                std::time::Duration::from_micros(count),
                 TimeUnit::Microseconds,
             )))
         }
         DateTimeField::Nanoseconds | DateTimeField::Nanosecond => {
             Ok(Field::Duration(DozerDuration(
-                std::time::Duration::from_nanos(dur as u64),
+                std::time::Duration::from_nanos(count),
                 TimeUnit::Nanoseconds,
             )))
         }
+        DateTimeField::Month | DateTimeField::Quarter | DateTimeField::Year => {
+            Err(PipelineError::InvalidOperandType(format!(
+                "INTERVAL '{value}' {field} can only shift a date or timestamp"
+            )))
+        }
         DateTimeField::Isodow
         | DateTimeField::Timezone
         | DateTimeField::Dow
@@ -226,14 +246,7 @@ pub(crate) fn evaluate_interval(
         | DateTimeField::TimezoneMinute
         | DateTimeField::Date
         | DateTimeField::NoDateTime
-        | DateTimeField::Day
-        | DateTimeField::Month
-        | DateTimeField::Year
-        | DateTimeField::Hour
-        | DateTimeField::Minute
-        | DateTimeField::Quarter
         | DateTimeField::Epoch
-        | DateTimeField::Week
         | DateTimeField::Century
         | DateTimeField::Decade
         | DateTimeField::Doy => Err(PipelineError::InvalidOperandType(format!(
@@ -241,3 +254,32 @@ pub(crate) fn evaluate_interval(
         ))),
     }
 }
+
+/// Returns the number of months in one unit of `field`, if `field` has no fixed duration.
+pub(crate) fn months_per_unit(field: &DateTimeField) -> Option<u64> {
+    match field {
+        DateTimeField::Month => Some(1),
+        DateTimeField::Quarter => Some(3),
+        DateTimeField::Year => Some(12),
+        _ => None,
+    }
+}
+
+/// Reads the number of units of an `INTERVAL`, or `None` if it is `NULL`.
+pub(crate) fn interval_count(
+    field: &DateTimeField,
+    value: &Field,
+) -> Result<Option<u64>, PipelineError> {
+    let count = match value {
+        Field::UInt(count) => Some(*count),
+        Field::Int(count) => u64::try_from(*count).ok(),
+        Field::String(count) | Field::Text(count) => count.trim().parse::<u64>().ok(),
+        Field::Null => return Ok(None),
+        _ => None,
+    };
+    count.map(Some).ok_or_else(|| {
+        InvalidValue(format!(
+            "INTERVAL '{value}' {field} is not a non-negative whole number of units"
+        ))
+    })
+}
@@ -1,9 +1,12 @@
+use crate::pipeline::aggregation::approx_count_distinct::validate_approx_count_distinct;
 use crate::pipeline::aggregation::avg::validate_avg;
 use crate::pipeline::aggregation::count::validate_count;
+use crate::pipeline::aggregation::custom::validate_custom;
 use crate::pipeline::aggregation::max::validate_max;
 use crate::pipeline::aggregation::min::validate_min;
 use crate::pipeline::aggregation::sum::validate_sum;
 use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::batch;
 use crate::pipeline::expression::conditional::{
     get_conditional_expr_type, ConditionalExpressionType,
 };
@@ -18,6 +21,8 @@ use uuid::Uuid;
 use super::aggregate::AggregateFunctionType;
 use super::cast::CastOperatorType;
 use super::scalar::string::{evaluate_like, get_like_operator_type};
+use super::udf::ScalarUdf;
+use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expression {
@@ -71,6 +76,13 @@ pub enum Expression {
     Now {
         fun: DateTimeFunctionType,
     },
+    /// A call to a function registered with [`register_scalar_function`].
+    ///
+    /// [`register_scalar_function`]: crate::pipeline::expression::udf::register_scalar_function
+    Udf {
+        fun: Arc<ScalarUdf>,
+        args: Vec<Expression>,
+    },
     #[cfg(feature = "python")]
     PythonUDF {
         name: String,
@@ -188,6 +200,17 @@ impl Expression {
                 fun.to_string() + "(" + arg.to_string(schema).as_str() + ")"
             }
             Expression::Now { fun } => fun.to_string() + "()",
+            Expression::Udf { fun, args } => {
+                fun.name().to_uppercase()
+                    + "("
+                    + args
+                        .iter()
+                        .map(|e| e.to_string(schema))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                        .as_str()
+                    + ")"
+            }
         }
     }
 }
@@ -220,18 +243,36 @@ impl Expression {}
 pub trait ExpressionExecutor: Send + Sync {
     fn evaluate(&self, record: &Record, schema: &Schema) -> Result<Field, PipelineError>;
     fn get_type(&self, schema: &Schema) -> Result<ExpressionType, PipelineError>;
+
+    /// Evaluates the expression over every record of `records`, returning one value per record.
+    ///
+    /// Returns the same values and errors as calling [`ExpressionExecutor::evaluate`] per record.
+    fn evaluate_batch(
+        &self,
+        records: &[Record],
+        schema: &Schema,
+    ) -> Result<Vec<Field>, PipelineError> {
+        records
+            .iter()
+            .map(|record| self.evaluate(record, schema))
+            .collect()
+    }
 }
 
 impl ExpressionExecutor for Expression {
     fn evaluate(&self, record: &Record, schema: &Schema) -> Result<Field, PipelineError> {
         match self {
             Expression::Literal(field) => Ok(field.clone()),
-            Expression::Column { index } => Ok(record
-                .get_value(*index)
-                .map_err(|_e| {
-                    PipelineError::InvalidInputType(format!("{} is an invalid field index", *index))
-                })?
-                .clone()),
+            Expression::Column { index } => {
+                record
+                    .values
+                    .get(*index)
+                    .cloned()
+                    .ok_or(PipelineError::InvalidColumnIndex {
+                        index: *index,
+                        schema_len: record.values.len(),
+                    })
+            }
             Expression::BinaryOperator {
                 left,
                 operator,
@@ -266,9 +307,18 @@ impl ExpressionExecutor for Expression {
             Expression::ConditionalExpression { fun, args } => fun.evaluate(schema, args, record),
             Expression::DateTimeFunction { fun, arg } => fun.evaluate(schema, arg, record),
             Expression::Now { fun } => fun.evaluate_now(),
+            Expression::Udf { fun, args } => fun.evaluate(args, record, schema),
         }
     }
 
+    fn evaluate_batch(
+        &self,
+        records: &[Record],
+        schema: &Schema,
+    ) -> Result<Vec<Field>, PipelineError> {
+        batch::evaluate_batch(self, records, schema)
+    }
+
     fn get_type(&self, schema: &Schema) -> Result<ExpressionType, PipelineError> {
         match self {
             Expression::Literal(field) => {
@@ -286,7 +336,13 @@ impl ExpressionExecutor for Expression {
                 }
             }
             Expression::Column { index } => {
-                let t = schema.fields.get(*index).unwrap();
+                let t = schema
+                    .fields
+                    .get(*index)
+                    .ok_or(PipelineError::InvalidColumnIndex {
+                        index: *index,
+                        schema_len: schema.fields.len(),
+                    })?;
 
                 Ok(ExpressionType::new(
                     t.typ,
@@ -331,6 +387,7 @@ impl ExpressionExecutor for Expression {
                 dozer_types::types::SourceDefinition::Dynamic,
                 false,
             )),
+            Expression::Udf { fun, args } => fun.get_type(args, schema),
             #[cfg(feature = "python")]
             Expression::PythonUDF { return_type, .. } => Ok(ExpressionType::new(
                 *return_type,
@@ -342,7 +399,7 @@ impl ExpressionExecutor for Expression {
     }
 }
 
-fn get_field_type(field: &Field) -> Option<FieldType> {
+pub(crate) fn get_field_type(field: &Field) -> Option<FieldType> {
     match field {
         Field::UInt(_) => Some(FieldType::UInt),
         Field::U128(_) => Some(FieldType::U128),
@@ -465,30 +522,64 @@ fn get_binary_operator_type(
                     SourceDefinition::Dynamic,
                     false,
                 )),
-                (FieldType::Timestamp, FieldType::Timestamp) => Ok(ExpressionType::new(
-                    FieldType::Duration,
-                    false,
-                    SourceDefinition::Dynamic,
-                    false,
-                )),
-                (FieldType::Timestamp, FieldType::Duration) => Ok(ExpressionType::new(
-                    FieldType::Timestamp,
-                    false,
-                    SourceDefinition::Dynamic,
-                    false,
-                )),
-                (FieldType::Duration, FieldType::Timestamp) => Ok(ExpressionType::new(
-                    FieldType::Timestamp,
-                    false,
-                    SourceDefinition::Dynamic,
-                    false,
-                )),
-                (FieldType::Duration, FieldType::Duration) => Ok(ExpressionType::new(
-                    FieldType::Duration,
-                    false,
-                    SourceDefinition::Dynamic,
-                    false,
-                )),
+                (FieldType::Timestamp, FieldType::Timestamp)
+                    if *operator == BinaryOperatorType::Sub =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Duration,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::Timestamp, FieldType::Duration)
+                    if matches!(operator, BinaryOperatorType::Add | BinaryOperatorType::Sub) =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Timestamp,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::Duration, FieldType::Timestamp)
+                    if *operator == BinaryOperatorType::Add =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Timestamp,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::Date, FieldType::Duration)
+                    if matches!(operator, BinaryOperatorType::Add | BinaryOperatorType::Sub) =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Date,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::Duration, FieldType::Date) if *operator == BinaryOperatorType::Add => {
+                    Ok(ExpressionType::new(
+                        FieldType::Date,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::Duration, FieldType::Duration)
+                    if matches!(operator, BinaryOperatorType::Add | BinaryOperatorType::Sub) =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Duration,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
                 (FieldType::Int, FieldType::Int)
                 | (FieldType::Int, FieldType::UInt)
                 | (FieldType::UInt, FieldType::Int) => Ok(ExpressionType::new(
@@ -611,10 +702,12 @@ fn get_aggregate_function_type(
     schema: &Schema,
 ) -> Result<ExpressionType, PipelineError> {
     match function {
+        AggregateFunctionType::ApproxCountDistinct => validate_approx_count_distinct(args, schema),
         AggregateFunctionType::Avg => validate_avg(args, schema),
         AggregateFunctionType::Count => validate_count(args, schema),
         AggregateFunctionType::Max => validate_max(args, schema),
         AggregateFunctionType::Min => validate_min(args, schema),
         AggregateFunctionType::Sum => validate_sum(args, schema),
+        AggregateFunctionType::Custom(name) => validate_custom(name),
     }
 }
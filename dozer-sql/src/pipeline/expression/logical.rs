@@ -2,6 +2,9 @@ use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
 use dozer_types::types::{Field, Record, Schema};
 
+/// Evaluates `left AND right` with SQL three-valued logic: `FALSE` if either operand is `FALSE`,
+/// otherwise `NULL` if either is `NULL`. The right operand is only evaluated if the left one is
+/// not `FALSE`.
 pub fn evaluate_and(
     schema: &Schema,
     left: &Expression,
@@ -9,47 +12,30 @@ pub fn evaluate_and(
     record: &Record,
 ) -> Result<Field, PipelineError> {
     let l_field = left.evaluate(record, schema)?;
-    let r_field = right.evaluate(record, schema)?;
     match l_field {
-        Field::Boolean(true) => match r_field {
-            Field::Boolean(true) => Ok(Field::Boolean(true)),
-            Field::Boolean(false) => Ok(Field::Boolean(false)),
-            Field::Null => Ok(Field::Boolean(false)),
-            Field::UInt(_)
-            | Field::U128(_)
-            | Field::Int(_)
-            | Field::I128(_)
-            | Field::Float(_)
-            | Field::String(_)
-            | Field::Text(_)
-            | Field::Binary(_)
-            | Field::Decimal(_)
-            | Field::Timestamp(_)
-            | Field::Date(_)
-            | Field::Json(_)
-            | Field::Point(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "AND".to_string())),
-        },
-        Field::Boolean(false) => match r_field {
-            Field::Boolean(true) => Ok(Field::Boolean(false)),
-            Field::Boolean(false) => Ok(Field::Boolean(false)),
-            Field::Null => Ok(Field::Boolean(false)),
-            Field::UInt(_)
-            | Field::U128(_)
-            | Field::Int(_)
-            | Field::I128(_)
-            | Field::Float(_)
-            | Field::String(_)
-            | Field::Text(_)
-            | Field::Binary(_)
-            | Field::Decimal(_)
-            | Field::Timestamp(_)
-            | Field::Date(_)
-            | Field::Json(_)
-            | Field::Point(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "AND".to_string())),
-        },
-        Field::Null => Ok(Field::Boolean(false)),
+        Field::Boolean(true) | Field::Null => {
+            let r_field = right.evaluate(record, schema)?;
+            match r_field {
+                Field::Boolean(true) => Ok(l_field),
+                Field::Boolean(false) => Ok(Field::Boolean(false)),
+                Field::Null => Ok(Field::Null),
+                Field::UInt(_)
+                | Field::U128(_)
+                | Field::Int(_)
+                | Field::I128(_)
+                | Field::Float(_)
+                | Field::String(_)
+                | Field::Text(_)
+                | Field::Binary(_)
+                | Field::Decimal(_)
+                | Field::Timestamp(_)
+                | Field::Date(_)
+                | Field::Json(_)
+                | Field::Point(_)
+                | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "AND".to_string())),
+            }
+        }
+        Field::Boolean(false) => Ok(Field::Boolean(false)),
         Field::UInt(_)
         | Field::U128(_)
         | Field::Int(_)
@@ -67,6 +53,9 @@ pub fn evaluate_and(
     }
 }
 
+/// Evaluates `left OR right` with SQL three-valued logic: `TRUE` if either operand is `TRUE`,
+/// otherwise `NULL` if either is `NULL`. The right operand is only evaluated if the left one is
+/// not `TRUE`.
 pub fn evaluate_or(
     schema: &Schema,
     left: &Expression,
@@ -74,46 +63,30 @@ pub fn evaluate_or(
     record: &Record,
 ) -> Result<Field, PipelineError> {
     let l_field = left.evaluate(record, schema)?;
-    let r_field = right.evaluate(record, schema)?;
     match l_field {
-        Field::Boolean(true) => match r_field {
-            Field::Boolean(false) => Ok(Field::Boolean(true)),
-            Field::Boolean(true) => Ok(Field::Boolean(true)),
-            Field::Null => Ok(Field::Boolean(true)),
-            Field::UInt(_)
-            | Field::U128(_)
-            | Field::Int(_)
-            | Field::I128(_)
-            | Field::Float(_)
-            | Field::String(_)
-            | Field::Text(_)
-            | Field::Binary(_)
-            | Field::Decimal(_)
-            | Field::Timestamp(_)
-            | Field::Date(_)
-            | Field::Json(_)
-            | Field::Point(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "OR".to_string())),
-        },
-        Field::Boolean(false) | Field::Null => match right.evaluate(record, schema)? {
-            Field::Boolean(false) => Ok(Field::Boolean(false)),
-            Field::Boolean(true) => Ok(Field::Boolean(true)),
-            Field::Null => Ok(Field::Boolean(false)),
-            Field::UInt(_)
-            | Field::U128(_)
-            | Field::Int(_)
-            | Field::I128(_)
-            | Field::Float(_)
-            | Field::String(_)
-            | Field::Text(_)
-            | Field::Binary(_)
-            | Field::Decimal(_)
-            | Field::Timestamp(_)
-            | Field::Date(_)
-            | Field::Json(_)
-            | Field::Point(_)
-            | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "OR".to_string())),
-        },
+        Field::Boolean(true) => Ok(Field::Boolean(true)),
+        Field::Boolean(false) | Field::Null => {
+            let r_field = right.evaluate(record, schema)?;
+            match r_field {
+                Field::Boolean(false) => Ok(l_field),
+                Field::Boolean(true) => Ok(Field::Boolean(true)),
+                Field::Null => Ok(Field::Null),
+                Field::UInt(_)
+                | Field::U128(_)
+                | Field::Int(_)
+                | Field::I128(_)
+                | Field::Float(_)
+                | Field::String(_)
+                | Field::Text(_)
+                | Field::Binary(_)
+                | Field::Decimal(_)
+                | Field::Timestamp(_)
+                | Field::Date(_)
+                | Field::Json(_)
+                | Field::Point(_)
+                | Field::Duration(_) => Err(PipelineError::InvalidType(r_field, "OR".to_string())),
+            }
+        }
         Field::UInt(_)
         | Field::U128(_)
         | Field::Int(_)
@@ -1,7 +1,11 @@
 use crate::pipeline::errors::OperationError;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::errors::SqlError::Operation;
+use crate::pipeline::expression::datetime::{
+    interval_count, months_per_unit, DateTimeFunctionType,
+};
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use dozer_types::chrono::{Months, NaiveDate, TimeZone};
 use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::Schema;
 use dozer_types::types::{DozerDuration, TimeUnit};
@@ -11,6 +15,7 @@ use dozer_types::{
     types::{Field, Record},
 };
 use num_traits::FromPrimitive;
+use sqlparser::ast::DateTimeField;
 use std::num::Wrapping;
 use std::ops::Neg;
 
@@ -22,6 +27,10 @@ macro_rules! define_math_operator {
             right: &Expression,
             record: &Record,
         ) -> Result<Field, PipelineError> {
+            if let Some(result) = evaluate_calendar_interval(schema, left, right, $op, record)? {
+                return Ok(result);
+            }
+
             let left_p = left.evaluate(&record, schema)?;
             let right_p = right.evaluate(&record, schema)?;
 
@@ -78,6 +87,19 @@ macro_rules! define_math_operator {
                                 $op.to_string(),
                             )),
                         },
+                        Field::Date(right_v) => match $op {
+                            "+" => Ok(Field::Date(shift_date(right_v, &left_v, false)?)),
+                            "-" | "*" | "/" | "%" => Err(PipelineError::InvalidTypeComparison(
+                                left_p,
+                                right_p,
+                                $op.to_string(),
+                            )),
+                            &_ => Err(PipelineError::InvalidTypeComparison(
+                                left_p,
+                                right_p,
+                                $op.to_string(),
+                            )),
+                        },
                         Field::UInt(_)
                         | Field::U128(_)
                         | Field::Int(_)
@@ -88,7 +110,6 @@ macro_rules! define_math_operator {
                         | Field::Text(_)
                         | Field::Binary(_)
                         | Field::Decimal(_)
-                        | Field::Date(_)
                         | Field::Json(_)
                         | Field::Point(_)
                         | Field::Null => Err(PipelineError::InvalidTypeComparison(
@@ -133,7 +154,7 @@ macro_rules! define_math_operator {
                     },
                     Field::Timestamp(right_v) => match $op {
                         "-" => {
-                            if left_v > right_v {
+                            if left_v >= right_v {
                                 let duration: i64 = (left_v - right_v).num_nanoseconds().ok_or(
                                     PipelineError::UnableToCast(
                                         format!("{}", left_v - right_v),
@@ -182,6 +203,41 @@ macro_rules! define_math_operator {
                         $op.to_string(),
                     )),
                 },
+                Field::Date(left_v) => match right_p {
+                    Field::Duration(right_v) => match $op {
+                        "-" => Ok(Field::Date(shift_date(left_v, &right_v, true)?)),
+                        "+" => Ok(Field::Date(shift_date(left_v, &right_v, false)?)),
+                        "*" | "/" | "%" => Err(PipelineError::InvalidTypeComparison(
+                            left_p,
+                            right_p,
+                            $op.to_string(),
+                        )),
+                        &_ => Err(PipelineError::InvalidTypeComparison(
+                            left_p,
+                            right_p,
+                            $op.to_string(),
+                        )),
+                    },
+                    Field::Null => Ok(Field::Null),
+                    Field::UInt(_)
+                    | Field::U128(_)
+                    | Field::Int(_)
+                    | Field::I128(_)
+                    | Field::Float(_)
+                    | Field::Boolean(_)
+                    | Field::String(_)
+                    | Field::Text(_)
+                    | Field::Binary(_)
+                    | Field::Decimal(_)
+                    | Field::Timestamp(_)
+                    | Field::Date(_)
+                    | Field::Json(_)
+                    | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
+                        left_p,
+                        right_p,
+                        $op.to_string(),
+                    )),
+                },
                 Field::Float(left_v) => match right_p {
                     // left: Float, right: Int
                     Field::Int(right_v) => Ok(Field::Float($fct(
@@ -1304,7 +1360,6 @@ macro_rules! define_math_operator {
                 | Field::String(_)
                 | Field::Text(_)
                 | Field::Binary(_)
-                | Field::Date(_)
                 | Field::Json(_)
                 | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
@@ -1322,6 +1377,119 @@ define_math_operator!(evaluate_mul, "*", |a, b| { a * b }, 0);
 define_math_operator!(evaluate_div, "/", |a, b| { a / b }, 1);
 define_math_operator!(evaluate_mod, "%", |a, b| { a % b }, 0);
 
+/// Shifts `date` by `duration` from midnight, dropping the time of day of the result.
+fn shift_date(
+    date: NaiveDate,
+    duration: &DozerDuration,
+    subtract: bool,
+) -> Result<NaiveDate, PipelineError> {
+    let midnight = date.and_hms_opt(0, 0, 0);
+    let shifted = chrono::Duration::from_std(duration.0)
+        .ok()
+        .zip(midnight)
+        .and_then(|(duration, midnight)| {
+            if subtract {
+                midnight.checked_sub_signed(duration)
+            } else {
+                midnight.checked_add_signed(duration)
+            }
+        });
+    shifted
+        .map(|timestamp| timestamp.date())
+        .ok_or(PipelineError::SqlError(Operation(if subtract {
+            OperationError::SubtractionOverflow
+        } else {
+            OperationError::AdditionOverflow
+        })))
+}
+
+/// Returns the field and the count of `expression`, if it is an `INTERVAL` in months, quarters
+/// or years.
+fn calendar_interval(expression: &Expression) -> Option<(&DateTimeField, &Expression)> {
+    match expression {
+        Expression::DateTimeFunction {
+            fun: DateTimeFunctionType::Interval { field },
+            arg,
+        } if months_per_unit(field).is_some() => Some((field, arg)),
+        _ => None,
+    }
+}
+
+/// Adds or subtracts an `INTERVAL` in months, quarters or years, which have no fixed duration, by
+/// shifting a date or timestamp by calendar months. The day of the month is clamped to the length
+/// of the resulting month, so `DATE '2023-01-31' + INTERVAL '1' MONTH` is `2023-02-28`.
+///
+/// Returns `None` if the operation isn't such an addition or subtraction.
+fn evaluate_calendar_interval(
+    schema: &Schema,
+    left: &Expression,
+    right: &Expression,
+    op: &str,
+    record: &Record,
+) -> Result<Option<Field>, PipelineError> {
+    let (value, (field, count), subtract) =
+        match (op, calendar_interval(left), calendar_interval(right)) {
+            ("+" | "-", _, Some(interval)) => (left, interval, op == "-"),
+            ("+", Some(interval), None) => (right, interval, false),
+            _ => return Ok(None),
+        };
+    let value = value.evaluate(record, schema)?;
+    let Some(count) = interval_count(field, &count.evaluate(record, schema)?)? else {
+        return Ok(Some(Field::Null));
+    };
+
+    let overflow = || {
+        PipelineError::SqlError(Operation(if subtract {
+            OperationError::SubtractionOverflow
+        } else {
+            OperationError::AdditionOverflow
+        }))
+    };
+    let months = months_per_unit(field)
+        .and_then(|months| count.checked_mul(months))
+        .and_then(|months| u32::try_from(months).ok())
+        .map(Months::new)
+        .ok_or_else(overflow)?;
+    let shift = |date: NaiveDate| {
+        if subtract {
+            date.checked_sub_months(months)
+        } else {
+            date.checked_add_months(months)
+        }
+    };
+
+    let shifted = match value {
+        Field::Date(date) => shift(date).map(Field::Date),
+        Field::Timestamp(timestamp) => shift(timestamp.date_naive())
+            .and_then(|date| {
+                timestamp
+                    .timezone()
+                    .from_local_datetime(&date.and_time(timestamp.time()))
+                    .single()
+            })
+            .map(Field::Timestamp),
+        Field::Null => Some(Field::Null),
+        Field::UInt(_)
+        | Field::U128(_)
+        | Field::Int(_)
+        | Field::I128(_)
+        | Field::Float(_)
+        | Field::Boolean(_)
+        | Field::String(_)
+        | Field::Text(_)
+        | Field::Binary(_)
+        | Field::Decimal(_)
+        | Field::Json(_)
+        | Field::Point(_)
+        | Field::Duration(_) => {
+            return Err(PipelineError::InvalidOperandType(format!(
+                "INTERVAL {field} can only shift a date or timestamp, not {value}"
+            )))
+        }
+    };
+    shifted.map(Some).ok_or_else(overflow)
+}
+
 pub fn evaluate_plus(
     schema: &Schema,
     expression: &Expression,
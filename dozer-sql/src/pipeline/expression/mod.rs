@@ -1,5 +1,6 @@
 pub mod aggregate;
 mod arg_utils;
+mod batch;
 pub mod builder;
 pub mod cast;
 pub mod comparison;
@@ -10,7 +11,10 @@ pub mod geo;
 pub mod logical;
 pub mod mathematical;
 pub mod operator;
+pub mod optimizer;
 pub mod scalar;
+pub mod typecheck;
+pub mod udf;
 
 #[cfg(feature = "python")]
 pub mod python_udf;
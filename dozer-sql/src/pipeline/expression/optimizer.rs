@@ -0,0 +1,144 @@
+use crate::pipeline::expression::datetime::DateTimeFunctionType;
+use crate::pipeline::expression::execution::{get_field_type, Expression, ExpressionExecutor};
+use dozer_types::types::{Record, Schema};
+
+/// Replaces literal-only sub-expressions, such as `2 * 3`, with their value so they are evaluated
+/// once at plan time instead of for every record.
+///
+/// `NOW()`, aggregations and user-defined functions are never folded. A sub-expression is also left as is if
+/// evaluating it fails, so the error surfaces at runtime like it would without folding, or if its
+/// value doesn't have the type the planner inferred for it.
+pub fn fold_constants(expression: Expression) -> Expression {
+    let expression = fold_children(expression);
+    if is_foldable(&expression) {
+        try_fold(expression)
+    } else {
+        expression
+    }
+}
+
+fn fold_all(expressions: Vec<Expression>) -> Vec<Expression> {
+    expressions.into_iter().map(fold_constants).collect()
+}
+
+fn fold_boxed(expression: Box<Expression>) -> Box<Expression> {
+    Box::new(fold_constants(*expression))
+}
+
+fn fold_children(expression: Expression) -> Expression {
+    match expression {
+        Expression::Column { .. } | Expression::Literal(_) | Expression::Now { .. } => expression,
+        Expression::UnaryOperator { operator, arg } => Expression::UnaryOperator {
+            operator,
+            arg: fold_boxed(arg),
+        },
+        Expression::BinaryOperator {
+            left,
+            operator,
+            right,
+        } => Expression::BinaryOperator {
+            left: fold_boxed(left),
+            operator,
+            right: fold_boxed(right),
+        },
+        Expression::ScalarFunction { fun, args } => Expression::ScalarFunction {
+            fun,
+            args: fold_all(args),
+        },
+        Expression::GeoFunction { fun, args } => Expression::GeoFunction {
+            fun,
+            args: fold_all(args),
+        },
+        Expression::ConditionalExpression { fun, args } => Expression::ConditionalExpression {
+            fun,
+            args: fold_all(args),
+        },
+        Expression::DateTimeFunction { fun, arg } => Expression::DateTimeFunction {
+            fun,
+            arg: fold_boxed(arg),
+        },
+        Expression::AggregateFunction { fun, args } => Expression::AggregateFunction {
+            fun,
+            args: fold_all(args),
+        },
+        Expression::Cast { arg, typ } => Expression::Cast {
+            arg: fold_boxed(arg),
+            typ,
+        },
+        Expression::Trim { arg, what, typ } => Expression::Trim {
+            arg: fold_boxed(arg),
+            what: what.map(fold_boxed),
+            typ,
+        },
+        Expression::Like {
+            arg,
+            pattern,
+            escape,
+        } => Expression::Like {
+            arg: fold_boxed(arg),
+            pattern: fold_boxed(pattern),
+            escape,
+        },
+        Expression::Udf { fun, args } => Expression::Udf {
+            fun,
+            args: fold_all(args),
+        },
+        #[cfg(feature = "python")]
+        Expression::PythonUDF {
+            name,
+            args,
+            return_type,
+        } => Expression::PythonUDF {
+            name,
+            args: fold_all(args),
+            return_type,
+        },
+    }
+}
+
+/// Whether `expression` is deterministic and all its operands are literals.
+fn is_foldable(expression: &Expression) -> bool {
+    let operands: Vec<&Expression> = match expression {
+        Expression::Column { .. }
+        | Expression::Literal(_)
+        | Expression::Now { .. }
+        | Expression::AggregateFunction { .. }
+        | Expression::Udf { .. } => return false,
+        #[cfg(feature = "python")]
+        Expression::PythonUDF { .. } => return false,
+        Expression::DateTimeFunction {
+            fun: DateTimeFunctionType::Now,
+            ..
+        } => return false,
+        Expression::UnaryOperator { arg, .. }
+        | Expression::DateTimeFunction { arg, .. }
+        | Expression::Cast { arg, .. } => vec![arg.as_ref()],
+        Expression::BinaryOperator { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+        Expression::ScalarFunction { args, .. }
+        | Expression::GeoFunction { args, .. }
+        | Expression::ConditionalExpression { args, .. } => args.iter().collect(),
+        Expression::Trim { arg, what, .. } => {
+            let mut operands = vec![arg.as_ref()];
+            operands.extend(what.as_deref());
+            operands
+        }
+        Expression::Like { arg, pattern, .. } => vec![arg.as_ref(), pattern.as_ref()],
+    };
+    operands
+        .iter()
+        .all(|operand| matches!(operand, Expression::Literal(_)))
+}
+
+fn try_fold(expression: Expression) -> Expression {
+    let schema = Schema::empty();
+    let record = Record::new(None, vec![]);
+    match (
+        expression.evaluate(&record, &schema),
+        expression.get_type(&schema),
+    ) {
+        (Ok(value), Ok(typ)) if get_field_type(&value) == Some(typ.return_type) => {
+            Expression::Literal(value)
+        }
+        _ => expression,
+    }
+}
@@ -1,9 +1,13 @@
 use crate::argv;
-use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::{FieldTypes, PipelineError};
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
-use crate::pipeline::expression::scalar::number::{evaluate_abs, evaluate_round};
+use crate::pipeline::expression::scalar::json::{evaluate_json_extract, validate_json_extract};
+use crate::pipeline::expression::scalar::number::{
+    evaluate_abs, evaluate_round, round_keeps_fraction,
+};
 use crate::pipeline::expression::scalar::string::{
-    evaluate_concat, evaluate_length, evaluate_ucase, validate_concat, validate_ucase,
+    evaluate_concat, evaluate_length, evaluate_lower, evaluate_ucase, validate_concat,
+    validate_lower, validate_ucase,
 };
 use dozer_types::types::{Field, FieldType, Record, Schema};
 use std::fmt::{Display, Formatter};
@@ -13,8 +17,15 @@ pub enum ScalarFunctionType {
     Abs,
     Round,
     Ucase,
+    /// `LOWER`, or `LCASE`.
+    Lower,
     Concat,
     Length,
+    JsonExtract,
+    /// `GROUPING(dimension, ...)`, whose bits tell which of its `GROUP BY` expressions are
+    /// aggregated away in the row's grouping set. The aggregation processor replaces it by its
+    /// value for each grouping set, so it's never evaluated on a record.
+    Grouping,
 }
 
 impl Display for ScalarFunctionType {
@@ -23,8 +34,11 @@ impl Display for ScalarFunctionType {
             ScalarFunctionType::Abs => f.write_str("ABS"),
             ScalarFunctionType::Round => f.write_str("ROUND"),
             ScalarFunctionType::Ucase => f.write_str("UCASE"),
+            ScalarFunctionType::Lower => f.write_str("LOWER"),
             ScalarFunctionType::Concat => f.write_str("CONCAT"),
             ScalarFunctionType::Length => f.write_str("LENGTH"),
+            ScalarFunctionType::JsonExtract => f.write_str("JSON_EXTRACT"),
+            ScalarFunctionType::Grouping => f.write_str("GROUPING"),
         }
     }
 }
@@ -37,9 +51,45 @@ pub(crate) fn get_scalar_function_type(
     match function {
         ScalarFunctionType::Abs => argv!(args, 0, ScalarFunctionType::Abs)?.get_type(schema),
         ScalarFunctionType::Round => {
-            let return_type = argv!(args, 0, ScalarFunctionType::Round)?
+            let arg_type = argv!(args, 0, ScalarFunctionType::Round)?
                 .get_type(schema)?
                 .return_type;
+            let return_type = match arg_type {
+                FieldType::UInt | FieldType::U128 | FieldType::Int | FieldType::I128
+                    if round_keeps_fraction(args.get(1)) =>
+                {
+                    FieldType::Decimal
+                }
+                FieldType::UInt
+                | FieldType::U128
+                | FieldType::Int
+                | FieldType::I128
+                | FieldType::Float
+                | FieldType::Decimal => arg_type,
+                FieldType::Boolean
+                | FieldType::String
+                | FieldType::Text
+                | FieldType::Binary
+                | FieldType::Timestamp
+                | FieldType::Date
+                | FieldType::Json
+                | FieldType::Point
+                | FieldType::Duration => {
+                    return Err(PipelineError::InvalidFunctionArgumentType(
+                        ScalarFunctionType::Round.to_string(),
+                        arg_type,
+                        FieldTypes::new(vec![
+                            FieldType::UInt,
+                            FieldType::U128,
+                            FieldType::Int,
+                            FieldType::I128,
+                            FieldType::Float,
+                            FieldType::Decimal,
+                        ]),
+                        0,
+                    ));
+                }
+            };
             Ok(ExpressionType::new(
                 return_type,
                 true,
@@ -50,6 +100,9 @@ pub(crate) fn get_scalar_function_type(
         ScalarFunctionType::Ucase => {
             validate_ucase(argv!(args, 0, ScalarFunctionType::Ucase)?, schema)
         }
+        ScalarFunctionType::Lower => {
+            validate_lower(argv!(args, 0, ScalarFunctionType::Lower)?, schema)
+        }
         ScalarFunctionType::Concat => validate_concat(args, schema),
         ScalarFunctionType::Length => Ok(ExpressionType::new(
             FieldType::UInt,
@@ -57,6 +110,16 @@ pub(crate) fn get_scalar_function_type(
             dozer_types::types::SourceDefinition::Dynamic,
             false,
         )),
+        ScalarFunctionType::JsonExtract => validate_json_extract(args, schema),
+        ScalarFunctionType::Grouping => {
+            argv!(args, 0, ScalarFunctionType::Grouping)?;
+            Ok(ExpressionType::new(
+                FieldType::UInt,
+                false,
+                dozer_types::types::SourceDefinition::Dynamic,
+                false,
+            ))
+        }
     }
 }
 
@@ -66,8 +129,11 @@ impl ScalarFunctionType {
             "abs" => Ok(ScalarFunctionType::Abs),
             "round" => Ok(ScalarFunctionType::Round),
             "ucase" => Ok(ScalarFunctionType::Ucase),
+            "lower" | "lcase" => Ok(ScalarFunctionType::Lower),
             "concat" => Ok(ScalarFunctionType::Concat),
             "length" => Ok(ScalarFunctionType::Length),
+            "json_extract" => Ok(ScalarFunctionType::JsonExtract),
+            "grouping" => Ok(ScalarFunctionType::Grouping),
             _ => Err(PipelineError::InvalidFunction(name.to_string())),
         }
     }
@@ -91,10 +157,22 @@ impl ScalarFunctionType {
             ScalarFunctionType::Ucase => {
                 evaluate_ucase(schema, argv!(args, 0, ScalarFunctionType::Ucase)?, record)
             }
+            ScalarFunctionType::Lower => {
+                evaluate_lower(schema, argv!(args, 0, ScalarFunctionType::Lower)?, record)
+            }
             ScalarFunctionType::Concat => evaluate_concat(schema, args, record),
             ScalarFunctionType::Length => {
                 evaluate_length(schema, argv!(args, 0, ScalarFunctionType::Length)?, record)
             }
+            ScalarFunctionType::JsonExtract => evaluate_json_extract(
+                schema,
+                argv!(args, 0, ScalarFunctionType::JsonExtract)?,
+                argv!(args, 1, ScalarFunctionType::JsonExtract)?,
+                record,
+            ),
+            ScalarFunctionType::Grouping => Err(PipelineError::InvalidQuery(
+                "GROUPING can only be used in a query with GROUP BY".to_string(),
+            )),
         }
     }
 }
@@ -0,0 +1,118 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::arg_utils::validate_arg_type;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use crate::{arg_str, argv};
+use dozer_types::json_types::JsonValue;
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+
+/// A step of a JSON path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum JsonPathStep {
+    Key(String),
+    Index(usize),
+}
+
+pub(crate) fn validate_json_extract(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    if args.len() > 2 {
+        return Err(PipelineError::TooManyArguments(
+            ScalarFunctionType::JsonExtract.to_string(),
+        ));
+    }
+    let json = argv!(args, 0, ScalarFunctionType::JsonExtract)?;
+    let path = argv!(args, 1, ScalarFunctionType::JsonExtract)?;
+    validate_arg_type(
+        json,
+        vec![FieldType::Json],
+        schema,
+        ScalarFunctionType::JsonExtract,
+        0,
+    )?;
+    validate_arg_type(
+        path,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        ScalarFunctionType::JsonExtract,
+        1,
+    )?;
+    Ok(ExpressionType::new(
+        FieldType::Json,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// Returns the value at `path` in `json`, or `NULL` if there is none.
+pub(crate) fn evaluate_json_extract(
+    schema: &Schema,
+    json: &Expression,
+    path: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let json = json.evaluate(record, schema)?;
+    let path = path.evaluate(record, schema)?;
+    if json == Field::Null || path == Field::Null {
+        return Ok(Field::Null);
+    }
+
+    let Field::Json(json) = json else {
+        return Err(PipelineError::InvalidFunctionArgument(
+            ScalarFunctionType::JsonExtract.to_string(),
+            json,
+            0,
+        ));
+    };
+    let path = arg_str!(path, ScalarFunctionType::JsonExtract, 1)?;
+    let steps = parse_json_path(&path)?;
+
+    let mut value = &json;
+    for step in &steps {
+        let next = match (step, value) {
+            (JsonPathStep::Key(key), JsonValue::Object(object)) => object.get(key),
+            (JsonPathStep::Index(index), JsonValue::Array(array)) => array.get(*index),
+            _ => None,
+        };
+        match next {
+            Some(next) => value = next,
+            None => return Ok(Field::Null),
+        }
+    }
+    Ok(Field::Json(value.clone()))
+}
+
+/// Parses a path such as `$.orders[0].items['unit price']`.
+pub(crate) fn parse_json_path(path: &str) -> Result<Vec<JsonPathStep>, PipelineError> {
+    let invalid = || PipelineError::InvalidArgument(format!("Invalid JSON path: {path}"));
+
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut steps = vec![];
+    while !rest.is_empty() {
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            steps.push(JsonPathStep::Key(after_dot[..end].to_string()));
+            rest = &after_dot[end..];
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let end = after_bracket.find(']').ok_or_else(invalid)?;
+            let selector = &after_bracket[..end];
+            let quoted = selector
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| selector.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+            steps.push(match quoted {
+                Some(key) => JsonPathStep::Key(key.to_string()),
+                None => JsonPathStep::Index(selector.parse().map_err(|_| invalid())?),
+            });
+            rest = &after_bracket[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(steps)
+}
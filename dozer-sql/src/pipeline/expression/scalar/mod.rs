@@ -1,3 +1,4 @@
 pub mod common;
+pub mod json;
 pub mod number;
 pub mod string;
@@ -3,8 +3,9 @@ use crate::pipeline::errors::PipelineError::InvalidFunctionArgument;
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::{Field, FieldType, Record, Schema};
-use num_traits::{Float, ToPrimitive};
+use num_traits::{Float, FromPrimitive, ToPrimitive};
 
 pub(crate) fn evaluate_abs(
     schema: &Schema,
@@ -36,6 +37,45 @@ pub(crate) fn evaluate_abs(
     }
 }
 
+/// Returns if `ROUND` called with the `decimals` argument can produce fractional digits.
+///
+/// Integer inputs are promoted to `Decimal` in that case, so the result type doesn't depend on
+/// the value of `decimals`. Only numeric literals are known at planning time; any other expression
+/// is assumed to be positive.
+pub(crate) fn round_keeps_fraction(decimals: Option<&Expression>) -> bool {
+    match decimals {
+        None => false,
+        Some(Expression::Literal(field)) => {
+            matches!(get_round_places(field), Ok(places) if places > 0)
+        }
+        Some(_) => true,
+    }
+}
+
+fn get_round_places(field: &Field) -> Result<i32, PipelineError> {
+    match field {
+        Field::UInt(u) => Ok(*u as i32),
+        Field::U128(u) => Ok(*u as i32),
+        Field::Int(i) => Ok(*i as i32),
+        Field::I128(i) => Ok(*i as i32),
+        Field::Float(f) => Ok(f.round().0 as i32),
+        Field::Decimal(d) => d.to_i32().ok_or(PipelineError::InvalidCast {
+            from: field.clone(),
+            to: FieldType::Decimal,
+        }),
+        Field::Boolean(_)
+        | Field::String(_)
+        | Field::Text(_)
+        | Field::Date(_)
+        | Field::Timestamp(_)
+        | Field::Binary(_)
+        | Field::Json(_)
+        | Field::Point(_)
+        | Field::Duration(_)
+        | Field::Null => Ok(0), // Truncate value to 0 decimals
+    }
+}
+
 pub(crate) fn evaluate_round(
     schema: &Schema,
     arg: &Expression,
@@ -43,39 +83,25 @@ pub(crate) fn evaluate_round(
     record: &Record,
 ) -> Result<Field, PipelineError> {
     let value = arg.evaluate(record, schema)?;
-    let mut places = 0;
-    if let Some(expression) = decimals {
-        let field = expression.evaluate(record, schema)?;
-        match field {
-            Field::UInt(u) => places = u as i32,
-            Field::U128(u) => places = u as i32,
-            Field::Int(i) => places = i as i32,
-            Field::I128(i) => places = i as i32,
-            Field::Float(f) => places = f.round().0 as i32,
-            Field::Decimal(d) => {
-                places = d
-                    .to_i32()
-                    .ok_or(PipelineError::InvalidCast {
-                        from: field,
-                        to: FieldType::Decimal,
-                    })
-                    .unwrap()
-            }
-            Field::Boolean(_)
-            | Field::String(_)
-            | Field::Text(_)
-            | Field::Date(_)
-            | Field::Timestamp(_)
-            | Field::Binary(_)
-            | Field::Json(_)
-            | Field::Point(_)
-            | Field::Duration(_)
-            | Field::Null => {} // Truncate value to 0 decimals
-        }
-    }
+    let places = match decimals {
+        Some(expression) => get_round_places(&expression.evaluate(record, schema)?)?,
+        None => 0,
+    };
     let order = OrderedFloat(10.0_f64.powi(places));
+    let promote = round_keeps_fraction(decimals);
+    let to_decimal = |d: Option<Decimal>| {
+        d.map(Field::Decimal)
+            .ok_or_else(|| PipelineError::InvalidCast {
+                from: value.clone(),
+                to: FieldType::Decimal,
+            })
+    };
 
     match value {
+        Field::UInt(u) if promote => to_decimal(Decimal::from_u64(u)),
+        Field::U128(u) if promote => to_decimal(Decimal::from_u128(u)),
+        Field::Int(i) if promote => to_decimal(Decimal::from_i64(i)),
+        Field::I128(i) if promote => to_decimal(Decimal::from_i128(i)),
         Field::UInt(u) => Ok(Field::UInt(u)),
         Field::U128(u) => Ok(Field::U128(u)),
         Field::Int(i) => Ok(Field::Int(i)),
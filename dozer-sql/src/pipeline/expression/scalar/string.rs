@@ -31,8 +31,34 @@ pub(crate) fn evaluate_ucase(
 ) -> Result<Field, PipelineError> {
     let f = arg.evaluate(record, schema)?;
     let v = arg_str!(f, ScalarFunctionType::Ucase, 0)?;
-    let ret = v.to_uppercase();
+    string_like(v.to_uppercase(), arg, schema)
+}
+
+pub(crate) fn validate_lower(
+    arg: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    validate_arg_type(
+        arg,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        ScalarFunctionType::Lower,
+        0,
+    )
+}
+
+pub(crate) fn evaluate_lower(
+    schema: &Schema,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    let v = arg_str!(f, ScalarFunctionType::Lower, 0)?;
+    string_like(v.to_lowercase(), arg, schema)
+}
 
+/// Returns `ret` as a `String` if `arg` is one, and as a `Text` otherwise.
+fn string_like(ret: String, arg: &Expression, schema: &Schema) -> Result<Field, PipelineError> {
     Ok(match arg.get_type(schema)?.return_type {
         FieldType::String => Field::String(ret),
         FieldType::UInt
@@ -120,7 +146,11 @@ pub(crate) fn evaluate_length(
     record: &Record,
 ) -> Result<Field, PipelineError> {
     let f0 = arg0.evaluate(record, schema)?;
-    let v0 = arg_str!(f0, ScalarFunctionType::Concat, 0)?;
+    // The length of a binary value is its number of bytes, not that of its text rendering.
+    if let Field::Binary(bytes) = &f0 {
+        return Ok(Field::UInt(bytes.len() as u64));
+    }
+    let v0 = arg_str!(f0, ScalarFunctionType::Length, 0)?;
     Ok(Field::UInt(v0.len() as u64))
 }
 
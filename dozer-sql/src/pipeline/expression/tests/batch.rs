@@ -0,0 +1,113 @@
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::expression::operator::BinaryOperatorType;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+fn schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "a".to_string(),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "b".to_string(),
+                FieldType::Float,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn records() -> Vec<Record> {
+    vec![
+        Record::new(None, vec![Field::Int(1), Field::Float(OrderedFloat(0.5))]),
+        Record::new(None, vec![Field::Null, Field::Float(OrderedFloat(-2.0))]),
+        Record::new(None, vec![Field::Int(-7), Field::Null]),
+        Record::new(None, vec![Field::Null, Field::Null]),
+    ]
+}
+
+fn binary(left: Expression, operator: BinaryOperatorType, right: Expression) -> Expression {
+    Expression::BinaryOperator {
+        left: Box::new(left),
+        operator,
+        right: Box::new(right),
+    }
+}
+
+fn assert_same_as_per_row(expression: &Expression, records: &[Record], schema: &Schema) {
+    let per_row = records
+        .iter()
+        .map(|record| expression.evaluate(record, schema))
+        .collect::<Result<Vec<_>, _>>();
+    let batch = expression.evaluate_batch(records, schema);
+    match (per_row, batch) {
+        (Ok(per_row), Ok(batch)) => assert_eq!(per_row, batch),
+        (Err(per_row), Err(batch)) => assert_eq!(per_row.to_string(), batch.to_string()),
+        (per_row, batch) => panic!("per row: {per_row:?}, batch: {batch:?}"),
+    }
+}
+
+#[test]
+fn test_batch_matches_per_row_evaluation() {
+    let schema = schema();
+    let records = records();
+
+    let a = || Expression::Column { index: 0 };
+    let b = || Expression::Column { index: 1 };
+    let expressions = vec![
+        Expression::Literal(Field::Int(42)),
+        a(),
+        binary(
+            a(),
+            BinaryOperatorType::Add,
+            Expression::Literal(Field::Int(1)),
+        ),
+        binary(a(), BinaryOperatorType::Mul, b()),
+        binary(
+            binary(
+                a(),
+                BinaryOperatorType::Gt,
+                Expression::Literal(Field::Int(0)),
+            ),
+            BinaryOperatorType::Or,
+            binary(
+                b(),
+                BinaryOperatorType::Lt,
+                Expression::Literal(Field::Int(0)),
+            ),
+        ),
+        Expression::ScalarFunction {
+            fun: ScalarFunctionType::Abs,
+            args: vec![binary(a(), BinaryOperatorType::Sub, b())],
+        },
+    ];
+
+    for expression in &expressions {
+        assert_same_as_per_row(expression, &records, &schema);
+        assert_same_as_per_row(expression, &[], &schema);
+    }
+}
+
+#[test]
+fn test_batch_reports_per_row_errors() {
+    let schema = schema();
+    let records = records();
+
+    let expression = binary(
+        Expression::Column { index: 0 },
+        BinaryOperatorType::Add,
+        Expression::Column { index: 5 },
+    );
+    assert!(expression.evaluate_batch(&records, &schema).is_err());
+    assert_same_as_per_row(&expression, &records, &schema);
+}
@@ -1,9 +1,12 @@
-use crate::pipeline::expression::datetime::evaluate_date_part;
-use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::builder::ExpressionBuilder;
+use crate::pipeline::expression::datetime::{evaluate_date_part, DateTimeFunctionType};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
 use crate::pipeline::expression::mathematical::{
     evaluate_add, evaluate_div, evaluate_mod, evaluate_mul, evaluate_sub,
 };
 use crate::pipeline::expression::tests::test_common::*;
+use crate::pipeline::tests::utils::get_select;
 use dozer_types::chrono;
 use dozer_types::chrono::{DateTime, Datelike, NaiveDate};
 use dozer_types::types::{
@@ -11,7 +14,7 @@ use dozer_types::types::{
 };
 use num_traits::ToPrimitive;
 use proptest::prelude::*;
-use sqlparser::ast::DateTimeField;
+use sqlparser::ast::{DateTimeField, SelectItem};
 
 #[test]
 fn test_time() {
@@ -154,6 +157,7 @@ fn test_duration_math(d1: u64, d2: u64, dt1: ArbitraryDateTime) {
     let row = Record::new(None, vec![]);
 
     let v = Expression::Literal(Field::Date(dt1.0.date_naive()));
+    let midnight = dt1.0.date_naive().and_hms_opt(0, 0, 0).unwrap();
     let dur1 = Expression::Literal(Field::Duration(DozerDuration(
         std::time::Duration::from_nanos(d1),
         TimeUnit::Nanoseconds,
@@ -191,45 +195,56 @@ fn test_duration_math(d1: u64, d2: u64, dt1: ArbitraryDateTime) {
     let result = evaluate_mod(&Schema::empty(), &dur1, &dur2, &row);
     assert!(result.is_err());
 
-    // Duration + Timestamp = Error
+    // Duration + Date = Date
     let result = evaluate_add(&Schema::empty(), &dur1, &v, &row);
-    assert!(result.is_err());
-    // Duration - Timestamp = Error
+    let sum = midnight
+        .checked_add_signed(
+            chrono::Duration::from_std(std::time::Duration::from_nanos(d1)).unwrap(),
+        )
+        .map(|ts| ts.date());
+    if result.is_ok() && sum.is_some() {
+        assert_eq!(result.unwrap(), Field::Date(sum.unwrap()));
+    }
+    // Duration - Date = Error
     let result = evaluate_sub(&Schema::empty(), &dur1, &v, &row);
     assert!(result.is_err());
-    // Duration * Timestamp = Error
+    // Duration * Date = Error
     let result = evaluate_mul(&Schema::empty(), &dur1, &v, &row);
     assert!(result.is_err());
-    // Duration / Timestamp = Error
+    // Duration / Date = Error
     let result = evaluate_div(&Schema::empty(), &dur1, &v, &row);
     assert!(result.is_err());
-    // Duration % Timestamp = Error
+    // Duration % Date = Error
     let result = evaluate_mod(&Schema::empty(), &dur1, &v, &row);
     assert!(result.is_err());
 
-    // Timestamp + Duration = Timestamp
+    // Date + Duration = Date
     let result = evaluate_add(&Schema::empty(), &v, &dur1, &row);
-    let sum = dt1
-        .0
-        .checked_add_signed(chrono::Duration::nanoseconds(d1 as i64));
+    let sum = midnight
+        .checked_add_signed(
+            chrono::Duration::from_std(std::time::Duration::from_nanos(d1)).unwrap(),
+        )
+        .map(|ts| ts.date());
     if result.is_ok() && sum.is_some() {
-        assert_eq!(result.unwrap(), Field::Timestamp(sum.unwrap()));
+        assert_eq!(result.unwrap(), Field::Date(sum.unwrap()));
     }
-    // Timestamp - Duration = Timestamp
+    // Date - Duration = Date
     let result = evaluate_sub(&Schema::empty(), &v, &dur2, &row);
-    let diff = dt1
-        .0
-        .checked_sub_signed(chrono::Duration::nanoseconds(d2 as i64));
+    let diff = midnight
+        .checked_sub_signed(
+            chrono::Duration::from_std(std::time::Duration::from_nanos(d2)).unwrap(),
+        )
+        .map(|ts| ts.date());
     if result.is_ok() && diff.is_some() {
-        assert_eq!(result.unwrap(), Field::Timestamp(diff.unwrap()));
+        assert_eq!(result.unwrap(), Field::Date(diff.unwrap()));
     }
-    // Timestamp * Duration = Error
+    // Date * Duration = Error
     let result = evaluate_mul(&Schema::empty(), &v, &dur1, &row);
     assert!(result.is_err());
-    // Timestamp / Duration = Error
+    // Date / Duration = Error
     let result = evaluate_div(&Schema::empty(), &v, &dur1, &row);
     assert!(result.is_err());
-    // Timestamp % Duration = Error
+    // Date % Duration = Error
     let result = evaluate_mod(&Schema::empty(), &v, &dur1, &row);
     assert!(result.is_err());
 }
@@ -303,6 +318,222 @@ fn test_interval() {
     );
 }
 
+fn temporal_schema(field_type: FieldType) -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("t"),
+                field_type,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn date(value: &str) -> Field {
+    Field::Date(NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap())
+}
+
+fn timestamp(value: &str) -> Field {
+    Field::Timestamp(DateTime::parse_from_rfc3339(value).unwrap())
+}
+
+#[test]
+fn test_interval_days_and_hours() {
+    let ts_schema = temporal_schema(FieldType::Timestamp);
+    let f = run_fct(
+        "SELECT t + INTERVAL '1' DAY FROM users",
+        ts_schema.clone(),
+        vec![timestamp("2023-01-31T23:30:00Z")],
+    );
+    assert_eq!(f, timestamp("2023-02-01T23:30:00Z"));
+
+    let f = run_fct(
+        "SELECT t - INTERVAL '2 hours' FROM users",
+        ts_schema.clone(),
+        vec![timestamp("2023-01-01T01:00:00+02:00")],
+    );
+    assert_eq!(f, timestamp("2022-12-31T23:00:00+02:00"));
+
+    let f = run_fct(
+        "SELECT INTERVAL '1 week' + t FROM users",
+        ts_schema,
+        vec![timestamp("2023-02-25T12:00:00Z")],
+    );
+    assert_eq!(f, timestamp("2023-03-04T12:00:00Z"));
+
+    let date_schema = temporal_schema(FieldType::Date);
+    let f = run_fct(
+        "SELECT t + INTERVAL '1 day' FROM users",
+        date_schema.clone(),
+        vec![date("2024-02-28")],
+    );
+    assert_eq!(f, date("2024-02-29"));
+
+    // The time of day of the result is dropped.
+    let f = run_fct(
+        "SELECT t - INTERVAL '1' HOUR FROM users",
+        date_schema,
+        vec![date("2023-03-01")],
+    );
+    assert_eq!(f, date("2023-02-28"));
+}
+
+#[test]
+fn test_interval_months() {
+    let date_schema = temporal_schema(FieldType::Date);
+    for (sql, input, expected) in [
+        (
+            "SELECT t + INTERVAL '1' MONTH FROM users",
+            "2023-01-31",
+            "2023-02-28",
+        ),
+        (
+            "SELECT t + INTERVAL '1 month' FROM users",
+            "2024-01-31",
+            "2024-02-29",
+        ),
+        (
+            "SELECT t - INTERVAL '1' MONTH FROM users",
+            "2023-03-31",
+            "2023-02-28",
+        ),
+        (
+            "SELECT t + INTERVAL '13 months' FROM users",
+            "2023-05-31",
+            "2024-06-30",
+        ),
+        (
+            "SELECT INTERVAL '1' QUARTER + t FROM users",
+            "2023-11-30",
+            "2024-02-29",
+        ),
+        (
+            "SELECT t + INTERVAL '1' YEAR FROM users",
+            "2024-02-29",
+            "2025-02-28",
+        ),
+        (
+            "SELECT t - INTERVAL '4 years' FROM users",
+            "2024-02-29",
+            "2020-02-29",
+        ),
+    ] {
+        let f = run_fct(sql, date_schema.clone(), vec![date(input)]);
+        assert_eq!(f, date(expected), "{sql} on {input}");
+    }
+
+    // Timestamps keep their time of day and offset.
+    let f = run_fct(
+        "SELECT t + INTERVAL '1 month' FROM users",
+        temporal_schema(FieldType::Timestamp),
+        vec![timestamp("2023-01-31T10:15:00+02:00")],
+    );
+    assert_eq!(f, timestamp("2023-02-28T10:15:00+02:00"));
+}
+
+#[test]
+fn test_timestamp_difference() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("ts1"),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("ts2"),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let f = run_fct(
+        "SELECT ts1 - ts2 FROM users",
+        schema.clone(),
+        vec![
+            timestamp("2023-03-01T01:00:00+01:00"),
+            timestamp("2023-02-28T00:00:00Z"),
+        ],
+    );
+    assert_eq!(
+        f,
+        Field::Duration(DozerDuration(
+            std::time::Duration::from_secs(24 * 60 * 60),
+            TimeUnit::Nanoseconds
+        ))
+    );
+
+    let f = run_fct(
+        "SELECT ts1 - ts2 FROM users",
+        schema,
+        vec![
+            timestamp("2023-02-28T00:00:00Z"),
+            timestamp("2023-02-28T00:00:00Z"),
+        ],
+    );
+    assert_eq!(
+        f,
+        Field::Duration(DozerDuration(
+            std::time::Duration::ZERO,
+            TimeUnit::Nanoseconds
+        ))
+    );
+}
+
+#[test]
+fn test_interval_requires_temporal_operand() {
+    let schema = temporal_schema(FieldType::Int);
+    for sql in [
+        "SELECT t + INTERVAL '1' DAY FROM users",
+        "SELECT t - INTERVAL '1 month' FROM users",
+        "SELECT INTERVAL '1' DAY * t FROM users",
+    ] {
+        let mut builder = ExpressionBuilder::new(schema.fields.len());
+        let expression = match &get_select(sql).unwrap().projection[0] {
+            SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema).unwrap(),
+            _ => panic!("Invalid expr"),
+        };
+        assert!(
+            matches!(
+                expression.get_type(&schema),
+                Err(PipelineError::InvalidExpression(_))
+            ),
+            "{sql}"
+        );
+    }
+
+    let month = Expression::DateTimeFunction {
+        fun: DateTimeFunctionType::Interval {
+            field: DateTimeField::Month,
+        },
+        arg: Box::new(Expression::Literal(Field::String("1".to_string()))),
+    };
+    let row = Record::new(None, vec![]);
+    assert!(matches!(
+        evaluate_add(
+            &Schema::empty(),
+            &Expression::Literal(Field::Int(1)),
+            &month,
+            &row
+        ),
+        Err(PipelineError::InvalidOperandType(_))
+    ));
+    assert!(matches!(
+        month.evaluate(&row, &Schema::empty()),
+        Err(PipelineError::InvalidOperandType(_))
+    ));
+}
+
 #[test]
 fn test_now() {
     let f = run_fct(
@@ -1,11 +1,12 @@
+use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::builder::ExpressionBuilder;
-use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
 use crate::pipeline::expression::operator::BinaryOperatorType;
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use crate::pipeline::tests::utils::get_select;
 
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
-use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
 use sqlparser::ast::SelectItem;
 
 #[test]
@@ -508,3 +509,180 @@ fn test_alias_resolution() {
         }
     );
 }
+
+fn get_join_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Table {
+                    connection: "connection1".to_string(),
+                    name: "t0".to_string(),
+                },
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Table {
+                    connection: "connection2".to_string(),
+                    name: "t1".to_string(),
+                },
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Table {
+                    connection: "connection2".to_string(),
+                    name: "t1".to_string(),
+                },
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+fn build_first_projection(sql: &str, schema: &Schema) -> Result<Expression, PipelineError> {
+    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    match &get_select(sql).unwrap().projection[0] {
+        SelectItem::UnnamedExpr(e) => builder.build(true, e, schema),
+        _ => panic!("Invalid expr"),
+    }
+}
+
+#[test]
+fn test_qualified_name_resolution() {
+    let schema = get_join_schema();
+
+    assert_eq!(
+        build_first_projection("SELECT t0.id FROM t0", &schema).unwrap(),
+        Expression::Column { index: 0 }
+    );
+    assert_eq!(
+        build_first_projection("SELECT t1.id FROM t0", &schema).unwrap(),
+        Expression::Column { index: 1 }
+    );
+    assert_eq!(
+        build_first_projection("SELECT connection2.t1.id FROM t0", &schema).unwrap(),
+        Expression::Column { index: 1 }
+    );
+    assert_eq!(
+        build_first_projection("SELECT name FROM t0", &schema).unwrap(),
+        Expression::Column { index: 2 }
+    );
+}
+
+#[test]
+fn test_ambiguous_and_unknown_name_resolution() {
+    let schema = get_join_schema();
+
+    assert!(matches!(
+        build_first_projection("SELECT id FROM t0", &schema),
+        Err(PipelineError::AmbiguousFieldIdentifier(name)) if name == "id"
+    ));
+    assert!(matches!(
+        build_first_projection("SELECT t2.id FROM t0", &schema),
+        Err(PipelineError::UnknownFieldIdentifier(name)) if name == "t2.id"
+    ));
+    assert!(matches!(
+        build_first_projection("SELECT t0.name FROM t0", &schema),
+        Err(PipelineError::UnknownFieldIdentifier(name)) if name == "t0.name"
+    ));
+    assert!(matches!(
+        build_first_projection("SELECT connection2.t0.id FROM t0", &schema),
+        Err(PipelineError::UnknownFieldIdentifier(name)) if name == "connection2.t0.id"
+    ));
+}
+
+#[test]
+fn test_out_of_range_column_index() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "a".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "b".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+
+    // Aggregation results placed over the source columns are rejected at build time.
+    let mut builder = ExpressionBuilder::new(1);
+    let select = get_select("SELECT SUM(b) FROM t0").unwrap();
+    let SelectItem::UnnamedExpr(e) = &select.projection[0] else {
+        panic!("Invalid expr");
+    };
+    assert!(matches!(
+        builder.build(true, e, &schema),
+        Err(PipelineError::InvalidColumnIndex {
+            index: 1,
+            schema_len: 2
+        })
+    ));
+
+    // Evaluating or typing a column past the record or schema is an error, not a panic.
+    let column = Expression::Column { index: 2 };
+    let record = Record::new(None, vec![Field::Int(1), Field::Int(2)]);
+    assert!(matches!(
+        column.evaluate(&record, &schema),
+        Err(PipelineError::InvalidColumnIndex {
+            index: 2,
+            schema_len: 2
+        })
+    ));
+    assert!(matches!(
+        column.get_type(&schema),
+        Err(PipelineError::InvalidColumnIndex {
+            index: 2,
+            schema_len: 2
+        })
+    ));
+}
+
+#[test]
+fn test_unsupported_sql_constructs() {
+    let schema = get_join_schema();
+
+    for (sql, expected_construct) in [
+        (
+            "SELECT ROW_NUMBER() OVER (PARTITION BY name) FROM t0",
+            "window functions",
+        ),
+        ("SELECT SUM(t0.id) OVER () FROM t0", "window functions"),
+        (
+            "SELECT CASE WHEN t0.id > 1 THEN name END FROM t0",
+            "CASE expressions",
+        ),
+        ("SELECT t0.id IN (1, 2) FROM t0", "IN lists"),
+        ("SELECT t0.id BETWEEN 1 AND 2 FROM t0", "BETWEEN"),
+        ("SELECT NULLIF(name, 'a') FROM t0", "NULLIF"),
+    ] {
+        match build_first_projection(sql, &schema) {
+            Err(PipelineError::UnsupportedSqlConstruct { construct, snippet }) => {
+                assert_eq!(construct, expected_construct, "{sql}");
+                assert!(sql.contains(&snippet), "{snippet} is not part of {sql}");
+            }
+            result => panic!("{sql} gave {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::scalar::json::{parse_json_path, JsonPathStep};
+use crate::pipeline::expression::tests::test_common::*;
+use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::json_types::{serde_json_to_json_value, JsonValue};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::serde_json::json;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+fn get_json_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "payload".to_string(),
+                FieldType::Json,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn get_payload() -> Field {
+    Field::Json(
+        serde_json_to_json_value(json!({
+            "customer": { "name": "Alice", "tags": ["vip", "eu"] },
+            "total": 42.5
+        }))
+        .unwrap(),
+    )
+}
+
+#[test]
+fn test_json_passes_through_projection() {
+    let f = run_fct(
+        "SELECT payload FROM users",
+        get_json_schema(),
+        vec![get_payload()],
+    );
+    assert_eq!(f, get_payload());
+}
+
+#[test]
+fn test_json_extract() {
+    let cases = [
+        (
+            "$.customer.name",
+            Field::Json(JsonValue::String("Alice".to_string())),
+        ),
+        (
+            "$.customer.tags[1]",
+            Field::Json(JsonValue::String("eu".to_string())),
+        ),
+        (
+            "$[\"total\"]",
+            Field::Json(JsonValue::Number(OrderedFloat(42.5))),
+        ),
+        ("$", get_payload()),
+        ("$.customer.email", Field::Null),
+        ("$.customer.tags[2]", Field::Null),
+        ("$.total.value", Field::Null),
+    ];
+    for (path, expected) in cases {
+        let f = run_fct(
+            &format!("SELECT JSON_EXTRACT(payload, '{path}') FROM users"),
+            get_json_schema(),
+            vec![get_payload()],
+        );
+        assert_eq!(f, expected, "{path}");
+    }
+
+    let f = run_fct(
+        "SELECT JSON_EXTRACT(payload, '$.customer') FROM users",
+        get_json_schema(),
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_parse_json_path() {
+    assert_eq!(
+        parse_json_path("$.a[0]['b c'][\"d\"]").unwrap(),
+        vec![
+            JsonPathStep::Key("a".to_string()),
+            JsonPathStep::Index(0),
+            JsonPathStep::Key("b c".to_string()),
+            JsonPathStep::Key("d".to_string()),
+        ]
+    );
+    for path in ["a.b", "$.", "$..a", "$[x]", "$[0", "$a"] {
+        assert!(
+            matches!(
+                parse_json_path(path),
+                Err(PipelineError::InvalidArgument(_))
+            ),
+            "{path}"
+        );
+    }
+}
+
+#[test]
+fn test_numeric_aggregation_over_json_is_rejected() {
+    for sql in [
+        "SELECT SUM(payload) FROM users",
+        "SELECT AVG(payload) FROM users",
+    ] {
+        let mut planner = CommonPlanner::new(get_json_schema());
+        assert!(matches!(
+            planner.plan(*get_select(sql).unwrap()),
+            Err(PipelineError::InvalidFunctionArgumentType(
+                _,
+                FieldType::Json,
+                _,
+                0
+            ))
+        ));
+    }
+}
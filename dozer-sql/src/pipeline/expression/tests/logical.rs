@@ -1,5 +1,7 @@
+use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::execution::Expression::Literal;
 use crate::pipeline::expression::logical::{evaluate_and, evaluate_not, evaluate_or};
+use crate::pipeline::expression::operator::BinaryOperatorType;
 use dozer_types::types::{Field, Record, Schema};
 use dozer_types::{ordered_float::OrderedFloat, rust_decimal::Decimal};
 #[cfg(test)]
@@ -15,8 +17,8 @@ fn test_logical() {
         _test_bool_null_and(Field::Null, Field::Boolean(bool1));
 
         _test_bool_bool_or(bool1, bool2);
-        _test_bool_null_or(bool1);
-        _test_null_bool_or(bool2);
+        _test_bool_null_or(Field::Boolean(bool1), Field::Null);
+        _test_bool_null_or(Field::Null, Field::Boolean(bool2));
 
         _test_bool_not(bool2);
 
@@ -27,12 +29,12 @@ fn test_logical() {
         _test_bool_non_bool_and(Field::String(str.clone()), Field::Boolean(bool1));
         _test_bool_non_bool_and(Field::Text(str.clone()), Field::Boolean(bool1));
 
-        _test_bool_non_bool_and(Field::Boolean(bool2), Field::UInt(u_num));
-        _test_bool_non_bool_and(Field::Boolean(bool2), Field::Int(i_num));
-        _test_bool_non_bool_and(Field::Boolean(bool2), Field::Float(OrderedFloat(f_num)));
-        _test_bool_non_bool_and(Field::Boolean(bool2), Field::Decimal(Decimal::from(u_num)));
-        _test_bool_non_bool_and(Field::Boolean(bool2), Field::String(str.clone()));
-        _test_bool_non_bool_and(Field::Boolean(bool2), Field::Text(str.clone()));
+        _test_bool_non_bool_and(Field::Boolean(true), Field::UInt(u_num));
+        _test_bool_non_bool_and(Field::Boolean(true), Field::Int(i_num));
+        _test_bool_non_bool_and(Field::Boolean(true), Field::Float(OrderedFloat(f_num)));
+        _test_bool_non_bool_and(Field::Boolean(true), Field::Decimal(Decimal::from(u_num)));
+        _test_bool_non_bool_and(Field::Boolean(true), Field::String(str.clone()));
+        _test_bool_non_bool_and(Field::Boolean(true), Field::Text(str.clone()));
 
         _test_bool_non_bool_or(Field::UInt(u_num), Field::Boolean(bool1));
         _test_bool_non_bool_or(Field::Int(i_num), Field::Boolean(bool1));
@@ -41,12 +43,12 @@ fn test_logical() {
         _test_bool_non_bool_or(Field::String(str.clone()), Field::Boolean(bool1));
         _test_bool_non_bool_or(Field::Text(str.clone()), Field::Boolean(bool1));
 
-        _test_bool_non_bool_or(Field::Boolean(bool2), Field::UInt(u_num));
-        _test_bool_non_bool_or(Field::Boolean(bool2), Field::Int(i_num));
-        _test_bool_non_bool_or(Field::Boolean(bool2), Field::Float(OrderedFloat(f_num)));
-        _test_bool_non_bool_or(Field::Boolean(bool2), Field::Decimal(Decimal::from(u_num)));
-        _test_bool_non_bool_or(Field::Boolean(bool2), Field::String(str.clone()));
-        _test_bool_non_bool_or(Field::Boolean(bool2), Field::Text(str));
+        _test_bool_non_bool_or(Field::Boolean(false), Field::UInt(u_num));
+        _test_bool_non_bool_or(Field::Boolean(false), Field::Int(i_num));
+        _test_bool_non_bool_or(Field::Boolean(false), Field::Float(OrderedFloat(f_num)));
+        _test_bool_non_bool_or(Field::Boolean(false), Field::Decimal(Decimal::from(u_num)));
+        _test_bool_non_bool_or(Field::Boolean(false), Field::String(str.clone()));
+        _test_bool_non_bool_or(Field::Boolean(false), Field::Text(str));
     });
 }
 
@@ -55,24 +57,28 @@ fn _test_bool_bool_and(bool1: bool, bool2: bool) {
     let l = Box::new(Literal(Field::Boolean(bool1)));
     let r = Box::new(Literal(Field::Boolean(bool2)));
     let _ans = bool1 & bool2;
-    assert!(
-        matches!(
-            evaluate_and(&Schema::empty(), &l, &r, &row)
-                .unwrap_or_else(|e| panic!("{}", e.to_string())),
-            Field::Boolean(_ans)
-        )
-    );
+    assert!(matches!(
+        evaluate_and(&Schema::empty(), &l, &r, &row)
+            .unwrap_or_else(|e| panic!("{}", e.to_string())),
+        Field::Boolean(_ans)
+    ));
 }
 
 fn _test_bool_null_and(f1: Field, f2: Field) {
     let row = Record::new(None, vec![]);
+    // `FALSE AND NULL` is `FALSE`, `TRUE AND NULL` is unknown.
+    let expected = if f1 == Field::Boolean(false) || f2 == Field::Boolean(false) {
+        Field::Boolean(false)
+    } else {
+        Field::Null
+    };
     let l = Box::new(Literal(f1));
     let r = Box::new(Literal(f2));
-    assert!(matches!(
+    assert_eq!(
         evaluate_and(&Schema::empty(), &l, &r, &row)
             .unwrap_or_else(|e| panic!("{}", e.to_string())),
-        Field::Boolean(false)
-    ));
+        expected
+    );
 }
 
 fn _test_bool_bool_or(bool1: bool, bool2: bool) {
@@ -86,24 +92,20 @@ fn _test_bool_bool_or(bool1: bool, bool2: bool) {
     ));
 }
 
-fn _test_bool_null_or(_bool: bool) {
+fn _test_bool_null_or(f1: Field, f2: Field) {
     let row = Record::new(None, vec![]);
-    let l = Box::new(Literal(Field::Boolean(_bool)));
-    let r = Box::new(Literal(Field::Null));
-    assert!(matches!(
-        evaluate_or(&Schema::empty(), &l, &r, &row).unwrap_or_else(|e| panic!("{}", e.to_string())),
-        Field::Boolean(_bool)
-    ));
-}
-
-fn _test_null_bool_or(_bool: bool) {
-    let row = Record::new(None, vec![]);
-    let l = Box::new(Literal(Field::Null));
-    let r = Box::new(Literal(Field::Boolean(_bool)));
-    assert!(matches!(
+    // `TRUE OR NULL` is `TRUE`, `FALSE OR NULL` is unknown.
+    let expected = if f1 == Field::Boolean(true) || f2 == Field::Boolean(true) {
+        Field::Boolean(true)
+    } else {
+        Field::Null
+    };
+    let l = Box::new(Literal(f1));
+    let r = Box::new(Literal(f2));
+    assert_eq!(
         evaluate_or(&Schema::empty(), &l, &r, &row).unwrap_or_else(|e| panic!("{}", e.to_string())),
-        Field::Boolean(_bool)
-    ));
+        expected
+    );
 }
 
 fn _test_bool_not(bool: bool) {
@@ -130,3 +132,108 @@ fn _test_bool_non_bool_or(f1: Field, f2: Field) {
     let r = Box::new(Literal(f2));
     assert!(evaluate_or(&Schema::empty(), &l, &r, &row).is_err());
 }
+
+#[test]
+fn test_and_or_short_circuit() {
+    let row = Record::new(None, vec![]);
+    // Evaluating this operand fails, so it must not be evaluated when the left one decides.
+    let division_by_zero = Box::new(Expression::BinaryOperator {
+        left: Box::new(Literal(Field::Decimal(Decimal::ONE))),
+        operator: BinaryOperatorType::Div,
+        right: Box::new(Literal(Field::Decimal(Decimal::ZERO))),
+    });
+    let literal = |field: Field| Box::new(Literal(field));
+
+    assert_eq!(
+        evaluate_and(
+            &Schema::empty(),
+            &literal(Field::Boolean(false)),
+            &division_by_zero,
+            &row
+        )
+        .unwrap(),
+        Field::Boolean(false)
+    );
+    assert_eq!(
+        evaluate_or(
+            &Schema::empty(),
+            &literal(Field::Boolean(true)),
+            &division_by_zero,
+            &row
+        )
+        .unwrap(),
+        Field::Boolean(true)
+    );
+
+    // Otherwise the right operand decides and is evaluated.
+    assert!(evaluate_and(
+        &Schema::empty(),
+        &literal(Field::Boolean(true)),
+        &division_by_zero,
+        &row
+    )
+    .is_err());
+    assert!(evaluate_or(
+        &Schema::empty(),
+        &literal(Field::Boolean(false)),
+        &division_by_zero,
+        &row
+    )
+    .is_err());
+    assert!(evaluate_or(
+        &Schema::empty(),
+        &literal(Field::Null),
+        &division_by_zero,
+        &row
+    )
+    .is_err());
+    assert!(evaluate_and(
+        &Schema::empty(),
+        &literal(Field::Null),
+        &division_by_zero,
+        &row
+    )
+    .is_err());
+}
+
+#[test]
+fn test_and_or_null_three_valued_logic() {
+    let row = Record::new(None, vec![]);
+    let literal = |field: Field| Box::new(Literal(field));
+    let and = |l: Field, r: Field| {
+        evaluate_and(&Schema::empty(), &literal(l), &literal(r), &row).unwrap()
+    };
+    let or =
+        |l: Field, r: Field| evaluate_or(&Schema::empty(), &literal(l), &literal(r), &row).unwrap();
+
+    assert_eq!(and(Field::Boolean(true), Field::Null), Field::Null);
+    assert_eq!(and(Field::Null, Field::Boolean(true)), Field::Null);
+    assert_eq!(and(Field::Null, Field::Null), Field::Null);
+    assert_eq!(
+        and(Field::Null, Field::Boolean(false)),
+        Field::Boolean(false)
+    );
+    assert_eq!(
+        and(Field::Boolean(false), Field::Null),
+        Field::Boolean(false)
+    );
+
+    assert_eq!(or(Field::Boolean(false), Field::Null), Field::Null);
+    assert_eq!(or(Field::Null, Field::Boolean(false)), Field::Null);
+    assert_eq!(or(Field::Null, Field::Null), Field::Null);
+    assert_eq!(or(Field::Null, Field::Boolean(true)), Field::Boolean(true));
+    assert_eq!(or(Field::Boolean(true), Field::Null), Field::Boolean(true));
+
+    // `NOT (TRUE AND NULL)` stays unknown rather than turning `TRUE`.
+    let not_and = evaluate_not(
+        &Schema::empty(),
+        &Expression::BinaryOperator {
+            left: literal(Field::Boolean(true)),
+            operator: BinaryOperatorType::And,
+            right: literal(Field::Null),
+        },
+        &row,
+    )
+    .unwrap();
+    assert_eq!(not_and, Field::Null);
+}
@@ -3,6 +3,8 @@ mod execution;
 #[cfg(test)]
 mod expression_builder_test;
 
+#[cfg(test)]
+mod batch;
 #[cfg(test)]
 mod cast;
 #[cfg(test)]
@@ -14,13 +16,21 @@ mod datetime;
 #[cfg(test)]
 mod distance;
 #[cfg(test)]
+mod json;
+#[cfg(test)]
 mod logical;
 #[cfg(test)]
 mod mathematical;
 #[cfg(test)]
 mod number;
 #[cfg(test)]
+mod optimizer;
+#[cfg(test)]
 mod point;
 #[cfg(test)]
 mod string;
+#[cfg(test)]
+mod typecheck;
+#[cfg(test)]
+mod udf;
 mod test_common;
@@ -0,0 +1,105 @@
+use crate::pipeline::expression::datetime::DateTimeFunctionType;
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::expression::operator::BinaryOperatorType;
+use crate::pipeline::expression::optimizer::fold_constants;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+fn literal(value: i64) -> Box<Expression> {
+    Box::new(Expression::Literal(Field::Int(value)))
+}
+
+#[test]
+fn test_fold_literal_only_expression() {
+    let expression = Expression::BinaryOperator {
+        left: literal(2),
+        operator: BinaryOperatorType::Mul,
+        right: literal(3),
+    };
+    assert_eq!(
+        fold_constants(expression),
+        Expression::Literal(Field::Int(6))
+    );
+}
+
+#[test]
+fn test_fold_literal_sub_expression() {
+    let expression = Expression::ScalarFunction {
+        fun: ScalarFunctionType::Round,
+        args: vec![
+            Expression::Column { index: 0 },
+            Expression::BinaryOperator {
+                left: literal(1),
+                operator: BinaryOperatorType::Add,
+                right: literal(1),
+            },
+        ],
+    };
+    assert_eq!(
+        fold_constants(expression),
+        Expression::ScalarFunction {
+            fun: ScalarFunctionType::Round,
+            args: vec![
+                Expression::Column { index: 0 },
+                Expression::Literal(Field::Int(2))
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_do_not_fold_non_deterministic_or_failing_expressions() {
+    let now_plus_one = Expression::BinaryOperator {
+        left: Box::new(Expression::Now {
+            fun: DateTimeFunctionType::Now,
+        }),
+        operator: BinaryOperatorType::Add,
+        right: literal(1),
+    };
+    assert_eq!(fold_constants(now_plus_one.clone()), now_plus_one);
+
+    let division_by_zero = Expression::BinaryOperator {
+        left: Box::new(Expression::Literal(Field::Decimal(Decimal::ONE))),
+        operator: BinaryOperatorType::Div,
+        right: Box::new(Expression::Literal(Field::Decimal(Decimal::ZERO))),
+    };
+    assert_eq!(fold_constants(division_by_zero.clone()), division_by_zero);
+}
+
+#[test]
+fn test_planner_folds_constants_but_keeps_field_names() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "a".to_string(),
+                FieldType::Float,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+
+    let mut planner = CommonPlanner::new(schema);
+    planner
+        .plan(*get_select("SELECT 2 * 3, ROUND(a, 1 + 1) FROM t0").unwrap())
+        .unwrap();
+
+    assert_eq!(
+        planner.projection_output,
+        vec![
+            Expression::Literal(Field::Int(6)),
+            Expression::ScalarFunction {
+                fun: ScalarFunctionType::Round,
+                args: vec![
+                    Expression::Column { index: 0 },
+                    Expression::Literal(Field::Int(2))
+                ],
+            }
+        ]
+    );
+    assert_eq!(planner.post_projection_schema.fields[0].name, "2*3");
+}
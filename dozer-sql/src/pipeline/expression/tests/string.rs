@@ -470,6 +470,31 @@ fn test_ucase_text() {
     assert_eq!(f, Field::Text("JOHN".to_string()));
 }
 
+#[test]
+fn test_lower() {
+    let schema = |typ| {
+        Schema::empty()
+            .field(
+                FieldDefinition::new(String::from("fn"), typ, false, SourceDefinition::Dynamic),
+                false,
+            )
+            .clone()
+    };
+    let f = run_fct(
+        "SELECT LOWER(fn) FROM USERS",
+        schema(FieldType::String),
+        vec![Field::String("John".to_string())],
+    );
+    assert_eq!(f, Field::String("john".to_string()));
+
+    let f = run_fct(
+        "SELECT LCASE(fn) FROM USERS",
+        schema(FieldType::Text),
+        vec![Field::Text("John".to_string())],
+    );
+    assert_eq!(f, Field::Text("john".to_string()));
+}
+
 #[test]
 fn test_length() {
     let f = run_fct(
@@ -490,6 +515,26 @@ fn test_length() {
     assert_eq!(f, Field::UInt(4));
 }
 
+#[test]
+fn test_length_binary() {
+    let f = run_fct(
+        "SELECT LENGTH(fn) FROM USERS",
+        Schema::empty()
+            .field(
+                FieldDefinition::new(
+                    String::from("fn"),
+                    FieldType::Binary,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone(),
+        vec![Field::Binary(vec![0, 1, 255])],
+    );
+    assert_eq!(f, Field::UInt(3));
+}
+
 #[test]
 fn test_trim_string() {
     let f = run_fct(
@@ -0,0 +1,87 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::expression::operator::{BinaryOperatorType, UnaryOperatorType};
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use crate::pipeline::expression::typecheck::typecheck;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+fn schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "a".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "b".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+#[test]
+fn test_typecheck_well_typed_expression() {
+    // ROUND(a + 1, 2)
+    let expression = Expression::ScalarFunction {
+        fun: ScalarFunctionType::Round,
+        args: vec![
+            Expression::BinaryOperator {
+                left: Box::new(Expression::Column { index: 0 }),
+                operator: BinaryOperatorType::Add,
+                right: Box::new(Expression::Literal(Field::Int(1))),
+            },
+            Expression::Literal(Field::Int(2)),
+        ],
+    };
+    assert_eq!(
+        typecheck(&expression, &schema()).unwrap(),
+        FieldType::Decimal
+    );
+}
+
+#[test]
+fn test_typecheck_ill_typed_function_argument() {
+    let expression = Expression::ScalarFunction {
+        fun: ScalarFunctionType::Round,
+        args: vec![Expression::Literal(Field::String("x".to_string()))],
+    };
+    assert!(matches!(
+        typecheck(&expression, &schema()),
+        Err(PipelineError::InvalidFunctionArgumentType(..))
+    ));
+
+    let expression = Expression::ScalarFunction {
+        fun: ScalarFunctionType::Abs,
+        args: vec![Expression::Column { index: 1 }],
+    };
+    assert!(matches!(
+        typecheck(&expression, &schema()),
+        Err(PipelineError::InvalidFunctionArgumentType(..))
+    ));
+}
+
+#[test]
+fn test_typecheck_ill_typed_nested_expression() {
+    // a + -b
+    let expression = Expression::BinaryOperator {
+        left: Box::new(Expression::Column { index: 0 }),
+        operator: BinaryOperatorType::Add,
+        right: Box::new(Expression::UnaryOperator {
+            operator: UnaryOperatorType::Minus,
+            arg: Box::new(Expression::Column { index: 1 }),
+        }),
+    };
+    assert!(matches!(
+        typecheck(&expression, &schema()),
+        Err(PipelineError::InvalidExpression(_))
+    ));
+}
@@ -0,0 +1,153 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::builder::ExpressionBuilder;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::expression::tests::test_common::run_fct;
+use crate::pipeline::expression::udf::{register_scalar_function, ScalarUdf};
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use sqlparser::ast::SelectItem;
+use std::sync::Once;
+
+static REGISTER: Once = Once::new();
+
+/// Registers `HYPOT_TEST(x, y)`, the length of the hypotenuse of a right triangle.
+fn register_hypot() {
+    REGISTER.call_once(|| {
+        register_scalar_function(ScalarUdf::new(
+            "hypot_test",
+            vec![
+                vec![FieldType::Float, FieldType::Int],
+                vec![FieldType::Float, FieldType::Int],
+            ],
+            |_| FieldType::Float,
+            |args| {
+                let value = |field: &Field| match field {
+                    Field::Float(f) => Some(f.0),
+                    Field::Int(i) => Some(*i as f64),
+                    _ => None,
+                };
+                Ok(match (value(&args[0]), value(&args[1])) {
+                    (Some(x), Some(y)) => Field::Float(OrderedFloat(x.hypot(y))),
+                    _ => Field::Null,
+                })
+            },
+        ))
+        .unwrap();
+    });
+}
+
+fn schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "x".to_string(),
+                FieldType::Float,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "y".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+fn build(sql: &str) -> Result<Expression, PipelineError> {
+    let schema = schema();
+    let mut builder = ExpressionBuilder::new(schema.fields.len());
+    match &get_select(sql).unwrap().projection[0] {
+        SelectItem::UnnamedExpr(e) => builder.build(true, e, &schema),
+        _ => panic!("Invalid expr"),
+    }
+}
+
+#[test]
+fn test_udf_builds_and_evaluates() {
+    register_hypot();
+
+    let expression = build("SELECT HYPOT_TEST(x, y) FROM t0").unwrap();
+    let Expression::Udf { fun, args } = &expression else {
+        panic!("expected a UDF call, got {expression:?}");
+    };
+    assert_eq!(fun.name(), "hypot_test");
+    assert_eq!(fun.arity(), 2);
+    assert_eq!(
+        args,
+        &vec![
+            Expression::Column { index: 0 },
+            Expression::Column { index: 1 }
+        ]
+    );
+    assert_eq!(
+        expression.get_type(&schema()).unwrap().return_type,
+        FieldType::Float
+    );
+
+    let result = run_fct(
+        "SELECT hypot_test(x, y) FROM t0",
+        schema(),
+        vec![
+            Field::Float(OrderedFloat(3.0)),
+            Field::Int(4),
+            Field::String("a".to_string()),
+        ],
+    );
+    assert_eq!(result, Field::Float(OrderedFloat(5.0)));
+}
+
+#[test]
+fn test_udf_checks_arguments() {
+    register_hypot();
+
+    assert!(matches!(
+        build("SELECT HYPOT_TEST(x, name) FROM t0"),
+        Err(PipelineError::InvalidFunctionArgumentType(
+            _,
+            FieldType::String,
+            _,
+            1
+        ))
+    ));
+    assert!(matches!(
+        build("SELECT HYPOT_TEST(x) FROM t0"),
+        Err(PipelineError::NotEnoughArguments(_))
+    ));
+    assert!(matches!(
+        build("SELECT HYPOT_TEST(x, y, y) FROM t0"),
+        Err(PipelineError::TooManyArguments(_))
+    ));
+}
+
+#[test]
+fn test_register_scalar_function_rejects_taken_names() {
+    register_hypot();
+
+    for name in ["round", "sum", "HYPOT_TEST"] {
+        assert!(matches!(
+            register_scalar_function(ScalarUdf::new(
+                name,
+                vec![],
+                |_| FieldType::Int,
+                |_| Ok(Field::Null)
+            )),
+            Err(PipelineError::DuplicateFunction(_))
+        ));
+    }
+}
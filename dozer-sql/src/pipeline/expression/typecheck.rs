@@ -0,0 +1,93 @@
+use crate::pipeline::errors::{FieldTypes, PipelineError};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::expression::operator::UnaryOperatorType;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use dozer_types::types::{Field, FieldType, Schema};
+
+const NUMERIC_TYPES: [FieldType; 6] = [
+    FieldType::UInt,
+    FieldType::U128,
+    FieldType::Int,
+    FieldType::I128,
+    FieldType::Float,
+    FieldType::Decimal,
+];
+
+/// Checks that `expression` is well typed over records of `schema`, without evaluating it, and
+/// returns its type.
+///
+/// Every sub-expression is checked, including the ones whose type
+/// [`ExpressionExecutor::get_type`] infers without looking at their arguments, so a query that
+/// would fail on every record is rejected when it is planned instead. `NULL` literals have no
+/// type of their own and are left to the expression they are an argument of.
+pub fn typecheck(expression: &Expression, schema: &Schema) -> Result<FieldType, PipelineError> {
+    for child in children(expression) {
+        if !is_null(child) {
+            typecheck(child, schema)?;
+        }
+    }
+    check_arguments(expression, schema)?;
+    Ok(expression.get_type(schema)?.return_type)
+}
+
+fn children(expression: &Expression) -> Vec<&Expression> {
+    match expression {
+        Expression::Column { .. } | Expression::Literal(_) | Expression::Now { .. } => vec![],
+        Expression::UnaryOperator { arg, .. }
+        | Expression::DateTimeFunction { arg, .. }
+        | Expression::Cast { arg, .. } => vec![arg.as_ref()],
+        Expression::BinaryOperator { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+        Expression::Like { arg, pattern, .. } => vec![arg.as_ref(), pattern.as_ref()],
+        Expression::Trim { arg, what, .. } => std::iter::once(arg.as_ref())
+            .chain(what.as_deref())
+            .collect(),
+        Expression::ScalarFunction { args, .. }
+        | Expression::GeoFunction { args, .. }
+        | Expression::ConditionalExpression { args, .. }
+        | Expression::AggregateFunction { args, .. }
+        | Expression::Udf { args, .. } => args.iter().collect(),
+        #[cfg(feature = "python")]
+        Expression::PythonUDF { args, .. } => args.iter().collect(),
+    }
+}
+
+/// Checks the arguments of the expressions whose type doesn't depend on them.
+fn check_arguments(expression: &Expression, schema: &Schema) -> Result<(), PipelineError> {
+    match expression {
+        Expression::UnaryOperator {
+            operator: operator @ (UnaryOperatorType::Plus | UnaryOperatorType::Minus),
+            arg,
+        } if !is_null(arg) => {
+            let arg_type = arg.get_type(schema)?.return_type;
+            let accepted = NUMERIC_TYPES.contains(&arg_type)
+                || (*operator == UnaryOperatorType::Minus && arg_type == FieldType::Timestamp);
+            if !accepted {
+                return Err(PipelineError::InvalidExpression(format!(
+                    "cannot apply {operator} to {arg_type:?}"
+                )));
+            }
+        }
+        Expression::ScalarFunction {
+            fun: ScalarFunctionType::Abs,
+            args,
+        } => {
+            if let Some(arg) = args.first().filter(|arg| !is_null(arg)) {
+                let arg_type = arg.get_type(schema)?.return_type;
+                if !NUMERIC_TYPES.contains(&arg_type) {
+                    return Err(PipelineError::InvalidFunctionArgumentType(
+                        ScalarFunctionType::Abs.to_string(),
+                        arg_type,
+                        FieldTypes::new(NUMERIC_TYPES.to_vec()),
+                        0,
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn is_null(expression: &Expression) -> bool {
+    matches!(expression, Expression::Literal(Field::Null))
+}
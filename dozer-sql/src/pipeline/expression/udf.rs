@@ -0,0 +1,151 @@
+use crate::pipeline::errors::{FieldTypes, PipelineError};
+use crate::pipeline::expression::aggregate::AggregateFunctionType;
+use crate::pipeline::expression::conditional::ConditionalExpressionType;
+use crate::pipeline::expression::datetime::DateTimeFunctionType;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+use crate::pipeline::expression::geo::common::GeoFunctionType;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+
+type ReturnTypeFn = Box<dyn Fn(&[FieldType]) -> FieldType + Send + Sync>;
+type EvaluateFn = Box<dyn Fn(&[Field]) -> Result<Field, PipelineError> + Send + Sync>;
+
+/// A scalar function defined outside of the crate, see [`register_scalar_function`].
+pub struct ScalarUdf {
+    name: String,
+    arg_types: Vec<Vec<FieldType>>,
+    return_type: ReturnTypeFn,
+    evaluate: EvaluateFn,
+}
+
+impl ScalarUdf {
+    /// Creates the function `name`, which SQL calls case-insensitively.
+    ///
+    /// It takes one argument per entry of `arg_types`, of one of the listed types, or of any type
+    /// if the list is empty. `return_type` infers the type of the result from the types of the
+    /// arguments, and `evaluate` computes the result from their values, which may be `NULL`.
+    pub fn new(
+        name: &str,
+        arg_types: Vec<Vec<FieldType>>,
+        return_type: impl Fn(&[FieldType]) -> FieldType + Send + Sync + 'static,
+        evaluate: impl Fn(&[Field]) -> Result<Field, PipelineError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_lowercase(),
+            arg_types,
+            return_type: Box::new(return_type),
+            evaluate: Box::new(evaluate),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn arity(&self) -> usize {
+        self.arg_types.len()
+    }
+
+    /// Checks `args` against the arity and argument types of the function, and infers the type
+    /// of its result. Results are nullable, as `evaluate` may return `NULL`.
+    pub(crate) fn get_type(
+        &self,
+        args: &[Expression],
+        schema: &Schema,
+    ) -> Result<ExpressionType, PipelineError> {
+        if args.len() < self.arity() {
+            return Err(PipelineError::NotEnoughArguments(self.name.clone()));
+        }
+        if args.len() > self.arity() {
+            return Err(PipelineError::TooManyArguments(self.name.clone()));
+        }
+
+        let mut types = Vec::with_capacity(args.len());
+        for (index, (arg, expected)) in args.iter().zip(&self.arg_types).enumerate() {
+            let typ = arg.get_type(schema)?.return_type;
+            if !expected.is_empty() && !expected.contains(&typ) {
+                return Err(PipelineError::InvalidFunctionArgumentType(
+                    self.name.clone(),
+                    typ,
+                    FieldTypes::new(expected.clone()),
+                    index,
+                ));
+            }
+            types.push(typ);
+        }
+
+        Ok(ExpressionType::new(
+            (self.return_type)(&types),
+            true,
+            SourceDefinition::Dynamic,
+            false,
+        ))
+    }
+
+    pub(crate) fn evaluate(
+        &self,
+        args: &[Expression],
+        record: &Record,
+        schema: &Schema,
+    ) -> Result<Field, PipelineError> {
+        let values = args
+            .iter()
+            .map(|arg| arg.evaluate(record, schema))
+            .collect::<Result<Vec<_>, _>>()?;
+        (self.evaluate)(&values)
+    }
+}
+
+impl Debug for ScalarUdf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScalarUdf")
+            .field("name", &self.name)
+            .field("arg_types", &self.arg_types)
+            .finish()
+    }
+}
+
+/// Functions are identified by name, as names are unique among registered functions.
+impl PartialEq for ScalarUdf {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+/// Functions registered with [`register_scalar_function`], by lowercase name.
+static SCALAR_UDFS: RwLock<BTreeMap<String, Arc<ScalarUdf>>> = RwLock::new(BTreeMap::new());
+
+/// Registers `udf`, for SQL to call it by name.
+///
+/// Built-in functions take precedence: registering the name of a built-in function, of a
+/// registered aggregate function, or a name registered already, fails. Registration applies to
+/// pipelines built afterwards.
+pub fn register_scalar_function(udf: ScalarUdf) -> Result<(), PipelineError> {
+    if is_builtin_function(&udf.name) || AggregateFunctionType::new(&udf.name).is_ok() {
+        return Err(PipelineError::DuplicateFunction(udf.name));
+    }
+
+    let mut udfs = SCALAR_UDFS.write().unwrap();
+    if udfs.contains_key(&udf.name) {
+        return Err(PipelineError::DuplicateFunction(udf.name));
+    }
+    udfs.insert(udf.name.clone(), Arc::new(udf));
+    Ok(())
+}
+
+/// Returns the function registered as `name`, in lowercase.
+pub(crate) fn get_scalar_udf(name: &str) -> Option<Arc<ScalarUdf>> {
+    SCALAR_UDFS.read().unwrap().get(name).cloned()
+}
+
+/// Returns whether `name`, in lowercase, is a built-in scalar, geo, conditional or date and time
+/// function.
+pub(crate) fn is_builtin_function(name: &str) -> bool {
+    ScalarFunctionType::new(name).is_ok()
+        || GeoFunctionType::new(name).is_ok()
+        || ConditionalExpressionType::new(name).is_ok()
+        || DateTimeFunctionType::new(name).is_ok()
+}
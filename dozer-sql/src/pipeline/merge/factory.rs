@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use dozer_core::{
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_types::types::{FieldType, Schema};
+
+use super::processor::MergeProcessor;
+
+#[derive(Debug)]
+pub struct MergeProcessorFactory {
+    input_ports: Vec<PortHandle>,
+    sequence_column: String,
+    max_buffered: Option<usize>,
+}
+
+impl MergeProcessorFactory {
+    /// Creates a new [`MergeProcessorFactory`], merging the inputs on `input_ports` in the order
+    /// of their `sequence_column`.
+    pub fn new(input_ports: Vec<PortHandle>, sequence_column: String) -> Self {
+        Self {
+            input_ports,
+            sequence_column,
+            max_buffered: None,
+        }
+    }
+
+    /// Bounds the number of operations held waiting for a quiet input, sending the earliest one
+    /// held once more than `max_buffered` are, even if that breaks the order.
+    pub fn with_max_buffered(mut self, max_buffered: Option<usize>) -> Self {
+        self.max_buffered = max_buffered;
+        self
+    }
+
+    /// Checks that every input has the sequence column at the same position, and returns its
+    /// position and type.
+    fn sequence_column<'a>(
+        &self,
+        mut input_schemas: impl Iterator<Item = (&'a PortHandle, &'a Schema)>,
+    ) -> Result<(usize, FieldType), ExecutionError> {
+        let (_, first) = input_schemas.next().ok_or_else(|| {
+            ExecutionError::InvalidOperation("merge needs at least one input".to_string())
+        })?;
+        let (index, definition) = first.get_field_index(&self.sequence_column)?;
+        for (port, schema) in input_schemas {
+            let (other_index, other) = schema.get_field_index(&self.sequence_column)?;
+            if other_index != index || other.typ != definition.typ {
+                return Err(ExecutionError::InvalidOperation(format!(
+                    "merge input on port {port} has column `{}` at a different position or type",
+                    self.sequence_column
+                )));
+            }
+        }
+        Ok((index, definition.typ))
+    }
+
+    fn input_schemas<'a, T>(
+        &'a self,
+        input_schemas: &'a HashMap<PortHandle, T>,
+    ) -> Result<Vec<(&'a PortHandle, &'a T)>, ExecutionError> {
+        self.input_ports
+            .iter()
+            .map(|port| {
+                input_schemas
+                    .get(port)
+                    .map(|schema| (port, schema))
+                    .ok_or(ExecutionError::InvalidPortHandle(*port))
+            })
+            .collect()
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for MergeProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        self.input_ports.clone()
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        let inputs = self.input_schemas(input_schemas)?;
+        self.sequence_column(inputs.iter().map(|(port, (schema, _))| (*port, schema)))?;
+        let (_, (schema, ctx)) = inputs[0];
+        Ok((schema.clone(), ctx.clone()))
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        let inputs = self.input_schemas(&input_schemas)?;
+        let (sequence_index, sequence_type) = self.sequence_column(inputs.into_iter())?;
+        Ok(Box::new(
+            MergeProcessor::new(&self.input_ports, sequence_index, sequence_type)
+                .with_max_buffered(self.max_buffered),
+        ))
+    }
+}
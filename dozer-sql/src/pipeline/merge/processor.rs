@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::chrono::{DateTime, FixedOffset};
+use dozer_types::types::{Field, FieldType, Operation};
+
+#[derive(Debug, Default)]
+struct Input {
+    buffer: VecDeque<(Field, Operation)>,
+    /// Sequence of the last operation received, which no later one can be behind.
+    last: Option<Field>,
+    /// Whether the input terminated, so it won't send anything anymore.
+    terminated: bool,
+}
+
+/// Merges inputs that are each ordered by a sequence column into one output ordered by it, an
+/// order preserving union.
+///
+/// An operation is held until no input can send one with a lower sequence anymore, that is until
+/// every input has sent an operation at or past its sequence. With a timestamp sequence, the
+/// watermark tells that no input will send anything before it, so a quiet input only holds back
+/// the others until the watermark passes them. A terminated input doesn't hold back the others,
+/// and everything held is sent once all inputs terminated.
+///
+/// With another sequence type, a quiet input holds back the others until it sends something. To
+/// bound memory, [`MergeProcessor::with_max_buffered`] sends the earliest operation held once too
+/// many are, at the cost of order if the quiet input then sends an earlier one.
+///
+/// An input sending an operation behind the previous one is an error.
+#[derive(Debug)]
+pub struct MergeProcessor {
+    sequence_index: usize,
+    sequence_type: FieldType,
+    inputs: BTreeMap<PortHandle, Input>,
+    watermark: Option<Field>,
+    max_buffered: Option<usize>,
+}
+
+impl MergeProcessor {
+    pub fn new(
+        input_ports: &[PortHandle],
+        sequence_index: usize,
+        sequence_type: FieldType,
+    ) -> Self {
+        Self {
+            sequence_index,
+            sequence_type,
+            inputs: input_ports
+                .iter()
+                .map(|port| (*port, Input::default()))
+                .collect(),
+            watermark: None,
+            max_buffered: None,
+        }
+    }
+
+    /// Sends the earliest operation held whenever more than `max_buffered` are.
+    pub fn with_max_buffered(mut self, max_buffered: Option<usize>) -> Self {
+        self.max_buffered = max_buffered;
+        self
+    }
+
+    /// Number of operations currently held.
+    pub fn buffered_count(&self) -> usize {
+        self.inputs.values().map(|input| input.buffer.len()).sum()
+    }
+
+    fn sequence(&self, op: &Operation) -> Result<Field, ExecutionError> {
+        let record = match op {
+            Operation::Insert { new } | Operation::Update { new, .. } => new,
+            Operation::Delete { old } => old,
+        };
+        match record.get_value(self.sequence_index)? {
+            Field::Null => Err(ExecutionError::InvalidOperation(
+                "merge sequence can't be null".to_string(),
+            )),
+            sequence => Ok(sequence.clone()),
+        }
+    }
+
+    /// The input whose next operation is the earliest, if it can be sent.
+    fn next_ready(&self) -> Option<PortHandle> {
+        let over_limit = self
+            .max_buffered
+            .map_or(false, |max_buffered| self.buffered_count() > max_buffered);
+        let (port, sequence) = self
+            .inputs
+            .iter()
+            .filter_map(|(port, input)| input.buffer.front().map(|(sequence, _)| (*port, sequence)))
+            .min_by_key(|(_, sequence)| *sequence)?;
+        let behind_watermark = self
+            .watermark
+            .as_ref()
+            .map_or(false, |watermark| sequence < watermark);
+        let passed_by_all = self.inputs.values().all(|input| {
+            input.terminated || input.last.as_ref().map_or(false, |last| sequence <= last)
+        });
+        (over_limit || behind_watermark || passed_by_all).then_some(port)
+    }
+
+    fn send_ready(&mut self, fw: &mut dyn ProcessorChannelForwarder) -> Result<(), ExecutionError> {
+        while let Some(port) = self.next_ready() {
+            let input = self.inputs.get_mut(&port).expect("port was just found");
+            let (_, op) = input.buffer.pop_front().expect("operation was just found");
+            fw.send(op, DEFAULT_PORT_HANDLE)?;
+        }
+        Ok(())
+    }
+}
+
+impl Processor for MergeProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        let sequence = self.sequence(&op)?;
+        let input = self
+            .inputs
+            .get_mut(&from_port)
+            .ok_or(ExecutionError::InvalidPortHandle(from_port))?;
+        if input.last.as_ref().map_or(false, |last| &sequence < last) {
+            return Err(ExecutionError::InvalidOperation(format!(
+                "merge input on port {from_port} is out of sequence order"
+            )));
+        }
+        input.last = Some(sequence.clone());
+        input.buffer.push_back((sequence, op));
+        self.send_ready(fw)
+    }
+
+    fn on_watermark(
+        &mut self,
+        timestamp: DateTime<FixedOffset>,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        if self.sequence_type != FieldType::Timestamp {
+            return Ok(());
+        }
+        self.watermark = Some(Field::Timestamp(timestamp));
+        self.send_ready(fw)
+    }
+
+    fn on_input_terminated(
+        &mut self,
+        port: PortHandle,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        self.inputs
+            .get_mut(&port)
+            .ok_or(ExecutionError::InvalidPortHandle(port))?
+            .terminated = true;
+        self.send_ready(fw)
+    }
+
+    fn state_size(&self) -> Option<usize> {
+        Some(self.buffered_count())
+    }
+}
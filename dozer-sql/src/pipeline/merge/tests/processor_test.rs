@@ -0,0 +1,166 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_types::chrono::DateTime;
+use dozer_types::types::{Field, FieldType, Operation, Record};
+
+use crate::pipeline::merge::processor::MergeProcessor;
+
+const LEFT: PortHandle = 1;
+const RIGHT: PortHandle = 2;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+fn insert(sequence: Field) -> Operation {
+    Operation::Insert {
+        new: Record::new(None, vec![sequence]),
+    }
+}
+
+fn process(processor: &mut MergeProcessor, port: PortHandle, op: Operation) -> Vec<Operation> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor.process(port, op, &mut fw).unwrap();
+    fw.operations
+}
+
+fn terminate(processor: &mut MergeProcessor, port: PortHandle) -> Vec<Operation> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor.on_input_terminated(port, &mut fw).unwrap();
+    fw.operations
+}
+
+fn sequences(ops: &[Operation]) -> Vec<u64> {
+    ops.iter()
+        .map(|op| match op {
+            Operation::Insert { new } => new.values[0].as_uint().unwrap(),
+            _ => panic!("expected an insert"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_merge_orders_inputs_globally() {
+    let mut processor = MergeProcessor::new(&[LEFT, RIGHT], 0, FieldType::UInt);
+
+    let mut output = vec![];
+    for (port, sequence) in [
+        (LEFT, 1),
+        (LEFT, 4),
+        (RIGHT, 2),
+        (RIGHT, 3),
+        (LEFT, 6),
+        (RIGHT, 5),
+        (RIGHT, 7),
+    ] {
+        output.extend(process(&mut processor, port, insert(Field::UInt(sequence))));
+    }
+
+    assert_eq!(sequences(&output), vec![1, 2, 3, 4, 5, 6]);
+    // 7 waits for the left input to pass it or terminate.
+    assert_eq!(processor.buffered_count(), 1);
+
+    assert_eq!(sequences(&terminate(&mut processor, LEFT)), vec![7]);
+    assert_eq!(processor.buffered_count(), 0);
+}
+
+#[test]
+fn test_merge_drains_in_order_at_end_of_stream() {
+    let mut processor = MergeProcessor::new(&[LEFT, RIGHT], 0, FieldType::UInt);
+
+    let mut output = vec![];
+    for (port, sequence) in [(LEFT, 1), (LEFT, 3), (RIGHT, 2), (RIGHT, 4)] {
+        output.extend(process(&mut processor, port, insert(Field::UInt(sequence))));
+    }
+    assert_eq!(sequences(&output), vec![1, 2, 3]);
+
+    // The left input may still send something before 4.
+    assert!(terminate(&mut processor, RIGHT).is_empty());
+    output.extend(process(&mut processor, LEFT, insert(Field::UInt(4))));
+    output.extend(terminate(&mut processor, LEFT));
+
+    assert_eq!(sequences(&output), vec![1, 2, 3, 4, 4]);
+    assert_eq!(processor.buffered_count(), 0);
+}
+
+#[test]
+fn test_merge_terminated_input_does_not_hold_back_others() {
+    let mut processor = MergeProcessor::new(&[LEFT, RIGHT], 0, FieldType::UInt);
+
+    assert!(terminate(&mut processor, RIGHT).is_empty());
+    let output = process(&mut processor, LEFT, insert(Field::UInt(10)));
+    assert_eq!(sequences(&output), vec![10]);
+    assert_eq!(processor.buffered_count(), 0);
+}
+
+#[test]
+fn test_merge_bounds_operations_held_for_quiet_input() {
+    let mut processor =
+        MergeProcessor::new(&[LEFT, RIGHT], 0, FieldType::UInt).with_max_buffered(Some(2));
+
+    let mut output = vec![];
+    for sequence in [10, 20, 30, 40] {
+        output.extend(process(&mut processor, LEFT, insert(Field::UInt(sequence))));
+    }
+    assert_eq!(sequences(&output), vec![10, 20]);
+    assert_eq!(processor.buffered_count(), 2);
+}
+
+#[test]
+fn test_merge_waits_for_lagging_input() {
+    let mut processor = MergeProcessor::new(&[LEFT, RIGHT], 0, FieldType::UInt);
+
+    // Until the right input sends anything, it could still send an earlier operation.
+    for sequence in [10, 20, 30] {
+        assert!(process(&mut processor, LEFT, insert(Field::UInt(sequence))).is_empty());
+    }
+    assert_eq!(processor.buffered_count(), 3);
+
+    let output = process(&mut processor, RIGHT, insert(Field::UInt(5)));
+    assert_eq!(sequences(&output), vec![5]);
+
+    let output = process(&mut processor, RIGHT, insert(Field::UInt(25)));
+    assert_eq!(sequences(&output), vec![10, 20, 25]);
+    // 30 waits for the right input to pass it or terminate.
+    assert_eq!(processor.buffered_count(), 1);
+
+    assert_eq!(sequences(&terminate(&mut processor, RIGHT)), vec![30]);
+    assert_eq!(processor.buffered_count(), 0);
+}
+
+#[test]
+fn test_merge_watermark_releases_quiet_input() {
+    let mut processor = MergeProcessor::new(&[LEFT, RIGHT], 0, FieldType::Timestamp);
+    let timestamp = |s: &str| DateTime::parse_from_rfc3339(s).unwrap();
+
+    let first = Field::Timestamp(timestamp("2023-01-01T00:00:00Z"));
+    let second = Field::Timestamp(timestamp("2023-01-01T00:01:00Z"));
+    assert!(process(&mut processor, LEFT, insert(first.clone())).is_empty());
+    assert!(process(&mut processor, LEFT, insert(second.clone())).is_empty());
+
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor
+        .on_watermark(timestamp("2023-01-01T00:00:30Z"), &mut fw)
+        .unwrap();
+    assert_eq!(fw.operations, vec![insert(first)]);
+    assert_eq!(processor.buffered_count(), 1);
+}
+
+#[test]
+fn test_merge_rejects_out_of_order_input() {
+    let mut processor = MergeProcessor::new(&[LEFT, RIGHT], 0, FieldType::UInt);
+    process(&mut processor, LEFT, insert(Field::UInt(2)));
+
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    assert!(processor
+        .process(LEFT, insert(Field::UInt(1)), &mut fw)
+        .is_err());
+}
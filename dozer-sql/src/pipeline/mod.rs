@@ -1,13 +1,19 @@
-mod aggregation;
+pub mod aggregation;
 pub mod builder;
+pub mod change_coalesce;
+pub mod dedup;
 pub mod errors;
-mod expression;
+pub mod expression;
+pub mod merge;
 mod pipeline_builder;
 mod planner;
 mod product;
 mod projection;
+mod scalar_subquery;
 mod selection;
-mod window;
+pub mod state_ttl;
+pub mod update_split;
+pub mod window;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,85 @@
+use crate::pipeline::expression::aggregate::AggregateFunctionType;
+use sqlparser::ast::{Expr, Function, FunctionArg, FunctionArgExpr, Ident, Select, SelectItem};
+
+/// Rewrites the `HAVING` of `select` to read the output columns of the query instead of
+/// computing aggregations, so that it can run as a filter after the aggregation.
+///
+/// A sub-expression that is an aliased item of the projection becomes a reference to its alias,
+/// and identifiers must name a column projected without an alias. Returns `None` if `select`
+/// has no `HAVING` or if it reads anything that isn't projected, e.g. an aggregation computed
+/// only for `HAVING` or a column that isn't selected, in which case the aggregation has to
+/// evaluate it.
+pub fn having_over_output(select: &Select) -> Option<Expr> {
+    let outputs = select
+        .projection
+        .iter()
+        .filter_map(|item| match item {
+            SelectItem::ExprWithAlias { expr, alias } => Some((expr, alias.value.as_str())),
+            SelectItem::UnnamedExpr(expr @ Expr::Identifier(ident)) => {
+                Some((expr, ident.value.as_str()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    OutputRewriter { outputs }.rewrite(select.having.as_ref()?)
+}
+
+struct OutputRewriter<'a> {
+    /// The named items of the projection, and the names of their output columns.
+    outputs: Vec<(&'a Expr, &'a str)>,
+}
+
+impl OutputRewriter<'_> {
+    fn rewrite(&self, expr: &Expr) -> Option<Expr> {
+        if let Some((_, name)) = self.outputs.iter().find(|(output, _)| *output == expr) {
+            return Some(Expr::Identifier(Ident::new(*name)));
+        }
+
+        match expr {
+            // Only projected columns were found above. Other identifiers are either not
+            // projected or, when they match an alias, may still refer to a source column.
+            Expr::Identifier(_) => None,
+            Expr::Value(_) => Some(expr.clone()),
+            Expr::Nested(expr) => Some(Expr::Nested(Box::new(self.rewrite(expr)?))),
+            Expr::UnaryOp { op, expr } => Some(Expr::UnaryOp {
+                op: *op,
+                expr: Box::new(self.rewrite(expr)?),
+            }),
+            Expr::BinaryOp { left, op, right } => Some(Expr::BinaryOp {
+                left: Box::new(self.rewrite(left)?),
+                op: op.clone(),
+                right: Box::new(self.rewrite(right)?),
+            }),
+            Expr::Cast { expr, data_type } => Some(Expr::Cast {
+                expr: Box::new(self.rewrite(expr)?),
+                data_type: data_type.clone(),
+            }),
+            Expr::Function(function) if !is_aggregate(function) => {
+                let mut function = function.clone();
+                function.args = function
+                    .args
+                    .iter()
+                    .map(|arg| match arg {
+                        FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) => Some(
+                            FunctionArg::Unnamed(FunctionArgExpr::Expr(self.rewrite(e)?)),
+                        ),
+                        FunctionArg::Named {
+                            name,
+                            arg: FunctionArgExpr::Expr(e),
+                        } => Some(FunctionArg::Named {
+                            name: name.clone(),
+                            arg: FunctionArgExpr::Expr(self.rewrite(e)?),
+                        }),
+                        _ => None,
+                    })
+                    .collect::<Option<_>>()?;
+                Some(Expr::Function(function))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_aggregate(function: &Function) -> bool {
+    AggregateFunctionType::new(&function.name.to_string().to_lowercase()).is_ok()
+}
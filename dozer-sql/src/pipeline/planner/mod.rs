@@ -1,4 +1,7 @@
+pub mod having;
 pub mod projection;
+pub mod pruning;
+pub mod subquery;
 
 #[cfg(test)]
 mod tests;
@@ -1,27 +1,73 @@
 #![allow(dead_code)]
 
+use crate::pipeline::aggregation::grouping::{reads_dimensions, rewrite_for_grouping_set};
+use crate::pipeline::aggregation::processor::AggregationStrategy;
+use crate::pipeline::aggregation::sum::promoted_sum_type;
 use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::builder::ExpressionBuilder;
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
-use dozer_types::types::{FieldDefinition, Schema};
-use sqlparser::ast::{Expr, Ident, Select, SelectItem};
+use crate::pipeline::expression::optimizer::fold_constants;
+use crate::pipeline::expression::typecheck::typecheck;
+use crate::pipeline::planner::pruning::collect_columns;
+use crate::pipeline::planner::subquery::SCALAR_SUBQUERY_PREFIX;
+use dozer_types::types::{FieldDefinition, NullOrdering, Schema, SourceDefinition};
+use sqlparser::ast::{
+    Expr, FunctionArg, FunctionArgExpr, ObjectName, OrderByExpr, Select, SelectItem,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::mem::take;
 
-#[derive(Clone, Copy)]
+/// What the output of a planned query uses as its primary key, see
+/// [`CommonPlanner::primary_key_action`].
+///
+/// Aggregations emit one row per group: an `Insert` when a group first passes `HAVING`, an
+/// `Update` when its values change, and a `Delete` when it empties or stops passing `HAVING`.
+/// Sinks that keep the current rows must therefore upsert by the group key, rather than append.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PrimaryKeyAction {
+    /// The output has no group key, and keeps the primary key of the input, if any.
     Retain,
+    /// The output is grouped by a key that isn't fully projected, so its rows have no primary key.
     Drop,
+    /// The projected `GROUP BY` columns are the primary key of the output, and every operation
+    /// refers to the row of one group.
     Force,
 }
 
+/// How the output fields of aggregations without an `AS` alias are named.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AggregateNaming {
+    /// The SQL text of the aggregation, e.g. `SUM(ROUND(a, 2))`.
+    #[default]
+    Expression,
+    /// The function in lower case followed by the columns it reads, in input schema order, e.g.
+    /// `sum_a` for `SUM(ROUND(a, 2))` and `count` for `COUNT(*)`. A name already taken by an
+    /// earlier field gets a numeric suffix, e.g. `sum_a_2`.
+    FunctionAndColumns,
+}
+
 pub struct CommonPlanner {
-    input_schema: Schema,
+    pub(crate) input_schema: Schema,
     pub post_aggregation_schema: Schema,
     pub post_projection_schema: Schema,
     // Vector of aggregations to be appended to the original record
     pub aggregation_output: Vec<Expression>,
     pub having: Option<Expression>,
     pub groupby: Vec<Expression>,
+    // Grouping sets of `ROLLUP`, `CUBE` or `GROUPING SETS`, as indexes into `groupby`. Empty for a
+    // plain `GROUP BY`, which groups by all of `groupby`.
+    pub grouping_sets: Vec<Vec<usize>>,
     pub projection_output: Vec<Expression>,
+    pub aggregation_strategy: AggregationStrategy,
+    // Where `ORDER BY` places `NULL`s in each leading column of the `GROUP BY` key it orders by
+    pub null_ordering: Vec<NullOrdering>,
+    // Plan integer `SUM`s as `Decimal`, so they don't overflow
+    sum_promote_on_overflow: bool,
+    // Names of the unaliased aggregations
+    aggregate_naming: AggregateNaming,
+    // Projection aliases, mapped to the expression they name
+    aliases: HashMap<String, Expr>,
 }
 
 impl CommonPlanner {
@@ -31,46 +77,107 @@ impl CommonPlanner {
         input_schema: &Schema,
         output_schema: &mut Schema,
     ) -> Result<(), PipelineError> {
+        typecheck(expr, input_schema)?;
         let expr_type = expr.get_type(input_schema)?;
         output_schema.fields.push(FieldDefinition::new(
-            alias.unwrap_or_else(|| expr.to_string(input_schema)),
+            expr.to_string(input_schema),
             expr_type.return_type,
             expr_type.nullable,
             expr_type.source,
         ));
+        if let Some(alias) = alias {
+            *output_schema = output_schema.rename_field(output_schema.fields.len() - 1, &alias);
+        }
 
         Ok(())
     }
 
+    /// Builds `expr`, whose aggregations are computed once whichever clause they appear in.
+    ///
+    /// Aggregations already in `aggregation_output` are reused, and new ones are added to it,
+    /// named after `alias` if any.
+    fn build_with_aggregations(
+        &mut self,
+        expr: &Expr,
+        alias: Option<String>,
+    ) -> Result<Expression, PipelineError> {
+        let existing = self.aggregation_output.len();
+        let mut builder = ExpressionBuilder::from(
+            self.input_schema.fields.len(),
+            self.aggregation_output.clone(),
+        );
+        let expression = builder.build(true, expr, &self.input_schema)?;
+
+        for new_aggr in builder.aggregations.into_iter().skip(existing) {
+            let name = alias.clone().or_else(|| self.aggregate_name(&new_aggr));
+            Self::append_to_schema(
+                &new_aggr,
+                name,
+                &self.input_schema,
+                &mut self.post_aggregation_schema,
+            )?;
+            if self.sum_promote_on_overflow && is_sum(&new_aggr) {
+                let field = self.post_aggregation_schema.fields.last_mut().unwrap();
+                field.typ = promoted_sum_type(field.typ);
+            }
+            self.aggregation_output.push(new_aggr);
+        }
+        Ok(expression)
+    }
+
+    /// Name of the unaliased aggregation `aggr` under [`AggregateNaming::FunctionAndColumns`],
+    /// or `None` to name it after its SQL text.
+    fn aggregate_name(&self, aggr: &Expression) -> Option<String> {
+        let (AggregateNaming::FunctionAndColumns, Expression::AggregateFunction { fun, args }) =
+            (self.aggregate_naming, aggr)
+        else {
+            return None;
+        };
+
+        let mut columns = BTreeSet::new();
+        for arg in args {
+            collect_columns(arg, &mut columns);
+        }
+        let name = std::iter::once(fun.to_string().to_lowercase())
+            .chain(
+                columns
+                    .into_iter()
+                    .map(|index| self.input_schema.fields[index].name.clone()),
+            )
+            .collect::<Vec<_>>()
+            .join("_");
+
+        let is_taken = |name: &str| {
+            self.post_aggregation_schema
+                .fields
+                .iter()
+                .any(|field| field.name == name)
+        };
+        if !is_taken(&name) {
+            return Some(name);
+        }
+        (2..)
+            .map(|suffix| format!("{name}_{suffix}"))
+            .find(|name| !is_taken(name))
+    }
+
     fn add_select_item(&mut self, item: SelectItem) -> Result<(), PipelineError> {
         let expr_items: Vec<(Expr, Option<String>)> = match item {
             SelectItem::UnnamedExpr(expr) => vec![(expr, None)],
             SelectItem::ExprWithAlias { expr, alias } => vec![(expr, Some(alias.value))],
-            SelectItem::QualifiedWildcard(_, _) => panic!("not supported yet"),
-            SelectItem::Wildcard(_) => self
-                .input_schema
-                .fields
-                .iter()
-                .map(|col| (Expr::Identifier(Ident::new(col.to_owned().name)), None))
-                .collect(),
+            SelectItem::QualifiedWildcard(qualifier, _) => {
+                return self.add_wildcard_items(Some(&qualifier))
+            }
+            SelectItem::Wildcard(_) => return self.add_wildcard_items(None),
         };
 
         for (expr, alias) in expr_items {
-            let mut builder = ExpressionBuilder::new(
-                self.input_schema.fields.len() + self.aggregation_output.len(),
-            );
-            let projection_expression = builder.build(true, &expr, &self.input_schema)?;
-
-            for new_aggr in builder.aggregations {
-                Self::append_to_schema(
-                    &new_aggr,
-                    alias.clone(),
-                    &self.input_schema,
-                    &mut self.post_aggregation_schema,
-                )?;
-                self.aggregation_output.push(new_aggr);
+            if let Some(alias) = &alias {
+                self.aliases.insert(alias.clone(), expr.clone());
             }
 
+            let projection_expression = self.build_with_aggregations(&expr, alias.clone())?;
+
             self.projection_output.push(projection_expression.clone());
             Self::append_to_schema(
                 &projection_expression,
@@ -87,25 +194,14 @@ impl CommonPlanner {
         let expr_items: Vec<(Expr, Option<String>)> = match item {
             SelectItem::UnnamedExpr(expr) => vec![(expr, None)],
             SelectItem::ExprWithAlias { expr, alias } => vec![(expr, Some(alias.value))],
-            SelectItem::QualifiedWildcard(_, _) => panic!("not supported yet"),
-            SelectItem::Wildcard(_) => panic!("not supported yet"),
+            SelectItem::QualifiedWildcard(qualifier, _) => {
+                return self.add_wildcard_items(Some(&qualifier))
+            }
+            SelectItem::Wildcard(_) => return self.add_wildcard_items(None),
         };
 
         for (expr, alias) in expr_items {
-            let mut builder = ExpressionBuilder::new(
-                self.input_schema.fields.len() + self.aggregation_output.len(),
-            );
-            let projection_expression = builder.build(true, &expr, &self.input_schema)?;
-
-            for new_aggr in builder.aggregations {
-                Self::append_to_schema(
-                    &new_aggr,
-                    alias.clone(),
-                    &self.input_schema,
-                    &mut self.post_aggregation_schema,
-                )?;
-                self.aggregation_output.push(new_aggr);
-            }
+            let projection_expression = self.build_with_aggregations(&expr, alias.clone())?;
 
             self.projection_output.push(projection_expression.clone());
             Self::append_to_schema(
@@ -119,48 +215,178 @@ impl CommonPlanner {
         Ok(())
     }
 
-    fn add_having_item(&mut self, expr: Expr) -> Result<(), PipelineError> {
-        let mut builder = ExpressionBuilder::from(
-            self.input_schema.fields.len(),
-            self.aggregation_output.clone(),
-        );
-        let having_expression = builder.build(true, &expr, &self.input_schema)?;
-
-        let mut post_aggregation_schema = self.input_schema.clone();
-        let mut aggregation_output = Vec::new();
+    /// Expands `*`, or `qualifier.*`, to one column per field of the input schema, keeping their
+    /// order, names and types.
+    ///
+    /// A qualifier is a table name or alias, optionally preceded by the connection of the table,
+    /// and selects the fields whose source it names, so that each side of a join can be expanded
+    /// on its own.
+    fn add_wildcard_items(&mut self, qualifier: Option<&ObjectName>) -> Result<(), PipelineError> {
+        let indexes: Vec<usize> = self
+            .input_schema
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| qualifier.map_or(true, |q| qualifies(q, &field.source)))
+            .filter(|(_, field)| !field.name.starts_with(SCALAR_SUBQUERY_PREFIX))
+            .map(|(index, _)| index)
+            .collect();
+        if let (Some(qualifier), true) = (qualifier, indexes.is_empty()) {
+            return Err(PipelineError::InvalidQuery(format!(
+                "{qualifier}.* matches no column"
+            )));
+        }
 
-        for new_aggr in builder.aggregations {
+        for index in indexes {
+            let expression = Expression::Column { index };
+            let name = self.input_schema.fields[index].name.clone();
+            self.projection_output.push(expression.clone());
             Self::append_to_schema(
-                &new_aggr,
-                None,
-                &self.input_schema,
-                &mut post_aggregation_schema,
+                &expression,
+                Some(name),
+                &self.post_aggregation_schema,
+                &mut self.post_projection_schema,
             )?;
-            aggregation_output.push(new_aggr);
         }
-        self.aggregation_output = aggregation_output;
-        self.post_aggregation_schema = post_aggregation_schema;
 
-        self.having = Some(having_expression);
+        Ok(())
+    }
+
+    /// Replaces bare identifiers that name a projection alias with the aliased expression, so
+    /// `GROUP BY`, `HAVING` and `ORDER BY` can refer to `SELECT` aliases.
+    ///
+    /// Source columns take precedence: an alias that shadows a column of the input schema is
+    /// ignored, and the identifier keeps referring to the source column.
+    fn resolve_aliases(&self, expr: Expr) -> Expr {
+        match expr {
+            Expr::Identifier(ident) => match self.aliases.get(&ident.value) {
+                Some(aliased)
+                    if !self
+                        .input_schema
+                        .fields
+                        .iter()
+                        .any(|f| f.name == ident.value) =>
+                {
+                    aliased.clone()
+                }
+                _ => Expr::Identifier(ident),
+            },
+            Expr::Nested(expr) => Expr::Nested(Box::new(self.resolve_aliases(*expr))),
+            Expr::UnaryOp { op, expr } => Expr::UnaryOp {
+                op,
+                expr: Box::new(self.resolve_aliases(*expr)),
+            },
+            Expr::BinaryOp { left, op, right } => Expr::BinaryOp {
+                left: Box::new(self.resolve_aliases(*left)),
+                op,
+                right: Box::new(self.resolve_aliases(*right)),
+            },
+            Expr::Cast { expr, data_type } => Expr::Cast {
+                expr: Box::new(self.resolve_aliases(*expr)),
+                data_type,
+            },
+            Expr::Function(mut function) => {
+                function.args = function
+                    .args
+                    .into_iter()
+                    .map(|arg| match arg {
+                        FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) => {
+                            FunctionArg::Unnamed(FunctionArgExpr::Expr(self.resolve_aliases(e)))
+                        }
+                        FunctionArg::Named {
+                            name,
+                            arg: FunctionArgExpr::Expr(e),
+                        } => FunctionArg::Named {
+                            name,
+                            arg: FunctionArgExpr::Expr(self.resolve_aliases(e)),
+                        },
+                        arg => arg,
+                    })
+                    .collect();
+                Expr::Function(function)
+            }
+            expr => expr,
+        }
+    }
+
+    fn add_having_item(&mut self, expr: Expr) -> Result<(), PipelineError> {
+        let expr = self.resolve_aliases(expr);
+        self.having = Some(self.build_with_aggregations(&expr, None)?);
 
         Ok(())
     }
 
     fn add_groupby_items(&mut self, expr_items: Vec<Expr>) -> Result<(), PipelineError> {
-        let mut indexes = vec![];
-        let mut set_pk = true;
+        let mut sets = vec![vec![]];
+        let mut has_grouping_sets = false;
         for expr in expr_items {
-            let mut builder = ExpressionBuilder::new(
-                self.input_schema.fields.len() + self.aggregation_output.len(),
-            );
-            let groupby_expression = builder.build(false, &expr, &self.input_schema)?;
-            self.groupby.push(groupby_expression.clone());
+            // The lists of expressions the item can group by, one per grouping set.
+            let alternatives = match expr {
+                Expr::Rollup(lists) => {
+                    has_grouping_sets = true;
+                    (0..=lists.len())
+                        .rev()
+                        .map(|n| lists[..n].concat())
+                        .collect()
+                }
+                Expr::Cube(lists) => {
+                    has_grouping_sets = true;
+                    (0..1usize << lists.len())
+                        .rev()
+                        .map(|mask| {
+                            lists
+                                .iter()
+                                .enumerate()
+                                .filter(|(i, _)| mask & (1 << (lists.len() - 1 - i)) != 0)
+                                .flat_map(|(_, list)| list.clone())
+                                .collect::<Vec<_>>()
+                        })
+                        .collect()
+                }
+                Expr::GroupingSets(lists) => {
+                    has_grouping_sets = true;
+                    lists
+                }
+                expr => vec![vec![expr]],
+            };
+
+            let mut alternative_indexes = Vec::with_capacity(alternatives.len());
+            for alternative in alternatives {
+                let indexes = alternative
+                    .into_iter()
+                    .map(|expr| self.add_groupby_expression(expr))
+                    .collect::<Result<Vec<_>, _>>()?;
+                alternative_indexes.push(indexes);
+            }
+            sets = sets
+                .iter()
+                .flat_map(|set| {
+                    alternative_indexes.iter().map(move |indexes| {
+                        let mut set = set.clone();
+                        set.extend(indexes);
+                        set
+                    })
+                })
+                .collect();
+        }
 
+        if has_grouping_sets {
+            for set in &mut sets {
+                set.sort();
+                set.dedup();
+            }
+            self.grouping_sets = sets;
+            return Ok(());
+        }
+
+        let mut indexes = vec![];
+        let mut set_pk = true;
+        for groupby_expression in &self.groupby {
             if let Some(e) = self
                 .projection_output
                 .iter()
                 .enumerate()
-                .find(|e| e.1 == &groupby_expression)
+                .find(|e| e.1 == groupby_expression)
             {
                 indexes.push(e.0);
             } else {
@@ -176,6 +402,62 @@ impl CommonPlanner {
         Ok(())
     }
 
+    /// Adds `expr` to the `GROUP BY` dimensions, unless it is one already, and returns its index.
+    fn add_groupby_expression(&mut self, expr: Expr) -> Result<usize, PipelineError> {
+        let expr = self.resolve_aliases(expr);
+        let mut builder =
+            ExpressionBuilder::new(self.input_schema.fields.len() + self.aggregation_output.len());
+        let groupby_expression = builder.build(false, &expr, &self.input_schema)?;
+
+        Ok(
+            match self.groupby.iter().position(|e| e == &groupby_expression) {
+                Some(index) => index,
+                None => {
+                    self.groupby.push(groupby_expression);
+                    self.groupby.len() - 1
+                }
+            },
+        )
+    }
+
+    /// Checks the `GROUPING` calls, and makes the output fields that read dimensions some grouping
+    /// set doesn't group by nullable.
+    fn plan_grouping(&mut self) -> Result<(), PipelineError> {
+        let all: Vec<usize> = (0..self.groupby.len()).collect();
+        for expression in self.projection_output.iter().chain(&self.having) {
+            rewrite_for_grouping_set(expression.clone(), &self.groupby, &all)?;
+        }
+
+        for set in &self.grouping_sets {
+            let missing: Vec<usize> = all
+                .iter()
+                .copied()
+                .filter(|index| !set.contains(index))
+                .collect();
+            for (expression, field) in self
+                .projection_output
+                .iter()
+                .zip(&mut self.post_projection_schema.fields)
+            {
+                if reads_dimensions(expression, &self.groupby, &missing) {
+                    field.nullable = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// How the output of the planned query is keyed. Must be called after `plan`.
+    pub fn primary_key_action(&self) -> PrimaryKeyAction {
+        if self.groupby.is_empty() {
+            PrimaryKeyAction::Retain
+        } else if self.post_projection_schema.primary_index.is_empty() {
+            PrimaryKeyAction::Drop
+        } else {
+            PrimaryKeyAction::Force
+        }
+    }
+
     pub fn plan(&mut self, select: Select) -> Result<(), PipelineError> {
         for expr in select.projection {
             self.add_select_item(expr)?;
@@ -188,9 +470,69 @@ impl CommonPlanner {
             self.add_having_item(having)?;
         }
 
+        self.plan_grouping()?;
+        self.fold_constants();
         Ok(())
     }
 
+    /// Picks the aggregation strategy for a query ordered by `order_by`. Must be called after
+    /// `plan`.
+    ///
+    /// Groups are kept sorted only when the output is ordered ascending by a prefix of the
+    /// `GROUP BY` key with `NULL`s last, which is the order a `BTreeMap` over the key iterates in.
+    /// Otherwise a hash map is faster, and is always used for grouping sets, whose groups don't
+    /// share a key.
+    ///
+    /// Also records where the `NULL`s go in the `GROUP BY` key columns `ORDER BY` starts with,
+    /// for the sorted output of the aggregation.
+    pub fn plan_order_by(&mut self, order_by: &[OrderByExpr]) -> Result<(), PipelineError> {
+        let mut sorted = !order_by.is_empty()
+            && order_by.len() <= self.groupby.len()
+            && self.grouping_sets.is_empty();
+        self.null_ordering.clear();
+        for (item, key) in order_by.iter().zip(&self.groupby) {
+            if item.asc == Some(false) || !self.grouping_sets.is_empty() {
+                sorted = false;
+                break;
+            }
+
+            let expr = self.resolve_aliases(item.expr.clone());
+            let mut builder = ExpressionBuilder::new(
+                self.input_schema.fields.len() + self.aggregation_output.len(),
+            );
+            let expression = fold_constants(builder.build(true, &expr, &self.input_schema)?);
+            if !builder.aggregations.is_empty() || &expression != key {
+                sorted = false;
+                break;
+            }
+
+            if item.nulls_first == Some(true) {
+                self.null_ordering.push(NullOrdering::First);
+                sorted = false;
+            } else {
+                self.null_ordering.push(NullOrdering::Last);
+            }
+        }
+
+        self.aggregation_strategy = if sorted {
+            AggregationStrategy::Sorted
+        } else {
+            AggregationStrategy::Hash
+        };
+        Ok(())
+    }
+
+    /// Folds constants once planning is done, so output field names still reflect the original SQL.
+    fn fold_constants(&mut self) {
+        let fold_all = |expressions: &mut Vec<Expression>| {
+            *expressions = take(expressions).into_iter().map(fold_constants).collect();
+        };
+        fold_all(&mut self.aggregation_output);
+        fold_all(&mut self.projection_output);
+        fold_all(&mut self.groupby);
+        self.having = self.having.take().map(fold_constants);
+    }
+
     pub fn new(input_schema: Schema) -> Self {
         Self {
             input_schema: input_schema.clone(),
@@ -199,7 +541,56 @@ impl CommonPlanner {
             aggregation_output: Vec::new(),
             having: None,
             groupby: Vec::new(),
+            grouping_sets: Vec::new(),
             projection_output: Vec::new(),
+            aggregation_strategy: AggregationStrategy::Hash,
+            null_ordering: Vec::new(),
+            sum_promote_on_overflow: false,
+            aggregate_naming: AggregateNaming::default(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Plans `SUM` of integers as a `Decimal` if `promote` is set, so that sums past the range of
+    /// the integer types are exact, instead of failing with an overflow error. Must be called
+    /// before planning.
+    pub fn with_sum_promote_on_overflow(mut self, promote: bool) -> Self {
+        self.sum_promote_on_overflow = promote;
+        self
+    }
+
+    /// Names the output fields of aggregations without an `AS` alias with `naming`. Must be
+    /// called before planning.
+    pub fn with_aggregate_naming(mut self, naming: AggregateNaming) -> Self {
+        self.aggregate_naming = naming;
+        self
+    }
+}
+
+fn is_sum(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::AggregateFunction {
+            fun: AggregateFunctionType::Sum,
+            ..
+        }
+    )
+}
+
+/// Returns whether `qualifier`, as in `qualifier.*`, names the source of a field.
+fn qualifies(qualifier: &ObjectName, source: &SourceDefinition) -> bool {
+    let idents: Vec<&str> = qualifier
+        .0
+        .iter()
+        .map(|ident| ident.value.as_str())
+        .collect();
+    match (source, idents.as_slice()) {
+        (SourceDefinition::Table { name, .. } | SourceDefinition::Alias { name }, [table]) => {
+            name == table
+        }
+        (SourceDefinition::Table { connection, name }, [conn, table]) => {
+            connection == conn && name == table
         }
+        _ => false,
     }
 }
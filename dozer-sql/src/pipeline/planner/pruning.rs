@@ -0,0 +1,279 @@
+use std::collections::BTreeSet;
+
+use dozer_types::types::Schema;
+
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::projection::processor::ProjectionProcessor;
+
+/// The source columns a planned query actually reads.
+///
+/// Records can be projected down to these columns as soon as they are ingested, with
+/// [`ColumnPruning::projection`], so that wide sources don't carry unused columns through the
+/// pipeline. Expressions planned against the full source schema must then be rewritten with
+/// [`ColumnPruning::remap`], which [`ColumnPruning::apply`] does for a whole planner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnPruning {
+    /// Indices of the referenced source columns, in source order.
+    columns: Vec<usize>,
+    /// Number of columns in the source schema.
+    input_len: usize,
+}
+
+impl ColumnPruning {
+    /// Collects the source columns referenced by `planner`, and by `filters` evaluated against the
+    /// same source schema, e.g. the `WHERE` clause.
+    pub fn new(planner: &CommonPlanner, filters: &[Expression]) -> Self {
+        let input_len = planner.input_schema.fields.len();
+        let mut columns = BTreeSet::new();
+
+        // Aggregations, GROUP BY and filters read the source schema.
+        for expression in planner
+            .aggregation_output
+            .iter()
+            .chain(planner.groupby.iter())
+            .chain(filters)
+        {
+            collect_columns(expression, &mut columns);
+        }
+        // Projections and HAVING read the source schema followed by the aggregation results.
+        let mut post_aggregation_columns = BTreeSet::new();
+        for expression in planner
+            .projection_output
+            .iter()
+            .chain(planner.having.iter())
+        {
+            collect_columns(expression, &mut post_aggregation_columns);
+        }
+        columns.extend(
+            post_aggregation_columns
+                .into_iter()
+                .filter(|index| *index < input_len),
+        );
+
+        Self {
+            columns: columns.into_iter().collect(),
+            input_len,
+        }
+    }
+
+    /// Indices of the referenced source columns, in source order.
+    pub fn columns(&self) -> &[usize] {
+        &self.columns
+    }
+
+    /// Whether every source column is referenced, so there is nothing to prune.
+    pub fn is_noop(&self) -> bool {
+        self.columns.len() == self.input_len
+    }
+
+    /// The source schema restricted to the referenced columns. The primary key is kept only if all
+    /// its columns are referenced.
+    pub fn pruned_schema(&self, schema: &Schema) -> Schema {
+        let mut pruned = Schema::empty();
+        pruned.identifier = schema.identifier;
+        pruned.fields = self
+            .columns
+            .iter()
+            .map(|index| schema.fields[*index].clone())
+            .collect();
+        pruned.primary_index = schema
+            .primary_index
+            .iter()
+            .map(|index| self.columns.binary_search(index).ok())
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default();
+        pruned
+    }
+
+    /// Expressions projecting a source record down to the referenced columns.
+    pub fn projection(&self) -> Vec<Expression> {
+        self.columns
+            .iter()
+            .map(|index| Expression::Column { index: *index })
+            .collect()
+    }
+
+    /// A processor that prunes records of `input_schema` at ingestion.
+    pub fn processor(&self, input_schema: Schema) -> ProjectionProcessor {
+        ProjectionProcessor::new(input_schema, self.projection())
+    }
+
+    /// Rewrites the column indices of `expression` for pruned records. Indices past the source
+    /// columns, which refer to aggregation results, are shifted down by the number of pruned
+    /// columns.
+    pub fn remap(&self, expression: Expression) -> Expression {
+        map_columns(expression, &|index| {
+            if index < self.input_len {
+                self.columns
+                    .binary_search(&index)
+                    .expect("BUG: column was not collected")
+            } else {
+                index - self.input_len + self.columns.len()
+            }
+        })
+    }
+
+    /// Rewrites all expressions and schemas of `planner` to read pruned records.
+    pub fn apply(&self, planner: &mut CommonPlanner) {
+        planner.input_schema = self.pruned_schema(&planner.input_schema);
+
+        let mut post_aggregation_schema = planner.input_schema.clone();
+        post_aggregation_schema.fields.extend(
+            planner
+                .post_aggregation_schema
+                .fields
+                .drain(self.input_len..),
+        );
+        planner.post_aggregation_schema = post_aggregation_schema;
+
+        let remap_all = |expressions: &mut Vec<Expression>| {
+            *expressions = std::mem::take(expressions)
+                .into_iter()
+                .map(|expression| self.remap(expression))
+                .collect();
+        };
+        remap_all(&mut planner.aggregation_output);
+        remap_all(&mut planner.groupby);
+        remap_all(&mut planner.projection_output);
+        planner.having = planner.having.take().map(|having| self.remap(having));
+    }
+}
+
+pub(crate) fn collect_columns(expression: &Expression, columns: &mut BTreeSet<usize>) {
+    match expression {
+        Expression::Column { index } => {
+            columns.insert(*index);
+        }
+        Expression::Literal(_) | Expression::Now { .. } => {}
+        Expression::UnaryOperator { arg, .. }
+        | Expression::DateTimeFunction { arg, .. }
+        | Expression::Cast { arg, .. } => collect_columns(arg, columns),
+        Expression::BinaryOperator { left, right, .. } => {
+            collect_columns(left, columns);
+            collect_columns(right, columns);
+        }
+        Expression::ScalarFunction { args, .. }
+        | Expression::GeoFunction { args, .. }
+        | Expression::ConditionalExpression { args, .. }
+        | Expression::AggregateFunction { args, .. }
+        | Expression::Udf { args, .. } => {
+            for arg in args {
+                collect_columns(arg, columns);
+            }
+        }
+        #[cfg(feature = "python")]
+        Expression::PythonUDF { args, .. } => {
+            for arg in args {
+                collect_columns(arg, columns);
+            }
+        }
+        Expression::Trim { arg, what, .. } => {
+            collect_columns(arg, columns);
+            if let Some(what) = what {
+                collect_columns(what, columns);
+            }
+        }
+        Expression::Like { arg, pattern, .. } => {
+            collect_columns(arg, columns);
+            collect_columns(pattern, columns);
+        }
+    }
+}
+
+fn map_columns(expression: Expression, f: &impl Fn(usize) -> usize) -> Expression {
+    replace_expressions(expression, &mut |expression| match expression {
+        Expression::Column { index } => Some(Expression::Column { index: f(*index) }),
+        _ => None,
+    })
+}
+
+/// Rebuilds `expression`, replacing every sub-expression for which `f` returns a replacement,
+/// outermost first. The children of a replaced sub-expression are not visited.
+pub(crate) fn replace_expressions(
+    expression: Expression,
+    f: &mut impl FnMut(&Expression) -> Option<Expression>,
+) -> Expression {
+    if let Some(replacement) = f(&expression) {
+        return replacement;
+    }
+    let mut boxed = |expression: Box<Expression>| Box::new(replace_expressions(*expression, f));
+    match expression {
+        Expression::Column { .. } | Expression::Literal(_) | Expression::Now { .. } => expression,
+        Expression::UnaryOperator { operator, arg } => Expression::UnaryOperator {
+            operator,
+            arg: boxed(arg),
+        },
+        Expression::BinaryOperator {
+            left,
+            operator,
+            right,
+        } => Expression::BinaryOperator {
+            left: boxed(left),
+            operator,
+            right: boxed(right),
+        },
+        Expression::ScalarFunction { fun, args } => Expression::ScalarFunction {
+            fun,
+            args: replace_all(args, f),
+        },
+        Expression::GeoFunction { fun, args } => Expression::GeoFunction {
+            fun,
+            args: replace_all(args, f),
+        },
+        Expression::ConditionalExpression { fun, args } => Expression::ConditionalExpression {
+            fun,
+            args: replace_all(args, f),
+        },
+        Expression::DateTimeFunction { fun, arg } => Expression::DateTimeFunction {
+            fun,
+            arg: boxed(arg),
+        },
+        Expression::AggregateFunction { fun, args } => Expression::AggregateFunction {
+            fun,
+            args: replace_all(args, f),
+        },
+        Expression::Udf { fun, args } => Expression::Udf {
+            fun,
+            args: replace_all(args, f),
+        },
+        Expression::Cast { arg, typ } => Expression::Cast {
+            arg: boxed(arg),
+            typ,
+        },
+        Expression::Trim { arg, what, typ } => Expression::Trim {
+            arg: boxed(arg),
+            what: what.map(boxed),
+            typ,
+        },
+        Expression::Like {
+            arg,
+            pattern,
+            escape,
+        } => Expression::Like {
+            arg: boxed(arg),
+            pattern: boxed(pattern),
+            escape,
+        },
+        #[cfg(feature = "python")]
+        Expression::PythonUDF {
+            name,
+            args,
+            return_type,
+        } => Expression::PythonUDF {
+            name,
+            args: replace_all(args, f),
+            return_type,
+        },
+    }
+}
+
+fn replace_all(
+    expressions: Vec<Expression>,
+    f: &mut impl FnMut(&Expression) -> Option<Expression>,
+) -> Vec<Expression> {
+    expressions
+        .into_iter()
+        .map(|expression| replace_expressions(expression, f))
+        .collect()
+}
@@ -0,0 +1,182 @@
+use crate::pipeline::errors::PipelineError;
+use sqlparser::ast::{
+    Expr, FunctionArg, FunctionArgExpr, Ident, Query, Select, SelectItem, SetExpr, TableFactor,
+    TableWithJoins,
+};
+
+/// Prefix of the names of the columns that hold the values of scalar subqueries. `*` doesn't
+/// expand to them.
+pub const SCALAR_SUBQUERY_PREFIX: &str = "__scalar_subquery_";
+
+/// Replaces the scalar subqueries of the projection of `select` with references to columns
+/// named with [`SCALAR_SUBQUERY_PREFIX`], and returns the subqueries with the names of their
+/// columns. The subqueries are left to be planned on their own, their values appended to the
+/// input of `select`.
+///
+/// A subquery is correlated if it qualifies a column with a table of the `FROM` of `select`
+/// that isn't in its own `FROM`, and is rejected. A column of an outer table that isn't
+/// qualified can't be told apart before the schemas are known, and fails as an unknown column
+/// of the subquery.
+pub fn extract_scalar_subqueries(
+    select: &mut Select,
+) -> Result<Vec<(String, Query)>, PipelineError> {
+    let mut extractor = SubqueryExtractor {
+        outer_tables: table_names(&select.from),
+        subqueries: vec![],
+    };
+    for item in &mut select.projection {
+        match item {
+            // Keep the name of a projected subquery from being that of its column.
+            SelectItem::UnnamedExpr(expr @ Expr::Subquery(_)) => {
+                let alias = Ident::new(expr.to_string());
+                extractor.extract(expr)?;
+                *item = SelectItem::ExprWithAlias {
+                    expr: expr.clone(),
+                    alias,
+                };
+            }
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                extractor.extract(expr)?
+            }
+            SelectItem::QualifiedWildcard(..) | SelectItem::Wildcard(_) => (),
+        }
+    }
+    Ok(extractor.subqueries)
+}
+
+struct SubqueryExtractor {
+    outer_tables: Vec<String>,
+    subqueries: Vec<(String, Query)>,
+}
+
+impl SubqueryExtractor {
+    fn extract(&mut self, expr: &mut Expr) -> Result<(), PipelineError> {
+        let Expr::Subquery(query) = &*expr else {
+            return children_mut(expr)
+                .into_iter()
+                .try_for_each(|child| self.extract(child));
+        };
+
+        if is_correlated(query, &self.outer_tables) {
+            return Err(PipelineError::UnsupportedSqlConstruct {
+                construct: "correlated subqueries".to_string(),
+                snippet: expr.to_string(),
+            });
+        }
+        let name = format!("{SCALAR_SUBQUERY_PREFIX}{}", self.subqueries.len());
+        self.subqueries.push((name.clone(), (**query).clone()));
+        *expr = Expr::Identifier(Ident::new(name));
+        Ok(())
+    }
+}
+
+fn is_correlated(query: &Query, outer_tables: &[String]) -> bool {
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return false;
+    };
+    let inner_tables = table_names(&select.from);
+    let is_outer = |expr: &Expr| match expr {
+        Expr::CompoundIdentifier(idents) if idents.len() >= 2 => {
+            let table = &idents[idents.len() - 2].value;
+            outer_tables.contains(table) && !inner_tables.contains(table)
+        }
+        _ => false,
+    };
+
+    select
+        .projection
+        .iter()
+        .filter_map(|item| match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => Some(expr),
+            SelectItem::QualifiedWildcard(..) | SelectItem::Wildcard(_) => None,
+        })
+        .chain(&select.selection)
+        .chain(&select.group_by)
+        .chain(&select.having)
+        .any(|expr| any_expr(expr, &is_outer))
+}
+
+/// Whether `predicate` holds for `expr` or any of its sub-expressions.
+fn any_expr(expr: &Expr, predicate: &impl Fn(&Expr) -> bool) -> bool {
+    predicate(expr) || children(expr).into_iter().any(|e| any_expr(e, predicate))
+}
+
+/// Names the tables of `from` can be referred to by: their alias, or the last part of their
+/// name.
+fn table_names(from: &[TableWithJoins]) -> Vec<String> {
+    from.iter()
+        .flat_map(|table| {
+            std::iter::once(&table.relation).chain(table.joins.iter().map(|join| &join.relation))
+        })
+        .filter_map(|relation| match relation {
+            TableFactor::Table { name, alias, .. } => alias
+                .as_ref()
+                .map(|alias| alias.name.value.clone())
+                .or_else(|| name.0.last().map(|ident| ident.value.clone())),
+            TableFactor::Derived { alias, .. } => alias.as_ref().map(|a| a.name.value.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The sub-expressions of `expr` a scalar subquery can appear in. Subqueries aren't entered.
+fn children(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Nested(e)
+        | Expr::UnaryOp { expr: e, .. }
+        | Expr::Cast { expr: e, .. }
+        | Expr::IsNull(e)
+        | Expr::IsNotNull(e)
+        | Expr::Extract { expr: e, .. } => vec![e.as_ref()],
+        Expr::BinaryOp { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+        Expr::Like { expr, pattern, .. } => vec![expr.as_ref(), pattern.as_ref()],
+        Expr::Between {
+            expr, low, high, ..
+        } => vec![expr.as_ref(), low.as_ref(), high.as_ref()],
+        Expr::InList { expr, list, .. } => std::iter::once(&**expr).chain(list).collect(),
+        Expr::Function(function) => function
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(e))
+                | FunctionArg::Named {
+                    arg: FunctionArgExpr::Expr(e),
+                    ..
+                } => Some(e),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Like [`children`], mutably.
+fn children_mut(expr: &mut Expr) -> Vec<&mut Expr> {
+    match expr {
+        Expr::Nested(e)
+        | Expr::UnaryOp { expr: e, .. }
+        | Expr::Cast { expr: e, .. }
+        | Expr::IsNull(e)
+        | Expr::IsNotNull(e)
+        | Expr::Extract { expr: e, .. } => vec![e.as_mut()],
+        Expr::BinaryOp { left, right, .. } => vec![left.as_mut(), right.as_mut()],
+        Expr::Like { expr, pattern, .. } => vec![expr.as_mut(), pattern.as_mut()],
+        Expr::Between {
+            expr, low, high, ..
+        } => vec![expr.as_mut(), low.as_mut(), high.as_mut()],
+        Expr::InList { expr, list, .. } => std::iter::once(&mut **expr).chain(list).collect(),
+        Expr::Function(function) => function
+            .args
+            .iter_mut()
+            .filter_map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(e))
+                | FunctionArg::Named {
+                    arg: FunctionArgExpr::Expr(e),
+                    ..
+                } => Some(e),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
@@ -0,0 +1,29 @@
+use crate::pipeline::planner::having::having_over_output;
+use crate::pipeline::tests::utils::get_select;
+
+fn lowered(sql: &str) -> Option<String> {
+    having_over_output(&get_select(sql).unwrap()).map(|having| having.to_string())
+}
+
+#[test]
+fn test_having_over_output() {
+    assert_eq!(
+        lowered("SELECT a, SUM(b) AS total FROM t0 GROUP BY a HAVING SUM(b) > 10 AND a <> 1"),
+        Some("total > 10 AND a <> 1".to_string())
+    );
+    assert_eq!(
+        lowered("SELECT a, SUM(b) AS total FROM t0 GROUP BY a HAVING ABS(SUM(b) - 1) > 10"),
+        Some("ABS(total - 1) > 10".to_string())
+    );
+
+    // Not projected, or not under a known name.
+    for sql in [
+        "SELECT a, SUM(b) FROM t0 GROUP BY a",
+        "SELECT a, SUM(b) FROM t0 GROUP BY a HAVING SUM(b) > 10",
+        "SELECT a, SUM(b) AS total FROM t0 GROUP BY a HAVING COUNT(b) > 1",
+        "SELECT SUM(b) AS total FROM t0 GROUP BY a HAVING a > 1",
+        "SELECT a, SUM(b) AS total FROM t0 GROUP BY a HAVING total > 10",
+    ] {
+        assert_eq!(lowered(sql), None, "{sql}");
+    }
+}
@@ -1,3 +1,6 @@
 #[cfg(test)]
+mod having_tests;
 mod projection_tests;
+mod pruning_tests;
 mod schema_tests;
+mod subquery_tests;
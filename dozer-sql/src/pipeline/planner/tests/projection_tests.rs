@@ -1,12 +1,21 @@
+use crate::pipeline::aggregation::aggregator::{
+    get_aggregator_type_from_aggregation_expression, AggregatorType,
+};
+use crate::pipeline::aggregation::processor::AggregationStrategy;
+use crate::pipeline::builder::DozerDialect;
+use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
+use crate::pipeline::expression::conditional::ConditionalExpressionType;
 
 use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::operator::BinaryOperatorType;
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
-use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::planner::projection::{AggregateNaming, CommonPlanner, PrimaryKeyAction};
 
 use crate::pipeline::tests::utils::get_select;
 use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use sqlparser::ast::Statement;
+use sqlparser::parser::Parser;
 
 #[test]
 fn test_basic_projection() {
@@ -84,7 +93,7 @@ fn test_basic_projection() {
             .field(
                 FieldDefinition::new(
                     "ROUND(SUM(ROUND(a,2)),2)".to_string(),
-                    FieldType::Int,
+                    FieldType::Decimal,
                     true,
                     SourceDefinition::Dynamic
                 ),
@@ -122,3 +131,509 @@ fn test_basic_projection() {
         })
     );
 }
+
+fn get_projected_type(sql: &str, input_type: FieldType) -> Result<FieldType, PipelineError> {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "a".to_string(),
+                input_type,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+
+    let mut projection_planner = CommonPlanner::new(schema);
+    projection_planner.plan(*get_select(sql).unwrap())?;
+    Ok(projection_planner.post_projection_schema.fields[0].typ)
+}
+
+#[test]
+fn test_aggregation_return_types() {
+    let cases = [
+        ("SELECT SUM(a) FROM t0", FieldType::Int, FieldType::Int),
+        ("SELECT SUM(a) FROM t0", FieldType::Float, FieldType::Float),
+        (
+            "SELECT SUM(a) FROM t0",
+            FieldType::Decimal,
+            FieldType::Decimal,
+        ),
+        ("SELECT AVG(a) FROM t0", FieldType::Int, FieldType::Decimal),
+        ("SELECT AVG(a) FROM t0", FieldType::Float, FieldType::Float),
+        (
+            "SELECT AVG(a) FROM t0",
+            FieldType::Decimal,
+            FieldType::Decimal,
+        ),
+        ("SELECT ROUND(a) FROM t0", FieldType::Int, FieldType::Int),
+        ("SELECT ROUND(a, 0) FROM t0", FieldType::Int, FieldType::Int),
+        (
+            "SELECT ROUND(a, 2) FROM t0",
+            FieldType::Int,
+            FieldType::Decimal,
+        ),
+        (
+            "SELECT ROUND(a, 2) FROM t0",
+            FieldType::Float,
+            FieldType::Float,
+        ),
+        (
+            "SELECT ROUND(a, 2) FROM t0",
+            FieldType::Decimal,
+            FieldType::Decimal,
+        ),
+        (
+            "SELECT SUM(ROUND(a, 2)) FROM t0",
+            FieldType::Int,
+            FieldType::Decimal,
+        ),
+        (
+            "SELECT ROUND(SUM(a), 2) FROM t0",
+            FieldType::Float,
+            FieldType::Float,
+        ),
+        (
+            "SELECT ROUND(AVG(a), 2) FROM t0",
+            FieldType::Int,
+            FieldType::Decimal,
+        ),
+        (
+            "SELECT ROUND(SUM(ROUND(a, 2)), 2) FROM t0",
+            FieldType::Decimal,
+            FieldType::Decimal,
+        ),
+    ];
+
+    for (sql, input_type, expected) in cases {
+        assert_eq!(
+            get_projected_type(sql, input_type).unwrap(),
+            expected,
+            "{sql} over {input_type}"
+        );
+    }
+}
+
+#[test]
+fn test_aggregation_over_non_numeric_is_a_planner_error() {
+    for sql in [
+        "SELECT SUM(a) FROM t0",
+        "SELECT AVG(a) FROM t0",
+        "SELECT ROUND(a, 2) FROM t0",
+        "SELECT ROUND(SUM(ROUND(a, 2)), 2) FROM t0",
+    ] {
+        assert!(matches!(
+            get_projected_type(sql, FieldType::String),
+            Err(PipelineError::InvalidFunctionArgumentType(..))
+        ));
+    }
+}
+
+#[test]
+fn test_aggregation_over_binary() {
+    for sql in ["SELECT MAX(a) FROM t0", "SELECT MIN(a) FROM t0"] {
+        assert_eq!(
+            get_projected_type(sql, FieldType::Binary).unwrap(),
+            FieldType::Binary,
+            "{sql}"
+        );
+    }
+    for sql in ["SELECT SUM(a) FROM t0", "SELECT AVG(a) FROM t0"] {
+        assert!(
+            matches!(
+                get_projected_type(sql, FieldType::Binary),
+                Err(PipelineError::InvalidFunctionArgumentType(
+                    _,
+                    FieldType::Binary,
+                    ..
+                ))
+            ),
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_ill_typed_projection_is_a_planner_error() {
+    for (sql, input_type) in [
+        ("SELECT ROUND('x') FROM t0", FieldType::Int),
+        ("SELECT ABS(a) FROM t0", FieldType::String),
+        ("SELECT -a FROM t0", FieldType::Boolean),
+    ] {
+        assert!(
+            get_projected_type(sql, input_type).is_err(),
+            "{sql} over {input_type}"
+        );
+    }
+}
+
+fn get_alias_test_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "a".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "b".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned()
+}
+
+#[test]
+fn test_group_by_and_having_resolve_aliases() {
+    let sql = "SELECT a AS x, SUM(b) AS total FROM t0 GROUP BY x HAVING total > 10";
+    let mut projection_planner = CommonPlanner::new(get_alias_test_schema());
+    projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+
+    assert_eq!(
+        projection_planner.groupby,
+        vec![Expression::Column { index: 0 }]
+    );
+    assert_eq!(
+        projection_planner.aggregation_output,
+        vec![Expression::AggregateFunction {
+            fun: AggregateFunctionType::Sum,
+            args: vec![Expression::Column { index: 1 }]
+        }]
+    );
+    assert_eq!(
+        projection_planner.having,
+        Some(Expression::BinaryOperator {
+            operator: BinaryOperatorType::Gt,
+            left: Box::new(Expression::Column { index: 2 }),
+            right: Box::new(Expression::Literal(Field::Int(10)))
+        })
+    );
+}
+
+#[test]
+fn test_source_column_shadows_alias() {
+    let sql = "SELECT b AS a FROM t0 GROUP BY a";
+    let mut projection_planner = CommonPlanner::new(get_alias_test_schema());
+    projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+
+    assert_eq!(
+        projection_planner.groupby,
+        vec![Expression::Column { index: 0 }]
+    );
+}
+
+fn plan_order_by_strategy(sql: &str) -> AggregationStrategy {
+    let statement = Parser::parse_sql(&DozerDialect, sql).unwrap().remove(0);
+    let Statement::Query(query) = statement else {
+        panic!("Expected a query");
+    };
+    let mut projection_planner = CommonPlanner::new(get_alias_test_schema());
+    projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+    projection_planner.plan_order_by(&query.order_by).unwrap();
+    projection_planner.aggregation_strategy
+}
+
+#[test]
+fn test_order_by_resolves_aliases() {
+    // `x` names the `GROUP BY` key, so the output is ordered by it.
+    assert_eq!(
+        plan_order_by_strategy("SELECT a AS x, SUM(b) FROM t0 GROUP BY a ORDER BY x"),
+        AggregationStrategy::Sorted
+    );
+    assert_eq!(
+        plan_order_by_strategy("SELECT b AS x, SUM(b) FROM t0 GROUP BY a ORDER BY x"),
+        AggregationStrategy::Hash
+    );
+    // The source column `a` shadows the alias, so the output is not ordered by `b`.
+    assert_eq!(
+        plan_order_by_strategy("SELECT b AS a, SUM(b) FROM t0 GROUP BY b ORDER BY a"),
+        AggregationStrategy::Hash
+    );
+}
+
+#[test]
+fn test_having_reuses_selected_aggregate() {
+    let sql = "SELECT a, SUM(b) AS total, SUM(b) + 1 FROM t0 GROUP BY a HAVING SUM(b) > 10";
+    let mut projection_planner = CommonPlanner::new(get_alias_test_schema());
+    projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+
+    assert_eq!(
+        projection_planner.aggregation_output,
+        vec![Expression::AggregateFunction {
+            fun: AggregateFunctionType::Sum,
+            args: vec![Expression::Column { index: 1 }]
+        }]
+    );
+    assert_eq!(
+        projection_planner.projection_output,
+        vec![
+            Expression::Column { index: 0 },
+            Expression::Column { index: 2 },
+            Expression::BinaryOperator {
+                operator: BinaryOperatorType::Add,
+                left: Box::new(Expression::Column { index: 2 }),
+                right: Box::new(Expression::Literal(Field::Int(1)))
+            }
+        ]
+    );
+    assert_eq!(
+        projection_planner.having,
+        Some(Expression::BinaryOperator {
+            operator: BinaryOperatorType::Gt,
+            left: Box::new(Expression::Column { index: 2 }),
+            right: Box::new(Expression::Literal(Field::Int(10)))
+        })
+    );
+    let post_aggregation_fields = &projection_planner.post_aggregation_schema.fields;
+    assert_eq!(post_aggregation_fields.len(), 3);
+    assert_eq!(post_aggregation_fields[2].name, "total");
+}
+
+#[test]
+fn test_having_adds_hidden_aggregate() {
+    let sql = "SELECT a, SUM(b) FROM t0 GROUP BY a HAVING COUNT(b) > 1";
+    let mut projection_planner = CommonPlanner::new(get_alias_test_schema());
+    projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+
+    assert_eq!(
+        projection_planner.aggregation_output,
+        vec![
+            Expression::AggregateFunction {
+                fun: AggregateFunctionType::Sum,
+                args: vec![Expression::Column { index: 1 }]
+            },
+            Expression::AggregateFunction {
+                fun: AggregateFunctionType::Count,
+                args: vec![Expression::Column { index: 1 }]
+            }
+        ]
+    );
+    assert_eq!(
+        projection_planner.having,
+        Some(Expression::BinaryOperator {
+            operator: BinaryOperatorType::Gt,
+            left: Box::new(Expression::Column { index: 3 }),
+            right: Box::new(Expression::Literal(Field::Int(1)))
+        })
+    );
+    assert_eq!(projection_planner.post_aggregation_schema.fields.len(), 4);
+    // The hidden measure is not part of the output.
+    assert_eq!(projection_planner.post_projection_schema.fields.len(), 2);
+}
+
+#[test]
+fn test_primary_key_action() {
+    for (sql, action, primary_index) in [
+        ("SELECT a, b FROM t0", PrimaryKeyAction::Retain, vec![]),
+        (
+            "SELECT a, SUM(b) FROM t0 GROUP BY a",
+            PrimaryKeyAction::Force,
+            vec![0],
+        ),
+        (
+            "SELECT SUM(b) FROM t0 GROUP BY a",
+            PrimaryKeyAction::Drop,
+            vec![],
+        ),
+    ] {
+        let mut projection_planner = CommonPlanner::new(get_alias_test_schema());
+        projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+
+        assert_eq!(projection_planner.primary_key_action(), action, "{sql}");
+        assert_eq!(
+            projection_planner.post_projection_schema.primary_index, primary_index,
+            "{sql}"
+        );
+    }
+}
+
+fn get_join_test_schema() -> Schema {
+    let field = |name: &str, typ: FieldType, table: &str| {
+        FieldDefinition::new(
+            name.to_string(),
+            typ,
+            false,
+            SourceDefinition::Table {
+                connection: "c0".to_string(),
+                name: table.to_string(),
+            },
+        )
+    };
+    Schema::empty()
+        .field(field("id", FieldType::Int, "t0"), false)
+        .field(field("name", FieldType::String, "t0"), false)
+        .field(field("id", FieldType::Int, "t1"), false)
+        .field(field("price", FieldType::Float, "t1"), false)
+        .to_owned()
+}
+
+#[test]
+fn test_wildcard_projection() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "a".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "b".to_string(),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+    let mut projection_planner = CommonPlanner::new(schema.clone());
+    projection_planner
+        .plan(*get_select("SELECT * FROM t0").unwrap())
+        .unwrap();
+
+    assert_eq!(
+        projection_planner.projection_output,
+        vec![
+            Expression::Column { index: 0 },
+            Expression::Column { index: 1 }
+        ]
+    );
+    assert_eq!(
+        projection_planner.post_projection_schema.fields,
+        schema.fields
+    );
+}
+
+#[test]
+fn test_qualified_wildcard_projection() {
+    let schema = get_join_test_schema();
+    let mut projection_planner = CommonPlanner::new(schema.clone());
+    projection_planner
+        .plan(*get_select("SELECT t1.*, t0.name FROM t0 JOIN t1 ON t0.id = t1.id").unwrap())
+        .unwrap();
+
+    assert_eq!(
+        projection_planner.projection_output,
+        vec![
+            Expression::Column { index: 2 },
+            Expression::Column { index: 3 },
+            Expression::Column { index: 1 }
+        ]
+    );
+    assert_eq!(
+        projection_planner.post_projection_schema.fields[..2],
+        schema.fields[2..]
+    );
+
+    let mut projection_planner = CommonPlanner::new(schema.clone());
+    projection_planner
+        .plan(*get_select("SELECT c0.t0.* FROM t0").unwrap())
+        .unwrap();
+    assert_eq!(
+        projection_planner.post_projection_schema.fields,
+        schema.fields[..2]
+    );
+}
+
+#[test]
+fn test_qualified_wildcard_matching_nothing() {
+    let mut projection_planner = CommonPlanner::new(get_join_test_schema());
+    let result = projection_planner.plan(*get_select("SELECT t2.* FROM t0").unwrap());
+    assert!(matches!(result, Err(PipelineError::InvalidQuery(_))));
+}
+
+#[test]
+fn test_aggregate_naming() {
+    let sql = "SELECT a, SUM(b) AS total, SUM(ROUND(b, 2)), COUNT(*), MAX(a + b), SUM(b) \
+        FROM t0 GROUP BY a";
+    let field_names = |naming| {
+        let mut projection_planner =
+            CommonPlanner::new(get_alias_test_schema()).with_aggregate_naming(naming);
+        projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+        projection_planner
+            .post_projection_schema
+            .fields
+            .into_iter()
+            .map(|field| field.name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        field_names(AggregateNaming::FunctionAndColumns),
+        vec!["a", "total", "sum_b", "count", "max_a_b", "total"]
+    );
+    // By default, unaliased aggregations are named after their SQL text.
+    let names = field_names(AggregateNaming::Expression);
+    assert_eq!(names[..2], ["a", "total"]);
+    assert!(names[2].starts_with("SUM(ROUND("), "{names:?}");
+    assert!(names[3].starts_with("COUNT("), "{names:?}");
+    assert!(names[4].starts_with("MAX("), "{names:?}");
+    assert_eq!(names[5], "total");
+}
+
+#[test]
+fn test_aggregates_over_conditional_arguments() {
+    // One measure per pivoted column, each keeping its own argument.
+    let sql = "SELECT a, SUM(COALESCE(b, 0)) AS filled, SUM(b * 2) AS doubled FROM t0 GROUP BY a";
+    let schema = get_alias_test_schema();
+    let mut projection_planner = CommonPlanner::new(schema.clone());
+    projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+
+    let filled = Expression::ConditionalExpression {
+        fun: ConditionalExpressionType::Coalesce,
+        args: vec![
+            Expression::Column { index: 1 },
+            Expression::Literal(Field::Int(0)),
+        ],
+    };
+    let doubled = Expression::BinaryOperator {
+        left: Box::new(Expression::Column { index: 1 }),
+        operator: BinaryOperatorType::Mul,
+        right: Box::new(Expression::Literal(Field::Int(2))),
+    };
+    assert_eq!(
+        projection_planner.aggregation_output,
+        vec![
+            Expression::AggregateFunction {
+                fun: AggregateFunctionType::Sum,
+                args: vec![filled.clone()]
+            },
+            Expression::AggregateFunction {
+                fun: AggregateFunctionType::Sum,
+                args: vec![doubled.clone()]
+            },
+        ]
+    );
+    assert_eq!(
+        projection_planner.projection_output,
+        vec![
+            Expression::Column { index: 0 },
+            Expression::Column { index: 2 },
+            Expression::Column { index: 3 },
+        ]
+    );
+
+    let measures = projection_planner
+        .aggregation_output
+        .iter()
+        .map(|measure| get_aggregator_type_from_aggregation_expression(measure, &schema).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        measures,
+        vec![
+            (vec![filled], AggregatorType::Sum),
+            (vec![doubled], AggregatorType::Sum),
+        ]
+    );
+}
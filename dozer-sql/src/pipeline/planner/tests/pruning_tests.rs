@@ -0,0 +1,98 @@
+use crate::pipeline::expression::aggregate::AggregateFunctionType;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::planner::pruning::ColumnPruning;
+
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+fn get_wide_schema() -> Schema {
+    let mut schema = Schema::empty();
+    for index in 0..10 {
+        schema.field(
+            FieldDefinition::new(
+                format!("c{index}"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            index == 0,
+        );
+    }
+    schema
+}
+
+#[test]
+fn test_prune_unreferenced_columns() {
+    let sql = "SELECT c2, SUM(c7) FROM t0 GROUP BY c2";
+    let schema = get_wide_schema();
+    let mut projection_planner = CommonPlanner::new(schema.clone());
+    projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+
+    let pruning = ColumnPruning::new(&projection_planner, &[]);
+    assert_eq!(pruning.columns(), &[2, 7]);
+    assert!(!pruning.is_noop());
+
+    // The primary key `c0` isn't referenced, so the pruned schema has none.
+    let pruned_schema = pruning.pruned_schema(&schema);
+    let names = pruned_schema
+        .fields
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["c2", "c7"]);
+    assert!(pruned_schema.primary_index.is_empty());
+
+    let record = Record::new(None, (0..10).map(Field::Int).collect());
+    let pruned_record = Record::new(
+        None,
+        pruning
+            .projection()
+            .iter()
+            .map(|expression| expression.evaluate(&record, &schema).unwrap())
+            .collect(),
+    );
+    assert_eq!(pruned_record.values, vec![Field::Int(2), Field::Int(7)]);
+
+    pruning.apply(&mut projection_planner);
+    assert_eq!(
+        projection_planner.groupby,
+        vec![Expression::Column { index: 0 }]
+    );
+    assert_eq!(
+        projection_planner.aggregation_output,
+        vec![Expression::AggregateFunction {
+            fun: AggregateFunctionType::Sum,
+            args: vec![Expression::Column { index: 1 }]
+        }]
+    );
+    // `SUM(c7)` was appended after the 10 source columns, now after the 2 kept ones.
+    assert_eq!(
+        projection_planner.projection_output,
+        vec![
+            Expression::Column { index: 0 },
+            Expression::Column { index: 2 }
+        ]
+    );
+    assert_eq!(projection_planner.post_aggregation_schema.fields.len(), 3);
+    assert_eq!(
+        projection_planner.groupby[0]
+            .evaluate(&pruned_record, &pruned_schema)
+            .unwrap(),
+        Field::Int(2)
+    );
+}
+
+#[test]
+fn test_pruning_keeps_referenced_primary_key() {
+    let sql = "SELECT c0, c5 FROM t0";
+    let schema = get_wide_schema();
+    let mut projection_planner = CommonPlanner::new(schema.clone());
+    projection_planner.plan(*get_select(sql).unwrap()).unwrap();
+
+    let filter = Expression::Column { index: 9 };
+    let pruning = ColumnPruning::new(&projection_planner, &[filter.clone()]);
+    assert_eq!(pruning.columns(), &[0, 5, 9]);
+    assert_eq!(pruning.pruned_schema(&schema).primary_index, vec![0]);
+    assert_eq!(pruning.remap(filter), Expression::Column { index: 2 });
+}
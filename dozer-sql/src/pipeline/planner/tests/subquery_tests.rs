@@ -0,0 +1,65 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::planner::subquery::extract_scalar_subqueries;
+use crate::pipeline::tests::utils::get_select;
+
+#[test]
+fn test_extract_scalar_subqueries() {
+    let mut select = get_select(
+        "SELECT a, b - (SELECT MAX(b) FROM t1) AS gap, (SELECT COUNT(c) FROM t0 AS x) FROM t0",
+    )
+    .unwrap();
+    let subqueries = extract_scalar_subqueries(&mut select).unwrap();
+
+    let subqueries = subqueries
+        .into_iter()
+        .map(|(column, query)| (column, query.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        subqueries,
+        vec![
+            (
+                "__scalar_subquery_0".to_string(),
+                "SELECT MAX(b) FROM t1".to_string()
+            ),
+            (
+                "__scalar_subquery_1".to_string(),
+                "SELECT COUNT(c) FROM t0 AS x".to_string()
+            ),
+        ]
+    );
+    let projection = select
+        .projection
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        projection,
+        vec![
+            "a",
+            "b - __scalar_subquery_0 AS gap",
+            "__scalar_subquery_1 AS (SELECT COUNT(c) FROM t0 AS x)",
+        ]
+    );
+}
+
+#[test]
+fn test_correlated_subqueries_are_rejected() {
+    for sql in [
+        "SELECT a, (SELECT MAX(b) FROM t1 WHERE t1.c = t0.c) FROM t0",
+        "SELECT a, (SELECT MAX(y.b) FROM t1 AS y WHERE y.c = x.c) FROM t0 AS x",
+    ] {
+        let mut select = get_select(sql).unwrap();
+        assert!(
+            matches!(
+                extract_scalar_subqueries(&mut select),
+                Err(PipelineError::UnsupportedSqlConstruct { construct, .. })
+                    if construct == "correlated subqueries"
+            ),
+            "{sql}"
+        );
+    }
+
+    // A table of the outer query read again in the subquery isn't a correlation.
+    let mut select = get_select("SELECT a, (SELECT MAX(t0.b) FROM t0) FROM t0").unwrap();
+    assert_eq!(extract_scalar_subqueries(&mut select).unwrap().len(), 1);
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use dozer_core::{
     errors::ExecutionError,
-    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+    node::{OutputPortDef, OutputPortType, Port, PortHandle, Processor, ProcessorFactory},
     DEFAULT_PORT_HANDLE,
 };
 use dozer_types::types::{FieldDefinition, Record, Schema};
@@ -12,6 +12,7 @@ use sqlparser::ast::{
 };
 
 use crate::pipeline::expression::builder::ExpressionBuilder;
+use crate::pipeline::state_ttl::StateTtl;
 use crate::pipeline::{builder::SchemaSQLContext, expression::builder::extend_schema_source_def};
 use crate::pipeline::{errors::JoinError, expression::builder::NameOrAlias};
 
@@ -20,14 +21,16 @@ use super::{
     processor::ProductProcessor,
 };
 
-pub(crate) const LEFT_JOIN_PORT: PortHandle = 0;
-pub(crate) const RIGHT_JOIN_PORT: PortHandle = 1;
+pub(crate) const LEFT_JOIN_PORT: PortHandle = Port::numbered(0).handle();
+pub(crate) const RIGHT_JOIN_PORT: PortHandle = Port::numbered(1).handle();
 
 #[derive(Debug)]
 pub struct JoinProcessorFactory {
     left: Option<NameOrAlias>,
     right: Option<NameOrAlias>,
     join_operator: SqlJoinOperator,
+    left_ttl: Option<StateTtl>,
+    right_ttl: Option<StateTtl>,
 }
 
 impl JoinProcessorFactory {
@@ -40,8 +43,17 @@ impl JoinProcessorFactory {
             left,
             right,
             join_operator,
+            left_ttl: None,
+            right_ttl: None,
         }
     }
+
+    /// Makes the built processors evict records of each branch once they are older than its TTL.
+    pub fn with_ttl(mut self, left: Option<StateTtl>, right: Option<StateTtl>) -> Self {
+        self.left_ttl = left;
+        self.right_ttl = right;
+        self
+    }
 }
 
 impl ProcessorFactory<SchemaSQLContext> for JoinProcessorFactory {
@@ -174,7 +186,9 @@ impl ProcessorFactory<SchemaSQLContext> for JoinProcessorFactory {
             Record::from_schema(&right_schema),
         );
 
-        Ok(Box::new(ProductProcessor::new(join_operator)))
+        Ok(Box::new(
+            ProductProcessor::new(join_operator).with_ttl(self.left_ttl, self.right_ttl),
+        ))
     }
 }
 
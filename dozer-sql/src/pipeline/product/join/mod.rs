@@ -3,6 +3,6 @@ use crate::pipeline::errors::JoinError;
 pub mod factory;
 
 pub(crate) mod operator;
-mod processor;
+pub(crate) mod processor;
 
 type JoinResult<T> = Result<T, JoinError>;
@@ -256,6 +256,26 @@ impl JoinOperator {
         Ok(matching_count)
     }
 
+    /// Whether `record` is currently held for the `from` branch.
+    pub fn contains(&self, from: &JoinBranch, record: &Record) -> bool {
+        let (join_map, join_key_indexes, primary_key_indexes) = match from {
+            JoinBranch::Left => (
+                &self.left_map,
+                &self.left_join_key_indexes,
+                &self.left_primary_key_indexes,
+            ),
+            JoinBranch::Right => (
+                &self.right_map,
+                &self.right_join_key_indexes,
+                &self.right_primary_key_indexes,
+            ),
+        };
+        join_map
+            .get(&get_record_key(record, join_key_indexes))
+            .and_then(|record_map| record_map.get(&get_record_key(record, primary_key_indexes)))
+            .map_or(false, |records| records.contains(record))
+    }
+
     pub fn delete(
         &mut self,
         from: &JoinBranch,
@@ -1,28 +1,103 @@
 use dozer_core::channels::ProcessorChannelForwarder;
 use dozer_core::epoch::Epoch;
 use dozer_core::errors::ExecutionError;
-use dozer_core::node::{PortHandle, Processor};
+use dozer_core::node::{InputSelectionPolicy, PortHandle, Processor};
 use dozer_core::DEFAULT_PORT_HANDLE;
-use dozer_types::types::Operation;
+use dozer_types::types::{Operation, Record};
+
+use crate::pipeline::state_ttl::{StateTtl, TtlTracker};
 
 use super::operator::{JoinAction, JoinBranch, JoinOperator};
 
 #[derive(Debug)]
 pub struct ProductProcessor {
     join_operator: JoinOperator,
+    left_ttl: Option<TtlTracker<Record>>,
+    right_ttl: Option<TtlTracker<Record>>,
 }
 
 impl ProductProcessor {
     pub fn new(join_operator: JoinOperator) -> Self {
-        Self { join_operator }
+        Self {
+            join_operator,
+            left_ttl: None,
+            right_ttl: None,
+        }
+    }
+
+    /// Evicts records of either branch once they are older than its TTL, at the next commit.
+    /// Evicting a record retracts the joined records it produced, as deleting it would.
+    ///
+    /// Each branch ages its records on its own timestamps.
+    pub fn with_ttl(mut self, left: Option<StateTtl>, right: Option<StateTtl>) -> Self {
+        self.left_ttl = left.map(TtlTracker::new);
+        self.right_ttl = right.map(TtlTracker::new);
+        self
+    }
+
+    fn track(&mut self, from: &JoinBranch, record: &Record) {
+        let ttl = match from {
+            JoinBranch::Left => &mut self.left_ttl,
+            JoinBranch::Right => &mut self.right_ttl,
+        };
+        if let Some(ttl) = ttl {
+            ttl.track(record.clone(), record);
+        }
+    }
+
+    fn evict_expired(
+        &mut self,
+        from: &JoinBranch,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        let ttl = match from {
+            JoinBranch::Left => &mut self.left_ttl,
+            JoinBranch::Right => &mut self.right_ttl,
+        };
+        let Some(ttl) = ttl else {
+            return Ok(());
+        };
+
+        for record in ttl.take_expired() {
+            if ttl.is_expired(&record) && self.join_operator.contains(from, &record) {
+                let records = self
+                    .join_operator
+                    .delete(from, &record)
+                    .map_err(|err| ExecutionError::InternalError(Box::new(err)))?;
+                send_records(records, fw)?;
+            }
+        }
+        Ok(())
     }
 }
 
+fn send_records(
+    records: Vec<(JoinAction, Record)>,
+    fw: &mut dyn ProcessorChannelForwarder,
+) -> Result<(), ExecutionError> {
+    for (action, record) in records {
+        match action {
+            JoinAction::Insert => {
+                fw.send(Operation::Insert { new: record }, DEFAULT_PORT_HANDLE)?;
+            }
+            JoinAction::Delete => {
+                fw.send(Operation::Delete { old: record }, DEFAULT_PORT_HANDLE)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 impl Processor for ProductProcessor {
     fn commit(&self, _epoch: &Epoch) -> Result<(), ExecutionError> {
         Ok(())
     }
 
+    fn input_selection_policy(&self) -> InputSelectionPolicy {
+        // Keep a flooding side of the join from starving the other one.
+        InputSelectionPolicy::RoundRobin
+    }
+
     fn process(
         &mut self,
         from_port: PortHandle,
@@ -39,16 +114,19 @@ impl Processor for ProductProcessor {
                 .join_operator
                 .delete(from_branch, old)
                 .map_err(|err| ExecutionError::InternalError(Box::new(err)))?,
-            Operation::Insert { ref new } => self
-                .join_operator
-                .insert(from_branch, new)
-                .map_err(|err| ExecutionError::InternalError(Box::new(err)))?,
+            Operation::Insert { ref new } => {
+                self.track(from_branch, new);
+                self.join_operator
+                    .insert(from_branch, new)
+                    .map_err(|err| ExecutionError::InternalError(Box::new(err)))?
+            }
             Operation::Update { ref old, ref new } => {
                 let old_records = self
                     .join_operator
                     .delete(from_branch, old)
                     .map_err(|err| ExecutionError::InternalError(Box::new(err)))?;
 
+                self.track(from_branch, new);
                 let new_records = self
                     .join_operator
                     .insert(from_branch, new)
@@ -61,17 +139,11 @@ impl Processor for ProductProcessor {
             }
         };
 
-        for (action, record) in records {
-            match action {
-                JoinAction::Insert => {
-                    fw.send(Operation::Insert { new: record }, DEFAULT_PORT_HANDLE)?;
-                }
-                JoinAction::Delete => {
-                    fw.send(Operation::Delete { old: record }, DEFAULT_PORT_HANDLE)?;
-                }
-            }
-        }
+        send_records(records, fw)
+    }
 
-        Ok(())
+    fn flush(&mut self, fw: &mut dyn ProcessorChannelForwarder) -> Result<(), ExecutionError> {
+        self.evict_expired(&JoinBranch::Left, fw)?;
+        self.evict_expired(&JoinBranch::Right, fw)
     }
 }
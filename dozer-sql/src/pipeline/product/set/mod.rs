@@ -1,4 +1,4 @@
 pub mod set_factory;
-mod set_processor;
+pub(crate) mod set_processor;
 
 pub(crate) mod operator;
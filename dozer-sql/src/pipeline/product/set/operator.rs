@@ -1,7 +1,7 @@
 use crate::pipeline::errors::PipelineError;
-use bloom::{CountingBloomFilter, ASMS};
-use dozer_types::types::Record;
+use dozer_types::types::{Field, Record};
 use sqlparser::ast::{SetOperator, SetQuantifier};
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, PartialEq, Eq, Copy)]
 pub enum SetAction {
@@ -10,6 +10,17 @@ pub enum SetAction {
     // Update,
 }
 
+/// Input of a set operation a record was received on.
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+pub enum SetBranch {
+    Left,
+    Right,
+}
+
+/// Number of times each distinct row is currently present in the left and the right input of a
+/// set operation.
+pub type RecordCounts = HashMap<Vec<Field>, [usize; 2]>;
+
 #[derive(Clone, Debug)]
 pub struct SetOperation {
     pub op: SetOperator,
@@ -24,68 +35,79 @@ impl SetOperation {
         }
     }
 
+    /// Applies `action` on `record`, received on `branch`, and returns the changes to the
+    /// output.
     pub fn execute(
         &self,
         action: SetAction,
+        branch: SetBranch,
         record: &Record,
-        record_map: &mut CountingBloomFilter,
+        record_counts: &mut RecordCounts,
     ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
         match (self.op, self.quantifier) {
             (SetOperator::Union, SetQuantifier::All) => Ok(vec![(action, record.clone())]),
-            (SetOperator::Union, SetQuantifier::None) => {
-                self.execute_union(action, record, record_map)
-            }
-            _ => Err(PipelineError::InvalidOperandType(self.op.to_string())),
+            _ => Ok(self.execute_counted(action, branch, record, record_counts)),
         }
     }
 
-    fn execute_union(
+    /// Updates the counts of the row on the input it was received on, and sends as many copies
+    /// of it as its number of occurrences in the output changed by.
+    fn execute_counted(
         &self,
         action: SetAction,
+        branch: SetBranch,
         record: &Record,
-        record_map: &mut CountingBloomFilter,
-    ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
-        match action {
-            SetAction::Insert => self.union_insert(action, record, record_map),
-            SetAction::Delete => self.union_delete(action, record, record_map),
-        }
-    }
+        record_counts: &mut RecordCounts,
+    ) -> Vec<(SetAction, Record)> {
+        let input = match branch {
+            SetBranch::Left => 0,
+            SetBranch::Right => 1,
+        };
 
-    fn union_insert(
-        &self,
-        action: SetAction,
-        record: &Record,
-        record_map: &mut CountingBloomFilter,
-    ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
-        let _count = self.update_map(record, false, record_map);
-        if _count == 1 {
-            Ok(vec![(action, record.to_owned())])
-        } else {
-            Ok(vec![])
+        let before;
+        let after;
+        match action {
+            SetAction::Insert => {
+                let counts = record_counts.entry(record.values.clone()).or_insert([0, 0]);
+                before = self.output_count(*counts);
+                counts[input] += 1;
+                after = self.output_count(*counts);
+            }
+            SetAction::Delete => {
+                let Some(counts) = record_counts.get_mut(&record.values) else {
+                    return vec![];
+                };
+                if counts[input] == 0 {
+                    return vec![];
+                }
+                before = self.output_count(*counts);
+                counts[input] -= 1;
+                after = self.output_count(*counts);
+                if *counts == [0, 0] {
+                    record_counts.remove(&record.values);
+                }
+            }
         }
-    }
 
-    fn union_delete(
-        &self,
-        action: SetAction,
-        record: &Record,
-        record_map: &mut CountingBloomFilter,
-    ) -> Result<Vec<(SetAction, Record)>, PipelineError> {
-        let _count = self.update_map(record, true, record_map);
-        if _count == 0 {
-            Ok(vec![(action, record.to_owned())])
+        let (action, copies) = if after >= before {
+            (SetAction::Insert, after - before)
         } else {
-            Ok(vec![])
-        }
+            (SetAction::Delete, before - after)
+        };
+        vec![(action, record.clone()); copies]
     }
 
-    fn update_map(&self, record: &Record, decr: bool, record_map: &mut CountingBloomFilter) -> u32 {
-        if decr {
-            record_map.remove(&record);
-        } else {
-            record_map.insert(&record);
+    /// Number of copies of a row in the output, given its number of occurrences in the left and
+    /// the right input.
+    fn output_count(&self, [left, right]: [usize; 2]) -> usize {
+        let distinct = !matches!(self.quantifier, SetQuantifier::All);
+        match self.op {
+            SetOperator::Union if distinct => usize::from(left + right > 0),
+            SetOperator::Union => left + right,
+            SetOperator::Except if distinct => usize::from(left > 0 && right == 0),
+            SetOperator::Except => left.saturating_sub(right),
+            SetOperator::Intersect if distinct => usize::from(left > 0 && right > 0),
+            SetOperator::Intersect => left.min(right),
         }
-
-        record_map.estimate_count(&record)
     }
 }
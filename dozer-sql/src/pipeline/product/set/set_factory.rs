@@ -17,13 +17,17 @@ use super::set_processor::SetProcessor;
 
 #[derive(Debug)]
 pub struct SetProcessorFactory {
+    set_operator: SetOperator,
     set_quantifier: SetQuantifier,
 }
 
 impl SetProcessorFactory {
-    /// Creates a new [`FromProcessorFactory`].
-    pub fn new(set_quantifier: SetQuantifier) -> Self {
-        Self { set_quantifier }
+    /// Creates a new [`SetProcessorFactory`].
+    pub fn new(set_operator: SetOperator, set_quantifier: SetQuantifier) -> Self {
+        Self {
+            set_operator,
+            set_quantifier,
+        }
     }
 }
 
@@ -44,37 +48,40 @@ impl ProcessorFactory<SchemaSQLContext> for SetProcessorFactory {
         _output_port: &PortHandle,
         input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
     ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
-        let output_columns = validate_set_operation_input_schemas(input_schemas)
+        let (left, _) = input_schemas
+            .get(&0)
+            .ok_or(ExecutionError::InvalidPortHandle(0))?;
+        let (right, _) = input_schemas
+            .get(&1)
+            .ok_or(ExecutionError::InvalidPortHandle(1))?;
+        let output_columns = validate_set_operation_input_schemas(left, right)
             .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
 
         let mut output_schema = Schema::empty();
         output_schema.fields = output_columns;
-        output_schema.identifier = input_schemas
-            .get(&0)
-            .map_or(Err(SetError::InvalidInputSchemas), Ok)
-            .unwrap()
-            .to_owned()
-            .0
-            .identifier;
-        output_schema.primary_index = input_schemas
-            .get(&0)
-            .map_or(Err(SetError::InvalidInputSchemas), Ok)
-            .unwrap()
-            .to_owned()
-            .0
-            .primary_index;
+        output_schema.identifier = left.identifier;
+        output_schema.primary_index = left.primary_index.clone();
 
         Ok((output_schema, SchemaSQLContext::default()))
     }
 
     fn build(
         &self,
-        _input_schemas: HashMap<PortHandle, Schema>,
+        input_schemas: HashMap<PortHandle, Schema>,
         _output_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Processor>, ExecutionError> {
+        let left = input_schemas
+            .get(&0)
+            .ok_or(ExecutionError::InvalidPortHandle(0))?;
+        let right = input_schemas
+            .get(&1)
+            .ok_or(ExecutionError::InvalidPortHandle(1))?;
+        validate_set_operation_input_schemas(left, right)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+
         Ok(Box::new(
             SetProcessor::new(SetOperation {
-                op: SetOperator::Union,
+                op: self.set_operator,
                 quantifier: self.set_quantifier,
             })
             .map_err(|err| ExecutionError::InternalError(Box::new(err)))?,
@@ -82,42 +89,33 @@ impl ProcessorFactory<SchemaSQLContext> for SetProcessorFactory {
     }
 }
 
+/// Checks that the inputs have the same number of columns, of the same types, matched by
+/// position, and returns the output columns. They take their names from the left input, and are
+/// nullable if either input column is.
 fn validate_set_operation_input_schemas(
-    input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    left: &Schema,
+    right: &Schema,
 ) -> Result<Vec<FieldDefinition>, PipelineError> {
-    let mut left_columns = input_schemas
-        .get(&0)
-        .map_or(Err(SetError::InvalidInputSchemas), Ok)
-        .unwrap()
-        .to_owned()
-        .0
-        .fields;
-    let mut right_columns = input_schemas
-        .get(&1)
-        .map_or(Err(SetError::InvalidInputSchemas), Ok)
-        .unwrap()
-        .to_owned()
-        .0
-        .fields;
-
-    left_columns.sort();
-    right_columns.sort();
+    if left.fields.len() != right.fields.len() {
+        return Err(PipelineError::SetError(SetError::ColumnCountMismatch(
+            left.fields.len(),
+            right.fields.len(),
+        )));
+    }
 
     let mut output_fields = Vec::new();
-    for (left, right) in left_columns.iter().zip(right_columns.iter()) {
-        if !is_similar_fields(left, right) {
-            return Err(PipelineError::SetError(SetError::InvalidInputSchemas));
+    for (index, (left, right)) in left.fields.iter().zip(&right.fields).enumerate() {
+        if left.typ != right.typ {
+            return Err(PipelineError::SetError(SetError::ColumnTypeMismatch(
+                index, left.typ, right.typ,
+            )));
         }
         output_fields.push(FieldDefinition::new(
             left.name.clone(),
             left.typ,
-            left.nullable,
+            left.nullable || right.nullable,
             SourceDefinition::Dynamic,
         ));
     }
     Ok(output_fields)
 }
-
-fn is_similar_fields(left: &FieldDefinition, right: &FieldDefinition) -> bool {
-    left.name == right.name && left.typ == right.typ && left.nullable == right.nullable
-}
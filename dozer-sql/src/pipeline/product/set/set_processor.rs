@@ -1,76 +1,73 @@
 use crate::pipeline::errors::{PipelineError, ProductError};
-use bloom::CountingBloomFilter;
 use dozer_core::channels::ProcessorChannelForwarder;
 use dozer_core::epoch::Epoch;
 use dozer_core::errors::ExecutionError;
 use dozer_core::node::{PortHandle, Processor};
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_types::types::{Operation, Record};
-use std::collections::hash_map::RandomState;
 use std::fmt::{Debug, Formatter};
 
-use super::operator::{SetAction, SetOperation};
+use super::operator::{RecordCounts, SetAction, SetBranch, SetOperation};
 
+/// Combines the records of its two inputs. `UNION ALL` forwards every record, while the other
+/// operations keep count of the copies of each row in either input, and emit or retract rows as
+/// their number of copies in the result changes.
 pub struct SetProcessor {
     /// Set operations
     operator: SetOperation,
-    /// Hashmap containing records with its occurrence
-    record_map: CountingBloomFilter,
+    /// Occurrences of each row in either input, for all but `UNION ALL`
+    record_counts: RecordCounts,
 }
 
-const BITS_PER_ENTRY: usize = 8;
-const FALSE_POSITIVE_RATE: f32 = 0.01;
-const EXPECTED_NUM_ITEMS: u32 = 10000000;
-
 impl SetProcessor {
     /// Creates a new [`SetProcessor`].
     pub fn new(operator: SetOperation) -> Result<Self, PipelineError> {
-        let _s = RandomState::new();
         Ok(Self {
             operator,
-            record_map: CountingBloomFilter::with_rate(
-                BITS_PER_ENTRY,
-                FALSE_POSITIVE_RATE,
-                EXPECTED_NUM_ITEMS,
-            ),
+            record_counts: RecordCounts::new(),
         })
     }
 
-    fn delete(&mut self, record: &Record) -> Result<Vec<(SetAction, Record)>, ProductError> {
+    fn delete(
+        &mut self,
+        branch: SetBranch,
+        record: &Record,
+    ) -> Result<Vec<(SetAction, Record)>, ProductError> {
         self.operator
-            .execute(SetAction::Delete, record, &mut self.record_map)
-            .map_err(|err| {
-                ProductError::DeleteError("UNION query error:".to_string(), Box::new(err))
-            })
+            .execute(SetAction::Delete, branch, record, &mut self.record_counts)
+            .map_err(|err| ProductError::DeleteError(self.query_error(), Box::new(err)))
     }
 
-    fn insert(&mut self, record: &Record) -> Result<Vec<(SetAction, Record)>, ProductError> {
+    fn insert(
+        &mut self,
+        branch: SetBranch,
+        record: &Record,
+    ) -> Result<Vec<(SetAction, Record)>, ProductError> {
         self.operator
-            .execute(SetAction::Insert, record, &mut self.record_map)
-            .map_err(|err| {
-                ProductError::InsertError("UNION query error:".to_string(), Box::new(err))
-            })
+            .execute(SetAction::Insert, branch, record, &mut self.record_counts)
+            .map_err(|err| ProductError::InsertError(self.query_error(), Box::new(err)))
+    }
+
+    fn query_error(&self) -> String {
+        format!("{} query error:", self.operator.op)
     }
 
     #[allow(clippy::type_complexity)]
     fn update(
         &mut self,
+        branch: SetBranch,
         old: &Record,
         new: &Record,
     ) -> Result<(Vec<(SetAction, Record)>, Vec<(SetAction, Record)>), ProductError> {
         let old_records = self
             .operator
-            .execute(SetAction::Delete, old, &mut self.record_map)
-            .map_err(|err| {
-                ProductError::UpdateOldError("UNION query error:".to_string(), Box::new(err))
-            })?;
+            .execute(SetAction::Delete, branch, old, &mut self.record_counts)
+            .map_err(|err| ProductError::UpdateOldError(self.query_error(), Box::new(err)))?;
 
         let new_records = self
             .operator
-            .execute(SetAction::Insert, new, &mut self.record_map)
-            .map_err(|err| {
-                ProductError::UpdateNewError("UNION query error:".to_string(), Box::new(err))
-            })?;
+            .execute(SetAction::Insert, branch, new, &mut self.record_counts)
+            .map_err(|err| ProductError::UpdateNewError(self.query_error(), Box::new(err)))?;
 
         Ok((old_records, new_records))
     }
@@ -89,14 +86,19 @@ impl Processor for SetProcessor {
 
     fn process(
         &mut self,
-        _from_port: PortHandle,
+        from_port: PortHandle,
         op: Operation,
         fw: &mut dyn ProcessorChannelForwarder,
     ) -> Result<(), ExecutionError> {
+        let branch = match from_port {
+            0 => SetBranch::Left,
+            1 => SetBranch::Right,
+            _ => return Err(ExecutionError::InvalidPort(from_port)),
+        };
         match op {
             Operation::Delete { ref old } => {
                 let records = self
-                    .delete(old)
+                    .delete(branch, old)
                     .map_err(|err| ExecutionError::ProductProcessorError(Box::new(err)))?;
 
                 for (action, record) in records.into_iter() {
@@ -112,7 +114,7 @@ impl Processor for SetProcessor {
             }
             Operation::Insert { ref new } => {
                 let records = self
-                    .insert(new)
+                    .insert(branch, new)
                     .map_err(|err| ExecutionError::ProductProcessorError(Box::new(err)))?;
 
                 for (action, record) in records.into_iter() {
@@ -128,7 +130,7 @@ impl Processor for SetProcessor {
             }
             Operation::Update { ref old, ref new } => {
                 let (old_records, new_records) = self
-                    .update(old, new)
+                    .update(branch, old, new)
                     .map_err(|err| ExecutionError::ProductProcessorError(Box::new(err)))?;
 
                 for (action, old) in old_records.into_iter() {
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_types::chrono::DateTime;
+use dozer_types::types::{Field, Operation, Record};
+
+use crate::pipeline::product::join::factory::{LEFT_JOIN_PORT, RIGHT_JOIN_PORT};
+use crate::pipeline::product::join::operator::{JoinOperator, JoinType};
+use crate::pipeline::product::join::processor::ProductProcessor;
+use crate::pipeline::state_ttl::StateTtl;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+/// A record of `(id, time)`, joined on `id`.
+fn record(id: i64, secs: u32) -> Record {
+    let time = DateTime::parse_from_rfc3339(&format!("2023-01-01T00:00:{secs:02}Z")).unwrap();
+    Record::new(None, vec![Field::Int(id), Field::Timestamp(time)])
+}
+
+fn joined(left: &Record, right: &Record) -> Record {
+    Record::new(
+        None,
+        left.values.iter().chain(&right.values).cloned().collect(),
+    )
+}
+
+#[test]
+fn test_expired_join_state_retracts_joined_records() {
+    let join_operator = JoinOperator::new(
+        JoinType::Inner,
+        vec![0],
+        vec![0],
+        vec![0, 1],
+        vec![0, 1],
+        Record::new(None, vec![Field::Null, Field::Null]),
+        Record::new(None, vec![Field::Null, Field::Null]),
+    );
+    let ttl = StateTtl::new(1, Duration::from_secs(10));
+    let mut processor = ProductProcessor::new(join_operator).with_ttl(Some(ttl), None);
+    let mut fw = TestChannelForwarder { operations: vec![] };
+
+    let (left_old, left_new, right) = (record(1, 0), record(1, 20), record(1, 0));
+    for (port, new) in [
+        (LEFT_JOIN_PORT, left_old.clone()),
+        (RIGHT_JOIN_PORT, right.clone()),
+    ] {
+        processor
+            .process(port, Operation::Insert { new }, &mut fw)
+            .unwrap();
+    }
+    processor.flush(&mut fw).unwrap();
+    assert_eq!(
+        fw.operations,
+        vec![Operation::Insert {
+            new: joined(&left_old, &right)
+        }]
+    );
+
+    // A newer left record moves time past the TTL of the first one.
+    fw.operations.clear();
+    processor
+        .process(
+            LEFT_JOIN_PORT,
+            Operation::Insert {
+                new: left_new.clone(),
+            },
+            &mut fw,
+        )
+        .unwrap();
+    processor.flush(&mut fw).unwrap();
+    assert_eq!(
+        fw.operations,
+        vec![
+            Operation::Insert {
+                new: joined(&left_new, &right)
+            },
+            Operation::Delete {
+                old: joined(&left_old, &right)
+            },
+        ]
+    );
+}
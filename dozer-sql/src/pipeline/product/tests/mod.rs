@@ -1,2 +1,6 @@
 #[cfg(test)]
+mod join_ttl_test;
+#[cfg(test)]
 mod pipeline_test;
+#[cfg(test)]
+mod set_operator_test;
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor, ProcessorFactory};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use sqlparser::ast::{SetOperator, SetQuantifier};
+
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::product::set::operator::SetOperation;
+use crate::pipeline::product::set::set_factory::SetProcessorFactory;
+use crate::pipeline::product::set::set_processor::SetProcessor;
+
+const LEFT: PortHandle = 0;
+const RIGHT: PortHandle = 1;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+fn record(id: i64) -> Record {
+    Record::new(None, vec![Field::Int(id)])
+}
+
+fn set(op: SetOperator, quantifier: SetQuantifier) -> SetProcessor {
+    SetProcessor::new(SetOperation { op, quantifier }).unwrap()
+}
+
+fn union(quantifier: SetQuantifier) -> SetProcessor {
+    set(SetOperator::Union, quantifier)
+}
+
+fn process(processor: &mut SetProcessor, ops: Vec<(PortHandle, Operation)>) -> Vec<Operation> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    for (port, op) in ops {
+        processor.process(port, op, &mut fw).unwrap();
+    }
+    fw.operations
+}
+
+#[test]
+fn test_union_all_forwards_every_record() {
+    let mut processor = union(SetQuantifier::All);
+    let ops = vec![
+        (LEFT, Operation::Insert { new: record(1) }),
+        (RIGHT, Operation::Insert { new: record(1) }),
+        (RIGHT, Operation::Insert { new: record(2) }),
+        (LEFT, Operation::Delete { old: record(1) }),
+    ];
+
+    let output = process(&mut processor, ops.clone());
+    assert_eq!(
+        output,
+        ops.into_iter().map(|(_, op)| op).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_union_removes_duplicates_across_inputs() {
+    let mut processor = union(SetQuantifier::None);
+
+    let output = process(
+        &mut processor,
+        vec![
+            (LEFT, Operation::Insert { new: record(1) }),
+            (RIGHT, Operation::Insert { new: record(1) }),
+            (RIGHT, Operation::Insert { new: record(2) }),
+            (LEFT, Operation::Insert { new: record(2) }),
+        ],
+    );
+    assert_eq!(
+        output,
+        vec![
+            Operation::Insert { new: record(1) },
+            Operation::Insert { new: record(2) },
+        ]
+    );
+
+    // The row stays until its last copy is deleted.
+    let output = process(
+        &mut processor,
+        vec![(LEFT, Operation::Delete { old: record(1) })],
+    );
+    assert!(output.is_empty());
+    let output = process(
+        &mut processor,
+        vec![(RIGHT, Operation::Delete { old: record(1) })],
+    );
+    assert_eq!(output, vec![Operation::Delete { old: record(1) }]);
+}
+
+#[test]
+fn test_except_keeps_left_only_rows() {
+    let mut processor = set(SetOperator::Except, SetQuantifier::None);
+
+    let output = process(
+        &mut processor,
+        vec![
+            (LEFT, Operation::Insert { new: record(1) }),
+            (LEFT, Operation::Insert { new: record(1) }),
+            (LEFT, Operation::Insert { new: record(2) }),
+            (RIGHT, Operation::Insert { new: record(2) }),
+            (RIGHT, Operation::Insert { new: record(3) }),
+        ],
+    );
+    assert_eq!(
+        output,
+        vec![
+            Operation::Insert { new: record(1) },
+            Operation::Insert { new: record(2) },
+            Operation::Delete { old: record(2) },
+        ]
+    );
+
+    // Deleting the row from the right input brings it back.
+    let output = process(
+        &mut processor,
+        vec![(RIGHT, Operation::Delete { old: record(2) })],
+    );
+    assert_eq!(output, vec![Operation::Insert { new: record(2) }]);
+}
+
+#[test]
+fn test_intersect_keeps_common_rows() {
+    let mut processor = set(SetOperator::Intersect, SetQuantifier::None);
+
+    let output = process(
+        &mut processor,
+        vec![
+            (LEFT, Operation::Insert { new: record(1) }),
+            (LEFT, Operation::Insert { new: record(2) }),
+            (LEFT, Operation::Insert { new: record(2) }),
+            (RIGHT, Operation::Insert { new: record(2) }),
+            (RIGHT, Operation::Insert { new: record(2) }),
+            (RIGHT, Operation::Insert { new: record(3) }),
+        ],
+    );
+    assert_eq!(output, vec![Operation::Insert { new: record(2) }]);
+
+    // The row stays until one of the inputs has no copy of it left.
+    let output = process(
+        &mut processor,
+        vec![(LEFT, Operation::Delete { old: record(2) })],
+    );
+    assert!(output.is_empty());
+    let output = process(
+        &mut processor,
+        vec![(LEFT, Operation::Delete { old: record(2) })],
+    );
+    assert_eq!(output, vec![Operation::Delete { old: record(2) }]);
+}
+
+#[test]
+fn test_except_all_and_intersect_all_count_copies() {
+    let ops = vec![
+        (LEFT, Operation::Insert { new: record(1) }),
+        (LEFT, Operation::Insert { new: record(1) }),
+        (LEFT, Operation::Insert { new: record(1) }),
+        (RIGHT, Operation::Insert { new: record(1) }),
+        (RIGHT, Operation::Insert { new: record(1) }),
+        (RIGHT, Operation::Insert { new: record(1) }),
+        (RIGHT, Operation::Insert { new: record(1) }),
+        (
+            LEFT,
+            Operation::Update {
+                old: record(1),
+                new: record(2),
+            },
+        ),
+    ];
+
+    // Three copies on the left, less one for every copy on the right.
+    let mut processor = set(SetOperator::Except, SetQuantifier::All);
+    let output = process(&mut processor, ops.clone());
+    assert_eq!(
+        output,
+        vec![
+            Operation::Insert { new: record(1) },
+            Operation::Insert { new: record(1) },
+            Operation::Insert { new: record(1) },
+            Operation::Delete { old: record(1) },
+            Operation::Delete { old: record(1) },
+            Operation::Delete { old: record(1) },
+            Operation::Insert { new: record(2) },
+        ]
+    );
+
+    // As many copies as the input with the fewest of them.
+    let mut processor = set(SetOperator::Intersect, SetQuantifier::All);
+    let output = process(&mut processor, ops);
+    assert_eq!(
+        output,
+        vec![
+            Operation::Insert { new: record(1) },
+            Operation::Insert { new: record(1) },
+            Operation::Insert { new: record(1) },
+            Operation::Delete { old: record(1) },
+        ]
+    );
+}
+
+fn schema(fields: &[(&str, FieldType)]) -> (Schema, SchemaSQLContext) {
+    let mut schema = Schema::empty();
+    for (name, typ) in fields {
+        schema.field(
+            FieldDefinition::new(name.to_string(), *typ, false, SourceDefinition::Dynamic),
+            false,
+        );
+    }
+    (schema, SchemaSQLContext::default())
+}
+
+#[test]
+fn test_union_output_schema() {
+    let factory = SetProcessorFactory::new(SetOperator::Union, SetQuantifier::All);
+
+    // Columns are matched by position and named after the left input.
+    let input_schemas = HashMap::from([
+        (
+            LEFT,
+            schema(&[("id", FieldType::Int), ("name", FieldType::String)]),
+        ),
+        (
+            RIGHT,
+            schema(&[("key", FieldType::Int), ("label", FieldType::String)]),
+        ),
+    ]);
+    let (output_schema, _) = factory.get_output_schema(&0, &input_schemas).unwrap();
+    let names: Vec<_> = output_schema
+        .fields
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["id", "name"]);
+
+    for right in [
+        schema(&[("id", FieldType::Int)]),
+        schema(&[("id", FieldType::String), ("name", FieldType::String)]),
+    ] {
+        let input_schemas = HashMap::from([
+            (
+                LEFT,
+                schema(&[("id", FieldType::Int), ("name", FieldType::String)]),
+            ),
+            (RIGHT, right),
+        ]);
+        assert!(factory.get_output_schema(&0, &input_schemas).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::PipelineError;
+use dozer_core::{
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_types::types::{FieldDefinition, Schema, SourceDefinition};
+
+use super::processor::ScalarSubqueryProcessor;
+
+/// Port of the records the value of the subquery is appended to.
+pub const OUTER_PORT: PortHandle = 0;
+/// Port of the output of the subquery.
+pub const SUBQUERY_PORT: PortHandle = 1;
+
+/// Appends the value of an uncorrelated scalar subquery to the records of the outer query, as a
+/// column named `column`.
+#[derive(Debug)]
+pub struct ScalarSubqueryProcessorFactory {
+    column: String,
+}
+
+impl ScalarSubqueryProcessorFactory {
+    /// Creates a new [`ScalarSubqueryProcessorFactory`].
+    pub fn new(column: String) -> Self {
+        Self { column }
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for ScalarSubqueryProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![OUTER_PORT, SUBQUERY_PORT]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        let (outer, _) = input_schemas
+            .get(&OUTER_PORT)
+            .ok_or(ExecutionError::InvalidPortHandle(OUTER_PORT))?;
+        let (subquery, _) = input_schemas
+            .get(&SUBQUERY_PORT)
+            .ok_or(ExecutionError::InvalidPortHandle(SUBQUERY_PORT))?;
+        let field = subquery_field(&self.column, subquery)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+
+        let mut output_schema = outer.clone();
+        output_schema.fields.push(field);
+        Ok((output_schema, SchemaSQLContext::default()))
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        let subquery = input_schemas
+            .get(&SUBQUERY_PORT)
+            .ok_or(ExecutionError::InvalidPortHandle(SUBQUERY_PORT))?;
+        subquery_field(&self.column, subquery)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+
+        Ok(Box::new(ScalarSubqueryProcessor::new()))
+    }
+}
+
+/// The column holding the value of a subquery with output `schema`, which must have a single
+/// field. It is `NULL` while the subquery has no row.
+fn subquery_field(column: &str, schema: &Schema) -> Result<FieldDefinition, PipelineError> {
+    match schema.fields.as_slice() {
+        [field] => Ok(FieldDefinition::new(
+            column.to_string(),
+            field.typ,
+            true,
+            SourceDefinition::Dynamic,
+        )),
+        fields => Err(PipelineError::InvalidQuery(format!(
+            "A scalar subquery must return one column, not {}",
+            fields.len()
+        ))),
+    }
+}
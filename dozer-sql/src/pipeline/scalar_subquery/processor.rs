@@ -0,0 +1,158 @@
+use crate::pipeline::errors::PipelineError;
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{Field, Operation, Record};
+use std::collections::HashMap;
+
+use super::factory::SUBQUERY_PORT;
+
+/// Appends the value of a subquery to the records of the outer query.
+///
+/// The value is that of the single row the subquery returns, e.g. an aggregation without
+/// `GROUP BY`, and `NULL` while it returns none. The operations of the subquery fail with
+/// `PipelineError::ScalarSubqueryMultipleRows` while it returns more than one row, and the value
+/// stays the same until it's back to one.
+///
+/// Every outer record is kept in memory, so that it can be updated when the value changes. The
+/// state grows with the number of distinct outer records, without bound.
+#[derive(Debug)]
+pub struct ScalarSubqueryProcessor {
+    value: Field,
+    /// The values of the rows the subquery returns.
+    rows: Vec<Field>,
+    /// The outer records, with how many times each is present.
+    records: HashMap<Record, usize>,
+}
+
+impl ScalarSubqueryProcessor {
+    pub fn new() -> Self {
+        Self {
+            value: Field::Null,
+            rows: vec![],
+            records: HashMap::new(),
+        }
+    }
+
+    fn with_value(&self, record: &Record, value: &Field) -> Record {
+        let mut record = record.clone();
+        record.values.push(value.clone());
+        record
+    }
+
+    fn add(&mut self, record: &Record) {
+        *self.records.entry(record.clone()).or_default() += 1;
+    }
+
+    fn remove(&mut self, record: &Record) {
+        if let Some(count) = self.records.get_mut(record) {
+            *count -= 1;
+            if *count == 0 {
+                self.records.remove(record);
+            }
+        }
+    }
+
+    fn process_outer(
+        &mut self,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        let op = match op {
+            Operation::Insert { new } => {
+                self.add(&new);
+                Operation::Insert {
+                    new: self.with_value(&new, &self.value),
+                }
+            }
+            Operation::Delete { old } => {
+                self.remove(&old);
+                Operation::Delete {
+                    old: self.with_value(&old, &self.value),
+                }
+            }
+            Operation::Update { old, new } => {
+                self.remove(&old);
+                self.add(&new);
+                Operation::Update {
+                    old: self.with_value(&old, &self.value),
+                    new: self.with_value(&new, &self.value),
+                }
+            }
+        };
+        fw.send(op, DEFAULT_PORT_HANDLE)
+    }
+
+    fn process_subquery(
+        &mut self,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        match op {
+            Operation::Insert { new } => self.add_row(new),
+            Operation::Delete { old } => self.remove_row(old),
+            Operation::Update { old, new } => {
+                self.remove_row(old);
+                self.add_row(new);
+            }
+        }
+        let value = match self.rows.as_slice() {
+            [] => Field::Null,
+            [value] => value.clone(),
+            _ => {
+                return Err(ExecutionError::InternalError(Box::new(
+                    PipelineError::ScalarSubqueryMultipleRows,
+                )))
+            }
+        };
+        if value == self.value {
+            return Ok(());
+        }
+
+        let old_value = std::mem::replace(&mut self.value, value);
+        for (record, count) in &self.records {
+            for _ in 0..*count {
+                fw.send(
+                    Operation::Update {
+                        old: self.with_value(record, &old_value),
+                        new: self.with_value(record, &self.value),
+                    },
+                    DEFAULT_PORT_HANDLE,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_row(&mut self, mut row: Record) {
+        self.rows.push(row.values.pop().unwrap_or(Field::Null));
+    }
+
+    fn remove_row(&mut self, mut row: Record) {
+        let value = row.values.pop().unwrap_or(Field::Null);
+        if let Some(index) = self.rows.iter().position(|kept| *kept == value) {
+            self.rows.swap_remove(index);
+        }
+    }
+}
+
+impl Processor for ScalarSubqueryProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        if from_port == SUBQUERY_PORT {
+            self.process_subquery(op, fw)
+        } else {
+            self.process_outer(op, fw)
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_types::types::{Field, Operation, Record};
+
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::scalar_subquery::factory::{OUTER_PORT, SUBQUERY_PORT};
+use crate::pipeline::scalar_subquery::processor::ScalarSubqueryProcessor;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+fn process(
+    processor: &mut ScalarSubqueryProcessor,
+    port: PortHandle,
+    op: Operation,
+) -> Result<Vec<Operation>, ExecutionError> {
+    let mut fw = TestChannelForwarder { operations: vec![] };
+    processor.process(port, op, &mut fw)?;
+    Ok(fw.operations)
+}
+
+fn outer(id: i64) -> Record {
+    Record::new(None, vec![Field::Int(id)])
+}
+
+fn outer_with(id: i64, value: Field) -> Record {
+    Record::new(None, vec![Field::Int(id), value])
+}
+
+fn subquery(value: i64) -> Record {
+    Record::new(None, vec![Field::Int(value)])
+}
+
+fn insert(new: Record) -> Operation {
+    Operation::Insert { new }
+}
+
+fn update(old: Record, new: Record) -> Operation {
+    Operation::Update { old, new }
+}
+
+fn delete(old: Record) -> Operation {
+    Operation::Delete { old }
+}
+
+#[test]
+fn test_value_changes_update_every_outer_record() {
+    let mut processor = ScalarSubqueryProcessor::new();
+    for id in [1, 2, 2] {
+        assert_eq!(
+            process(&mut processor, OUTER_PORT, insert(outer(id))).unwrap(),
+            vec![insert(outer_with(id, Field::Null))]
+        );
+    }
+
+    let mut ops = process(&mut processor, SUBQUERY_PORT, insert(subquery(5))).unwrap();
+    ops.sort_by_key(|op| format!("{op:?}"));
+    assert_eq!(
+        ops,
+        vec![
+            update(outer_with(1, Field::Null), outer_with(1, Field::Int(5))),
+            update(outer_with(2, Field::Null), outer_with(2, Field::Int(5))),
+            update(outer_with(2, Field::Null), outer_with(2, Field::Int(5))),
+        ]
+    );
+
+    // Deleted outer records are no longer updated.
+    process(&mut processor, OUTER_PORT, delete(outer(2))).unwrap();
+    process(&mut processor, OUTER_PORT, delete(outer(2))).unwrap();
+    assert_eq!(
+        process(
+            &mut processor,
+            SUBQUERY_PORT,
+            update(subquery(5), subquery(7))
+        )
+        .unwrap(),
+        vec![update(
+            outer_with(1, Field::Int(5)),
+            outer_with(1, Field::Int(7))
+        )]
+    );
+
+    // An unchanged value sends nothing.
+    assert!(process(
+        &mut processor,
+        SUBQUERY_PORT,
+        update(subquery(7), subquery(7))
+    )
+    .unwrap()
+    .is_empty());
+}
+
+#[test]
+fn test_empty_subquery_updates_outer_records_to_null() {
+    let mut processor = ScalarSubqueryProcessor::new();
+    process(&mut processor, SUBQUERY_PORT, insert(subquery(5))).unwrap();
+    assert_eq!(
+        process(&mut processor, OUTER_PORT, insert(outer(1))).unwrap(),
+        vec![insert(outer_with(1, Field::Int(5)))]
+    );
+
+    assert_eq!(
+        process(&mut processor, SUBQUERY_PORT, delete(subquery(5))).unwrap(),
+        vec![update(
+            outer_with(1, Field::Int(5)),
+            outer_with(1, Field::Null)
+        )]
+    );
+    assert_eq!(
+        process(&mut processor, OUTER_PORT, insert(outer(2))).unwrap(),
+        vec![insert(outer_with(2, Field::Null))]
+    );
+}
+
+#[test]
+fn test_subquery_returning_more_than_one_row_fails() {
+    let mut processor = ScalarSubqueryProcessor::new();
+    process(&mut processor, SUBQUERY_PORT, insert(subquery(5))).unwrap();
+    process(&mut processor, OUTER_PORT, insert(outer(1))).unwrap();
+
+    assert!(matches!(
+        process(&mut processor, SUBQUERY_PORT, insert(subquery(6))),
+        Err(ExecutionError::InternalError(e))
+            if matches!(
+                e.downcast_ref::<PipelineError>(),
+                Some(PipelineError::ScalarSubqueryMultipleRows)
+            )
+    ));
+    // The value stays the same meanwhile.
+    assert_eq!(
+        process(&mut processor, OUTER_PORT, insert(outer(2))).unwrap(),
+        vec![insert(outer_with(2, Field::Int(5)))]
+    );
+
+    // Back to a single row, whose value is now the one of the subquery.
+    let mut ops = process(&mut processor, SUBQUERY_PORT, delete(subquery(5))).unwrap();
+    ops.sort_by_key(|op| format!("{op:?}"));
+    assert_eq!(
+        ops,
+        vec![
+            update(outer_with(1, Field::Int(5)), outer_with(1, Field::Int(6))),
+            update(outer_with(2, Field::Int(5)), outer_with(2, Field::Int(6))),
+        ]
+    );
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use crate::pipeline::builder::SchemaSQLContext;
 use crate::pipeline::expression::builder::ExpressionBuilder;
+use crate::pipeline::expression::optimizer::fold_constants;
 use dozer_core::{
     errors::ExecutionError,
     node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
@@ -59,7 +60,7 @@ impl ProcessorFactory<SchemaSQLContext> for SelectionProcessorFactory {
         match ExpressionBuilder::new(schema.fields.len()).build(false, &self.statement, schema) {
             Ok(expression) => Ok(Box::new(SelectionProcessor::new(
                 schema.clone(),
-                expression,
+                fold_constants(expression),
             ))),
             Err(e) => Err(ExecutionError::InternalStringError(e.to_string())),
         }
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+use std::mem::replace;
+use std::time::Duration;
+
+use dozer_types::chrono::{self, DateTime, FixedOffset};
+use dozer_types::types::{Field, Record};
+
+/// How long a stateful processor keeps records, measured on a timestamp column of the records.
+///
+/// Time advances with the newest timestamp seen, so the column can hold either event time or
+/// ingestion time. Records whose column isn't a `Timestamp`, e.g. `NULL`, never expire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateTtl {
+    /// Index of the column records are aged by.
+    pub time_index: usize,
+    pub ttl: Duration,
+}
+
+impl StateTtl {
+    pub fn new(time_index: usize, ttl: Duration) -> Self {
+        Self { time_index, ttl }
+    }
+}
+
+/// Remembers which state entries expire when, for processors to evict at commit.
+#[derive(Debug)]
+pub(crate) struct TtlTracker<K> {
+    settings: StateTtl,
+    /// Newest timestamp seen.
+    now: Option<DateTime<FixedOffset>>,
+    /// Tracked keys by the timestamp of their record.
+    keys: BTreeMap<DateTime<FixedOffset>, Vec<K>>,
+}
+
+impl<K> TtlTracker<K> {
+    pub fn new(settings: StateTtl) -> Self {
+        Self {
+            settings,
+            now: None,
+            keys: BTreeMap::new(),
+        }
+    }
+
+    fn time_of(&self, record: &Record) -> Option<DateTime<FixedOffset>> {
+        match record.values.get(self.settings.time_index) {
+            Some(Field::Timestamp(time)) => Some(*time),
+            _ => None,
+        }
+    }
+
+    /// Records older than this have expired.
+    fn cutoff(&self) -> Option<DateTime<FixedOffset>> {
+        let ttl = chrono::Duration::from_std(self.settings.ttl).ok()?;
+        self.now?.checked_sub_signed(ttl)
+    }
+
+    /// Tracks `key` as holding `record`, and advances time to the record's timestamp if it's newer.
+    pub fn track(&mut self, key: K, record: &Record) {
+        if let Some(time) = self.time_of(record) {
+            self.keys.entry(time).or_default().push(key);
+            if self.now.map_or(true, |now| time > now) {
+                self.now = Some(time);
+            }
+        }
+    }
+
+    pub fn is_expired(&self, record: &Record) -> bool {
+        match (self.time_of(record), self.cutoff()) {
+            (Some(time), Some(cutoff)) => time < cutoff,
+            _ => false,
+        }
+    }
+
+    /// Stops tracking and returns the keys whose record had expired when it was tracked.
+    ///
+    /// A key's entry may have been replaced or removed since, so callers should check the record
+    /// they currently hold with [`TtlTracker::is_expired`] before evicting it.
+    pub fn take_expired(&mut self) -> Vec<K> {
+        let Some(cutoff) = self.cutoff() else {
+            return vec![];
+        };
+        let live = self.keys.split_off(&cutoff);
+        replace(&mut self.keys, live)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+}
@@ -1,12 +1,14 @@
 use dozer_core::app::{App, AppPipeline};
 use dozer_core::appsource::{AppSource, AppSourceManager};
 use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::epoch::Epoch;
 use dozer_core::errors::ExecutionError;
 use dozer_core::executor::{DagExecutor, ExecutorOptions};
 use dozer_core::node::{
     OutputPortDef, OutputPortType, PortHandle, Sink, SinkFactory, Source, SourceFactory,
 };
 use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::epoch::{OpOrigin, OpTags};
 use dozer_types::ingestion_types::IngestionMessage;
 use dozer_types::log::debug;
 use dozer_types::ordered_float::OrderedFloat;
@@ -17,7 +19,7 @@ use dozer_types::types::{
 use std::collections::HashMap;
 
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
 
@@ -25,11 +27,21 @@ use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
 #[derive(Debug)]
 pub struct TestSourceFactory {
     output_ports: Vec<PortHandle>,
+    tags: Option<OpTags>,
 }
 
 impl TestSourceFactory {
     pub fn new(output_ports: Vec<PortHandle>) -> Self {
-        Self { output_ports }
+        Self {
+            output_ports,
+            tags: None,
+        }
+    }
+
+    /// Attaches `tags` to every message of the source.
+    pub fn with_tags(mut self, tags: OpTags) -> Self {
+        self.tags = Some(tags);
+        self
     }
 }
 
@@ -83,12 +95,16 @@ impl SourceFactory<SchemaSQLContext> for TestSourceFactory {
         &self,
         _output_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Source>, ExecutionError> {
-        Ok(Box::new(TestSource {}))
+        Ok(Box::new(TestSource {
+            tags: self.tags.clone(),
+        }))
     }
 }
 
 #[derive(Debug)]
-pub struct TestSource {}
+pub struct TestSource {
+    tags: Option<OpTags>,
+}
 
 impl Source for TestSource {
     fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
@@ -101,23 +117,24 @@ impl Source for TestSource {
         _last_checkpoint: Option<(u64, u64)>,
     ) -> Result<(), ExecutionError> {
         for n in 0..10000 {
-            fw.send(
-                IngestionMessage::new_op(
-                    n,
-                    0,
-                    Operation::Insert {
-                        new: Record::new(
-                            None,
-                            vec![
-                                Field::Int(0),
-                                Field::String("Italy".to_string()),
-                                Field::Float(OrderedFloat(5.5)),
-                            ],
-                        ),
-                    },
-                ),
-                DEFAULT_PORT_HANDLE,
-            )
+            let message = IngestionMessage::new_op(
+                n,
+                0,
+                Operation::Insert {
+                    new: Record::new(
+                        None,
+                        vec![
+                            Field::Int(0),
+                            Field::String("Italy".to_string()),
+                            Field::Float(OrderedFloat(5.5)),
+                        ],
+                    ),
+                },
+            );
+            match &self.tags {
+                Some(tags) => fw.send_with_tags(message, tags.clone(), DEFAULT_PORT_HANDLE),
+                None => fw.send(message, DEFAULT_PORT_HANDLE),
+            }
             .unwrap();
         }
         Ok(())
@@ -127,11 +144,21 @@ impl Source for TestSource {
 #[derive(Debug)]
 pub struct TestSinkFactory {
     input_ports: Vec<PortHandle>,
+    origins: Option<Arc<Mutex<Vec<Option<OpOrigin>>>>>,
 }
 
 impl TestSinkFactory {
     pub fn new(input_ports: Vec<PortHandle>) -> Self {
-        Self { input_ports }
+        Self {
+            input_ports,
+            origins: None,
+        }
+    }
+
+    /// Records the origin of every operation the sink processes in `origins`.
+    pub fn with_origins(mut self, origins: Arc<Mutex<Vec<Option<OpOrigin>>>>) -> Self {
+        self.origins = Some(origins);
+        self
     }
 }
 
@@ -144,7 +171,9 @@ impl SinkFactory<SchemaSQLContext> for TestSinkFactory {
         &self,
         _input_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Sink>, ExecutionError> {
-        Ok(Box::new(TestSink {}))
+        Ok(Box::new(TestSink {
+            origins: self.origins.clone(),
+        }))
     }
 
     fn prepare(
@@ -156,14 +185,28 @@ impl SinkFactory<SchemaSQLContext> for TestSinkFactory {
 }
 
 #[derive(Debug)]
-pub struct TestSink {}
+pub struct TestSink {
+    origins: Option<Arc<Mutex<Vec<Option<OpOrigin>>>>>,
+}
 
 impl Sink for TestSink {
-    fn process(&mut self, _from_port: PortHandle, _op: Operation) -> Result<(), ExecutionError> {
+    fn process(&mut self, from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        self.process_with_origin(from_port, op, None)
+    }
+
+    fn process_with_origin(
+        &mut self,
+        _from_port: PortHandle,
+        _op: Operation,
+        origin: Option<&OpOrigin>,
+    ) -> Result<(), ExecutionError> {
+        if let Some(origins) = &self.origins {
+            origins.lock().unwrap().push(origin.cloned());
+        }
         Ok(())
     }
 
-    fn commit(&mut self) -> Result<(), ExecutionError> {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
         Ok(())
     }
 
@@ -227,3 +270,140 @@ fn test_pipeline_builder() {
     let elapsed = now.elapsed();
     debug!("Elapsed: {:.2?}", elapsed);
 }
+
+#[test]
+fn test_source_tags_reach_sink_through_projection() {
+    let mut pipeline = AppPipeline::new();
+    let context = statement_to_pipeline(
+        "SELECT Country, Spending FROM users",
+        &mut pipeline,
+        Some("results".to_string()),
+    )
+    .unwrap();
+    let table_info = context.output_tables_map.get("results").unwrap();
+
+    let tags: OpTags = Arc::new(
+        [("ingest".to_string(), "batch-42".to_string())]
+            .into_iter()
+            .collect(),
+    );
+    let mut asm = AppSourceManager::new();
+    asm.add(AppSource::new(
+        "mem".to_string(),
+        Arc::new(TestSourceFactory::new(vec![DEFAULT_PORT_HANDLE]).with_tags(tags.clone())),
+        vec![("users".to_string(), DEFAULT_PORT_HANDLE)]
+            .into_iter()
+            .collect(),
+    ))
+    .unwrap();
+
+    let origins = Arc::new(Mutex::new(vec![]));
+    pipeline.add_sink(
+        Arc::new(TestSinkFactory::new(vec![DEFAULT_PORT_HANDLE]).with_origins(origins.clone())),
+        "sink",
+    );
+    pipeline
+        .connect_nodes(
+            &table_info.node,
+            Some(table_info.port),
+            "sink",
+            Some(DEFAULT_PORT_HANDLE),
+            true,
+        )
+        .unwrap();
+
+    let mut app = App::new(asm);
+    app.add_pipeline(pipeline);
+    DagExecutor::new(app.get_dag().unwrap(), ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let origins = origins.lock().unwrap();
+    assert_eq!(origins.len(), 10000);
+    for origin in origins.iter() {
+        let origin = origin.as_ref().expect("origin must be known");
+        assert_eq!(origin.tags.as_ref(), Some(&tags));
+    }
+}
+
+/// Runs `sql` over two copies of the test source, `users` and `customers`, and returns the number
+/// of operations reaching the sink.
+fn run_union(sql: &str) -> usize {
+    let mut pipeline = AppPipeline::new();
+    let context = statement_to_pipeline(sql, &mut pipeline, Some("results".to_string())).unwrap();
+    let table_info = context.output_tables_map.get("results").unwrap();
+
+    let mut asm = AppSourceManager::new();
+    for (connection, table) in [("mem_users", "users"), ("mem_customers", "customers")] {
+        asm.add(AppSource::new(
+            connection.to_string(),
+            Arc::new(TestSourceFactory::new(vec![DEFAULT_PORT_HANDLE])),
+            vec![(table.to_string(), DEFAULT_PORT_HANDLE)]
+                .into_iter()
+                .collect(),
+        ))
+        .unwrap();
+    }
+
+    let origins = Arc::new(Mutex::new(vec![]));
+    pipeline.add_sink(
+        Arc::new(TestSinkFactory::new(vec![DEFAULT_PORT_HANDLE]).with_origins(origins.clone())),
+        "sink",
+    );
+    pipeline
+        .connect_nodes(
+            &table_info.node,
+            Some(table_info.port),
+            "sink",
+            Some(DEFAULT_PORT_HANDLE),
+            true,
+        )
+        .unwrap();
+
+    let mut app = App::new(asm);
+    app.add_pipeline(pipeline);
+    DagExecutor::new(app.get_dag().unwrap(), ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let origins = origins.lock().unwrap();
+    origins.len()
+}
+
+#[test]
+fn test_union_all_of_two_queries() {
+    let count = run_union(
+        "SELECT Country, Spending FROM users \
+        UNION ALL \
+        SELECT Country, Spending FROM customers",
+    );
+    assert_eq!(count, 20000);
+}
+
+#[test]
+fn test_union_of_two_queries_removes_duplicates() {
+    // Every record of both sources is the same row.
+    let count = run_union(
+        "SELECT Country, Spending FROM users \
+        UNION \
+        SELECT Country, Spending FROM customers",
+    );
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_intersect_of_two_queries() {
+    // Every record of both sources is the same row.
+    let count = run_union(
+        "SELECT Country, Spending FROM users \
+        INTERSECT \
+        SELECT Country, Spending FROM customers",
+    );
+    assert_eq!(count, 1);
+}
@@ -1,5 +1,9 @@
 #[cfg(test)]
 mod builder_test;
+#[cfg(test)]
+pub mod sql_runner;
+#[cfg(test)]
+mod sql_runner_test;
 
 #[cfg(test)]
 pub mod utils;
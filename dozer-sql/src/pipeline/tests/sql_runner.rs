@@ -0,0 +1,217 @@
+use dozer_core::app::{App, AppPipeline};
+use dozer_core::appsource::{AppSource, AppSourceManager};
+use dozer_core::channels::SourceChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::ExecutionError;
+use dozer_core::executor::{DagExecutor, ExecutorOptions};
+use dozer_core::node::{
+    OutputPortDef, OutputPortType, PortHandle, Sink, SinkFactory, Source, SourceFactory,
+};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::types::{Operation, Record, Schema};
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+use crate::pipeline::builder::{
+    statement_to_pipeline_with_options, PlannerOptions, SchemaSQLContext,
+};
+
+/// Name of the table holding the input rows of [`run_sql`].
+pub const INPUT_TABLE: &str = "input_table";
+
+/// Runs `sql` over `input_rows` through the whole DAG and returns the rows of its result.
+///
+/// The rows are inserted into [`INPUT_TABLE`], described by `schema`. The pipeline is built
+/// with [`statement_to_pipeline`] and run to completion by a [`DagExecutor`]. The operations
+/// reaching the sink are applied in order, so the result holds the final state of the query,
+/// in the order its rows were first inserted.
+///
+/// [`statement_to_pipeline`]: crate::pipeline::builder::statement_to_pipeline
+pub fn run_sql(sql: &str, schema: Schema, input_rows: Vec<Record>) -> Vec<Record> {
+    run_sql_with_options(sql, PlannerOptions::default(), schema, input_rows)
+}
+
+/// Like [`run_sql`], planning the query with `options`.
+pub fn run_sql_with_options(
+    sql: &str,
+    options: PlannerOptions,
+    schema: Schema,
+    input_rows: Vec<Record>,
+) -> Vec<Record> {
+    let mut pipeline = AppPipeline::new();
+    let context = statement_to_pipeline_with_options(
+        sql,
+        &mut pipeline,
+        Some("results".to_string()),
+        options,
+    )
+    .unwrap();
+    let table_info = context.output_tables_map.get("results").unwrap();
+
+    let mut asm = AppSourceManager::new();
+    asm.add(AppSource::new(
+        "vec".to_string(),
+        Arc::new(VecSourceFactory { schema, input_rows }),
+        [(INPUT_TABLE.to_string(), DEFAULT_PORT_HANDLE)]
+            .into_iter()
+            .collect(),
+    ))
+    .unwrap();
+
+    let ops = Arc::new(Mutex::new(vec![]));
+    pipeline.add_sink(Arc::new(VecSinkFactory { ops: ops.clone() }), "sink");
+    pipeline
+        .connect_nodes(
+            &table_info.node,
+            Some(table_info.port),
+            "sink",
+            Some(DEFAULT_PORT_HANDLE),
+            true,
+        )
+        .unwrap();
+
+    let mut app = App::new(asm);
+    app.add_pipeline(pipeline);
+    let dag = app.get_dag().unwrap();
+
+    DagExecutor::new(dag, ExecutorOptions::default())
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let ops = std::mem::take(&mut *ops.lock().unwrap());
+    apply(ops)
+}
+
+fn apply(ops: Vec<Operation>) -> Vec<Record> {
+    let position = |rows: &[Record], record: &Record| {
+        rows.iter()
+            .position(|row| row.values == record.values)
+            .unwrap_or_else(|| panic!("{record:?} isn't in the result"))
+    };
+
+    let mut rows = vec![];
+    for op in ops {
+        match op {
+            Operation::Insert { new } => rows.push(new),
+            Operation::Delete { old } => {
+                let index = position(&rows, &old);
+                rows.remove(index);
+            }
+            Operation::Update { old, new } => {
+                let index = position(&rows, &old);
+                rows[index] = new;
+            }
+        }
+    }
+    rows
+}
+
+#[derive(Debug)]
+struct VecSourceFactory {
+    schema: Schema,
+    input_rows: Vec<Record>,
+}
+
+impl SourceFactory<SchemaSQLContext> for VecSourceFactory {
+    fn get_output_schema(
+        &self,
+        _port: &PortHandle,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        Ok((self.schema.clone(), SchemaSQLContext::default()))
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn build(
+        &self,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Source>, ExecutionError> {
+        Ok(Box::new(VecSource {
+            input_rows: self.input_rows.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct VecSource {
+    input_rows: Vec<Record>,
+}
+
+impl Source for VecSource {
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
+        Ok(false)
+    }
+
+    fn start(
+        &self,
+        fw: &mut dyn SourceChannelForwarder,
+        _last_checkpoint: Option<(u64, u64)>,
+    ) -> Result<(), ExecutionError> {
+        for (n, row) in self.input_rows.iter().enumerate() {
+            fw.send(
+                IngestionMessage::new_op(n as u64 + 1, 0, Operation::Insert { new: row.clone() }),
+                DEFAULT_PORT_HANDLE,
+            )?;
+        }
+        // Returning disconnects the source, which makes the executor commit and terminate.
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct VecSinkFactory {
+    ops: Arc<Mutex<Vec<Operation>>>,
+}
+
+impl SinkFactory<SchemaSQLContext> for VecSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        Ok(Box::new(VecSink {
+            ops: self.ops.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct VecSink {
+    ops: Arc<Mutex<Vec<Operation>>>,
+}
+
+impl Sink for VecSink {
+    fn process(&mut self, _from_port: PortHandle, op: Operation) -> Result<(), ExecutionError> {
+        self.ops.lock().unwrap().push(op);
+        Ok(())
+    }
+
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+use crate::pipeline::builder::PlannerOptions;
+use crate::pipeline::tests::sql_runner::{run_sql, run_sql_with_options};
+
+fn users_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("country"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("spending"),
+                FieldType::Float,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn users() -> Vec<Record> {
+    [
+        (1, "Italy", 10.0),
+        (2, "Singapore", 5.5),
+        (3, "Italy", 2.5),
+        (4, "Germany", 20.0),
+        (5, "Singapore", 1.0),
+    ]
+    .into_iter()
+    .map(|(id, country, spending)| {
+        Record::new(
+            None,
+            vec![
+                Field::Int(id),
+                Field::String(country.to_string()),
+                Field::Float(OrderedFloat(spending)),
+            ],
+        )
+    })
+    .collect()
+}
+
+#[test]
+fn test_filter_and_projection() {
+    let rows = run_sql(
+        "SELECT id, spending * 2 FROM input_table WHERE spending > 5",
+        users_schema(),
+        users(),
+    );
+
+    let values = rows.into_iter().map(|row| row.values).collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            vec![Field::Int(1), Field::Float(OrderedFloat(20.0))],
+            vec![Field::Int(2), Field::Float(OrderedFloat(11.0))],
+            vec![Field::Int(4), Field::Float(OrderedFloat(40.0))],
+        ]
+    );
+}
+
+#[test]
+fn test_group_by_aggregation() {
+    let rows = run_sql(
+        "SELECT country, COUNT(spending), SUM(spending) FROM input_table GROUP BY country",
+        users_schema(),
+        users(),
+    );
+
+    let mut values = rows.into_iter().map(|row| row.values).collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(
+        values,
+        vec![
+            vec![
+                Field::String("Germany".to_string()),
+                Field::Int(1),
+                Field::Float(OrderedFloat(20.0)),
+            ],
+            vec![
+                Field::String("Italy".to_string()),
+                Field::Int(2),
+                Field::Float(OrderedFloat(12.5)),
+            ],
+            vec![
+                Field::String("Singapore".to_string()),
+                Field::Int(2),
+                Field::Float(OrderedFloat(6.5)),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_having_as_filter() {
+    let options = PlannerOptions {
+        having_as_filter: true,
+        ..Default::default()
+    };
+    let sorted_values = |sql: &str, options: PlannerOptions| {
+        let mut values = run_sql_with_options(sql, options, users_schema(), users())
+            .into_iter()
+            .map(|row| row.values)
+            .collect::<Vec<_>>();
+        values.sort();
+        values
+    };
+
+    // Reads a projected aggregation, so runs after the aggregation.
+    let sql = "SELECT country, SUM(spending) AS total FROM input_table GROUP BY country \
+        HAVING SUM(spending) > 10";
+    let values = sorted_values(sql, options);
+    assert_eq!(
+        values,
+        vec![
+            vec![
+                Field::String("Germany".to_string()),
+                Field::Float(OrderedFloat(20.0)),
+            ],
+            vec![
+                Field::String("Italy".to_string()),
+                Field::Float(OrderedFloat(12.5)),
+            ],
+        ]
+    );
+    assert_eq!(sorted_values(sql, PlannerOptions::default()), values);
+
+    // Reads an aggregation that isn't projected, so stays within the aggregation.
+    let sql = "SELECT country, SUM(spending) AS total FROM input_table GROUP BY country \
+        HAVING COUNT(spending) > 1";
+    let values = sorted_values(sql, options);
+    assert_eq!(
+        values,
+        vec![
+            vec![
+                Field::String("Italy".to_string()),
+                Field::Float(OrderedFloat(12.5)),
+            ],
+            vec![
+                Field::String("Singapore".to_string()),
+                Field::Float(OrderedFloat(6.5)),
+            ],
+        ]
+    );
+    assert_eq!(sorted_values(sql, PlannerOptions::default()), values);
+}
+
+#[test]
+fn test_uncorrelated_scalar_subquery() {
+    let rows = run_sql(
+        "SELECT id, (SELECT MAX(spending) FROM input_table) AS max_spending FROM input_table \
+        WHERE spending > 5",
+        users_schema(),
+        users(),
+    );
+
+    let mut values = rows.into_iter().map(|row| row.values).collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(
+        values,
+        vec![
+            vec![Field::Int(1), Field::Float(OrderedFloat(20.0))],
+            vec![Field::Int(2), Field::Float(OrderedFloat(20.0))],
+            vec![Field::Int(4), Field::Float(OrderedFloat(20.0))],
+        ]
+    );
+}
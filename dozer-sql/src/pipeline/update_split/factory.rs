@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use dozer_core::{
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_types::types::Schema;
+
+use super::processor::UpdateSplitProcessor;
+
+#[derive(Debug, Default)]
+pub struct UpdateSplitProcessorFactory {}
+
+impl UpdateSplitProcessorFactory {
+    /// Creates a new [`UpdateSplitProcessorFactory`].
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for UpdateSplitProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .cloned()
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(UpdateSplitProcessor::new()))
+    }
+}
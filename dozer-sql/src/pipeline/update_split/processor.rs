@@ -0,0 +1,39 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::Operation;
+
+/// Rewrites every update into a delete of the old record followed by an insert of the new one,
+/// for sinks that can't apply updates, such as append-only stores. Inserts and deletes pass
+/// through unchanged.
+#[derive(Debug, Default)]
+pub struct UpdateSplitProcessor {}
+
+impl UpdateSplitProcessor {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Processor for UpdateSplitProcessor {
+    fn commit(&self, _epoch: &Epoch) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+    ) -> Result<(), ExecutionError> {
+        match op {
+            Operation::Update { old, new } => {
+                fw.send(Operation::Delete { old }, DEFAULT_PORT_HANDLE)?;
+                fw.send(Operation::Insert { new }, DEFAULT_PORT_HANDLE)
+            }
+            op => fw.send(op, DEFAULT_PORT_HANDLE),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use dozer_core::channels::ProcessorChannelForwarder;
+use dozer_core::errors::ExecutionError;
+use dozer_core::node::{PortHandle, Processor};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::{Field, Operation, Record};
+
+use crate::pipeline::update_split::processor::UpdateSplitProcessor;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+fn record(id: i64, name: &str) -> Record {
+    Record::new(None, vec![Field::Int(id), Field::String(name.to_string())])
+}
+
+#[test]
+fn test_updates_become_delete_then_insert() {
+    let mut processor = UpdateSplitProcessor::new();
+    let mut fw = TestChannelForwarder { operations: vec![] };
+
+    for op in [
+        Operation::Insert {
+            new: record(1, "a"),
+        },
+        Operation::Update {
+            old: record(1, "a"),
+            new: record(1, "b"),
+        },
+        Operation::Update {
+            old: record(1, "b"),
+            new: record(2, "b"),
+        },
+        Operation::Delete {
+            old: record(2, "b"),
+        },
+    ] {
+        processor.process(DEFAULT_PORT_HANDLE, op, &mut fw).unwrap();
+    }
+
+    assert_eq!(
+        fw.operations,
+        vec![
+            Operation::Insert {
+                new: record(1, "a")
+            },
+            Operation::Delete {
+                old: record(1, "a")
+            },
+            Operation::Insert {
+                new: record(1, "b")
+            },
+            Operation::Delete {
+                old: record(1, "b")
+            },
+            Operation::Insert {
+                new: record(2, "b")
+            },
+            Operation::Delete {
+                old: record(2, "b")
+            },
+        ]
+    );
+}
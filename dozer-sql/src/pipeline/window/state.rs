@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use dozer_types::chrono::{DateTime, Duration, DurationRound, FixedOffset};
+use dozer_types::types::{Field, FieldType};
+
+use crate::pipeline::aggregation::aggregator::{
+    get_aggregator_from_aggregator_type, Aggregator, AggregatorEnum, AggregatorType,
+};
+use crate::pipeline::errors::{PipelineError, WindowError};
+
+/// A window closed by the watermark, with the final values of its measures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosedWindow<K> {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    pub key: K,
+    pub values: Vec<Field>,
+}
+
+#[derive(Debug)]
+struct OpenWindow {
+    aggregators: Vec<AggregatorEnum>,
+    values: Vec<Field>,
+}
+
+impl OpenWindow {
+    fn new(types: &[AggregatorType], ret_types: &[FieldType]) -> Self {
+        let mut aggregators = Vec::with_capacity(types.len());
+        for (typ, ret_type) in types.iter().zip(ret_types) {
+            let mut aggregator = get_aggregator_from_aggregator_type(typ);
+            aggregator.init(*ret_type);
+            aggregators.push(aggregator);
+        }
+        Self {
+            aggregators,
+            values: vec![Field::Null; types.len()],
+        }
+    }
+}
+
+/// The open windows of a windowed aggregation, keyed by `(window_start, group_key)`, and closed
+/// as the watermark advances.
+///
+/// Windows are `size` long and one starts every `hop_size`, so tumbling windows have both equal.
+/// A window `[start, end)` stays open until the watermark reaches `end + allowed_lateness`, so
+/// records arriving out of order within the allowed lateness are still aggregated into it.
+#[derive(Debug)]
+pub struct WindowState<K> {
+    size: Duration,
+    hop_size: Duration,
+    allowed_lateness: Duration,
+    measures_types: Vec<AggregatorType>,
+    measures_return_types: Vec<FieldType>,
+    windows: BTreeMap<(DateTime<FixedOffset>, K), OpenWindow>,
+    watermark: Option<DateTime<FixedOffset>>,
+}
+
+impl<K: Ord + Clone> WindowState<K> {
+    /// Creates the state of windows aggregating one measure per element of `measures_types`,
+    /// returning the type at the same index of `measures_return_types`.
+    pub fn new(
+        size: Duration,
+        hop_size: Duration,
+        allowed_lateness: Duration,
+        measures_types: Vec<AggregatorType>,
+        measures_return_types: Vec<FieldType>,
+    ) -> Self {
+        Self {
+            size,
+            hop_size,
+            allowed_lateness,
+            measures_types,
+            measures_return_types,
+            windows: BTreeMap::new(),
+            watermark: None,
+        }
+    }
+
+    /// Aggregates a record of the group `key` into every window containing its `event_time`.
+    /// `args` holds the arguments of each measure.
+    ///
+    /// Returns whether the record was accepted. A record is rejected if all its windows are
+    /// closed, and is only aggregated into the open ones otherwise.
+    pub fn insert(
+        &mut self,
+        event_time: DateTime<FixedOffset>,
+        key: K,
+        args: &[Vec<Field>],
+    ) -> Result<bool, PipelineError> {
+        let mut accepted = false;
+        for start in self.window_starts(event_time)? {
+            if self.is_closed(start) {
+                continue;
+            }
+
+            let window = self.windows.entry((start, key.clone())).or_insert_with(|| {
+                OpenWindow::new(&self.measures_types, &self.measures_return_types)
+            });
+            for (index, aggregator) in window.aggregators.iter_mut().enumerate() {
+                window.values[index] = aggregator.insert(&args[index])?;
+            }
+            accepted = true;
+        }
+        Ok(accepted)
+    }
+
+    /// Advances the watermark to `watermark`, and returns the windows it closes, by start then
+    /// key. A watermark that isn't ahead of the current one closes nothing.
+    pub fn advance_watermark(&mut self, watermark: DateTime<FixedOffset>) -> Vec<ClosedWindow<K>> {
+        if self.watermark.map_or(false, |current| watermark <= current) {
+            return vec![];
+        }
+        self.watermark = Some(watermark);
+
+        let mut closed = vec![];
+        while let Some(((start, _), _)) = self.windows.first_key_value() {
+            if !self.is_closed(*start) {
+                break;
+            }
+            let ((start, key), window) = self.windows.pop_first().unwrap();
+            closed.push(ClosedWindow {
+                start,
+                end: start + self.size,
+                key,
+                values: window.values,
+            });
+        }
+        closed
+    }
+
+    /// Number of windows currently open.
+    pub fn open_windows(&self) -> usize {
+        self.windows.len()
+    }
+
+    fn is_closed(&self, start: DateTime<FixedOffset>) -> bool {
+        self.watermark.map_or(false, |watermark| {
+            start + self.size + self.allowed_lateness <= watermark
+        })
+    }
+
+    /// Starts of the windows containing `event_time`, earliest first.
+    fn window_starts(
+        &self,
+        event_time: DateTime<FixedOffset>,
+    ) -> Result<Vec<DateTime<FixedOffset>>, WindowError> {
+        let last = event_time
+            .duration_trunc(self.hop_size)
+            .map_err(WindowError::TumbleRoundingError)?;
+
+        let mut starts = vec![];
+        let mut start = last - self.size + self.hop_size;
+        while start <= last {
+            starts.push(start);
+            start += self.hop_size;
+        }
+        Ok(starts)
+    }
+}
@@ -0,0 +1,104 @@
+use dozer_types::{
+    chrono::{DateTime, Duration, FixedOffset},
+    types::{Field, FieldType},
+};
+
+use crate::pipeline::aggregation::aggregator::AggregatorType;
+use crate::pipeline::window::state::{ClosedWindow, WindowState};
+
+fn time(minute: u32) -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(&format!("2020-01-01T00:{minute:02}:00Z")).unwrap()
+}
+
+/// Counts and sums the records of five minute windows of `key`.
+fn window_state(hop_size: i64, allowed_lateness: i64) -> WindowState<String> {
+    WindowState::new(
+        Duration::minutes(5),
+        Duration::minutes(hop_size),
+        Duration::minutes(allowed_lateness),
+        vec![AggregatorType::Count, AggregatorType::Sum],
+        vec![FieldType::Int, FieldType::Int],
+    )
+}
+
+fn insert(state: &mut WindowState<String>, minute: u32, key: &str, value: i64) -> bool {
+    state
+        .insert(
+            time(minute),
+            key.to_string(),
+            &[vec![Field::Int(value)], vec![Field::Int(value)]],
+        )
+        .unwrap()
+}
+
+fn closed(start: u32, key: &str, count: i64, sum: i64) -> ClosedWindow<String> {
+    ClosedWindow {
+        start: time(start),
+        end: time(start + 5),
+        key: key.to_string(),
+        values: vec![Field::Int(count), Field::Int(sum)],
+    }
+}
+
+#[test]
+fn test_window_closes_on_watermark() {
+    let mut state = window_state(5, 0);
+    assert!(insert(&mut state, 1, "b", 1));
+    assert!(insert(&mut state, 3, "a", 2));
+    assert!(insert(&mut state, 4, "b", 3));
+    assert!(insert(&mut state, 7, "a", 4));
+    assert_eq!(state.open_windows(), 3);
+
+    assert_eq!(state.advance_watermark(time(4)), vec![]);
+    assert_eq!(
+        state.advance_watermark(time(5)),
+        vec![closed(0, "a", 1, 2), closed(0, "b", 2, 4)]
+    );
+    // A watermark going back closes nothing.
+    assert_eq!(state.advance_watermark(time(2)), vec![]);
+    assert_eq!(state.open_windows(), 1);
+    assert_eq!(
+        state.advance_watermark(time(10)),
+        vec![closed(5, "a", 1, 4)]
+    );
+    assert_eq!(state.open_windows(), 0);
+}
+
+#[test]
+fn test_window_accepts_records_within_allowed_lateness() {
+    let mut state = window_state(5, 2);
+    assert!(insert(&mut state, 3, "a", 1));
+    assert!(state.advance_watermark(time(6)).is_empty());
+
+    // Late for the window ending at 5, but within the allowed lateness.
+    assert!(insert(&mut state, 4, "a", 2));
+    assert_eq!(state.advance_watermark(time(7)), vec![closed(0, "a", 2, 3)]);
+
+    // The window is closed now, so later records for it are dropped.
+    assert!(!insert(&mut state, 2, "a", 4));
+    assert!(!insert(&mut state, 4, "b", 8));
+    assert_eq!(state.open_windows(), 0);
+    assert!(insert(&mut state, 6, "a", 16));
+    assert_eq!(
+        state.advance_watermark(time(12)),
+        vec![closed(5, "a", 1, 16)]
+    );
+}
+
+#[test]
+fn test_hopping_window_drops_records_from_closed_windows_only() {
+    let mut state = window_state(1, 0);
+    assert!(state.advance_watermark(time(8)).is_empty());
+
+    // Of the windows of 00:06, those starting at 2 and 3 are closed.
+    assert!(insert(&mut state, 6, "a", 1));
+    assert_eq!(state.open_windows(), 3);
+    assert_eq!(
+        state
+            .advance_watermark(time(10))
+            .into_iter()
+            .map(|window| window.start)
+            .collect::<Vec<_>>(),
+        vec![time(4), time(5)]
+    );
+}
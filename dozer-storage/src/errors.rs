@@ -1,4 +1,5 @@
 #![allow(clippy::enum_variant_names)]
+use std::path::PathBuf;
 use std::thread::ThreadId;
 
 use dozer_types::errors::internal::BoxedError;
@@ -11,6 +12,8 @@ pub enum StorageError {
     BadPageSize { map_size: usize, page_size: usize },
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
+    #[error("File system error {0:?}: {1}")]
+    FileSystem(PathBuf, #[source] std::io::Error),
     #[error("Transaction was created in thread: {create_thread_id:?}, but committed in thread: {commit_thread_id:?}")]
     TransactionCommittedAcrossThread {
         create_thread_id: ThreadId,
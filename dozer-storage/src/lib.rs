@@ -1,11 +1,13 @@
 pub mod errors;
 pub mod lmdb_storage;
 pub use lmdb_storage::{LmdbEnvironment, RoLmdbEnvironment, RwLmdbEnvironment};
+mod lmdb_readers;
+pub use lmdb_readers::{ReadTransaction, ReaderSlots};
 
 mod lmdb_database;
 pub use lmdb_database::{
-    BorrowEncode, Decode, Encode, Encoded, Iterator, KeyIterator, LmdbKey, LmdbKeyType, LmdbVal,
-    ValueIterator,
+    lmdb_cmp, scan, BorrowEncode, Decode, Encode, Encoded, Iterator, KeyIterator, LmdbKey,
+    LmdbKeyType, LmdbVal, ValueIterator,
 };
 mod lmdb_map;
 pub use lmdb_map::LmdbMap;
@@ -1,11 +1,11 @@
-use std::ops::Bound;
+use std::{cmp::Ordering, ops::Bound};
 
 use dozer_types::borrow::Cow;
-use lmdb::Cursor;
+use lmdb::{Cursor, Database, Transaction};
 
 use crate::{errors::StorageError, Decode, Encode, Encoded};
 
-use super::{lmdb_val::BorrowEncode, raw_iterator::RawIterator};
+use super::{lmdb_cmp::lmdb_cmp, lmdb_val::BorrowEncode, raw_iterator::RawIterator};
 
 pub struct KeyIterator<'txn, C: Cursor<'txn>, K> {
     inner: RawIterator<'txn, C>,
@@ -144,3 +144,57 @@ impl<'txn, C: Cursor<'txn>, K: Decode + 'txn, V: Decode + 'txn> std::iter::Itera
         }
     }
 }
+
+/// Iterates over the entries of `db` from `start_key` to `end_key`, in the order of the keys, or in
+/// reverse if `ascending` is `false`, in which case `start_key` is the upper bound.
+///
+/// Keys are compared with the comparison function of `db`, so a prefix scan of an
+/// order-preserving key encoding is a scan from the prefix to the prefix's successor.
+pub fn scan<'txn, T: Transaction, K: BorrowEncode + Decode + 'txn, V: Decode + 'txn>(
+    txn: &'txn T,
+    db: Database,
+    start_key: Bound<K::Encode<'_>>,
+    end_key: Bound<K::Encode<'_>>,
+    ascending: bool,
+) -> Result<
+    impl std::iter::Iterator<Item = Result<(Cow<'txn, K>, Cow<'txn, V>), StorageError>> + 'txn,
+    StorageError,
+> {
+    let cursor = txn.open_ro_cursor(db)?;
+    let start_key = encode_bound(start_key)?;
+    let inner = RawIterator::new(cursor, bound_as_ref(&start_key), ascending)?;
+    let end_key = match encode_bound(end_key)? {
+        Bound::Included(key) => Bound::Included(key.as_ref().to_vec()),
+        Bound::Excluded(key) => Bound::Excluded(key.as_ref().to_vec()),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+
+    Ok(inner
+        .take_while(move |result| match result {
+            Ok((key, _)) => !is_past_end(txn, db, key, &end_key, ascending),
+            Err(_) => true,
+        })
+        .map(|result| match result {
+            Ok((key, value)) => decode_key_value(key, value),
+            Err(e) => Err(e.into()),
+        }))
+}
+
+fn is_past_end<T: Transaction>(
+    txn: &T,
+    db: Database,
+    key: &[u8],
+    end_key: &Bound<Vec<u8>>,
+    ascending: bool,
+) -> bool {
+    let (end_key, inclusive) = match end_key {
+        Bound::Included(end_key) => (end_key, true),
+        Bound::Excluded(end_key) => (end_key, false),
+        Bound::Unbounded => return false,
+    };
+    match lmdb_cmp(txn, db, key, end_key) {
+        Ordering::Less => !ascending,
+        Ordering::Equal => !inclusive,
+        Ordering::Greater => ascending,
+    }
+}
@@ -1,7 +1,10 @@
-use dozer_storage::lmdb::{Database, Transaction};
-use dozer_storage::lmdb_sys as ffi;
 use std::{cmp::Ordering, ffi::c_void};
 
+use lmdb::{Database, Transaction};
+use lmdb_sys as ffi;
+
+/// Compares two keys with the comparison function of `db`, which for integer keys and databases
+/// with a custom comparator differs from comparing the bytes.
 pub fn lmdb_cmp<T: Transaction>(txn: &T, db: Database, a: &[u8], b: &[u8]) -> Ordering {
     let a: ffi::MDB_val = ffi::MDB_val {
         mv_size: a.len(),
@@ -11,6 +14,6 @@ pub fn lmdb_cmp<T: Transaction>(txn: &T, db: Database, a: &[u8], b: &[u8]) -> Or
         mv_size: b.len(),
         mv_data: b.as_ptr() as *mut c_void,
     };
-    let result = unsafe { dozer_storage::lmdb_sys::mdb_cmp(txn.txn(), db.dbi(), &a, &b) };
+    let result = unsafe { ffi::mdb_cmp(txn.txn(), db.dbi(), &a, &b) };
     result.cmp(&0)
 }
@@ -1,6 +1,8 @@
 mod iterator;
+mod lmdb_cmp;
 mod lmdb_val;
 mod raw_iterator;
 
-pub use iterator::{Iterator, KeyIterator, ValueIterator};
+pub use iterator::{scan, Iterator, KeyIterator, ValueIterator};
+pub use lmdb_cmp::lmdb_cmp;
 pub use lmdb_val::{BorrowEncode, Decode, Encode, Encoded, LmdbKey, LmdbKeyType, LmdbVal};
@@ -7,7 +7,7 @@ use lmdb_sys::MDB_LAST;
 use crate::{
     errors::StorageError,
     lmdb_storage::{LmdbEnvironment, RwLmdbEnvironment},
-    Encode, Iterator, KeyIterator, LmdbKey, LmdbKeyType, LmdbVal, ValueIterator,
+    scan, Encode, Iterator, KeyIterator, LmdbKey, LmdbKeyType, LmdbVal, ValueIterator,
 };
 
 #[derive(Debug)]
@@ -157,6 +157,20 @@ impl<K: LmdbKey, V: LmdbVal> LmdbMap<K, V> {
         KeyIterator::new(cursor, Bound::Unbounded, true)
     }
 
+    /// See [`scan`](crate::scan).
+    pub fn scan<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+        start_key: Bound<K::Encode<'_>>,
+        end_key: Bound<K::Encode<'_>>,
+        ascending: bool,
+    ) -> Result<
+        impl std::iter::Iterator<Item = Result<(Cow<'txn, K>, Cow<'txn, V>), StorageError>> + 'txn,
+        StorageError,
+    > {
+        scan(txn, self.db, start_key, end_key, ascending)
+    }
+
     pub fn values<'txn, T: Transaction>(
         &self,
         txn: &'txn T,
@@ -269,4 +283,110 @@ mod tests {
             assert_eq!(map.get(txn, &(i + 1)).unwrap(), None);
         }
     }
+
+    #[test]
+    fn test_lmdb_map_scan() {
+        let temp_dir = TempDir::new("test_lmdb_map_scan").unwrap();
+        let mut env = LmdbEnvironmentManager::create_rw(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<Vec<u8>, u64>::create(&mut env, None).unwrap();
+
+        let txn = env.txn_mut().unwrap();
+        for (value, key) in [b"a/1", b"a/2", b"a/3", b"b/1", b"b/2"].iter().enumerate() {
+            map.insert(txn, key.as_slice(), &(value as u64)).unwrap();
+        }
+
+        let txn = &*txn;
+        let scan = |start: Bound<&[u8]>, end: Bound<&[u8]>, ascending: bool| {
+            map.scan(txn, start, end, ascending)
+                .unwrap()
+                .map(|result| result.map(|(key, _)| key.into_owned()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+
+        // Forward.
+        assert_eq!(
+            scan(Bound::Unbounded, Bound::Unbounded, true),
+            vec![b"a/1", b"a/2", b"a/3", b"b/1", b"b/2"]
+        );
+        assert_eq!(
+            scan(
+                Bound::Excluded(b"a/1".as_slice()),
+                Bound::Included(b"b/1".as_slice()),
+                true
+            ),
+            vec![b"a/2", b"a/3", b"b/1"]
+        );
+        // Reverse.
+        assert_eq!(
+            scan(Bound::Unbounded, Bound::Unbounded, false),
+            vec![b"b/2", b"b/1", b"a/3", b"a/2", b"a/1"]
+        );
+        assert_eq!(
+            scan(
+                Bound::Included(b"b/1".as_slice()),
+                Bound::Excluded(b"a/1".as_slice()),
+                false
+            ),
+            vec![b"b/1", b"a/3", b"a/2"]
+        );
+        // Prefix, up to the successor of the prefix.
+        assert_eq!(
+            scan(
+                Bound::Included(b"a/".as_slice()),
+                Bound::Excluded(b"a0".as_slice()),
+                true
+            ),
+            vec![b"a/1", b"a/2", b"a/3"]
+        );
+        assert_eq!(
+            scan(
+                Bound::Excluded(b"b0".as_slice()),
+                Bound::Included(b"b/".as_slice()),
+                false
+            ),
+            vec![b"b/2", b"b/1"]
+        );
+        // Empty range.
+        assert!(scan(Bound::Included(b"c".as_slice()), Bound::Unbounded, true).is_empty());
+    }
+
+    #[test]
+    fn test_lmdb_map_scan_integer_keys() {
+        let temp_dir = TempDir::new("test_lmdb_map_scan_integer_keys").unwrap();
+        let mut env = LmdbEnvironmentManager::create_rw(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u64, u64>::create(&mut env, None).unwrap();
+
+        // Integer keys don't sort as their bytes, so the end bound must be compared as LMDB does.
+        let txn = env.txn_mut().unwrap();
+        for i in [1u64, 255, 256, 1000] {
+            map.insert(txn, &i, &i).unwrap();
+        }
+
+        let values = map
+            .scan(txn, Bound::Included(&2), Bound::Excluded(&1000), true)
+            .unwrap()
+            .map(|result| result.map(|(_, value)| value.into_owned()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(values, vec![255, 256]);
+
+        let values = map
+            .scan(txn, Bound::Unbounded, Bound::Included(&256), false)
+            .unwrap()
+            .map(|result| result.map(|(_, value)| value.into_owned()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(values, vec![1000, 256]);
+    }
 }
@@ -8,7 +8,7 @@ use crate::{
     errors::StorageError,
     lmdb_map::database_key_flag,
     lmdb_storage::{LmdbEnvironment, RwLmdbEnvironment},
-    Encode, Iterator, LmdbKey, LmdbKeyType,
+    scan, Encode, Iterator, LmdbKey, LmdbKeyType,
 };
 
 #[derive(Debug)]
@@ -151,6 +151,20 @@ impl<K: LmdbKey, V: LmdbKey> LmdbMultimap<K, V> {
         let cursor = txn.open_ro_cursor(self.db)?;
         Iterator::new(cursor, starting_key, ascending)
     }
+
+    /// See [`scan`](crate::scan).
+    pub fn scan<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+        start_key: Bound<K::Encode<'_>>,
+        end_key: Bound<K::Encode<'_>>,
+        ascending: bool,
+    ) -> Result<
+        impl std::iter::Iterator<Item = Result<(Cow<'txn, K>, Cow<'txn, V>), StorageError>> + 'txn,
+        StorageError,
+    > {
+        scan(txn, self.db, start_key, end_key, ascending)
+    }
 }
 
 fn database_flag<K: LmdbKey, V: LmdbKey>() -> DatabaseFlags {
@@ -0,0 +1,106 @@
+use std::mem::ManuallyDrop;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread;
+
+use dozer_types::log::warn;
+use lmdb::{RoTransaction, Transaction};
+
+/// Share of the reader slots in use from which a warning is logged.
+const WARN_RATIO: f64 = 0.9;
+
+/// Accounting of the read transactions open on an environment, whose number is capped by its
+/// `max_readers`.
+///
+/// A warning is logged when usage approaches the cap, as a transaction that is never dropped
+/// holds its reader slot until the environment is closed and eventually stalls all readers. In
+/// debug builds, closing an environment with read transactions still open panics.
+#[derive(Debug)]
+pub struct ReaderSlots {
+    max_readers: u32,
+    warn_threshold: u32,
+    in_use: AtomicU32,
+    warned: AtomicBool,
+}
+
+impl ReaderSlots {
+    pub fn new(max_readers: u32) -> Self {
+        Self {
+            max_readers,
+            warn_threshold: ((max_readers as f64 * WARN_RATIO).ceil() as u32).max(1),
+            in_use: AtomicU32::new(0),
+            warned: AtomicBool::new(false),
+        }
+    }
+
+    pub fn max_readers(&self) -> u32 {
+        self.max_readers
+    }
+
+    /// Number of read transactions currently open.
+    pub fn in_use(&self) -> u32 {
+        self.in_use.load(Ordering::Relaxed)
+    }
+
+    fn acquire(&self) {
+        let in_use = self.in_use.fetch_add(1, Ordering::Relaxed) + 1;
+        if in_use >= self.warn_threshold && !self.warned.swap(true, Ordering::Relaxed) {
+            warn!(
+                "{} of {} LMDB reader slots are in use. Read transactions may be leaking.",
+                in_use, self.max_readers
+            );
+        }
+    }
+
+    fn release(&self) {
+        let in_use = self.in_use.fetch_sub(1, Ordering::Relaxed) - 1;
+        if in_use < self.warn_threshold {
+            self.warned.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for ReaderSlots {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            debug_assert_eq!(
+                self.in_use(),
+                0,
+                "LMDB environment closed with read transactions leaked"
+            );
+        }
+    }
+}
+
+/// A read transaction, holding one of the reader slots of its environment until dropped.
+#[derive(Debug)]
+pub struct ReadTransaction<'env> {
+    txn: RoTransaction<'env>,
+    slots: &'env ReaderSlots,
+}
+
+impl<'env> ReadTransaction<'env> {
+    pub(crate) fn new(txn: RoTransaction<'env>, slots: &'env ReaderSlots) -> Self {
+        slots.acquire();
+        Self { txn, slots }
+    }
+}
+
+impl Transaction for ReadTransaction<'_> {
+    fn txn(&self) -> *mut lmdb_sys::MDB_txn {
+        self.txn.txn()
+    }
+
+    fn commit(self) -> lmdb::Result<()> {
+        let this = ManuallyDrop::new(self);
+        this.slots.release();
+        // SAFETY: `this` is never dropped, so `txn` is only dropped once, by `commit`.
+        let txn = unsafe { std::ptr::read(&this.txn) };
+        txn.commit()
+    }
+}
+
+impl Drop for ReadTransaction<'_> {
+    fn drop(&mut self) {
+        self.slots.release();
+    }
+}
@@ -1,9 +1,11 @@
 use crate::errors::StorageError;
+use crate::lmdb_readers::{ReadTransaction, ReaderSlots};
 use dozer_types::log::error;
 use lmdb::{
-    Database, DatabaseFlags, Environment, EnvironmentFlags, RoCursor, RoTransaction, RwCursor,
-    RwTransaction, Transaction, WriteFlags,
+    Database, DatabaseFlags, Environment, EnvironmentFlags, RoCursor, RwCursor, RwTransaction,
+    Transaction, WriteFlags,
 };
+use std::io::ErrorKind;
 use std::path::Path;
 use std::sync::Arc;
 use std::thread::ThreadId;
@@ -51,12 +53,16 @@ impl Default for LmdbEnvironmentOptions {
 pub trait LmdbEnvironment {
     fn env(&self) -> &Environment;
 
+    /// The reader slots of the environment, shared by all its handles.
+    fn readers(&self) -> &ReaderSlots;
+
     fn open_database(&self, name: Option<&str>) -> Result<Database, StorageError> {
         self.env().open_db(name).map_err(Into::into)
     }
 
-    fn begin_txn(&self) -> Result<RoTransaction<'_>, StorageError> {
-        self.env().begin_ro_txn().map_err(Into::into)
+    fn begin_txn(&self) -> Result<ReadTransaction<'_>, StorageError> {
+        let txn = self.env().begin_ro_txn()?;
+        Ok(ReadTransaction::new(txn, self.readers()))
     }
 }
 
@@ -74,6 +80,19 @@ impl LmdbEnvironmentManager {
         let _ = fs::remove_file(full_path);
     }
 
+    /// Returns the on-disk size of the environment `name`, 0 if it doesn't exist.
+    ///
+    /// Environments are created with `NO_SUB_DIR`, so this is the size of a single data file.
+    /// The lock file next to it is not counted.
+    pub fn size(path: &Path, name: &str) -> Result<u64, StorageError> {
+        let full_path = path.join(Path::new(name));
+        match fs::metadata(&full_path) {
+            Ok(metadata) => Ok(metadata.len()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(StorageError::FileSystem(full_path, e)),
+        }
+    }
+
     pub fn create_rw(
         base_path: &Path,
         name: &str,
@@ -92,7 +111,7 @@ impl LmdbEnvironmentManager {
             ));
         }
         let env = Self::open_env(base_path, name, options)?;
-        RwLmdbEnvironment::new(env)
+        RwLmdbEnvironment::new(env, options.max_readers)
     }
 
     pub fn create_ro(
@@ -102,7 +121,10 @@ impl LmdbEnvironmentManager {
     ) -> Result<RoLmdbEnvironment, StorageError> {
         options.flags |= EnvironmentFlags::READ_ONLY;
         let env = Self::open_env(base_path, name, options)?;
-        Ok(RoLmdbEnvironment::new(Arc::new(env)))
+        Ok(RoLmdbEnvironment::new(
+            Arc::new(env),
+            Arc::new(ReaderSlots::new(options.max_readers)),
+        ))
     }
 
     fn open_env(
@@ -132,25 +154,31 @@ impl LmdbEnvironmentManager {
 pub struct RwLmdbEnvironment {
     inner: Option<(RwTransaction<'static>, ThreadId)>,
     env: Arc<Environment>,
+    readers: Arc<ReaderSlots>,
 }
 
 impl LmdbEnvironment for RwLmdbEnvironment {
     fn env(&self) -> &Environment {
         &self.env
     }
+
+    fn readers(&self) -> &ReaderSlots {
+        &self.readers
+    }
 }
 
 impl RwLmdbEnvironment {
-    fn new(env: Environment) -> Result<Self, StorageError> {
+    fn new(env: Environment, max_readers: u32) -> Result<Self, StorageError> {
         Ok(Self {
             inner: None,
             env: Arc::new(env),
+            readers: Arc::new(ReaderSlots::new(max_readers)),
         })
     }
 
     /// Shares this read-write environment with a read-only environment.
     pub fn share(&self) -> RoLmdbEnvironment {
-        RoLmdbEnvironment::new(self.env.clone())
+        RoLmdbEnvironment::new(self.env.clone(), self.readers.clone())
     }
 
     pub fn create_database(
@@ -264,17 +292,24 @@ unsafe impl Send for RwLmdbEnvironment {}
 unsafe impl Sync for RwLmdbEnvironment {}
 
 #[derive(Debug, Clone)]
-pub struct RoLmdbEnvironment(Arc<Environment>);
+pub struct RoLmdbEnvironment {
+    env: Arc<Environment>,
+    readers: Arc<ReaderSlots>,
+}
 
 impl LmdbEnvironment for RoLmdbEnvironment {
     fn env(&self) -> &Environment {
-        &self.0
+        &self.env
+    }
+
+    fn readers(&self) -> &ReaderSlots {
+        &self.readers
     }
 }
 
 impl RoLmdbEnvironment {
-    pub fn new(env: Arc<Environment>) -> Self {
-        Self(env)
+    pub fn new(env: Arc<Environment>, readers: Arc<ReaderSlots>) -> Self {
+        Self { env, readers }
     }
 }
 
@@ -308,4 +343,78 @@ mod tests {
         .unwrap();
         ro_env.open_database(db_name).unwrap();
     }
+
+    #[test]
+    fn test_size_grows_with_data() {
+        let temp_dir = TempDir::new("test").unwrap();
+        let name = "test";
+        assert_eq!(
+            LmdbEnvironmentManager::size(temp_dir.path(), name).unwrap(),
+            0
+        );
+
+        let mut rw_env = LmdbEnvironmentManager::create_rw(
+            temp_dir.path(),
+            name,
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let db = rw_env
+            .create_database(None, DatabaseFlags::empty())
+            .unwrap();
+
+        let mut write = |keys: std::ops::Range<u32>| {
+            for key in keys {
+                rw_env.put(db, &key.to_be_bytes(), &[0; 1024]).unwrap();
+            }
+            rw_env.commit().unwrap();
+            LmdbEnvironmentManager::size(temp_dir.path(), name).unwrap()
+        };
+        let small = write(0..10);
+        assert!(small > 0);
+        let large = write(10..1000);
+        assert!(large > small, "{large} <= {small}");
+    }
+
+    #[test]
+    fn test_reader_slots_are_released_on_drop() {
+        let temp_dir = TempDir::new("test").unwrap();
+        let rw_env = LmdbEnvironmentManager::create_rw(
+            temp_dir.path(),
+            "test",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let ro_env = rw_env.share();
+
+        let txn = rw_env.begin_txn().unwrap();
+        let other_thread = std::thread::spawn(move || {
+            let txn = ro_env.begin_txn().unwrap();
+            assert_eq!(ro_env.readers().in_use(), 2);
+            drop(txn);
+            ro_env
+        });
+        let ro_env = other_thread.join().unwrap();
+        assert_eq!(ro_env.readers().in_use(), 1);
+        drop(txn);
+        assert_eq!(rw_env.readers().in_use(), 0);
+        assert_eq!(rw_env.readers().max_readers(), 256);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "read transactions leaked")]
+    fn test_leaked_reader_is_detected() {
+        let temp_dir = TempDir::new("test").unwrap();
+        let rw_env = LmdbEnvironmentManager::create_rw(
+            temp_dir.path(),
+            "test",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+
+        std::mem::forget(rw_env.begin_txn().unwrap());
+        assert_eq!(rw_env.readers().in_use(), 1);
+        drop(rw_env);
+    }
 }
@@ -9,8 +9,8 @@ use arg::SqlLogicTestArgs;
 use clap::Parser;
 // use arg::SqlLogicTestArgs;
 // use clap::Parser;
+use dozer_sql::pipeline::builder::DozerDialect;
 use dozer_sql::sqlparser::ast::Statement;
-use dozer_sql::sqlparser::dialect::AnsiDialect;
 use dozer_sql::sqlparser::parser::Parser as SqlParser;
 use dozer_types::types::Operation;
 use error::DozerSqlLogicTestError;
@@ -58,7 +58,7 @@ impl AsyncDB for Dozer {
         use std::println as info;
         info!("SQL [{}] is running", sql);
 
-        let ast = SqlParser::parse_sql(&AnsiDialect {}, sql)?;
+        let ast = SqlParser::parse_sql(&DozerDialect, sql)?;
         let statement: &Statement = &ast[0];
         match statement {
             // If sql is create table, run `source_db` to get table schema
@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
 
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -39,10 +42,40 @@ impl Display for Epoch {
     }
 }
 
+/// User metadata a source attaches to the operations it ingests, for lineage.
+pub type OpTags = Arc<BTreeMap<String, String>>;
+
+/// The source an operation was ingested from, and its position in that source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpOrigin {
+    pub source: Arc<NodeHandle>,
+    pub id: OpIdentifier,
+    /// Tags the source attached to the operation, if any. Operations a processor derives from it
+    /// carry the same tags.
+    pub tags: Option<OpTags>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExecutorOperation {
-    Op { op: Operation },
-    Commit { epoch: Epoch },
+    Op {
+        op: Operation,
+        /// Set by the executor while the operation flows through the DAG. Not persisted.
+        #[serde(skip)]
+        origin: Option<OpOrigin>,
+    },
+    Commit {
+        epoch: Epoch,
+    },
     Terminate,
     SnapshottingDone {},
+    /// No record with an event time earlier than `timestamp` is expected anymore.
+    Watermark {
+        timestamp: DateTime<FixedOffset>,
+    },
+    /// Operations a node sends on a port as one message, in order. Only exchanged between the
+    /// nodes of a DAG, never persisted.
+    #[serde(skip)]
+    Batch {
+        ops: Vec<(Operation, Option<OpOrigin>)>,
+    },
 }
@@ -1,3 +1,4 @@
+use chrono::{DateTime, FixedOffset};
 use prettytable::Table as PrettyTable;
 use std::fmt::Debug;
 
@@ -36,6 +37,13 @@ impl IngestionMessage {
             kind: IngestionMessageKind::SnapshottingStarted,
         }
     }
+
+    pub fn new_watermark(txn: u64, seq_no: u64, timestamp: DateTime<FixedOffset>) -> Self {
+        Self {
+            identifier: OpIdentifier::new(txn, seq_no),
+            kind: IngestionMessageKind::Watermark(timestamp),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -48,6 +56,9 @@ pub enum IngestionMessageKind {
     /// A connector uses this message kind to notify Dozer that a initial snapshot of the source table is done,
     /// and the data is up-to-date until next CDC event.
     SnapshottingDone,
+    /// A connector uses this message kind to notify Dozer that no more events with an event time
+    /// earlier than the given timestamp will be sent.
+    Watermark(DateTime<FixedOffset>),
 }
 
 #[derive(Error, Debug)]
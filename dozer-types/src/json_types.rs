@@ -1,11 +1,15 @@
-use crate::errors::types::DeserializationError;
-use crate::types::{DozerDuration, Field, DATE_FORMAT};
+use crate::errors::types::{DeserializationError, TypeError};
+use crate::helper::json_value_to_field;
+use crate::types::{DozerDuration, Field, FieldType, DATE_FORMAT};
+use base64::{engine::general_purpose, Engine};
 use chrono::SecondsFormat;
 use ordered_float::OrderedFloat;
 use prost_types::value::Kind;
 use prost_types::{ListValue, Struct, Value as ProstValue};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Map, Value};
+use serde_json::{json, Map, Number, Value};
 use std::collections::BTreeMap;
 
 use std::fmt::{Display, Formatter};
@@ -89,6 +93,97 @@ pub fn field_to_json_value(field: Field) -> Result<Value, DeserializationError>
     }
 }
 
+/// Options for [`field_to_json`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonEncodingOptions {
+    /// Encode `UInt`, `Int`, `Float` and `Decimal` as strings. JSON numbers are read as `f64` by
+    /// many clients, so large integers and precise decimals only survive as strings.
+    pub numbers_as_strings: bool,
+}
+
+fn number_to_json<T: Into<Value> + ToString>(n: T, options: JsonEncodingOptions) -> Value {
+    if options.numbers_as_strings {
+        Value::String(n.to_string())
+    } else {
+        n.into()
+    }
+}
+
+/// Encodes a field for JSON-speaking sinks. [`field_from_json`] reverses it.
+///
+/// Unlike `field_to_json_value`, binary data is base64 and timestamps keep their full precision.
+/// `U128` and `I128` are always strings. Non-finite floats are strings in either mode, as JSON
+/// has no representation for them.
+pub fn field_to_json(
+    field: &Field,
+    options: JsonEncodingOptions,
+) -> Result<Value, DeserializationError> {
+    Ok(match field {
+        Field::UInt(n) => number_to_json(*n, options),
+        Field::U128(n) => Value::String(n.to_string()),
+        Field::Int(n) => number_to_json(*n, options),
+        Field::I128(n) => Value::String(n.to_string()),
+        Field::Float(n) if !n.is_finite() => Value::String(n.to_string()),
+        Field::Float(n) => number_to_json(n.0, options),
+        Field::Decimal(d) if options.numbers_as_strings => Value::String(d.to_string()),
+        Field::Decimal(d) => d
+            .to_f64()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or(DeserializationError::F64TypeConversionError)?,
+        Field::Boolean(b) => Value::Bool(*b),
+        Field::String(s) | Field::Text(s) => Value::String(s.clone()),
+        Field::Binary(b) => Value::String(general_purpose::STANDARD.encode(b)),
+        Field::Timestamp(ts) => Value::String(ts.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+        Field::Date(d) => Value::String(d.format(DATE_FORMAT).to_string()),
+        Field::Json(b) => json_value_to_serde_json(b.clone())?,
+        Field::Point(point) => convert_x_y_to_object(&point.0.x_y()),
+        Field::Duration(d) => convert_duration_to_object(d),
+        Field::Null => Value::Null,
+    })
+}
+
+/// Decodes a field encoded by [`field_to_json`], in either number mode.
+pub fn field_from_json(value: Value, typ: FieldType) -> Result<Field, TypeError> {
+    if value.is_null() {
+        return Ok(Field::Null);
+    }
+
+    let invalid = || TypeError::InvalidFieldValue {
+        field_type: typ,
+        nullable: true,
+        value: value.to_string(),
+    };
+    match (typ, &value) {
+        (FieldType::Json, _) => serde_json_to_json_value(value.clone())
+            .map(Field::Json)
+            .map_err(TypeError::DeserializationError),
+        (FieldType::Point | FieldType::Duration, _) => {
+            json_value_to_field(value.clone(), typ, false)
+        }
+        (FieldType::UInt, Value::Number(n)) => n.as_u64().map(Field::UInt).ok_or_else(invalid),
+        (FieldType::Int, Value::Number(n)) => n.as_i64().map(Field::Int).ok_or_else(invalid),
+        (FieldType::Float, Value::Number(n)) => n
+            .as_f64()
+            .map(|n| Field::Float(OrderedFloat(n)))
+            .ok_or_else(invalid),
+        (FieldType::Decimal, Value::Number(n)) => {
+            let n = n.to_string();
+            Decimal::from_str(&n)
+                .or_else(|_| Decimal::from_scientific(&n))
+                .map(Field::Decimal)
+                .map_err(|_| invalid())
+        }
+        (FieldType::Boolean, Value::Bool(b)) => Ok(Field::Boolean(*b)),
+        (FieldType::Binary, Value::String(s)) => general_purpose::STANDARD
+            .decode(s)
+            .map(Field::Binary)
+            .map_err(|_| invalid()),
+        (_, Value::String(s)) => Field::from_str(s, typ, false),
+        _ => Err(invalid()),
+    }
+}
+
 pub fn json_value_to_serde_json(value: JsonValue) -> Result<Value, DeserializationError> {
     match value {
         JsonValue::Null => Ok(Value::Null),
@@ -194,7 +289,7 @@ pub fn serde_json_to_json_value(value: Value) -> Result<JsonValue, Deserializati
 #[cfg(test)]
 mod tests {
     use crate::{
-        chrono::{NaiveDate, Offset, TimeZone, Utc},
+        chrono::{NaiveDate, Offset, TimeZone, Timelike, Utc},
         json_value_to_field,
         ordered_float::OrderedFloat,
         rust_decimal::Decimal,
@@ -267,4 +362,118 @@ mod tests {
             test_field_conversion(field_type, field);
         }
     }
+
+    fn round_trip_cases() -> Vec<(FieldType, Field)> {
+        vec![
+            (FieldType::UInt, Field::UInt(u64::MAX)),
+            (FieldType::U128, Field::U128(u128::MAX)),
+            (FieldType::Int, Field::Int(i64::MIN)),
+            (FieldType::I128, Field::I128(i128::MIN)),
+            (FieldType::Float, Field::Float(OrderedFloat(1.1))),
+            (FieldType::Float, Field::Float(OrderedFloat(f64::NAN))),
+            (FieldType::Float, Field::Float(OrderedFloat(f64::INFINITY))),
+            (FieldType::Decimal, Field::Decimal(Decimal::new(202, 2))),
+            (FieldType::Boolean, Field::Boolean(false)),
+            (FieldType::String, Field::String("a".to_string())),
+            (FieldType::Text, Field::Text("lorem ipsum".to_string())),
+            (FieldType::Binary, Field::Binary(vec![0, 1, 254, 255])),
+            (
+                FieldType::Timestamp,
+                Field::Timestamp(
+                    Utc.fix()
+                        .with_ymd_and_hms(2001, 1, 1, 0, 4, 0)
+                        .unwrap()
+                        .with_nanosecond(123_456_789)
+                        .unwrap(),
+                ),
+            ),
+            (
+                FieldType::Date,
+                Field::Date(NaiveDate::from_ymd_opt(2022, 11, 24).unwrap()),
+            ),
+            (
+                FieldType::Json,
+                Field::Json(JsonValue::Object(BTreeMap::from([(
+                    "a".to_string(),
+                    JsonValue::Array(vec![JsonValue::Bool(true), JsonValue::Null]),
+                )]))),
+            ),
+            (
+                FieldType::Point,
+                Field::Point(DozerPoint::from((3.234, 4.567))),
+            ),
+            (
+                FieldType::Duration,
+                Field::Duration(DozerDuration(
+                    Duration::from_nanos(123_u64),
+                    TimeUnit::Nanoseconds,
+                )),
+            ),
+            (FieldType::Int, Field::Null),
+        ]
+    }
+
+    #[test]
+    fn test_field_json_round_trip() {
+        for numbers_as_strings in [false, true] {
+            let options = JsonEncodingOptions { numbers_as_strings };
+            for (field_type, field) in round_trip_cases() {
+                let value = field_to_json(&field, options).unwrap();
+                assert_eq!(
+                    field_from_json(value.clone(), field_type).unwrap(),
+                    field,
+                    "{value} with {options:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_json_number_encoding() {
+        let field = Field::Int(42);
+        assert_eq!(
+            field_to_json(&field, JsonEncodingOptions::default()).unwrap(),
+            json!(42)
+        );
+        assert_eq!(
+            field_to_json(
+                &field,
+                JsonEncodingOptions {
+                    numbers_as_strings: true
+                }
+            )
+            .unwrap(),
+            json!("42")
+        );
+        assert_eq!(
+            field_to_json(
+                &Field::Binary(b"dozer".to_vec()),
+                JsonEncodingOptions::default()
+            )
+            .unwrap(),
+            json!("ZG96ZXI=")
+        );
+    }
+
+    #[test]
+    fn test_field_json_preserves_decimal_precision() {
+        let decimal = Decimal::from_str("79228162514264.337593543950335").unwrap();
+        let options = JsonEncodingOptions {
+            numbers_as_strings: true,
+        };
+        let value = field_to_json(&Field::Decimal(decimal), options).unwrap();
+        assert_eq!(value, json!("79228162514264.337593543950335"));
+        assert_eq!(
+            field_from_json(value, FieldType::Decimal).unwrap(),
+            Field::Decimal(decimal)
+        );
+
+        // Trailing zeros are part of a decimal's scale and survive too.
+        let decimal = Decimal::new(1000, 3);
+        let value = field_to_json(&Field::Decimal(decimal), options).unwrap();
+        let Field::Decimal(decoded) = field_from_json(value, FieldType::Decimal).unwrap() else {
+            panic!("Expected a decimal");
+        };
+        assert_eq!(decoded.scale(), 3);
+    }
 }
@@ -8,6 +8,7 @@ use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use serde::{self, Deserialize, Serialize};
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use std::time::Duration;
@@ -160,7 +161,7 @@ impl Field {
         }
     }
 
-    fn get_type_prefix(&self) -> u8 {
+    pub(crate) fn get_type_prefix(&self) -> u8 {
         match self {
             Field::UInt(_) => 0,
             Field::U128(_) => 1,
@@ -551,6 +552,36 @@ impl Field {
     }
 }
 
+/// Where `NULL` values are placed when comparing `Field`s with [`Field::cmp_with_nulls`].
+///
+/// The derived `Ord` on `Field` happens to sort `Field::Null` after every other value, which is
+/// the same as `NullOrdering::Last`. Code that sorts values on behalf of a query should not rely
+/// on that and should pass the policy explicitly instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NullOrdering {
+    First,
+    #[default]
+    Last,
+}
+
+impl Field {
+    /// Compares two fields, placing `NULL`s according to `nulls`.
+    ///
+    /// Two `NULL`s compare as equal. Non-`NULL` values use the regular `Field` ordering.
+    pub fn cmp_with_nulls(&self, other: &Field, nulls: NullOrdering) -> Ordering {
+        match (self, other, nulls) {
+            (Field::Null, Field::Null, _) => Ordering::Equal,
+            (Field::Null, _, NullOrdering::First) | (_, Field::Null, NullOrdering::Last) => {
+                Ordering::Less
+            }
+            (Field::Null, _, NullOrdering::Last) | (_, Field::Null, NullOrdering::First) => {
+                Ordering::Greater
+            }
+            (_, _, _) => self.cmp(other),
+        }
+    }
+}
+
 impl Display for Field {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
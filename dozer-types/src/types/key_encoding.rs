@@ -0,0 +1,341 @@
+//! An order-preserving byte encoding of `Field`s, for use as storage keys.
+//!
+//! [`Field::encode`] writes integers as two's complement and floats as IEEE 754 bytes, so
+//! comparing encoded keys byte by byte, as LMDB does, doesn't follow the `Field` ordering.
+//! [`Field::to_bytes`] writes every value so that `a < b` implies `a.to_bytes() < b.to_bytes()`.
+//! Encoded values are self-delimiting, so the encodings of several fields can be concatenated
+//! into a composite key that sorts by its first field, then its second, and so on.
+use crate::errors::types::{DeserializationError, TypeError};
+use crate::json_types::JsonValue;
+use crate::types::{DozerDuration, DozerPoint, Field, TimeUnit};
+use chrono::{Datelike, FixedOffset, NaiveDate, TimeZone, Utc};
+use ordered_float::OrderedFloat;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+/// Escapes a zero byte inside a variable length value.
+const ESCAPE: u8 = 0xff;
+/// Ends a variable length value. Sorts before any escaped zero byte, so a value sorts before all
+/// values it is a prefix of.
+const TERMINATOR: u8 = 0x01;
+/// Fractional digits kept for the fractional part of a decimal, its maximum scale.
+const DECIMAL_SCALE: u32 = 28;
+/// Ends a JSON array or object. Sorts before the start of any element or entry, so an array or
+/// object sorts before all those it is a prefix of.
+const JSON_END: u8 = 0x00;
+/// Starts an entry of a JSON object.
+const JSON_ENTRY: u8 = 0x01;
+
+impl Field {
+    /// Returns the order-preserving encoding of this value.
+    ///
+    /// The first byte identifies the variant, in declaration order, like the derived `Ord`.
+    /// Values of a variant then sort as follows:
+    ///
+    /// - Integers are big endian, with the sign bit flipped for signed ones.
+    /// - Floats have their sign bit flipped if positive and all bits flipped if negative. `-0.0`
+    ///   is written as `0.0` and every NaN as `f64::NAN`, as `OrderedFloat` considers them equal.
+    /// - Strings, texts and binaries have every zero byte escaped and are terminated, so that they
+    ///   sort lexicographically and can be followed by other fields.
+    /// - JSON values are their variant byte, in declaration order, then their value encoded as
+    ///   above. Arrays are their elements and objects their entries, key then value, followed by
+    ///   an end byte.
+    /// - Decimals are their floor followed by their fractional part at a scale of 28.
+    /// - Timestamps are their instant, then their offset. Equal instants in different offsets
+    ///   compare as equal `Field`s but encode differently.
+    /// - Dates are days since the common era, durations their length then their unit, and points
+    ///   their `x` then their `y` coordinate.
+    /// - `Null` is the variant byte alone, sorting after every other value.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // The length of the `Field::encode` encoding, a good estimate, except for JSON values.
+        let capacity = match self {
+            Field::Json(_) => 1,
+            _ => self.encoding_len(),
+        };
+        let mut result = Vec::with_capacity(capacity);
+        self.write_bytes(&mut result);
+        result
+    }
+
+    /// Appends the encoding of [`Field::to_bytes`] to `buf`, to build composite keys.
+    pub fn write_bytes(&self, buf: &mut Vec<u8>) {
+        buf.push(self.get_type_prefix());
+        match self {
+            Field::UInt(i) => buf.extend_from_slice(&i.to_be_bytes()),
+            Field::U128(i) => buf.extend_from_slice(&i.to_be_bytes()),
+            Field::Int(i) => buf.extend_from_slice(&flip_i64(*i).to_be_bytes()),
+            Field::I128(i) => buf.extend_from_slice(&flip_i128(*i).to_be_bytes()),
+            Field::Float(f) => buf.extend_from_slice(&flip_f64(f.0).to_be_bytes()),
+            Field::Boolean(b) => buf.push(u8::from(*b)),
+            Field::String(s) | Field::Text(s) => write_escaped(buf, s.as_bytes()),
+            Field::Binary(b) => write_escaped(buf, b),
+            Field::Decimal(d) => {
+                let (int, fraction) = split_decimal(*d);
+                buf.extend_from_slice(&flip_i128(int).to_be_bytes());
+                buf.extend_from_slice(&fraction.to_be_bytes());
+            }
+            Field::Timestamp(t) => {
+                buf.extend_from_slice(&flip_i64(t.timestamp()).to_be_bytes());
+                buf.extend_from_slice(&t.timestamp_subsec_nanos().to_be_bytes());
+                let offset = t.offset().local_minus_utc();
+                buf.extend_from_slice(&flip_i32(offset).to_be_bytes());
+            }
+            Field::Date(d) => buf.extend_from_slice(&flip_i32(d.num_days_from_ce()).to_be_bytes()),
+            Field::Json(j) => write_json(buf, j),
+            Field::Point(p) => {
+                buf.extend_from_slice(&flip_f64(p.0.x().0).to_be_bytes());
+                buf.extend_from_slice(&flip_f64(p.0.y().0).to_be_bytes());
+            }
+            Field::Duration(d) => {
+                buf.extend_from_slice(&d.0.as_secs().to_be_bytes());
+                buf.extend_from_slice(&d.0.subsec_nanos().to_be_bytes());
+                buf.extend_from_slice(&d.1.to_bytes());
+            }
+            Field::Null => {}
+        }
+    }
+
+    /// Decodes a value encoded with [`Field::to_bytes`].
+    pub fn from_bytes(buf: &[u8]) -> Result<Field, DeserializationError> {
+        let mut rest = buf;
+        let field = Field::read_bytes(&mut rest)?;
+        if !rest.is_empty() {
+            return Err(DeserializationError::BadDataLength);
+        }
+        Ok(field)
+    }
+
+    /// Decodes the value at the start of `buf` and advances `buf` past it, to split composite
+    /// keys built with [`Field::write_bytes`].
+    pub fn read_bytes(buf: &mut &[u8]) -> Result<Field, DeserializationError> {
+        let prefix = take::<1>(buf).map_err(|_| DeserializationError::EmptyInput)?[0];
+        match prefix {
+            0 => Ok(Field::UInt(u64::from_be_bytes(take(buf)?))),
+            1 => Ok(Field::U128(u128::from_be_bytes(take(buf)?))),
+            2 => Ok(Field::Int(flip_i64_back(u64::from_be_bytes(take(buf)?)))),
+            3 => Ok(Field::I128(flip_i128_back(u128::from_be_bytes(take(buf)?)))),
+            4 => Ok(Field::Float(OrderedFloat(flip_f64_back(
+                u64::from_be_bytes(take(buf)?),
+            )))),
+            5 => Ok(Field::Boolean(take::<1>(buf)?[0] == 1)),
+            6 => Ok(Field::String(
+                String::from_utf8(read_escaped(buf)?)
+                    .map_err(|e| DeserializationError::Utf8(e.utf8_error()))?,
+            )),
+            7 => Ok(Field::Text(
+                String::from_utf8(read_escaped(buf)?)
+                    .map_err(|e| DeserializationError::Utf8(e.utf8_error()))?,
+            )),
+            8 => Ok(Field::Binary(read_escaped(buf)?)),
+            9 => {
+                let int = flip_i128_back(u128::from_be_bytes(take(buf)?));
+                let fraction = u128::from_be_bytes(take(buf)?);
+                let decimal = Decimal::try_from_i128_with_scale(int, 0)
+                    .ok()
+                    .zip(Decimal::try_from_i128_with_scale(fraction as i128, DECIMAL_SCALE).ok())
+                    .and_then(|(int, fraction)| int.checked_add(fraction))
+                    .ok_or(DeserializationError::BadDataLength)?;
+                Ok(Field::Decimal(decimal.normalize()))
+            }
+            10 => {
+                let secs = flip_i64_back(u64::from_be_bytes(take(buf)?));
+                let nanos = u32::from_be_bytes(take(buf)?);
+                let offset = flip_i32_back(u32::from_be_bytes(take(buf)?));
+                let offset = FixedOffset::east_opt(offset).ok_or_else(|| {
+                    DeserializationError::Custom(Box::new(TypeError::InvalidTimestamp))
+                })?;
+                let timestamp = Utc.timestamp_opt(secs, nanos).single().ok_or_else(|| {
+                    DeserializationError::Custom(Box::new(TypeError::InvalidTimestamp))
+                })?;
+                Ok(Field::Timestamp(timestamp.with_timezone(&offset)))
+            }
+            11 => {
+                let days = flip_i32_back(u32::from_be_bytes(take(buf)?));
+                NaiveDate::from_num_days_from_ce_opt(days)
+                    .map(Field::Date)
+                    .ok_or(DeserializationError::BadDataLength)
+            }
+            12 => Ok(Field::Json(read_json(buf)?)),
+            13 => {
+                let x = flip_f64_back(u64::from_be_bytes(take(buf)?));
+                let y = flip_f64_back(u64::from_be_bytes(take(buf)?));
+                Ok(Field::Point(DozerPoint::from((x, y))))
+            }
+            14 => {
+                let secs = u64::from_be_bytes(take(buf)?);
+                let nanos = u32::from_be_bytes(take(buf)?);
+                let unit = TimeUnit::from_bytes(&take::<1>(buf)?)?;
+                Ok(Field::Duration(DozerDuration(
+                    std::time::Duration::new(secs, nanos),
+                    unit,
+                )))
+            }
+            15 => Ok(Field::Null),
+            other => Err(DeserializationError::UnrecognisedFieldType(other)),
+        }
+    }
+}
+
+fn take<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N], DeserializationError> {
+    if buf.len() < N {
+        return Err(DeserializationError::BadDataLength);
+    }
+    let (head, rest) = buf.split_at(N);
+    *buf = rest;
+    Ok(head.try_into().expect("head has N bytes"))
+}
+
+fn write_escaped(buf: &mut Vec<u8>, bytes: &[u8]) {
+    for byte in bytes {
+        buf.push(*byte);
+        if *byte == 0 {
+            buf.push(ESCAPE);
+        }
+    }
+    buf.extend_from_slice(&[0, TERMINATOR]);
+}
+
+fn read_escaped(buf: &mut &[u8]) -> Result<Vec<u8>, DeserializationError> {
+    let mut result = vec![];
+    loop {
+        let [byte] = take::<1>(buf)?;
+        if byte != 0 {
+            result.push(byte);
+            continue;
+        }
+        match take::<1>(buf)? {
+            [ESCAPE] => result.push(0),
+            [TERMINATOR] => return Ok(result),
+            _ => return Err(DeserializationError::BadDataLength),
+        }
+    }
+}
+
+fn write_json(buf: &mut Vec<u8>, value: &JsonValue) {
+    match value {
+        JsonValue::Null => buf.push(1),
+        JsonValue::Bool(b) => {
+            buf.push(2);
+            buf.push(u8::from(*b));
+        }
+        JsonValue::Number(n) => {
+            buf.push(3);
+            buf.extend_from_slice(&flip_f64(n.0).to_be_bytes());
+        }
+        JsonValue::String(s) => {
+            buf.push(4);
+            write_escaped(buf, s.as_bytes());
+        }
+        JsonValue::Array(values) => {
+            buf.push(5);
+            values.iter().for_each(|value| write_json(buf, value));
+            buf.push(JSON_END);
+        }
+        JsonValue::Object(entries) => {
+            buf.push(6);
+            for (key, value) in entries {
+                buf.push(JSON_ENTRY);
+                write_escaped(buf, key.as_bytes());
+                write_json(buf, value);
+            }
+            buf.push(JSON_END);
+        }
+    }
+}
+
+fn read_json(buf: &mut &[u8]) -> Result<JsonValue, DeserializationError> {
+    let read_string = |buf: &mut &[u8]| {
+        String::from_utf8(read_escaped(buf)?)
+            .map_err(|e| DeserializationError::Utf8(e.utf8_error()))
+    };
+    match take::<1>(buf)?[0] {
+        1 => Ok(JsonValue::Null),
+        2 => Ok(JsonValue::Bool(take::<1>(buf)?[0] == 1)),
+        3 => Ok(JsonValue::Number(OrderedFloat(flip_f64_back(
+            u64::from_be_bytes(take(buf)?),
+        )))),
+        4 => Ok(JsonValue::String(read_string(buf)?)),
+        5 => {
+            let mut values = vec![];
+            while buf.first() != Some(&JSON_END) {
+                values.push(read_json(buf)?);
+            }
+            take::<1>(buf)?;
+            Ok(JsonValue::Array(values))
+        }
+        6 => {
+            let mut entries = BTreeMap::new();
+            loop {
+                match take::<1>(buf)? {
+                    [JSON_ENTRY] => {
+                        let key = read_string(buf)?;
+                        entries.insert(key, read_json(buf)?);
+                    }
+                    [JSON_END] => return Ok(JsonValue::Object(entries)),
+                    _ => return Err(DeserializationError::BadDataLength),
+                }
+            }
+        }
+        _ => Err(DeserializationError::BadDataLength),
+    }
+}
+
+fn flip_i32(i: i32) -> u32 {
+    (i as u32) ^ (1 << 31)
+}
+
+fn flip_i32_back(u: u32) -> i32 {
+    (u ^ (1 << 31)) as i32
+}
+
+fn flip_i64(i: i64) -> u64 {
+    (i as u64) ^ (1 << 63)
+}
+
+fn flip_i64_back(u: u64) -> i64 {
+    (u ^ (1 << 63)) as i64
+}
+
+fn flip_i128(i: i128) -> u128 {
+    (i as u128) ^ (1 << 127)
+}
+
+fn flip_i128_back(u: u128) -> i128 {
+    (u ^ (1 << 127)) as i128
+}
+
+fn flip_f64(f: f64) -> u64 {
+    let f = if f.is_nan() {
+        f64::NAN
+    } else if f == 0.0 {
+        0.0
+    } else {
+        f
+    };
+    let bits = f.to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits ^ (1 << 63)
+    }
+}
+
+fn flip_f64_back(u: u64) -> f64 {
+    if u >> 63 == 1 {
+        f64::from_bits(u ^ (1 << 63))
+    } else {
+        f64::from_bits(!u)
+    }
+}
+
+/// Splits `d` into its floor and its fractional part, scaled to an integer.
+fn split_decimal(d: Decimal) -> (i128, u128) {
+    let floor = d.floor();
+    let fraction = d - floor;
+    let int = floor
+        .to_i128()
+        .expect("the floor of a decimal is an integer");
+    let fraction = fraction.mantissa() as u128 * 10_u128.pow(DECIMAL_SCALE - fraction.scale());
+    (int, fraction)
+}
@@ -5,18 +5,22 @@ use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use crate::errors::types::TypeError;
 use prettytable::{Cell, Row, Table};
 use serde::{self, Deserialize, Serialize};
 
 pub mod field;
+mod key_encoding;
+mod operation_format;
 #[cfg(test)]
 mod tests;
 
 use crate::errors::internal::BoxedError;
 use crate::errors::types::TypeError::InvalidFieldValue;
-pub use field::{field_test_cases, Field, FieldType, DATE_FORMAT};
+pub use field::{field_test_cases, Field, FieldType, NullOrdering, DATE_FORMAT};
+pub use operation_format::format_operation;
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum SourceDefinition {
@@ -120,6 +124,157 @@ impl Schema {
     pub fn is_append_only(&self) -> bool {
         false
     }
+
+    /// Compares this schema with a newer version of it. Fields are matched by name.
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        let mut diff = SchemaDiff::default();
+        for field in &self.fields {
+            match other.fields.iter().find(|f| f.name == field.name) {
+                Some(new_field) if new_field.typ != field.typ => diff.retyped.push(RetypedField {
+                    name: field.name.clone(),
+                    old: field.typ,
+                    new: new_field.typ,
+                }),
+                Some(_) => {}
+                None => diff.removed.push(field.clone()),
+            }
+        }
+        diff.added = other
+            .fields
+            .iter()
+            .filter(|field| !self.fields.iter().any(|f| f.name == field.name))
+            .cloned()
+            .collect();
+
+        let old_primary_key = self.primary_key_names();
+        let new_primary_key = other.primary_key_names();
+        if old_primary_key != new_primary_key {
+            diff.primary_key = Some((old_primary_key, new_primary_key));
+        }
+        diff
+    }
+
+    /// Returns a copy of this schema where the field at `index` is named `new_name`.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn rename_field(&self, index: usize, new_name: &str) -> Schema {
+        let mut schema = self.clone();
+        schema.fields[index].name = new_name.to_string();
+        schema
+    }
+
+    /// Returns a schema made of the fields at `order`, in that order, with the primary index
+    /// pointing at the new positions of the primary key fields.
+    ///
+    /// If `order` leaves out a primary key field, the new schema has no primary key. Panics if an
+    /// index in `order` is out of bounds.
+    pub fn reorder_fields(&self, order: &[usize]) -> Schema {
+        let primary_index: Option<Vec<usize>> = self
+            .primary_index
+            .iter()
+            .map(|old| order.iter().position(|index| index == old))
+            .collect();
+        Schema {
+            identifier: self.identifier,
+            fields: order
+                .iter()
+                .map(|index| self.fields[*index].clone())
+                .collect(),
+            primary_index: primary_index.unwrap_or_default(),
+        }
+    }
+
+    /// A fingerprint of the names and types of the fields, in order, and of the primary key, to
+    /// be stored and compared instead of the whole schema to detect that it changed.
+    ///
+    /// It's the FNV-1a hash of a fixed encoding of these, so it's the same across runs and
+    /// platforms, and only changes if they do or if the variants of [`FieldType`] are reordered.
+    pub fn hash_id(&self) -> u64 {
+        let mut hasher = Fnv1a::default();
+        hasher.write_len(self.fields.len());
+        for field in &self.fields {
+            hasher.write_len(field.name.len());
+            hasher.write(field.name.as_bytes());
+            hasher.write(&[field.typ as u8]);
+        }
+        hasher.write_len(self.primary_index.len());
+        for index in &self.primary_index {
+            hasher.write_len(*index);
+        }
+        hasher.0
+    }
+
+    fn primary_key_names(&self) -> Vec<String> {
+        self.primary_index
+            .iter()
+            .map(|index| self.fields[*index].name.clone())
+            .collect()
+    }
+}
+
+/// 64-bit FNV-1a, whose output doesn't depend on the process or the platform, unlike the hashers
+/// of the standard library.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Writes `len` as 8 bytes whatever the width of `usize`.
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+}
+
+/// A field whose type differs between two schema versions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetypedField {
+    pub name: String,
+    pub old: FieldType,
+    pub new: FieldType,
+}
+
+/// Differences between two versions of a schema, as returned by [`Schema::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    /// Fields only in the new schema.
+    pub added: Vec<FieldDefinition>,
+    /// Fields only in the old schema.
+    pub removed: Vec<FieldDefinition>,
+    /// Fields in both schemas, with different types.
+    pub retyped: Vec<RetypedField>,
+    /// Names of the old and new primary key fields, if they differ.
+    pub primary_key: Option<(Vec<String>, Vec<String>)>,
+}
+
+impl SchemaDiff {
+    /// Returns if the schemas are identical, up to field order and nullability.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.retyped.is_empty()
+            && self.primary_key.is_none()
+    }
+
+    /// Returns if records of the old schema can be read with the new one.
+    ///
+    /// That is the case if the new schema only adds nullable fields, which old records leave `NULL`.
+    pub fn is_backward_compatible(&self) -> bool {
+        self.added.iter().all(|field| field.nullable)
+            && self.removed.is_empty()
+            && self.retyped.is_empty()
+            && self.primary_key.is_none()
+    }
 }
 
 impl Display for Schema {
@@ -139,6 +294,9 @@ pub enum IndexDefinition {
 
 pub type SchemaWithIndex = (Schema, Vec<IndexDefinition>);
 
+/// A shared [`Schema`], cheap to hand out along with every operation it describes.
+pub type SchemaRef = Arc<Schema>;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct Record {
     /// Schema implemented by this Record
@@ -199,6 +357,17 @@ impl Record {
         fields
     }
 
+    /// Returns a record made of the values at `indexes`, in that order, such as a record of a
+    /// schema built with [`Schema::reorder_fields`]. The schema identifier is kept.
+    ///
+    /// Panics if an index is out of bounds.
+    pub fn project(&self, indexes: &[usize]) -> Record {
+        Record {
+            schema_id: self.schema_id,
+            values: indexes.iter().map(|i| self.values[*i].clone()).collect(),
+        }
+    }
+
     pub fn get_key(&self, indexes: &Vec<usize>) -> Vec<u8> {
         debug_assert!(!indexes.is_empty(), "Primary key indexes cannot be empty");
 
@@ -0,0 +1,72 @@
+use std::fmt::Write;
+
+use crate::types::{Field, Operation, Record, Schema, DATE_FORMAT};
+
+/// Renders an `Operation` in a human readable form, using the field names from `schema`.
+///
+/// Inserts and deletes print every field. Updates print every field too, but fields whose value
+/// changed are rendered as `old -> new`, so it's easy to spot what an update actually touched.
+pub fn format_operation(op: &Operation, schema: &Schema) -> String {
+    match op {
+        Operation::Insert { new } => format!("INSERT {}", format_record(new, schema)),
+        Operation::Delete { old } => format!("DELETE {}", format_record(old, schema)),
+        Operation::Update { old, new } => format!("UPDATE {}", format_update(old, new, schema)),
+    }
+}
+
+fn format_record(record: &Record, schema: &Schema) -> String {
+    let fields = record
+        .values
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| format!("{}: {}", field_name(schema, idx), format_field(value)))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", fields.join(", "))
+}
+
+fn format_update(old: &Record, new: &Record, schema: &Schema) -> String {
+    let len = old.values.len().max(new.values.len());
+    let mut fields = Vec::with_capacity(len);
+    for idx in 0..len {
+        let mut rendered = format!("{}: ", field_name(schema, idx));
+        match (old.values.get(idx), new.values.get(idx)) {
+            (Some(old_value), Some(new_value)) if old_value == new_value => {
+                rendered.push_str(&format_field(new_value));
+            }
+            (old_value, new_value) => {
+                let old_value = old_value.map_or_else(|| "<missing>".to_string(), format_field);
+                let new_value = new_value.map_or_else(|| "<missing>".to_string(), format_field);
+                let _ = write!(rendered, "{old_value} -> {new_value}");
+            }
+        }
+        fields.push(rendered);
+    }
+    format!("{{{}}}", fields.join(", "))
+}
+
+fn field_name(schema: &Schema, idx: usize) -> String {
+    schema
+        .fields
+        .get(idx)
+        .map_or_else(|| format!("#{idx}"), |f| f.name.clone())
+}
+
+fn format_field(field: &Field) -> String {
+    match field {
+        Field::UInt(v) => v.to_string(),
+        Field::U128(v) => v.to_string(),
+        Field::Int(v) => v.to_string(),
+        Field::I128(v) => v.to_string(),
+        Field::Float(v) => v.to_string(),
+        Field::Boolean(v) => v.to_string(),
+        Field::String(v) | Field::Text(v) => format!("{v:?}"),
+        Field::Binary(v) => format!("{v:x?}"),
+        Field::Decimal(v) => v.to_string(),
+        Field::Timestamp(v) => v.to_rfc3339(),
+        Field::Date(v) => v.format(DATE_FORMAT).to_string(),
+        Field::Json(v) => v.to_string(),
+        Field::Point(v) => v.to_string(),
+        Field::Duration(v) => format!("{:?}", v.0),
+        Field::Null => "NULL".to_string(),
+    }
+}
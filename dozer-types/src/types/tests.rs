@@ -1,7 +1,12 @@
-use crate::types::{field_test_cases, DozerDuration, DozerPoint, Field, TimeUnit};
+use crate::types::{
+    field_test_cases, format_operation, DozerDuration, DozerPoint, Field, FieldDefinition,
+    FieldType, NullOrdering, Operation, Record, RetypedField, Schema, SchemaIdentifier,
+    SourceDefinition, TimeUnit,
+};
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use ordered_float::OrderedFloat;
 use rust_decimal::Decimal;
+use std::cmp::Ordering;
 
 use crate::json_types::JsonValue;
 
@@ -599,3 +604,477 @@ fn test_to_conversion() {
     assert!(field.to_duration().is_ok());
     assert!(field.to_null().is_some());
 }
+
+fn format_test_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "age".to_string(),
+                FieldType::UInt,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_format_operation_insert_and_delete() {
+    let schema = format_test_schema();
+    let record = Record::new(
+        None,
+        vec![Field::Int(1), Field::String("a".to_string()), Field::Null],
+    );
+
+    assert_eq!(
+        format_operation(
+            &Operation::Insert {
+                new: record.clone()
+            },
+            &schema
+        ),
+        r#"INSERT {id: 1, name: "a", age: NULL}"#
+    );
+    assert_eq!(
+        format_operation(&Operation::Delete { old: record }, &schema),
+        r#"DELETE {id: 1, name: "a", age: NULL}"#
+    );
+}
+
+#[test]
+fn test_format_operation_update_highlights_changed_fields() {
+    let schema = format_test_schema();
+    let old = Record::new(
+        None,
+        vec![
+            Field::Int(1),
+            Field::String("a".to_string()),
+            Field::UInt(30),
+        ],
+    );
+    let new = Record::new(
+        None,
+        vec![
+            Field::Int(1),
+            Field::String("b".to_string()),
+            Field::UInt(30),
+        ],
+    );
+
+    let rendered = format_operation(&Operation::Update { old, new }, &schema);
+    assert_eq!(rendered, r#"UPDATE {id: 1, name: "a" -> "b", age: 30}"#);
+    assert_eq!(rendered.matches("->").count(), 1);
+}
+
+#[test]
+fn test_format_operation_renders_all_field_types() {
+    for field in field_test_cases() {
+        let record = Record::new(None, vec![field]);
+        let rendered = format_operation(&Operation::Insert { new: record }, &Schema::empty());
+        assert!(rendered.starts_with("INSERT {#0: "));
+    }
+}
+
+#[test]
+fn test_cmp_with_nulls() {
+    let one = Field::Int(1);
+
+    assert_eq!(
+        Field::Null.cmp_with_nulls(&Field::Null, NullOrdering::First),
+        Ordering::Equal
+    );
+    assert_eq!(
+        Field::Null.cmp_with_nulls(&Field::Null, NullOrdering::Last),
+        Ordering::Equal
+    );
+    assert_eq!(
+        Field::Null.cmp_with_nulls(&one, NullOrdering::First),
+        Ordering::Less
+    );
+    assert_eq!(
+        Field::Null.cmp_with_nulls(&one, NullOrdering::Last),
+        Ordering::Greater
+    );
+    assert_eq!(
+        one.cmp_with_nulls(&Field::Null, NullOrdering::First),
+        Ordering::Greater
+    );
+    assert_eq!(
+        one.cmp_with_nulls(&Field::Null, NullOrdering::Last),
+        Ordering::Less
+    );
+    assert_eq!(
+        one.cmp_with_nulls(&Field::Int(2), NullOrdering::First),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn test_sort_honours_null_ordering() {
+    let values = vec![Field::Int(2), Field::Null, Field::Int(1), Field::Null];
+
+    let mut nulls_first = values.clone();
+    nulls_first.sort_by(|a, b| a.cmp_with_nulls(b, NullOrdering::First));
+    assert_eq!(
+        nulls_first,
+        vec![Field::Null, Field::Null, Field::Int(1), Field::Int(2)]
+    );
+
+    let mut nulls_last = values;
+    nulls_last.sort_by(|a, b| a.cmp_with_nulls(b, NullOrdering::Last));
+    assert_eq!(
+        nulls_last,
+        vec![Field::Int(1), Field::Int(2), Field::Null, Field::Null]
+    );
+}
+
+fn diff_test_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_schema_diff_additive_change_is_compatible() {
+    let old = diff_test_schema();
+    assert!(old.diff(&old).is_empty());
+
+    let nullable = FieldDefinition::new(
+        "email".to_string(),
+        FieldType::String,
+        true,
+        SourceDefinition::Dynamic,
+    );
+    let new = old.clone().field(nullable.clone(), false).clone();
+    let diff = old.diff(&new);
+    assert_eq!(diff.added, vec![nullable]);
+    assert!(diff.removed.is_empty() && diff.retyped.is_empty() && diff.primary_key.is_none());
+    assert!(diff.is_backward_compatible());
+
+    // Old records have no value for a non-nullable field.
+    let non_nullable = FieldDefinition::new(
+        "age".to_string(),
+        FieldType::UInt,
+        false,
+        SourceDefinition::Dynamic,
+    );
+    let new = old.clone().field(non_nullable, false).clone();
+    assert!(!old.diff(&new).is_backward_compatible());
+}
+
+#[test]
+fn test_schema_diff_type_change_is_incompatible() {
+    let old = diff_test_schema();
+    let mut new = old.clone();
+    new.fields[1].typ = FieldType::Text;
+
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.retyped,
+        vec![RetypedField {
+            name: "name".to_string(),
+            old: FieldType::String,
+            new: FieldType::Text,
+        }]
+    );
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+    assert!(!diff.is_backward_compatible());
+}
+
+#[test]
+fn test_schema_diff_primary_key_change_is_incompatible() {
+    let old = diff_test_schema();
+    let mut new = old.clone();
+    new.primary_index = vec![0, 1];
+
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.primary_key,
+        Some((
+            vec!["id".to_string()],
+            vec!["id".to_string(), "name".to_string()]
+        ))
+    );
+    assert!(!diff.is_backward_compatible());
+
+    // Reordering fields keeps the key.
+    let mut reordered = old.clone();
+    reordered.fields.swap(0, 1);
+    reordered.primary_index = vec![1];
+    assert!(old.diff(&reordered).is_empty());
+}
+
+#[test]
+fn test_schema_hash_id() {
+    let schema = diff_test_schema();
+    assert_eq!(schema.hash_id(), diff_test_schema().hash_id());
+    // Pinned, so that a change to the hash that would invalidate stored ones is noticed.
+    assert_eq!(schema.hash_id(), 0xcbb7_db47_e9b4_dc5a);
+
+    let mut retyped = schema.clone();
+    retyped.fields[1].typ = FieldType::Text;
+    assert_ne!(schema.hash_id(), retyped.hash_id());
+
+    let mut reordered = schema.clone();
+    reordered.fields.swap(0, 1);
+    reordered.primary_index = vec![1];
+    assert_ne!(schema.hash_id(), reordered.hash_id());
+
+    let mut rekeyed = schema.clone();
+    rekeyed.primary_index = vec![0, 1];
+    assert_ne!(schema.hash_id(), rekeyed.hash_id());
+
+    // Only the names, types and primary key count.
+    let mut identified = schema.clone();
+    identified.identifier = Some(SchemaIdentifier { id: 1, version: 2 });
+    identified.fields[1].nullable = false;
+    assert_eq!(schema.hash_id(), identified.hash_id());
+}
+
+#[test]
+fn test_to_bytes_round_trips() {
+    for field in field_test_cases() {
+        assert_eq!(Field::from_bytes(&field.to_bytes()).unwrap(), field);
+    }
+}
+
+#[test]
+fn test_to_bytes_preserves_ordering() {
+    let timestamp = |s: &str| Field::Timestamp(DateTime::parse_from_rfc3339(s).unwrap());
+    let number = |n: f64| JsonValue::Number(OrderedFloat(n));
+    let object = |entries: &[(&str, JsonValue)]| {
+        Field::Json(JsonValue::Object(
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        ))
+    };
+    let sorted = [
+        Field::UInt(0),
+        Field::UInt(255),
+        Field::UInt(256),
+        Field::UInt(u64::MAX),
+        Field::U128(1),
+        Field::U128(u128::MAX),
+        Field::Int(i64::MIN),
+        Field::Int(-256),
+        Field::Int(-1),
+        Field::Int(0),
+        Field::Int(1),
+        Field::Int(i64::MAX),
+        Field::I128(i128::MIN),
+        Field::I128(-1),
+        Field::I128(i128::MAX),
+        Field::Float(OrderedFloat(f64::NEG_INFINITY)),
+        Field::Float(OrderedFloat(-2.5)),
+        Field::Float(OrderedFloat(-0.5)),
+        Field::Float(OrderedFloat(0.0)),
+        Field::Float(OrderedFloat(f64::MIN_POSITIVE)),
+        Field::Float(OrderedFloat(2.5)),
+        Field::Float(OrderedFloat(f64::INFINITY)),
+        Field::Float(OrderedFloat(f64::NAN)),
+        Field::Boolean(false),
+        Field::Boolean(true),
+        Field::String("".to_string()),
+        Field::String("a".to_string()),
+        Field::String("a\0".to_string()),
+        Field::String("a\0b".to_string()),
+        Field::String("aa".to_string()),
+        Field::String("b".to_string()),
+        Field::Text("b".to_string()),
+        Field::Text("ba".to_string()),
+        Field::Binary(vec![]),
+        Field::Binary(vec![0]),
+        Field::Binary(vec![0, 0]),
+        Field::Binary(vec![1]),
+        Field::Binary(vec![255]),
+        Field::Decimal(Decimal::MIN),
+        Field::Decimal(Decimal::new(-15, 1)),
+        Field::Decimal(Decimal::new(-1, 0)),
+        Field::Decimal(Decimal::new(-1, 28)),
+        Field::Decimal(Decimal::ZERO),
+        Field::Decimal(Decimal::new(1, 28)),
+        Field::Decimal(Decimal::new(99, 2)),
+        Field::Decimal(Decimal::new(1, 0)),
+        Field::Decimal(Decimal::new(101, 2)),
+        Field::Decimal(Decimal::MAX),
+        timestamp("1969-12-31T23:59:59.5Z"),
+        timestamp("1970-01-01T00:00:00Z"),
+        timestamp("2020-01-01T08:00:00+09:00"),
+        timestamp("2020-01-01T00:00:00Z"),
+        Field::Date(NaiveDate::from_ymd_opt(-1, 12, 31).unwrap()),
+        Field::Date(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()),
+        Field::Date(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()),
+        Field::Json(JsonValue::Null),
+        Field::Json(JsonValue::Bool(false)),
+        Field::Json(JsonValue::Bool(true)),
+        Field::Json(number(-1.0)),
+        Field::Json(number(2.0)),
+        Field::Json(JsonValue::String("".to_string())),
+        Field::Json(JsonValue::String("a\0".to_string())),
+        Field::Json(JsonValue::Array(vec![])),
+        Field::Json(JsonValue::Array(vec![number(1.0)])),
+        Field::Json(JsonValue::Array(vec![number(1.0), JsonValue::Null])),
+        Field::Json(JsonValue::Array(vec![number(2.0)])),
+        object(&[]),
+        object(&[("", JsonValue::Null)]),
+        object(&[("a", JsonValue::Null)]),
+        object(&[("a", JsonValue::Null), ("b", JsonValue::Null)]),
+        object(&[("a", JsonValue::Bool(true))]),
+        object(&[("a\0", JsonValue::Null)]),
+        Field::Point(DozerPoint::from((-1.0, 5.0))),
+        Field::Point(DozerPoint::from((0.0, -5.0))),
+        Field::Point(DozerPoint::from((0.0, 5.0))),
+        Field::Duration(DozerDuration(
+            std::time::Duration::from_nanos(1),
+            TimeUnit::Nanoseconds,
+        )),
+        Field::Duration(DozerDuration(
+            std::time::Duration::from_secs(1),
+            TimeUnit::Seconds,
+        )),
+        Field::Null,
+    ];
+
+    for pair in sorted.windows(2) {
+        assert!(pair[0] < pair[1], "{:?} < {:?}", pair[0], pair[1]);
+        assert!(
+            pair[0].to_bytes() < pair[1].to_bytes(),
+            "{:?} < {:?}",
+            pair[0],
+            pair[1]
+        );
+    }
+    for field in sorted {
+        assert_eq!(Field::from_bytes(&field.to_bytes()).unwrap(), field);
+    }
+}
+
+#[test]
+fn test_write_bytes_builds_composite_keys() {
+    let keys = [
+        [Field::String("a".to_string()), Field::Int(2)],
+        [Field::String("a".to_string()), Field::Int(10)],
+        [Field::String("ab".to_string()), Field::Int(-1)],
+        [Field::Null, Field::Int(0)],
+    ];
+
+    let encoded = keys
+        .iter()
+        .map(|key| {
+            let mut buf = vec![];
+            key.iter().for_each(|field| field.write_bytes(&mut buf));
+            buf
+        })
+        .collect::<Vec<_>>();
+    assert!(encoded.windows(2).all(|pair| pair[0] < pair[1]));
+
+    for (key, buf) in keys.iter().zip(&encoded) {
+        let mut rest = buf.as_slice();
+        let decoded = [
+            Field::read_bytes(&mut rest).unwrap(),
+            Field::read_bytes(&mut rest).unwrap(),
+        ];
+        assert!(rest.is_empty());
+        assert_eq!(&decoded, key);
+    }
+}
+
+#[test]
+fn test_schema_rename_field_preserves_types() {
+    let schema = diff_test_schema();
+    let renamed = schema.rename_field(1, "full_name");
+
+    assert_eq!(renamed.fields[1].name, "full_name");
+    assert_eq!(renamed.fields[1].typ, FieldType::String);
+    assert!(renamed.fields[1].nullable);
+    assert_eq!(renamed.fields[0], schema.fields[0]);
+    assert_eq!(renamed.primary_index, schema.primary_index);
+    assert_eq!(schema.fields[1].name, "name");
+}
+
+#[test]
+fn test_schema_reorder_fields_remaps_primary_index() {
+    let schema = diff_test_schema()
+        .field(
+            FieldDefinition::new(
+                "email".to_string(),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let reordered = schema.reorder_fields(&[2, 1, 0]);
+    let names: Vec<&str> = reordered.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["email", "name", "id"]);
+    assert_eq!(reordered.primary_index, vec![2]);
+
+    let projected = schema.reorder_fields(&[0, 2]);
+    assert_eq!(projected.fields[1].name, "email");
+    assert_eq!(projected.primary_index, vec![0]);
+
+    // Without the key field, rows are no longer identified by it.
+    assert!(schema.reorder_fields(&[1, 2]).primary_index.is_empty());
+}
+
+#[test]
+fn test_record_project() {
+    let record = Record::new(
+        Some(SchemaIdentifier { id: 1, version: 1 }),
+        vec![
+            Field::UInt(1),
+            Field::String("a".to_string()),
+            Field::Int(-1),
+            Field::Boolean(true),
+            Field::Null,
+        ],
+    );
+
+    let projected = record.project(&[3, 1]);
+    assert_eq!(
+        projected.values,
+        vec![Field::Boolean(true), Field::String("a".to_string())]
+    );
+    assert_eq!(projected.schema_id, record.schema_id);
+    assert!(record.project(&[]).values.is_empty());
+}